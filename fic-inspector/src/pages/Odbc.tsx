@@ -36,6 +36,8 @@ const Odbc: React.FC = () => {
   const [loadingRelations, setLoadingRelations] = useState(false);
   const [selectedFolder, setSelectedFolder] = useState<string | null>(null);
   const [useLocalFolder, setUseLocalFolder] = useState(false);
+  // Base enregistrée côté serveur pour le dossier local (relations inférées), null si inconnue
+  const [localDatabase, setLocalDatabase] = useState<string | null>(null);
   const folderInputRef = useRef<HTMLInputElement>(null);
   const [showFolderPicker, setShowFolderPicker] = useState(false);
   
@@ -847,6 +849,20 @@ const Odbc: React.FC = () => {
     }
   };
 
+  // Relations inférées par le moteur pour un dossier scanné (pas de clés étrangères sans ODBC)
+  const loadInferredRelations = useCallback(async (database: string) => {
+    setLoadingRelations(true);
+    try {
+      const report = await apiClient.getInferredRelations(database);
+      setRelations(report.relations);
+    } catch (error: any) {
+      console.error('Erreur lors de l\'inférence des relations:', error);
+      setRelations([]);
+    } finally {
+      setLoadingRelations(false);
+    }
+  }, []);
+
   const loadTables = useCallback(async () => {
     const currentDsn = getCurrentDsn();
     if (!currentDsn) return;
@@ -863,6 +879,10 @@ const Odbc: React.FC = () => {
           setTables(response.tables);
           setSelectedFolder(dsnConfig.path);
           setUseLocalFolder(true);
+          setLocalDatabase(response.database ?? null);
+          if (response.database !== undefined) {
+            loadInferredRelations(response.database);
+          }
           console.log(`✅ ${response.tables.length} table(s) trouvée(s) dans ${dsnConfig.path}`);
         } else {
           console.warn(`⚠️ Erreur lors du scan du dossier ${dsnConfig.path}, tentative via ODBC...`);
@@ -886,7 +906,7 @@ const Odbc: React.FC = () => {
     } finally {
       setLoadingTables(false);
     }
-  }, [useCustomDsn, dsn, customDsn, availableDsns]);
+  }, [useCustomDsn, dsn, customDsn, availableDsns, loadInferredRelations]);
 
   const loadRelations = useCallback(async () => {
     const currentDsn = getCurrentDsn();
@@ -910,8 +930,8 @@ const Odbc: React.FC = () => {
       // Charger les tables automatiquement quand un DSN est sélectionné
       // Le loadTables vérifiera si le DSN a un chemin et utilisera scanDirectory si disponible
       loadTables();
-      // Charger les relations seulement si on n'utilise pas un dossier local
-      // (les relations ne sont disponibles que via ODBC, pas pour les fichiers locaux)
+      // Charger les relations ODBC seulement si on n'utilise pas un dossier local
+      // (pour un dossier, loadTables charge les relations inférées par le moteur)
       const dsnConfig = availableDsns.find(d => d.name === currentDsn);
       if (!dsnConfig || !dsnConfig.path || dsnConfig.path.trim() === '') {
         loadRelations();
@@ -1003,8 +1023,9 @@ const Odbc: React.FC = () => {
     if (ficFiles.size > 0) {
       setTables(Array.from(ficFiles).sort());
       setUseLocalFolder(true);
+      setLocalDatabase(null);
       setSelectedTables(new Set());
-      setRelations([]); // Pas de relations pour les fichiers locaux
+      setRelations([]); // Fichiers lus par le navigateur : pas de relations
       console.log(`✅ ${ficFiles.size} fichier(s) .FIC détecté(s):`, Array.from(ficFiles));
     } else {
       // Afficher un message si aucun fichier .fic n'a été trouvé
@@ -1032,7 +1053,12 @@ const Odbc: React.FC = () => {
         setTables(response.tables);
         setUseLocalFolder(true);
         setSelectedTables(new Set());
-        setRelations([]); // Pas de relations pour les fichiers locaux
+        setLocalDatabase(response.database ?? null);
+        if (response.database !== undefined) {
+          loadInferredRelations(response.database);
+        } else {
+          setRelations([]);
+        }
         console.log(`✅ ${response.tables.length} fichier(s) .FIC trouvé(s) dans ${path}`);
       } else {
         console.error('Erreur lors du scan:', response.error);
@@ -1048,6 +1074,7 @@ const Odbc: React.FC = () => {
 
   const switchToOdbc = () => {
     setUseLocalFolder(false);
+    setLocalDatabase(null);
     setSelectedFolder(null);
     setSelectedTables(new Set());
     if (folderInputRef.current) {
@@ -1768,9 +1795,9 @@ const Odbc: React.FC = () => {
           <div className="bg-theme-input border border-theme-input rounded-lg p-4 flex flex-col min-h-0" style={{ width: `${100 - tablesColumnWidth}%` }}>
             <div className="flex items-center justify-between mb-3">
               <label className="text-sm font-semibold text-theme-statusbar">Relations entre tables</label>
-              {!useLocalFolder && (
+              {(!useLocalFolder || localDatabase !== null) && (
                 <button
-                  onClick={loadRelations}
+                  onClick={() => (useLocalFolder && localDatabase !== null ? loadInferredRelations(localDatabase) : loadRelations())}
                   disabled={loadingRelations}
                   className="px-3 py-1 text-xs bg-theme-secondary rounded transition-colors flex items-center gap-1 disabled:opacity-50"
                   title="Actualiser"
@@ -1781,10 +1808,10 @@ const Odbc: React.FC = () => {
               )}
             </div>
             <div className="flex-1 overflow-auto relative">
-              {useLocalFolder ? (
+              {useLocalFolder && localDatabase === null ? (
                 <div className="flex items-center justify-center h-full">
                   <p className="text-theme-secondary text-sm text-center">
-                    Les relations ne sont disponibles que pour les bases de données ODBC
+                    Les relations ne sont disponibles que pour les bases ODBC ou les dossiers scannés par le serveur
                   </p>
                 </div>
               ) : loadingRelations ? (
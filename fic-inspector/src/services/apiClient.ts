@@ -2,6 +2,7 @@ import axios, { AxiosInstance } from 'axios';
 import type {
   HealthResponse,
   ActivityResponse,
+  RelationReport,
 } from '../types/api';
 
 class ApiClient {
@@ -93,6 +94,14 @@ class ApiClient {
     return response.data;
   }
 
+  async getInferredRelations(database?: string, minScore?: number): Promise<RelationReport> {
+    const params = new URLSearchParams();
+    if (database !== undefined) params.append('database', database);
+    if (minScore !== undefined) params.append('min_score', minScore.toString());
+    const response = await this.client.get<RelationReport>(`/relations?${params.toString()}`);
+    return response.data;
+  }
+
   async getLogs(level?: 'info' | 'warn' | 'error' | 'debug', limit?: number): Promise<{ success: boolean; logs?: any[]; total?: number; error?: string }> {
     try {
       const params = new URLSearchParams();
@@ -120,9 +129,9 @@ class ApiClient {
     return response.data;
   }
 
-  async scanDirectory(path: string): Promise<{ success: boolean; tables: string[]; error?: string }> {
+  async scanDirectory(path: string): Promise<{ success: boolean; database?: string; tables: string[]; error?: string }> {
     try {
-      const response = await this.client.post<{ success: boolean; tables: string[]; error?: string }>(
+      const response = await this.client.post<{ success: boolean; database?: string; tables: string[]; error?: string }>(
         '/scan',
         { path }
       );
@@ -17,12 +17,16 @@ export interface FieldInfo {
   offset: number;
   length: number;
   field_type: 'String' | 'Integer' | 'Float' | 'Date' | 'Memo' | 'Binary' | 'Unknown';
+  encoding: TextEncoding;
 }
 
+export type TextEncoding =
+  | 'auto' | 'utf-8' | 'windows-1252' | 'windows-1250' | 'iso-8859-1'
+  | 'iso-8859-15' | 'cp850' | 'cp437' | 'macintosh';
+
 export interface Record {
   id: number;
-  fields: Record<string, FieldValue>;
-  memo_data: Record<string, string>;
+  fields: Record<string, FieldValue>; // ordre du schéma, mémos compris
 }
 
 export type FieldValue = 
@@ -30,19 +34,31 @@ export type FieldValue =
   | { type: 'integer'; value: number }
   | { type: 'float'; value: number }
   | { type: 'binary'; value: string } // hex string
+  | { type: 'memo_text'; value: string; size: number }
+  | { type: 'memo_binary'; value: string; size: number; mime: string } // hex string
   | { type: 'null'; value: null };
 
 export interface QueryResult {
   records: Record[];
-  total: number;
+  total: number | null;
   offset: number;
   limit: number;
+  next_cursor: string | null;
+}
+
+export interface SampleSpec {
+  method: 'reservoir' | 'stratified';
+  size: number;
+  strata?: number | null;
+  seed?: number | null;
 }
 
 export interface QueryFilters {
   limit?: number;
   offset?: number;
   field_filters?: Record<string, string>;
+  sample?: SampleSpec;
+  cursor?: string;
 }
 
 export interface NdxEntry {
@@ -84,3 +100,20 @@ export interface ActivityResponse {
   dsn_activities: DsnActivity[];
 }
 
+// Relations inférées entre les tables d'une base (GET /relations)
+export interface InferredRelation {
+  from_table: string;
+  from_column: string;
+  to_table: string;
+  to_column: string;
+  score: number;
+  name_match: 'convention' | 'same_name' | 'table_name';
+  overlap: number | null;
+  sampled: number;
+}
+
+export interface RelationReport {
+  tables: number;
+  duration_ms: number;
+  relations: InferredRelation[];
+}
@@ -6,6 +6,20 @@
  * 
  * - health : Vérification de santé du serveur
  * - activity : Historique d'activité (bases de données et DSN)
+ * - scan_directory : Scan récursif d'un dossier et enregistrement de ses tables
+ * - list_databases, add_database, use_database : Gestion des bases de données
+ * - list_records, get_record : Lecture paginée et triée des enregistrements
+ * - cache_stats : Statistiques du cache des schémas et fichiers ouverts
+ * - table_events : Flux des tables ajoutées, supprimées ou modifiées (Server-Sent Events)
+ * - search_table : Recherche full-text dans une table (index .ftx)
+ * - table_history : Historique des modifications d'une table (journaux .jnl)
+ * - table_stats : Profil des rubriques d'une table (nulls, distincts, min/max, formats)
+ * - table_encoding : Encodages candidats des textes d'une table (échantillon)
+ * - infer_relations : Relations probables entre les tables d'une base (noms, types, valeurs)
+ * 
+ * Les lectures du moteur passent par AppState.queries (src/storage/async_engine.rs) :
+ * elles s'exécutent sur un pool dédié, sont annulées si le client se déconnecte
+ * et retournent 504 si leur délai est dépassé.
  * 
  * Liens avec d'autres modules :
  * - Les endpoints SQL et ODBC sont gérés par src/sql/server.rs
@@ -13,10 +27,14 @@
 
 use crate::api::server::AppState;
 use crate::logger::{get_logger, LogLevel};
+use crate::storage::Interrupted;
 use axum::{
     extract::{Path, Query, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     response::Json,
 };
+use tokio::sync::broadcast::error::RecvError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -280,11 +298,13 @@ pub async fn get_logs(Query(query): Query<LogsQuery>) -> Json<LogsResponse> {
     })
 }
 
-/// Requête pour scanner un dossier et lister les fichiers .fic
+/// Requête pour scanner un dossier et enregistrer ses tables
 #[derive(Deserialize)]
 pub struct ScanDirectoryRequest {
     /// Chemin du dossier à scanner
     pub path: String,
+    /// Nom de la base (nom du dossier si absent)
+    pub name: Option<String>,
 }
 
 /// Réponse avec la liste des tables trouvées
@@ -292,106 +312,191 @@ pub struct ScanDirectoryRequest {
 pub struct ScanDirectoryResponse {
     /// Succès de l'opération
     pub success: bool,
-    /// Liste des noms de tables (noms de fichiers .fic sans extension)
+    /// Nom de la base enregistrée ("" pour le dossier de données)
+    pub database: Option<String>,
+    /// Liste des noms qualifiés des tables ("compta.CLIENT")
     pub tables: Vec<String>,
     /// Message d'erreur éventuel
     pub error: Option<String>,
 }
 
 /**
- * Handler POST /scan - Scanne un dossier et liste les fichiers .fic.
+ * Handler POST /scan - Scanne un dossier et enregistre ses tables.
  * 
- * Parcourt le dossier spécifié, détecte tous les fichiers .fic
- * (insensible à la casse) et retourne la liste des noms de tables.
+ * Parcourt récursivement le dossier spécifié, détecte tous les fichiers .fic
+ * (insensible à la casse) et les enregistre dans le moteur comme une base
+ * nommée d'après le dossier (ou `name`). Scanner le dossier de données
+ * rafraîchit la base racine.
  * 
+ * @param state - État de l'application (injecté par Axum)
  * @param request - Requête contenant le chemin du dossier
- * @returns Json<ScanDirectoryResponse> - Liste des tables trouvées
+ * @returns Json<ScanDirectoryResponse> - Liste des tables enregistrées
  * 
  * Effets de bord :
- * - Lit le contenu du dossier spécifié
+ * - Lit le contenu du dossier spécifié et de ses sous-dossiers
+ * - Met à jour les tables et bases du moteur
  */
 pub async fn scan_directory(
+    State(state): State<AppState>,
     Json(request): Json<ScanDirectoryRequest>,
 ) -> Json<ScanDirectoryResponse> {
-    use std::fs;
-    use std::path::Path;
-    
-    let path = Path::new(&request.path);
-    
-    if !path.exists() {
-        return Json(ScanDirectoryResponse {
-            success: false,
-            tables: Vec::new(),
-            error: Some(format!("Le dossier n'existe pas: {}", request.path)),
-        });
-    }
-    
-    if !path.is_dir() {
-        return Json(ScanDirectoryResponse {
-            success: false,
-            tables: Vec::new(),
-            error: Some(format!("Le chemin n'est pas un dossier: {}", request.path)),
-        });
-    }
-    
-    let entries = match fs::read_dir(path) {
-        Ok(entries) => entries,
+    let path = std::path::PathBuf::from(&request.path);
+    let is_data_dir = request.name.is_none()
+        && path.canonicalize().ok() == state.engine.data_dir().canonicalize().ok();
+    let database = if is_data_dir {
+        String::new()
+    } else {
+        request.name.clone().unwrap_or_else(|| database_name(&path))
+    };
+
+    let scan_database = database.clone();
+    let result = state.queries.run(move |engine, _| {
+        if scan_database.is_empty() {
+            engine.scan_tables()
+        } else {
+            engine.add_database(&scan_database, &path)
+        }
+    })
+    .await;
+
+    match result {
+        Ok(mut tables) => {
+            tables.sort();
+            get_logger().log_with_source(
+                LogLevel::Info,
+                format!("Scanné le dossier {}: {} table(s) enregistrée(s)", request.path, tables.len()),
+                Some("API".to_string()),
+            );
+            Json(ScanDirectoryResponse {
+                success: true,
+                database: Some(database),
+                tables,
+                error: None,
+            })
+        }
         Err(e) => {
             get_logger().log_with_source(
                 LogLevel::Error,
-                format!("Erreur lors de la lecture du dossier {}: {}", request.path, e),
+                format!("Erreur lors du scan du dossier {}: {}", request.path, e),
                 Some("API".to_string()),
             );
-            return Json(ScanDirectoryResponse {
+            Json(ScanDirectoryResponse {
                 success: false,
+                database: None,
                 tables: Vec::new(),
-                error: Some(format!("Impossible de lire le dossier: {}", e)),
-            });
-        }
-    };
-    
-    let mut tables = Vec::new();
-    
-    for entry in entries {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                get_logger().log_with_source(
-                    LogLevel::Warn,
-                    format!("Erreur lors de la lecture d'une entrée: {}", e),
-                    Some("API".to_string()),
-                );
-                continue;
-            }
-        };
-        
-        let file_path = entry.path();
-        
-        // Vérifier l'extension (insensible à la casse)
-        if let Some(ext) = file_path.extension() {
-            let ext_lower = ext.to_string_lossy().to_lowercase();
-            if ext_lower == "fic" {
-                if let Some(name) = file_path.file_stem() {
-                    let table_name = name.to_string_lossy().to_string();
-                    tables.push(table_name);
-                }
-            }
+                error: Some(format!("{:#}", e)),
+            })
         }
     }
-    
-    tables.sort();
-    
+}
+
+/**
+ * Déduit un nom de base valide à partir du nom d'un dossier.
+ *
+ * @param path - Dossier de la base
+ * @returns String - Nom du dossier, caractères non alphanumériques remplacés par '_'
+ */
+fn database_name(path: &std::path::Path) -> String {
+    let name: String = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() { "base".to_string() } else { name }
+}
+
+/// Réponse de la liste des bases de données
+#[derive(Serialize)]
+pub struct DatabasesResponse {
+    /// Base utilisée pour les noms de tables non qualifiés (None = racine)
+    pub current: Option<String>,
+    /// Bases enregistrées
+    pub databases: Vec<crate::storage::DatabaseInfo>,
+}
+
+/**
+ * Handler GET /databases - Liste les bases de données enregistrées.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @returns Json<DatabasesResponse> - Bases, tables et base courante
+ */
+pub async fn list_databases(State(state): State<AppState>) -> Json<DatabasesResponse> {
+    Json(DatabasesResponse {
+        current: state.engine.current_database(),
+        databases: state.engine.list_databases(),
+    })
+}
+
+/// Requête pour ajouter une base de données
+#[derive(Deserialize)]
+pub struct AddDatabaseRequest {
+    /// Nom de la base (préfixe des tables)
+    pub name: String,
+    /// Dossier contenant les fichiers .fic
+    pub path: String,
+    /// Utiliser cette base pour les noms non qualifiés
+    #[serde(default)]
+    pub activate: bool,
+}
+
+/**
+ * Handler POST /databases - Ajoute une base de données à chaud.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param request - Nom et dossier de la base
+ * @returns Result<Json<DatabasesResponse>, ...> - Bases après l'ajout, ou erreur 400
+ *
+ * Effets de bord :
+ * - Lit le contenu du dossier et de ses sous-dossiers
+ * - Enregistre les tables dans le moteur
+ */
+pub async fn add_database(
+    State(state): State<AppState>,
+    Json(request): Json<AddDatabaseRequest>,
+) -> Result<Json<DatabasesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (name, path) = (request.name.clone(), request.path.clone());
+    let tables = state.queries.run(move |engine, _| engine.add_database(&name, &path))
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+    if request.activate {
+        state.engine.use_database(Some(&request.name))
+            .map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+    }
+
     get_logger().log_with_source(
         LogLevel::Info,
-        format!("Scanné le dossier {}: {} fichier(s) .fic trouvé(s)", request.path, tables.len()),
+        format!("Base {} ajoutée depuis {}: {} table(s)", request.name, request.path, tables.len()),
         Some("API".to_string()),
     );
-    
-    Json(ScanDirectoryResponse {
-        success: true,
-        tables,
-        error: None,
-    })
+
+    Ok(list_databases(State(state)).await)
+}
+
+/// Requête pour changer de base courante
+#[derive(Deserialize)]
+pub struct UseDatabaseRequest {
+    /// Nom de la base (None = base racine)
+    pub name: Option<String>,
+}
+
+/**
+ * Handler PUT /databases/current - Change la base utilisée pour les noms non qualifiés.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param request - Nom de la base
+ * @returns Result<Json<DatabasesResponse>, ...> - Bases après le changement, ou erreur 404
+ *
+ * Effets de bord :
+ * - Modifie la résolution des noms de tables des requêtes suivantes
+ */
+pub async fn use_database(
+    State(state): State<AppState>,
+    Json(request): Json<UseDatabaseRequest>,
+) -> Result<Json<DatabasesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    state.engine.use_database(request.name.as_deref())
+        .map_err(|e| error_response(StatusCode::NOT_FOUND, e))?;
+    Ok(list_databases(State(state)).await)
 }
 
 /// Requête pour tester une connexion de base de données
@@ -445,26 +550,27 @@ pub async fn test_migration_connection(
         params: request.params,
     };
 
-    match Migrator::new(
-        state.engine.clone(),
-        connection,
-        crate::migration::types::MigrationOptions::default(),
-    ) {
-        Ok(migrator) => {
-            match migrator.test_connection() {
-                Ok(_) => Json(TestConnectionResponse {
-                    success: true,
-                    error: None,
-                }),
-                Err(e) => Json(TestConnectionResponse {
-                    success: false,
-                    error: Some(format!("Erreur de connexion: {}", e)),
-                }),
-            }
-        }
+    // Connexion bloquante : exécutée sur le pool du moteur
+    let result = state.queries.run(move |engine, _| {
+        let migrator = Migrator::new(
+            engine.clone(),
+            connection,
+            crate::migration::types::MigrationOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Erreur lors de la création du connecteur: {}", e))?;
+        migrator.test_connection()
+            .map_err(|e| anyhow::anyhow!("Erreur de connexion: {}", e))
+    })
+    .await;
+
+    match result {
+        Ok(_) => Json(TestConnectionResponse {
+            success: true,
+            error: None,
+        }),
         Err(e) => Json(TestConnectionResponse {
             success: false,
-            error: Some(format!("Erreur lors de la création du connecteur: {}", e)),
+            error: Some(e.to_string()),
         }),
     }
 }
@@ -538,33 +644,29 @@ pub async fn start_migration(
         .unwrap()
         .as_millis());
 
-    // Démarrer la migration dans un thread séparé
-    let engine_clone = state.engine.clone();
+    // Démarrer la migration en arrière-plan, sur le pool du moteur (sans délai)
+    let queries = state.queries.clone();
     let migration_id_clone = migration_id.clone();
     
     tokio::spawn(async move {
-        let connection_clone = connection.clone();
-        match Migrator::new(engine_clone, connection_clone, options) {
-            Ok(mut migrator) => {
-                let result = migrator.migrate();
-                let mut migrations = MIGRATIONS.lock().unwrap();
-                if let Some(migration_result) = migrations.get_mut(&migration_id_clone) {
-                    *migration_result.lock().unwrap() = result.ok();
-                }
-            }
-            Err(e) => {
-                let mut migrations = MIGRATIONS.lock().unwrap();
-                if let Some(migration_result) = migrations.get_mut(&migration_id_clone) {
-                    *migration_result.lock().unwrap() = Some(crate::migration::types::MigrationResult {
-                        status: crate::migration::types::MigrationStatus::Failed,
-                        tables_migrated: 0,
-                        records_migrated: 0,
-                        duration_ms: 0,
-                        error: Some(format!("Erreur: {}", e)),
-                        table_details: Vec::new(),
-                    });
-                }
-            }
+        let outcome = queries.run(move |engine, _| {
+            let mut migrator = Migrator::new(engine.clone(), connection, options)?;
+            Ok(migrator.migrate().ok())
+        })
+        .await;
+        let mut migrations = MIGRATIONS.lock().unwrap();
+        if let Some(migration_result) = migrations.get_mut(&migration_id_clone) {
+            *migration_result.lock().unwrap() = match outcome {
+                Ok(result) => result,
+                Err(e) => Some(crate::migration::types::MigrationResult {
+                    status: crate::migration::types::MigrationStatus::Failed,
+                    tables_migrated: 0,
+                    records_migrated: 0,
+                    duration_ms: 0,
+                    error: Some(format!("Erreur: {}", e)),
+                    table_details: Vec::new(),
+                }),
+            };
         }
     });
 
@@ -601,3 +703,501 @@ pub async fn get_migration_status(
         Json(None)
     }
 }
+
+/// Réponse d'erreur des endpoints REST sur les tables
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    /// Description de l'erreur
+    pub error: String,
+    /// Code HTTP
+    pub code: u16,
+    /// Détails supplémentaires (chaîne des causes)
+    pub details: Option<String>,
+}
+
+/// Construit une réponse d'erreur à partir d'une erreur anyhow
+fn error_response(status: StatusCode, error: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            code: status.as_u16(),
+            details: error.source().map(|_| format!("{:#}", error)),
+        }),
+    )
+}
+
+/// Construit la réponse d'erreur d'une lecture : 504 si son délai est dépassé, `status` sinon
+fn query_error(status: StatusCode, error: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    match Interrupted::find(&error) {
+        Some(Interrupted::TimedOut(_)) => error_response(StatusCode::GATEWAY_TIMEOUT, error),
+        _ => error_response(status, error),
+    }
+}
+
+/**
+ * Handler GET /cache/stats - Statistiques du cache des schémas.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @returns Json<CacheStats> - Accès servis par le cache, rechargements, fichiers réutilisés
+ */
+pub async fn cache_stats(State(state): State<AppState>) -> Json<crate::storage::CacheStats> {
+    Json(state.engine.cache_stats())
+}
+
+/**
+ * Handler GET /events/tables - Flux des changements de tables (Server-Sent Events).
+ *
+ * Chaque table ajoutée, supprimée ou modifiée détectée par un scan (surveillance
+ * du dossier, POST /scan, POST /databases) est envoyée comme un événement
+ * `table_change` dont les données sont un TableChange en JSON. Si le client
+ * lit trop lentement, un événement `lagged` indique le nombre d'événements perdus.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @returns Sse<...> - Flux d'événements, ouvert jusqu'à la déconnexion du client
+ */
+pub async fn table_events(
+    State(state): State<AppState>,
+) -> Sse<impl futures::Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = state.engine.subscribe_changes();
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        let event = match receiver.recv().await {
+            Ok(change) => Event::default().event("table_change").json_data(&change),
+            Err(RecvError::Lagged(lost)) => Ok(Event::default().event("lagged").data(lost.to_string())),
+            Err(RecvError::Closed) => return None,
+        };
+        Some((event, receiver))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Paramètres réservés de GET /tables/:table/records (les autres sont des filtres)
+const RECORDS_PARAMS: [&str; 7] = ["limit", "offset", "sort", "columns", "sample", "seed", "cursor"];
+
+/**
+ * Convertit les paramètres de GET /tables/:table/records en filtres de requête.
+ *
+ * `sort` contient des clés séparées par des virgules, au format
+ * `champ[:asc|desc][:nulls_first|nulls_last][:collation]`. `columns` liste
+ * les rubriques à retourner (seules celles-ci sont décodées). `sample`
+ * demande un échantillon aléatoire au format `méthode:taille[:strates]`
+ * (ex: `stratified:500:20`), rejouable avec la même `seed`. `cursor` reprend
+ * après la page précédente (jeton `next_cursor` de sa réponse). Tout autre
+ * paramètre est une condition d'égalité sur la rubrique du même nom.
+ *
+ * @param params - Paramètres de la requête HTTP
+ * @returns anyhow::Result<QueryFilters> - Filtres, ou erreur si un paramètre est invalide
+ */
+fn records_filters(params: &HashMap<String, String>) -> anyhow::Result<crate::storage::QueryFilters> {
+    use anyhow::Context;
+
+    let number = |name: &str| -> anyhow::Result<Option<u32>> {
+        params.get(name)
+            .map(|value| value.parse::<u32>().with_context(|| format!("Paramètre {} invalide: {}", name, value)))
+            .transpose()
+    };
+    let order_by = params.get("sort")
+        .map(|sort| crate::storage::SortKey::parse_list(sort))
+        .transpose()?
+        .unwrap_or_default();
+    let mut field_filters: Vec<crate::storage::FieldFilter> = params.iter()
+        .filter(|(name, _)| !RECORDS_PARAMS.contains(&name.as_str()))
+        .map(|(name, value)| crate::storage::FieldFilter {
+            field: name.clone(),
+            operator: crate::storage::FilterOperator::Equal,
+            value: value.clone(),
+        })
+        .collect();
+    field_filters.sort_by(|a, b| a.field.cmp(&b.field));
+
+    Ok(crate::storage::QueryFilters {
+        limit: number("limit")?,
+        offset: number("offset")?,
+        field_filters,
+        order_by,
+        projection: params.get("columns")
+            .map(|columns| columns.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()),
+        sample: params.get("sample")
+            .map(|sample| -> anyhow::Result<crate::storage::SampleSpec> {
+                let mut spec: crate::storage::SampleSpec = sample.parse()?;
+                spec.seed = params.get("seed")
+                    .map(|seed| seed.parse::<u64>().with_context(|| format!("Paramètre seed invalide: {}", seed)))
+                    .transpose()?;
+                Ok(spec)
+            })
+            .transpose()?,
+        cursor: params.get("cursor").cloned(),
+    })
+}
+
+/**
+ * Handler GET /tables/:table/records - Liste paginée des enregistrements.
+ *
+ * Paramètres : `limit`, `offset`, `sort` (ex: `sort=nom:nocase,age:desc`),
+ * `columns` (ex: `columns=nom,ville`), `sample` et `seed` (ex:
+ * `sample=reservoir:500&seed=42`), `cursor` (jeton `next_cursor` de la page
+ * précédente) et des filtres d'égalité par rubrique (ex: `ville=Paris`).
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param params - Paramètres de la requête
+ * @returns Result<Json<QueryResult>, ...> - Page d'enregistrements, total et plan, ou erreur 404/400/504 (délai dépassé)
+ *
+ * Effets de bord :
+ * - Lit les fichiers de la table (et des fichiers temporaires pour un tri volumineux)
+ */
+pub async fn list_records(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<crate::storage::QueryResult>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+    let filters = records_filters(&params).map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+
+    let result = state.queries.select(table.clone(), filters)
+        .await
+        .map_err(|e| query_error(StatusCode::BAD_REQUEST, e))?;
+
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!(
+            "Lecture de {}: {} enregistrement(s) sur {} ({})",
+            table,
+            result.records.len(),
+            result.total.map_or_else(|| "?".to_string(), |total| total.to_string()),
+            result.plan.detail
+        ),
+        Some("API".to_string()),
+    );
+    Ok(Json(result))
+}
+
+/**
+ * Handler GET /tables/:table/records/:id - Lit un enregistrement par son ID.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param id - ID de l'enregistrement
+ * @returns Result<Json<Record>, ...> - Enregistrement décodé, ou erreur 404/504
+ *
+ * Effets de bord :
+ * - Lit l'enregistrement dans le fichier .fic (et ses mémos)
+ */
+pub async fn get_record(
+    State(state): State<AppState>,
+    Path((table, id)): Path<(String, u32)>,
+) -> Result<Json<crate::storage::Record>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+
+    state.queries.get_by_id(table, id)
+        .await
+        .map(Json)
+        .map_err(|e| query_error(StatusCode::NOT_FOUND, e))
+}
+
+/// Paramètres de requête pour la recherche full-text
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    /// Mots recherchés ("client*" = préfixe)
+    pub q: String,
+    /// Rubrique à interroger (tous les index full-text si absent)
+    pub field: Option<String>,
+    /// Nombre maximum de résultats
+    #[serde(default = "default_search_limit")]
+    pub limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    100
+}
+
+/// Réponse d'une recherche full-text
+#[derive(Serialize)]
+pub struct SearchResponse {
+    /// Nom de la table
+    pub table: String,
+    /// Requête exécutée
+    pub query: String,
+    /// Nombre total d'enregistrements trouvés
+    pub total: usize,
+    /// Enregistrements trouvés, par pertinence décroissante
+    pub hits: Vec<crate::core::FullTextHit>,
+}
+
+/**
+ * Handler GET /tables/:table/search - Recherche full-text dans une table.
+ *
+ * Interroge les index .ftx de la table et retourne les IDs des
+ * enregistrements trouvés, classés par pertinence.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param query - Paramètres (q, field, limit)
+ * @returns Result<Json<SearchResponse>, ...> - Résultats ou erreur 404/400/504
+ *
+ * Effets de bord :
+ * - Lit les fichiers .ftx de la table
+ */
+pub async fn search_table(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+
+    let hits = state.queries.search(table.clone(), query.q.clone(), query.field.clone())
+        .await
+        .map_err(|e| query_error(StatusCode::BAD_REQUEST, e))?;
+
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!("Recherche full-text '{}' dans {}: {} résultat(s)", query.q, table, hits.len()),
+        Some("API".to_string()),
+    );
+
+    Ok(Json(SearchResponse {
+        table,
+        query: query.q,
+        total: hits.len(),
+        hits: hits.into_iter().take(query.limit).collect(),
+    }))
+}
+
+/// Paramètres de requête pour l'historique des modifications
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    /// ID de l'enregistrement à suivre
+    pub record_id: Option<u32>,
+    /// Date de début incluse (AAAA-MM-JJ[THH:MM[:SS]], UTC)
+    pub since: Option<String>,
+    /// Date de fin exclue (AAAA-MM-JJ[THH:MM[:SS]], UTC)
+    pub until: Option<String>,
+    /// Type d'opération (insert, update, delete)
+    pub operation: Option<String>,
+    /// Utilisateur ayant effectué les modifications
+    pub user: Option<String>,
+    /// Nombre maximum d'entrées (les plus récentes)
+    #[serde(default = "default_history_limit")]
+    pub limit: u32,
+}
+
+fn default_history_limit() -> u32 {
+    1000
+}
+
+/// Réponse de l'historique des modifications
+#[derive(Serialize)]
+pub struct HistoryResponse {
+    /// Nom de la table
+    pub table: String,
+    /// Nombre d'entrées retournées
+    pub total: usize,
+    /// Modifications, de la plus récente à la plus ancienne
+    pub entries: Vec<crate::storage::HistoryEntry>,
+}
+
+/**
+ * Handler GET /tables/:table/history - Historique des modifications d'une table.
+ *
+ * Lit les journaux .jnl de la table et retourne les opérations retenues par
+ * les filtres, avec les images avant/après décodées.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param query - Paramètres (record_id, since, until, operation, user, limit)
+ * @returns Result<Json<HistoryResponse>, ...> - Historique ou erreur 404/400/504
+ *
+ * Effets de bord :
+ * - Lit les fichiers .jnl de la table
+ */
+pub async fn table_history(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+
+    let bad_request = |e| error_response(StatusCode::BAD_REQUEST, e);
+    let operation = match &query.operation {
+        Some(name) => Some(crate::core::JournalOperation::parse(name)
+            .ok_or_else(|| bad_request(anyhow::anyhow!("Opération inconnue: {}", name)))?),
+        None => None,
+    };
+    let filters = crate::storage::HistoryFilters {
+        record_id: query.record_id,
+        since: query.since.as_deref().map(crate::core::jnl::parse_timestamp).transpose().map_err(bad_request)?,
+        until: query.until.as_deref().map(crate::core::jnl::parse_timestamp).transpose().map_err(bad_request)?,
+        operation,
+        user: query.user,
+        limit: Some(query.limit),
+    };
+
+    let entries = state.queries.history(table.clone(), filters)
+        .await
+        .map_err(|e| query_error(StatusCode::BAD_REQUEST, e))?;
+
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!("Historique de {}: {} modification(s)", table, entries.len()),
+        Some("API".to_string()),
+    );
+
+    Ok(Json(HistoryResponse {
+        table,
+        total: entries.len(),
+        entries,
+    }))
+}
+
+/// Paramètres du profilage d'une table
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    /// Nombre de valeurs les plus fréquentes par rubrique
+    #[serde(default = "default_stats_top")]
+    pub top: usize,
+}
+
+fn default_stats_top() -> usize {
+    crate::profile::DEFAULT_TOP
+}
+
+/**
+ * Handler GET /tables/:table/stats - Profil des rubriques d'une table.
+ *
+ * Parcourt tous les enregistrements et retourne, par rubrique, les nulls,
+ * les valeurs distinctes, le min/max, les valeurs fréquentes, l'histogramme
+ * des longueurs et les formats reconnus.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param query - Paramètres (top)
+ * @returns Result<Json<TableProfile>, ...> - Profil ou erreur 404/500/504
+ *
+ * Effets de bord :
+ * - Lit tous les enregistrements de la table
+ */
+pub async fn table_stats(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<crate::profile::TableProfile>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+
+    let profile = state.queries.query(move |engine, cancel| crate::profile::profile_table(engine, &table, query.top, cancel))
+        .await
+        .map_err(|e| query_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!("Profil de {}: {} enregistrement(s) en {} ms", profile.table, profile.records, profile.duration_ms),
+        Some("API".to_string()),
+    );
+
+    Ok(Json(profile))
+}
+
+/// Paramètres de la détection d'encodage
+#[derive(Deserialize)]
+pub struct EncodingQuery {
+    /// Nombre d'enregistrements échantillonnés
+    #[serde(default = "default_encoding_sample")]
+    pub sample: u32,
+}
+
+fn default_encoding_sample() -> u32 {
+    crate::profile::encoding::DEFAULT_SAMPLE
+}
+
+/**
+ * Handler GET /tables/:table/encoding - Encodages candidats des textes d'une table.
+ *
+ * Décode les valeurs non ASCII des premiers enregistrements avec chaque
+ * encodage candidat et retourne les scores, globaux et par rubrique.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param query - Paramètres (sample)
+ * @returns Result<Json<EncodingReport>, ...> - Scores ou erreur 404/500/504
+ *
+ * Effets de bord :
+ * - Lit les `sample` premiers enregistrements de la table
+ */
+pub async fn table_encoding(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<EncodingQuery>,
+) -> Result<Json<crate::profile::EncodingReport>, (StatusCode, Json<ErrorResponse>)> {
+    if !state.engine.has_table(&table) {
+        return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Table '{}' non trouvée", table)));
+    }
+
+    let report = state.queries.query(move |engine, cancel| crate::profile::detect_encoding(engine, &table, query.sample, cancel))
+        .await
+        .map_err(|e| query_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(report))
+}
+
+/// Paramètres de l'inférence des relations
+#[derive(Deserialize)]
+pub struct RelationsQuery {
+    /// Base à examiner ("" pour la base racine, toutes si absent)
+    pub database: Option<String>,
+    /// Enregistrements échantillonnés par table source
+    #[serde(default = "default_relations_sample")]
+    pub sample: u32,
+    /// Score minimal d'une relation retournée (0 à 1)
+    #[serde(default = "default_relations_min_score")]
+    pub min_score: f64,
+}
+
+fn default_relations_sample() -> u32 {
+    crate::profile::relations::DEFAULT_SAMPLE
+}
+
+fn default_relations_min_score() -> f64 {
+    crate::profile::relations::DEFAULT_MIN_SCORE
+}
+
+/**
+ * Handler GET /relations - Relations probables entre les tables d'une base.
+ *
+ * Rapproche les rubriques par leur nom (IDCLIENT → CLIENT.IDCLIENT) et leur
+ * type, puis vérifie sur un échantillon que les valeurs existent dans la
+ * table cible. Alternative à POST /odbc/relations sans DSN.
+ *
+ * @param state - État de l'application (injecté par Axum)
+ * @param query - Paramètres (database, sample, min_score)
+ * @returns Result<Json<RelationReport>, ...> - Relations ou erreur 404/500/504
+ *
+ * Effets de bord :
+ * - Lit les rubriques candidates des tables de la base
+ */
+pub async fn infer_relations(
+    State(state): State<AppState>,
+    Query(query): Query<RelationsQuery>,
+) -> Result<Json<crate::profile::RelationReport>, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(database) = &query.database {
+        if !state.engine.list_databases().iter().any(|info| info.name.eq_ignore_ascii_case(database)) {
+            return Err(error_response(StatusCode::NOT_FOUND, anyhow::anyhow!("Base '{}' non trouvée", database)));
+        }
+    }
+
+    let report = state.queries.query(move |engine, cancel| {
+        crate::profile::infer_relations(engine, query.database.as_deref(), query.sample, query.min_score, cancel)
+    })
+    .await
+    .map_err(|e| query_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(report))
+}
@@ -7,7 +7,14 @@
  * 
  * Endpoints exposés :
  * - GET /health : Vérification de santé du serveur
+ * - POST /scan : Scan récursif d'un dossier et enregistrement de ses tables
+ * - GET /databases : Liste des bases de données
+ * - POST /databases : Ajouter une base de données
+ * - PUT /databases/current : Changer de base courante
  * - POST /sql : Exécution de requêtes SQL
+ * - GET /events/tables : Flux des changements de tables (Server-Sent Events)
+ * - GET /tables/:table/search : Recherche full-text (index .ftx)
+ * - GET /tables/:table/history : Historique des modifications (journaux .jnl)
  * - POST /odbc/tables : Liste des tables ODBC
  * - POST /odbc/relations : Relations entre tables ODBC
  * - GET /dsn : Liste des DSN utilisateur
@@ -20,7 +27,8 @@
  * Liens avec d'autres modules :
  * - Utilise src/api/handlers.rs pour les handlers HTTP
  * - Utilise src/sql/server.rs pour les endpoints SQL
- * - Utilise src/storage/StorageEngine pour accéder aux données
+ * - Utilise src/storage/StorageEngine pour accéder aux données (via AsyncEngine
+ *   pour les lectures bloquantes)
  */
 
 use crate::activity::ActivityTracker;
@@ -28,7 +36,7 @@ use crate::ai::handlers as ai_handlers;
 use crate::api::handlers;
 use crate::dsn::handlers as dsn_handlers;
 use crate::sql::server as sql_server;
-use crate::storage::StorageEngine;
+use crate::storage::{AsyncEngine, StorageEngine};
 use anyhow::Context;
 use axum::{
     routing::{get, post, put, delete},
@@ -43,8 +51,10 @@ use tracing::info;
 /// État global de l'application partagé entre tous les handlers
 #[derive(Clone)]
 pub struct AppState {
-    /// Moteur de stockage
+    /// Moteur de stockage (opérations non bloquantes : tables, bases, cache)
     pub engine: Arc<StorageEngine>,
+    /// Lectures du moteur sur un pool dédié (annulables, avec délai)
+    pub queries: AsyncEngine,
     /// Gestionnaire d'activité
     pub tracker: Arc<ActivityTracker>,
 }
@@ -55,7 +65,7 @@ pub struct AppState {
  * Configure tous les endpoints REST, les middlewares (CORS, logging,
  * limite de taille), puis démarre le serveur en mode asynchrone.
  * 
- * @param engine - Moteur de stockage partagé (Arc pour thread-safety)
+ * @param queries - Façade asynchrone du moteur de stockage partagé
  * @param host - Adresse IP ou hostname d'écoute (ex: "127.0.0.1")
  * @param port - Port d'écoute (ex: 8080)
  * @returns Result<()> - Succès si le serveur démarre, erreur sinon
@@ -65,7 +75,8 @@ pub struct AppState {
  * - Affiche des informations sur stdout (endpoints disponibles)
  * - Log les requêtes HTTP via tracing
  */
-pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) -> anyhow::Result<()> {
+pub async fn start_server(queries: AsyncEngine, host: &str, port: u16) -> anyhow::Result<()> {
+    let engine = queries.engine().clone();
     // Créer le gestionnaire d'activité
     // Le fichier sera stocké dans le dossier de données
     let activity_path = engine.data_dir().parent()
@@ -80,6 +91,7 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
     // Créer l'état global de l'application
     let app_state = AppState {
         engine: engine.clone(),
+        queries,
         tracker,
     };
 
@@ -88,7 +100,19 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
         .route("/activity", get(handlers::get_activity))
         .route("/logs", get(handlers::get_logs))
         .route("/scan", post(handlers::scan_directory))
+        .route("/databases", get(handlers::list_databases))
+        .route("/databases", post(handlers::add_database))
+        .route("/databases/current", put(handlers::use_database))
         .route("/sql", post(sql_server::execute_sql))
+        .route("/cache/stats", get(handlers::cache_stats))
+        .route("/events/tables", get(handlers::table_events))
+        .route("/tables/:table/records", get(handlers::list_records))
+        .route("/tables/:table/records/:id", get(handlers::get_record))
+        .route("/tables/:table/search", get(handlers::search_table))
+        .route("/tables/:table/history", get(handlers::table_history))
+        .route("/tables/:table/stats", get(handlers::table_stats))
+        .route("/tables/:table/encoding", get(handlers::table_encoding))
+        .route("/relations", get(handlers::infer_relations))
         .route("/odbc/tables", post(sql_server::get_odbc_tables))
         .route("/odbc/relations", post(sql_server::get_odbc_relations))
         .route("/dsn", get(dsn_handlers::list_dsns))
@@ -127,8 +151,20 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
         "GET  /health",
         "GET  /activity - Historique d'activité",
         "GET  /logs - Logs de l'application",
-        "POST /scan - Scanner un dossier et lister les fichiers .fic",
+        "POST /scan - Scanner un dossier et enregistrer ses tables",
+        "GET  /databases - Liste des bases de données",
+        "POST /databases - Ajouter une base de données",
+        "PUT  /databases/current - Changer de base courante",
         "POST /sql - Exécuter des requêtes SQL",
+        "GET  /cache/stats - Statistiques du cache des schémas",
+        "GET  /events/tables - Flux des changements de tables (SSE)",
+        "GET  /tables/:table/records - Enregistrements (pagination, filtres, tri)",
+        "GET  /tables/:table/records/:id - Enregistrement par ID",
+        "GET  /tables/:table/search - Recherche full-text",
+        "GET  /tables/:table/history - Historique des modifications",
+        "GET  /tables/:table/stats - Profil des rubriques",
+        "GET  /tables/:table/encoding - Détection de l'encodage des textes",
+        "GET  /relations - Relations probables entre les tables (sans ODBC)",
         "POST /odbc/tables - Liste des tables ODBC",
         "POST /odbc/relations - Relations entre tables ODBC",
         "GET  /dsn - Liste des DSN utilisateur",
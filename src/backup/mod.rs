@@ -0,0 +1,539 @@
+/**
+ * Module de sauvegarde cohérente d'une base HFSQL.
+ *
+ * `fic backup` copie les fichiers des tables détectées (.fic, .mmo, .ndx et
+ * leurs compagnons .ftx, .jnl, segments) dans un dossier ou une archive ZIP
+ * horodatés. Une base peut être modifiée pendant la copie : l'état (taille,
+ * date) des fichiers d'une table est relevé avant et après sa copie, et la
+ * table est recopiée tant qu'il change, dans la limite des tentatives.
+ *
+ * La sauvegarde contient un manifeste (manifest.json) : version du moteur,
+ * et pour chaque table le nombre d'enregistrements lu dans le header du
+ * .fic, puis la taille et l'empreinte SHA-256 de chaque fichier.
+ * `fic backup verify` relit une sauvegarde (dossier ou archive) et la
+ * compare à son manifeste.
+ *
+ * La sauvegarde est écrite sous un nom temporaire (`.partial`) puis
+ * renommée : une sauvegarde interrompue n'est jamais prise pour complète.
+ *
+ * - mod.rs : Sauvegarde, manifeste et vérification
+ * - writer.rs : Écriture dans un dossier ou une archive ZIP
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs (détection des tables) et src/core/source.rs (lecture)
+ * - Utilisé par la commande `fic backup` (src/cli/commands.rs)
+ */
+
+pub mod writer;
+
+pub use writer::BackupWriter;
+
+use crate::core::jnl::format_timestamp;
+use crate::core::{source, FicFile};
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::StorageEngine;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Nom du manifeste à la racine de la sauvegarde
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// Version du format du manifeste
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Nombre de nouvelles copies d'une table modifiée pendant la copie
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Attente avant de recopier une table modifiée
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Dossier des fichiers situés hors du dossier sauvegardé (tables d'un .rep)
+const EXTERNAL_DIR: &str = "_externes";
+
+/// Forme de la sauvegarde
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFormat {
+    /// Dossier
+    #[default]
+    #[serde(rename = "dir")]
+    Directory,
+    /// Archive ZIP non compressée
+    Zip,
+}
+
+impl std::str::FromStr for BackupFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dir" => Ok(BackupFormat::Directory),
+            "zip" => Ok(BackupFormat::Zip),
+            _ => anyhow::bail!("Format de sauvegarde inconnu: {}. Options: dir, zip", s),
+        }
+    }
+}
+
+impl std::fmt::Display for BackupFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BackupFormat::Directory => "dir",
+            BackupFormat::Zip => "zip",
+        })
+    }
+}
+
+/// Options d'une sauvegarde
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    pub format: BackupFormat,
+    /// Nouvelles copies d'une table modifiée pendant la copie
+    pub retries: u32,
+    pub retry_delay: Duration,
+    /// Active le multi-threading pour la détection des tables
+    pub parallel: bool,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        Self {
+            format: BackupFormat::Directory,
+            retries: DEFAULT_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            parallel: true,
+        }
+    }
+}
+
+/// Manifeste d'une sauvegarde
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Version du format du manifeste
+    pub manifest_version: u32,
+    /// Version du moteur ayant écrit la sauvegarde
+    pub engine_version: String,
+    /// Date de la sauvegarde ("AAAA-MM-JJ HH:MM:SS", UTC)
+    pub created_at: String,
+    /// Dossier (ou archive) sauvegardé
+    pub source: String,
+    pub tables: Vec<ManifestTable>,
+}
+
+/// Table sauvegardée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestTable {
+    /// Nom qualifié de la table
+    pub name: String,
+    /// Nombre d'enregistrements (header du .fic)
+    pub record_count: u32,
+    /// Nombre d'enregistrements supprimés (header du .fic)
+    pub deleted_count: u32,
+    /// Copies effectuées (plus d'une si la table a été modifiée pendant la copie)
+    pub attempts: u32,
+    /// Fichiers de la table, .fic en premier
+    pub files: Vec<ManifestFile>,
+}
+
+/// Fichier sauvegardé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Chemin relatif dans la sauvegarde (séparateur '/')
+    pub path: String,
+    pub size: u64,
+    /// Empreinte SHA-256 (hexadécimal)
+    pub sha256: String,
+}
+
+/// Résultat d'une sauvegarde
+#[derive(Debug, Clone)]
+pub struct BackupResult {
+    /// Dossier ou archive créé
+    pub path: PathBuf,
+    pub manifest: Manifest,
+}
+
+/// Anomalie trouvée par la vérification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyProblem {
+    /// Chemin relatif du fichier dans la sauvegarde
+    pub path: String,
+    pub problem: String,
+}
+
+/// Résultat de la vérification d'une sauvegarde
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Sauvegarde vérifiée
+    pub path: PathBuf,
+    /// Date et version du moteur lues dans le manifeste
+    pub created_at: String,
+    pub engine_version: String,
+    pub tables: usize,
+    /// Fichiers vérifiés
+    pub files: usize,
+    /// Octets relus
+    pub bytes: u64,
+    pub problems: Vec<VerifyProblem>,
+}
+
+impl VerifyReport {
+    /// Indique si la sauvegarde est conforme à son manifeste
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Taille et date de chaque fichier (None s'il a disparu)
+type Stamps = Vec<Option<(u64, Option<SystemTime>)>>;
+
+fn stamps(paths: &[&PathBuf]) -> Stamps {
+    paths.iter()
+        .map(|path| source::metadata(path).ok().map(|metadata| (metadata.len, metadata.modified)))
+        .collect()
+}
+
+/**
+ * Chemin d'un fichier dans la sauvegarde.
+ *
+ * Les fichiers du dossier sauvegardé gardent leur chemin relatif ; ceux
+ * d'un autre dossier (tables déclarées dans un .rep) sont rangés dans
+ * `_externes/{table}/`.
+ *
+ * @param root - Dossier sauvegardé
+ * @param table - Nom qualifié de la table
+ * @param path - Fichier de la table
+ * @returns String - Chemin relatif (séparateur '/')
+ */
+fn backup_path(root: &Path, table: &str, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => format!(
+            "{}/{}/{}",
+            EXTERNAL_DIR,
+            table,
+            path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()
+        ),
+    }
+}
+
+/**
+ * Nom horodaté d'une sauvegarde ("DONNEES-20251018-140903").
+ *
+ * @param source - Dossier sauvegardé
+ * @param timestamp - Secondes Unix
+ * @returns String - Nom sans extension
+ */
+fn backup_name(source: &Path, timestamp: i64) -> String {
+    let stem = source.canonicalize().ok()
+        .as_deref()
+        .unwrap_or(source)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "fic".to_string());
+    let date: String = format_timestamp(timestamp).chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | ':' => None,
+            c => Some(c),
+        })
+        .collect();
+    format!("{}-{}", stem, date)
+}
+
+/**
+ * Copie les fichiers d'une table, en recommençant si elle change pendant la copie.
+ *
+ * @param writer - Destination
+ * @param engine - Moteur ayant scanné le dossier
+ * @param root - Dossier sauvegardé
+ * @param table - Nom qualifié de la table
+ * @param options - Tentatives et délai
+ * @returns Result<ManifestTable> - Entrée du manifeste, ou erreur si la table change à chaque tentative
+ */
+fn copy_table(writer: &mut BackupWriter, engine: &StorageEngine, root: &Path, table: &str, options: &BackupOptions) -> Result<ManifestTable> {
+    let files = engine.table_files(table)?;
+    let paths = files.found_paths();
+    let mut attempt = 1;
+    loop {
+        let before = stamps(&paths);
+        let header = FicFile::open(&files.fic_path)?.header().clone();
+        let mark = writer.mark();
+        let mut copied = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let name = backup_path(root, table, path);
+            let mut file = source::open(path).with_context(|| format!("Impossible d'ouvrir {:?}", path))?;
+            let (size, sha256) = writer.add(&name, &mut file)
+                .with_context(|| format!("Impossible de copier {:?}", path))?;
+            copied.push(ManifestFile { path: name, size, sha256 });
+        }
+
+        let after = stamps(&paths);
+        let complete = copied.iter().zip(&before).all(|(file, stamp)| stamp.is_some_and(|(len, _)| len == file.size));
+        if before == after && complete {
+            return Ok(ManifestTable {
+                name: table.to_string(),
+                record_count: header.record_count,
+                deleted_count: header.deleted_count,
+                attempts: attempt,
+                files: copied,
+            });
+        }
+
+        writer.rollback(mark)?;
+        if attempt > options.retries {
+            anyhow::bail!("La table {} a été modifiée pendant chacune des {} copies", table, attempt);
+        }
+        get_logger().log_with_source(
+            LogLevel::Warn,
+            format!("Table {} modifiée pendant la copie, nouvelle tentative ({}/{})", table, attempt, options.retries),
+            Some("Backup".to_string()),
+        );
+        std::thread::sleep(options.retry_delay);
+        attempt += 1;
+    }
+}
+
+/**
+ * Sauvegarde les tables d'un dossier dans un dossier ou une archive horodatés.
+ *
+ * @param source_dir - Dossier de données (ou archive) à sauvegarder
+ * @param dest - Dossier dans lequel la sauvegarde est créée
+ * @param options - Format, tentatives, multi-threading
+ * @returns Result<BackupResult> - Chemin et manifeste de la sauvegarde
+ *
+ * Effets de bord :
+ * - Lit tous les fichiers des tables
+ * - Crée `dest` si besoin, puis la sauvegarde (supprimée en cas d'erreur)
+ */
+pub fn create_backup(source_dir: &Path, dest: &Path, options: &BackupOptions) -> Result<BackupResult> {
+    if !source::is_dir(source_dir) {
+        anyhow::bail!("Le chemin n'est pas un dossier ni une archive: {:?}", source_dir);
+    }
+    let engine = StorageEngine::new_with_parallel(source_dir, true, options.parallel)?;
+    let mut tables = engine.scan_tables()?;
+    if tables.is_empty() {
+        anyhow::bail!("Aucun fichier .fic trouvé dans {:?}", source_dir);
+    }
+    tables.sort();
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    std::fs::create_dir_all(dest).with_context(|| format!("Impossible de créer le dossier: {:?}", dest))?;
+    let name = backup_name(source_dir, timestamp);
+    let extension = match options.format {
+        BackupFormat::Directory => "",
+        BackupFormat::Zip => ".zip",
+    };
+    // Deux sauvegardes dans la même seconde : suffixe numéroté
+    let path = (1..)
+        .map(|n| if n == 1 { dest.join(format!("{}{}", name, extension)) } else { dest.join(format!("{}-{}{}", name, n, extension)) })
+        .find(|path| !path.exists() && !partial_path(path).exists())
+        .unwrap();
+    let partial = partial_path(&path);
+
+    let result = write_backup(&engine, source_dir, &partial, &tables, timestamp, options);
+    let manifest = match result {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = if partial.is_dir() { std::fs::remove_dir_all(&partial) } else { std::fs::remove_file(&partial) };
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, &path)
+        .with_context(|| format!("Impossible de renommer {:?} en {:?}", partial, path))?;
+
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!("Sauvegarde {:?} créée: {} table(s)", path, manifest.tables.len()),
+        Some("Backup".to_string()),
+    );
+    Ok(BackupResult { path, manifest })
+}
+
+/// Nom temporaire d'une sauvegarde en cours d'écriture
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// Copie les tables puis écrit le manifeste
+fn write_backup(
+    engine: &StorageEngine,
+    source_dir: &Path,
+    partial: &Path,
+    tables: &[String],
+    timestamp: i64,
+    options: &BackupOptions,
+) -> Result<Manifest> {
+    let mut writer = match options.format {
+        BackupFormat::Directory => BackupWriter::directory(partial),
+        BackupFormat::Zip => BackupWriter::zip(partial, timestamp),
+    }
+    .with_context(|| format!("Impossible de créer la sauvegarde: {:?}", partial))?;
+
+    let mut manifest = Manifest {
+        manifest_version: MANIFEST_VERSION,
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: format_timestamp(timestamp),
+        source: source_dir.display().to_string(),
+        tables: Vec::with_capacity(tables.len()),
+    };
+    for table in tables {
+        manifest.tables.push(copy_table(&mut writer, engine, source_dir, table, options)?);
+    }
+
+    let content = serde_json::to_vec_pretty(&manifest)?;
+    writer.add(MANIFEST_NAME, &mut content.as_slice())?;
+    writer.finish().with_context(|| format!("Impossible de terminer la sauvegarde: {:?}", partial))?;
+    Ok(manifest)
+}
+
+/**
+ * Lit le manifeste d'une sauvegarde.
+ *
+ * @param backup - Dossier ou archive de la sauvegarde
+ * @returns Result<Manifest> - Manifeste, ou erreur s'il est absent ou illisible
+ */
+pub fn read_manifest(backup: &Path) -> Result<Manifest> {
+    let path = backup.join(MANIFEST_NAME);
+    let mut content = Vec::new();
+    source::open(&path)
+        .and_then(|mut file| file.read_to_end(&mut content))
+        .with_context(|| format!("Manifeste illisible: {:?}", path))?;
+    serde_json::from_slice(&content).with_context(|| format!("Manifeste invalide: {:?}", path))
+}
+
+/**
+ * Vérifie une sauvegarde par rapport à son manifeste.
+ *
+ * Chaque fichier est relu : présence, taille, empreinte SHA-256, et nombre
+ * d'enregistrements du header pour les .fic.
+ *
+ * @param backup - Dossier ou archive de la sauvegarde
+ * @returns Result<VerifyReport> - Anomalies trouvées (vide si conforme), ou erreur si le manifeste est illisible
+ *
+ * Effets de bord :
+ * - Lit tous les fichiers de la sauvegarde
+ */
+pub fn verify_backup(backup: &Path) -> Result<VerifyReport> {
+    let manifest = read_manifest(backup)?;
+    let mut report = VerifyReport {
+        path: backup.to_path_buf(),
+        created_at: manifest.created_at.clone(),
+        engine_version: manifest.engine_version.clone(),
+        tables: manifest.tables.len(),
+        files: 0,
+        bytes: 0,
+        problems: Vec::new(),
+    };
+    let mut problems = Vec::new();
+    for table in &manifest.tables {
+        for (index, file) in table.files.iter().enumerate() {
+            report.files += 1;
+            let path = file.path.split('/').fold(backup.to_path_buf(), |path, part| path.join(part));
+            let mut reader = match source::open(&path) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    problems.push(problem(&file.path, format!("fichier absent ou illisible ({})", e)));
+                    continue;
+                }
+            };
+            let mut sha = Sha256::new();
+            let mut buffer = vec![0u8; 64 * 1024];
+            let mut size = 0u64;
+            let read = loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) => break Ok(()),
+                    Ok(read) => {
+                        sha.update(&buffer[..read]);
+                        size += read as u64;
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => break Err(e),
+                }
+            };
+            report.bytes += size;
+            if let Err(e) = read {
+                problems.push(problem(&file.path, format!("lecture interrompue ({})", e)));
+                continue;
+            }
+            if size != file.size {
+                problems.push(problem(&file.path, format!("taille {} au lieu de {}", size, file.size)));
+            }
+            let digest = hex::encode(sha.finalize());
+            if digest != file.sha256 {
+                problems.push(problem(&file.path, format!("empreinte SHA-256 {} au lieu de {}", digest, file.sha256)));
+            }
+            // Le .fic est le premier fichier de la table
+            if index == 0 {
+                match FicFile::open(&path) {
+                    Ok(fic) if fic.header().record_count != table.record_count => problems.push(problem(
+                        &file.path,
+                        format!("{} enregistrement(s) au lieu de {}", fic.header().record_count, table.record_count),
+                    )),
+                    Ok(_) => {}
+                    Err(e) => problems.push(problem(&file.path, format!("header illisible ({:#})", e))),
+                }
+            }
+        }
+    }
+    report.problems = problems;
+    Ok(report)
+}
+
+/// Anomalie de vérification (loggée)
+fn problem(path: &str, message: String) -> VerifyProblem {
+    get_logger().log_with_source(
+        LogLevel::Warn,
+        format!("Sauvegarde: {} : {}", path, message),
+        Some("Backup".to_string()),
+    );
+    VerifyProblem { path: path.to_string(), problem: message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backup_and_verify() {
+        let dir = TempDir::new().unwrap();
+        let source_dir = dir.path().join("DONNEES");
+        std::fs::create_dir_all(source_dir.join("compta")).unwrap();
+        write_fic(&source_dir.join("CLIENT.FIC"), 16, &[(false, 7); 3]).unwrap();
+        std::fs::write(source_dir.join("CLIENT.MMO"), b"memo").unwrap();
+        write_fic(&source_dir.join("compta").join("FACTURE.FIC"), 16, &[(false, 7); 2]).unwrap();
+
+        for format in [BackupFormat::Directory, BackupFormat::Zip] {
+            let options = BackupOptions { format, parallel: false, ..Default::default() };
+            let result = create_backup(&source_dir, &dir.path().join("sauvegardes"), &options).unwrap();
+            assert!(result.path.file_name().unwrap().to_string_lossy().starts_with("DONNEES-"));
+            assert_eq!(result.path.extension().is_some(), format == BackupFormat::Zip);
+            let tables: Vec<&str> = result.manifest.tables.iter().map(|t| t.name.as_str()).collect();
+            assert_eq!(tables, vec!["CLIENT", "compta.FACTURE"]);
+            assert_eq!(result.manifest.tables[0].files[1].path, "CLIENT.MMO");
+            assert_eq!(result.manifest.tables[1].files[0].path, "compta/FACTURE.FIC");
+
+            let report = verify_backup(&result.path).unwrap();
+            assert!(report.is_ok(), "{:?}", report.problems);
+            assert_eq!((report.tables, report.files), (2, 3));
+        }
+
+        // Fichier altéré dans une sauvegarde dossier
+        let backup = create_backup(&source_dir, &dir.path().join("autres"), &BackupOptions { parallel: false, ..Default::default() }).unwrap();
+        std::fs::write(backup.path.join("CLIENT.MMO"), b"MEMO").unwrap();
+        std::fs::remove_file(backup.path.join("compta").join("FACTURE.FIC")).unwrap();
+        let report = verify_backup(&backup.path).unwrap();
+        let paths: Vec<&str> = report.problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["CLIENT.MMO", "compta/FACTURE.FIC"]);
+    }
+}
@@ -0,0 +1,290 @@
+/**
+ * Écriture d'une sauvegarde : dossier ou archive ZIP.
+ *
+ * Chaque fichier est copié par blocs et haché (SHA-256) au fil de la
+ * copie. Un point de reprise (mark) permet d'annuler les fichiers d'une
+ * table copiée pendant qu'elle était modifiée, avant de la recopier.
+ *
+ * L'archive ZIP est écrite sans compression (les entrées restent lisibles
+ * en accès direct par src/core/archive.rs) au format ZIP64 : taille et
+ * position des fichiers ne sont pas limitées à 4 Go.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise les crates sha2 (SHA-256) et crc32fast (CRC-32)
+ * - Utilisé par src/backup/mod.rs
+ */
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Taille des blocs copiés
+const COPY_BUFFER: usize = 64 * 1024;
+
+/// Version ZIP requise pour lire les extensions ZIP64
+const ZIP64_VERSION: u16 = 45;
+
+/// Drapeau ZIP : noms encodés en UTF-8
+const UTF8_FLAG: u16 = 0x0800;
+
+/// Fichier écrit dans une archive ZIP
+#[derive(Debug, Clone)]
+pub struct ZipEntry {
+    name: String,
+    offset: u64,
+    size: u64,
+    crc: u32,
+}
+
+/// Point de reprise : état de la sauvegarde avant la copie d'une table
+#[derive(Debug, Clone, Copy)]
+pub struct Mark {
+    entries: usize,
+    offset: u64,
+}
+
+/// Destination d'une sauvegarde
+pub enum BackupWriter {
+    Directory {
+        root: PathBuf,
+        written: Vec<PathBuf>,
+    },
+    Zip {
+        file: BufWriter<File>,
+        entries: Vec<ZipEntry>,
+        offset: u64,
+        /// Date et heure MS-DOS des entrées
+        dos_time: (u16, u16),
+    },
+}
+
+/**
+ * Convertit un timestamp Unix en date et heure MS-DOS (UTC).
+ *
+ * @param timestamp - Secondes depuis 1970-01-01 UTC
+ * @returns (u16, u16) - Heure et date au format MS-DOS (1980 au minimum)
+ */
+fn dos_time(timestamp: i64) -> (u16, u16) {
+    let (year, month, day) = crate::core::jnl::civil_from_days(timestamp.div_euclid(86400));
+    if year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let seconds = timestamp.rem_euclid(86400);
+    let time = ((seconds / 3600) << 11) | (((seconds % 3600) / 60) << 5) | ((seconds % 60) / 2);
+    let date = (((year - 1980).min(127)) << 9) | ((month as i64) << 5) | day as i64;
+    (time as u16, date as u16)
+}
+
+impl BackupWriter {
+    /**
+     * Crée une sauvegarde sous forme de dossier.
+     *
+     * @param root - Dossier à créer
+     * @returns io::Result<BackupWriter> - Destination prête
+     */
+    pub fn directory(root: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(root)?;
+        Ok(BackupWriter::Directory { root: root.to_path_buf(), written: Vec::new() })
+    }
+
+    /**
+     * Crée une sauvegarde sous forme d'archive ZIP.
+     *
+     * @param path - Fichier à créer
+     * @param timestamp - Date des entrées (secondes Unix)
+     * @returns io::Result<BackupWriter> - Destination prête
+     */
+    pub fn zip(path: &Path, timestamp: i64) -> io::Result<Self> {
+        Ok(BackupWriter::Zip {
+            file: BufWriter::new(File::create(path)?),
+            entries: Vec::new(),
+            offset: 0,
+            dos_time: dos_time(timestamp),
+        })
+    }
+
+    /// Point de reprise avant la copie d'une table
+    pub fn mark(&self) -> Mark {
+        match self {
+            BackupWriter::Directory { written, .. } => Mark { entries: written.len(), offset: 0 },
+            BackupWriter::Zip { entries, offset, .. } => Mark { entries: entries.len(), offset: *offset },
+        }
+    }
+
+    /**
+     * Annule les fichiers écrits depuis un point de reprise.
+     *
+     * @param mark - Point de reprise retourné par mark
+     * @returns io::Result<()> - Erreur si les fichiers ne peuvent pas être supprimés
+     */
+    pub fn rollback(&mut self, mark: Mark) -> io::Result<()> {
+        match self {
+            BackupWriter::Directory { written, .. } => {
+                for path in written.drain(mark.entries..) {
+                    std::fs::remove_file(path)?;
+                }
+            }
+            BackupWriter::Zip { file, entries, offset, .. } => {
+                file.flush()?;
+                file.get_mut().set_len(mark.offset)?;
+                file.get_mut().seek(SeekFrom::Start(mark.offset))?;
+                entries.truncate(mark.entries);
+                *offset = mark.offset;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Copie un fichier dans la sauvegarde.
+     *
+     * @param name - Chemin relatif dans la sauvegarde (séparateur '/')
+     * @param reader - Contenu à copier
+     * @returns io::Result<(u64, String)> - Taille copiée et empreinte SHA-256 (hexadécimal)
+     */
+    pub fn add(&mut self, name: &str, reader: &mut dyn Read) -> io::Result<(u64, String)> {
+        match self {
+            BackupWriter::Directory { root, written } => {
+                let path = name.split('/').fold(root.clone(), |path, part| path.join(part));
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let mut file = File::create(&path)?;
+                written.push(path);
+                let (size, sha, _) = copy(reader, &mut file)?;
+                file.sync_all()?;
+                Ok((size, sha))
+            }
+            BackupWriter::Zip { file, entries, offset, dos_time } => {
+                let start = *offset;
+                // En-tête local : tailles dans le champ ZIP64, complétées après la copie
+                let mut header = Vec::with_capacity(50 + name.len());
+                header.extend(0x04034B50u32.to_le_bytes());
+                header.extend(ZIP64_VERSION.to_le_bytes());
+                header.extend(UTF8_FLAG.to_le_bytes());
+                header.extend(0u16.to_le_bytes());
+                header.extend(dos_time.0.to_le_bytes());
+                header.extend(dos_time.1.to_le_bytes());
+                header.extend(0u32.to_le_bytes());
+                header.extend(u32::MAX.to_le_bytes());
+                header.extend(u32::MAX.to_le_bytes());
+                header.extend((name.len() as u16).to_le_bytes());
+                header.extend(20u16.to_le_bytes());
+                header.extend(name.as_bytes());
+                header.extend(0x0001u16.to_le_bytes());
+                header.extend(16u16.to_le_bytes());
+                header.extend([0u8; 16]);
+                file.write_all(&header)?;
+
+                let (size, sha, crc) = copy(reader, file)?;
+                file.flush()?;
+                let inner = file.get_mut();
+                inner.seek(SeekFrom::Start(start + 14))?;
+                inner.write_all(&crc.to_le_bytes())?;
+                inner.seek(SeekFrom::Start(start + 30 + name.len() as u64 + 4))?;
+                inner.write_all(&size.to_le_bytes())?;
+                inner.write_all(&size.to_le_bytes())?;
+                inner.seek(SeekFrom::End(0))?;
+
+                *offset = start + header.len() as u64 + size;
+                entries.push(ZipEntry { name: name.to_string(), offset: start, size, crc });
+                Ok((size, sha))
+            }
+        }
+    }
+
+    /**
+     * Termine la sauvegarde (répertoire central pour une archive ZIP).
+     *
+     * @returns io::Result<()> - Erreur d'écriture
+     *
+     * Effets de bord :
+     * - Force l'écriture sur disque de l'archive
+     */
+    pub fn finish(self) -> io::Result<()> {
+        let BackupWriter::Zip { mut file, entries, offset, dos_time } = self else {
+            return Ok(());
+        };
+        let mut directory = Vec::new();
+        for entry in &entries {
+            // Champ ZIP64 : uniquement les valeurs qui dépassent 32 bits, dans l'ordre
+            let mut extra = Vec::new();
+            if entry.size >= u32::MAX as u64 {
+                extra.extend(entry.size.to_le_bytes());
+                extra.extend(entry.size.to_le_bytes());
+            }
+            if entry.offset >= u32::MAX as u64 {
+                extra.extend(entry.offset.to_le_bytes());
+            }
+            let size = entry.size.min(u32::MAX as u64) as u32;
+            directory.extend(0x02014B50u32.to_le_bytes());
+            directory.extend(ZIP64_VERSION.to_le_bytes());
+            directory.extend(ZIP64_VERSION.to_le_bytes());
+            directory.extend(UTF8_FLAG.to_le_bytes());
+            directory.extend(0u16.to_le_bytes());
+            directory.extend(dos_time.0.to_le_bytes());
+            directory.extend(dos_time.1.to_le_bytes());
+            directory.extend(entry.crc.to_le_bytes());
+            directory.extend(size.to_le_bytes());
+            directory.extend(size.to_le_bytes());
+            directory.extend((entry.name.len() as u16).to_le_bytes());
+            directory.extend((if extra.is_empty() { 0 } else { extra.len() as u16 + 4 }).to_le_bytes());
+            directory.extend([0u8; 10]);
+            directory.extend((entry.offset.min(u32::MAX as u64) as u32).to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+            if !extra.is_empty() {
+                directory.extend(0x0001u16.to_le_bytes());
+                directory.extend((extra.len() as u16).to_le_bytes());
+                directory.extend(extra);
+            }
+        }
+        file.write_all(&directory)?;
+
+        let (count, cd_size, cd_offset) = (entries.len() as u64, directory.len() as u64, offset);
+        if count >= 0xFFFF || cd_size >= u32::MAX as u64 || cd_offset >= u32::MAX as u64 {
+            let eocd64 = cd_offset + cd_size;
+            file.write_all(&0x06064B50u32.to_le_bytes())?;
+            file.write_all(&44u64.to_le_bytes())?;
+            file.write_all(&ZIP64_VERSION.to_le_bytes())?;
+            file.write_all(&ZIP64_VERSION.to_le_bytes())?;
+            file.write_all(&[0u8; 8])?;
+            file.write_all(&count.to_le_bytes())?;
+            file.write_all(&count.to_le_bytes())?;
+            file.write_all(&cd_size.to_le_bytes())?;
+            file.write_all(&cd_offset.to_le_bytes())?;
+            file.write_all(&0x07064B50u32.to_le_bytes())?;
+            file.write_all(&0u32.to_le_bytes())?;
+            file.write_all(&eocd64.to_le_bytes())?;
+            file.write_all(&1u32.to_le_bytes())?;
+        }
+        file.write_all(&0x06054B50u32.to_le_bytes())?;
+        file.write_all(&[0u8; 4])?;
+        file.write_all(&(count.min(0xFFFF) as u16).to_le_bytes())?;
+        file.write_all(&(count.min(0xFFFF) as u16).to_le_bytes())?;
+        file.write_all(&(cd_size.min(u32::MAX as u64) as u32).to_le_bytes())?;
+        file.write_all(&(cd_offset.min(u32::MAX as u64) as u32).to_le_bytes())?;
+        file.write_all(&0u16.to_le_bytes())?;
+        file.flush()?;
+        file.get_ref().sync_all()
+    }
+}
+
+/// Copie un flux en calculant son empreinte SHA-256 et son CRC-32
+fn copy(reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<(u64, String, u32)> {
+    let mut buffer = vec![0u8; COPY_BUFFER];
+    let (mut sha, mut crc, mut size) = (Sha256::new(), crc32fast::Hasher::new(), 0u64);
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        sha.update(&buffer[..read]);
+        crc.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+    Ok((size, hex::encode(sha.finalize()), crc.finalize()))
+}
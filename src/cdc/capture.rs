@@ -0,0 +1,302 @@
+/**
+ * Capture des changements d'une table par comparaison d'empreintes.
+ *
+ * Le point de reprise (CdcCheckpoint) conserve, pour chaque enregistrement
+ * actif, une empreinte de 64 bits de son contenu (rubriques et mémos). À
+ * chaque exécution, la table est parcourue enregistrement par enregistrement :
+ * - ID absent du point de reprise : insert (avec l'enregistrement)
+ * - Empreinte différente : update (avec le nouvel enregistrement)
+ * - ID du point de reprise qui n'existe plus ou est supprimé : delete
+ *
+ * L'empreinte est un FNV-1a calculé sur les rubriques triées par nom : elle
+ * est stable entre deux exécutions et deux versions de l'application.
+ *
+ * Les IDs HFSQL sont des positions dans le .fic : un emplacement libéré puis
+ * réutilisé par une nouvelle fiche apparaît comme un update.
+ */
+
+use crate::storage::{FieldValue, Record, StorageEngine};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version du format du fichier de point de reprise
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Point de reprise : empreinte de chaque enregistrement lors de la dernière exécution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdcCheckpoint {
+    /// Version du format
+    pub version: u32,
+    /// Table suivie
+    pub table: String,
+    /// Numéro de l'exécution qui a produit ce point de reprise
+    pub run: u64,
+    /// Date de l'exécution (secondes depuis l'époque Unix)
+    pub timestamp: u64,
+    /// Empreinte de chaque enregistrement actif, par ID
+    pub fingerprints: BTreeMap<u32, u64>,
+}
+
+/// Nature d'un changement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CdcOperation {
+    /// Nouvel enregistrement
+    Insert,
+    /// Enregistrement modifié
+    Update,
+    /// Enregistrement supprimé
+    Delete,
+}
+
+/// Changement émis (une ligne NDJSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdcEvent {
+    /// Nature du changement
+    pub op: CdcOperation,
+    /// Table concernée
+    pub table: String,
+    /// ID de l'enregistrement
+    pub id: u32,
+    /// Numéro de l'exécution
+    pub run: u64,
+    /// Date de l'exécution (secondes depuis l'époque Unix)
+    pub timestamp: u64,
+    /// Nouvel état de l'enregistrement (absent pour un delete)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<Record>,
+}
+
+/// Bilan d'une exécution
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CdcSummary {
+    /// Numéro de l'exécution
+    pub run: u64,
+    /// Enregistrements actifs parcourus
+    pub records: u32,
+    /// Enregistrements insérés
+    pub inserted: u32,
+    /// Enregistrements modifiés
+    pub updated: u32,
+    /// Enregistrements supprimés
+    pub deleted: u32,
+    /// Enregistrements inchangés
+    pub unchanged: u32,
+}
+
+impl CdcCheckpoint {
+    /**
+     * Lit un point de reprise.
+     *
+     * @param path - Chemin du fichier JSON
+     * @returns Result<Option<CdcCheckpoint>> - None si le fichier n'existe pas (première exécution)
+     */
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Impossible de lire le point de reprise: {:?}", path))?;
+        let checkpoint: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Point de reprise invalide: {:?}", path))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            anyhow::bail!("Version de point de reprise non supportée: {} (attendue: {})", checkpoint.version, CHECKPOINT_VERSION);
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /**
+     * Enregistre le point de reprise.
+     *
+     * Le fichier est écrit à côté puis renommé : une interruption laisse
+     * l'ancien point de reprise intact.
+     *
+     * @param path - Chemin du fichier JSON
+     * @returns Result<()> - Erreur si l'écriture échoue
+     *
+     * Effets de bord :
+     * - Écrit `{path}.tmp` puis le renomme en `path`
+     */
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)
+            .context("Impossible de sérialiser le point de reprise")?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, json)
+            .with_context(|| format!("Impossible d'écrire le point de reprise: {:?}", tmp))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Impossible de remplacer le point de reprise: {:?}", path))?;
+        Ok(())
+    }
+}
+
+/**
+ * Calcule l'empreinte du contenu d'un enregistrement.
+ *
+ * @param record - Enregistrement décodé
+ * @returns u64 - Empreinte FNV-1a des rubriques et mémos, triés par nom
+ */
+pub fn fingerprint(record: &Record) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    // Les mémos sont hachés à part, après les rubriques et sur leur seul
+    // contenu : empreintes identiques à celles des points de reprise existants
+    let null = FieldValue::null();
+    let mut fields: Vec<_> = record.fields.iter()
+        .map(|(name, value)| (name, if value.is_memo() { &null } else { value }))
+        .collect();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in fields {
+        feed(name.as_bytes());
+        feed(&[0]);
+        feed(serde_json::to_string(value).unwrap_or_default().as_bytes());
+        feed(&[0]);
+    }
+    let mut memos: Vec<_> = record.fields.iter().filter(|(_, value)| value.is_memo()).collect();
+    memos.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in memos {
+        feed(&[1]);
+        feed(name.as_bytes());
+        feed(&[0]);
+        feed(value.to_string().as_bytes());
+        feed(&[0]);
+    }
+    hash
+}
+
+/**
+ * Compare une table avec le point de reprise précédent et émet les changements.
+ *
+ * Les inserts et updates sont émis dans l'ordre des IDs pendant le parcours,
+ * puis les deletes. Le nouveau point de reprise n'est pas enregistré : à
+ * l'appelant de le faire une fois les événements livrés.
+ *
+ * @param engine - Moteur de stockage (tables déjà scannées)
+ * @param table - Nom de la table
+ * @param previous - Point de reprise de l'exécution précédente (None = tout est inséré)
+ * @param emit - Appelé pour chaque changement ; une erreur arrête la capture
+ * @returns Result<(CdcCheckpoint, CdcSummary)> - Nouveau point de reprise et bilan
+ *
+ * Effets de bord :
+ * - Lit tous les enregistrements de la table
+ */
+pub fn capture(
+    engine: &StorageEngine,
+    table: &str,
+    previous: Option<&CdcCheckpoint>,
+    mut emit: impl FnMut(&CdcEvent) -> Result<()>,
+) -> Result<(CdcCheckpoint, CdcSummary)> {
+    if let Some(previous) = previous {
+        if !previous.table.eq_ignore_ascii_case(table) {
+            anyhow::bail!("Le point de reprise concerne la table '{}', pas '{}'", previous.table, table);
+        }
+    }
+    let empty = BTreeMap::new();
+    let known = previous.map(|p| &p.fingerprints).unwrap_or(&empty);
+    let run = previous.map(|p| p.run + 1).unwrap_or(1);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut summary = CdcSummary { run, ..Default::default() };
+    let mut fingerprints = BTreeMap::new();
+    let event = |op, id, record| CdcEvent {
+        op,
+        table: table.to_string(),
+        id,
+        run,
+        timestamp,
+        record,
+    };
+
+    summary.records = engine.for_each_record(table, |record| {
+        let hash = fingerprint(&record);
+        let id = record.id;
+        fingerprints.insert(id, hash);
+        match known.get(&id) {
+            None => {
+                summary.inserted += 1;
+                emit(&event(CdcOperation::Insert, id, Some(record)))
+            }
+            Some(previous_hash) if *previous_hash != hash => {
+                summary.updated += 1;
+                emit(&event(CdcOperation::Update, id, Some(record)))
+            }
+            Some(_) => {
+                summary.unchanged += 1;
+                Ok(())
+            }
+        }
+    })?;
+
+    for id in known.keys().filter(|id| !fingerprints.contains_key(id)) {
+        summary.deleted += 1;
+        emit(&event(CdcOperation::Delete, *id, None))?;
+    }
+
+    let checkpoint = CdcCheckpoint {
+        version: CHECKPOINT_VERSION,
+        table: table.to_string(),
+        run,
+        timestamp,
+        fingerprints,
+    };
+    Ok((checkpoint, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use tempfile::TempDir;
+
+    fn run(engine: &StorageEngine, previous: Option<&CdcCheckpoint>) -> Result<(CdcCheckpoint, Vec<(CdcOperation, u32)>)> {
+        let mut events = Vec::new();
+        let (checkpoint, _) = capture(engine, "CLIENT", previous, |event| {
+            events.push((event.op, event.id));
+            Ok(())
+        })?;
+        Ok((checkpoint, events))
+    }
+
+    #[test]
+    fn test_capture_between_runs() -> Result<()> {
+        let dir = TempDir::new()?;
+        let fic_path = dir.path().join("CLIENT.FIC");
+        write_fic(&fic_path, 8, &[(false, 1), (false, 2), (false, 3)])?;
+        let engine = StorageEngine::new_with_parallel(dir.path(), true, false)?;
+        engine.scan_tables()?;
+
+        let (first, events) = run(&engine, None)?;
+        assert_eq!(events, vec![(CdcOperation::Insert, 0), (CdcOperation::Insert, 1), (CdcOperation::Insert, 2)]);
+
+        let (same, events) = run(&engine, Some(&first))?;
+        assert!(events.is_empty());
+        assert_eq!(same.run, 2);
+
+        // Enregistrement 1 modifié, 2 supprimé, 3 ajouté
+        write_fic(&fic_path, 8, &[(false, 1), (false, 9), (true, 3), (false, 4)])?;
+        let (_, events) = run(&engine, Some(&same))?;
+        assert_eq!(events, vec![(CdcOperation::Update, 1), (CdcOperation::Insert, 3), (CdcOperation::Delete, 2)]);
+
+        let path = dir.path().join("client.cdc.json");
+        same.save(&path)?;
+        let loaded = CdcCheckpoint::load(&path)?.expect("point de reprise");
+        assert_eq!(loaded.fingerprints, same.fingerprints);
+        assert!(capture(&engine, "COMMANDE", Some(&loaded), |_| Ok(())).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+/**
+ * Module de capture des changements (CDC) entre deux exécutions.
+ *
+ * Les traitements d'intégration ont besoin de savoir ce qui a changé dans
+ * une table HFSQL depuis leur dernier passage. Ce module compare l'état
+ * courant d'une table avec un point de reprise (empreinte de chaque
+ * enregistrement, par ID) et émet les enregistrements insérés, modifiés et
+ * supprimés sous forme de NDJSON (un événement JSON par ligne).
+ *
+ * - capture.rs : Empreintes, point de reprise et comparaison
+ * - sink.rs : Destinations des événements (fichier, stdout, webhook local)
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs pour parcourir les enregistrements
+ * - Utilisé par la commande `fic cdc` (src/cli/commands.rs)
+ */
+
+pub mod capture;
+pub mod sink;
+
+pub use capture::{capture, CdcCheckpoint, CdcEvent, CdcOperation, CdcSummary};
+pub use sink::{CdcSink, CdcWriter};
@@ -0,0 +1,155 @@
+/**
+ * Destinations des événements CDC (NDJSON).
+ *
+ * - Stdout : une ligne JSON par événement sur la sortie standard
+ * - File : lignes ajoutées à la fin d'un fichier (créé si absent)
+ * - Webhook : lignes envoyées par lots en POST (Content-Type
+ *   application/x-ndjson) à une URL locale
+ *
+ * Le webhook est limité aux adresses locales (localhost, 127.0.0.1, ::1) :
+ * il est destiné à un agent d'intégration sur le même poste, pas à exposer
+ * les données HFSQL sur le réseau.
+ */
+
+use crate::cdc::CdcEvent;
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Nombre d'événements envoyés par requête au webhook
+pub const WEBHOOK_BATCH_SIZE: usize = 500;
+
+/// Destination des événements
+#[derive(Debug, Clone)]
+pub enum CdcSink {
+    /// Sortie standard
+    Stdout,
+    /// Fichier NDJSON (ajout en fin de fichier)
+    File(PathBuf),
+    /// URL d'un webhook local
+    Webhook(String),
+}
+
+/// Écriture des événements vers une destination
+pub struct CdcWriter {
+    output: Output,
+    written: u64,
+}
+
+enum Output {
+    Stream(Box<dyn Write>),
+    Webhook {
+        url: String,
+        client: reqwest::blocking::Client,
+        batch: Vec<u8>,
+        pending: usize,
+    },
+}
+
+impl CdcSink {
+    /**
+     * Ouvre la destination.
+     *
+     * @returns Result<CdcWriter> - Écrivain prêt à recevoir les événements
+     *
+     * Effets de bord :
+     * - Crée le fichier de sortie s'il n'existe pas
+     */
+    pub fn open(&self) -> Result<CdcWriter> {
+        let output = match self {
+            CdcSink::Stdout => Output::Stream(Box::new(BufWriter::new(std::io::stdout()))),
+            CdcSink::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Impossible d'ouvrir le fichier de sortie: {:?}", path))?;
+                Output::Stream(Box::new(BufWriter::new(file)))
+            }
+            CdcSink::Webhook(url) => {
+                let parsed = reqwest::Url::parse(url)
+                    .with_context(|| format!("URL de webhook invalide: {}", url))?;
+                let local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+                if !matches!(parsed.scheme(), "http" | "https") || !local {
+                    anyhow::bail!("Le webhook doit être une URL http(s) locale (localhost, 127.0.0.1, [::1]): {}", url);
+                }
+                Output::Webhook {
+                    url: url.clone(),
+                    client: reqwest::blocking::Client::builder()
+                        .timeout(Duration::from_secs(30))
+                        .build()
+                        .context("Impossible de créer le client HTTP")?,
+                    batch: Vec::new(),
+                    pending: 0,
+                }
+            }
+        };
+        Ok(CdcWriter { output, written: 0 })
+    }
+}
+
+impl CdcWriter {
+    /**
+     * Écrit un événement (une ligne JSON).
+     *
+     * @param event - Changement à livrer
+     * @returns Result<()> - Erreur d'écriture ou d'envoi au webhook
+     *
+     * Effets de bord :
+     * - Envoie un lot au webhook tous les WEBHOOK_BATCH_SIZE événements
+     */
+    pub fn write(&mut self, event: &CdcEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(event).context("Impossible de sérialiser l'événement")?;
+        line.push(b'\n');
+        self.written += 1;
+        match &mut self.output {
+            Output::Stream(out) => out.write_all(&line).context("Impossible d'écrire l'événement"),
+            Output::Webhook { batch, pending, .. } => {
+                batch.extend_from_slice(&line);
+                *pending += 1;
+                if *pending >= WEBHOOK_BATCH_SIZE {
+                    self.flush()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /**
+     * Livre les événements en attente.
+     *
+     * @returns Result<()> - Erreur si le fichier ou le webhook refuse les données
+     */
+    pub fn flush(&mut self) -> Result<()> {
+        match &mut self.output {
+            Output::Stream(out) => out.flush().context("Impossible d'écrire les événements"),
+            Output::Webhook { url, client, batch, pending } => {
+                if *pending == 0 {
+                    return Ok(());
+                }
+                let response = client.post(url.as_str())
+                    .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+                    .body(std::mem::take(batch))
+                    .send()
+                    .with_context(|| format!("Envoi au webhook {} impossible", url))?;
+                if !response.status().is_success() {
+                    anyhow::bail!("Le webhook {} a répondu {}", url, response.status());
+                }
+                *pending = 0;
+                Ok(())
+            }
+        }
+    }
+
+    /**
+     * Livre les derniers événements et retourne le nombre d'événements écrits.
+     *
+     * @returns Result<u64> - Nombre total d'événements livrés
+     */
+    pub fn finish(mut self) -> Result<u64> {
+        self.flush()?;
+        Ok(self.written)
+    }
+}
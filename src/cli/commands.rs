@@ -7,6 +7,14 @@
  * - scan_tables : Détecte et liste les tables HFSQL dans un dossier
  * - export_table : Exporte les données d'une table vers JSON ou CSV
  * - debug_file : Affiche des informations de debug sur un fichier
+ * - reindex_table : Reconstruit ou vérifie les index .ndx d'une table
+ * - show_history : Affiche l'historique des modifications (journaux .jnl)
+ * - capture_changes : Émet les changements d'une table depuis la dernière exécution (CDC)
+ * - diff_copies : Compare deux copies d'une base et produit un rapport JSON ou HTML
+ * - show_stats : Profile les rubriques d'une table (nulls, distincts, min/max, formats)
+ * - detect_encoding : Affiche les encodages candidats des textes d'une table
+ * - backup_database : Sauvegarde les tables d'un dossier avec un manifeste
+ * - verify_backup : Vérifie une sauvegarde par rapport à son manifeste
  * 
  * Liens avec d'autres modules :
  * - Utilise src/storage/engine.rs pour accéder aux données
@@ -15,7 +23,8 @@
 
 use anyhow::Result;
 use crate::storage::StorageEngine;
-use crate::storage::engine::QueryFilters;
+use crate::storage::engine::{FieldValue, HistoryFilters, SortKey};
+use serde::ser::{SerializeSeq, Serializer};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
@@ -25,8 +34,10 @@ use tracing::info;
  * Scanne un dossier et affiche les tables HFSQL trouvées.
  * 
  * Détecte tous les fichiers .fic dans le dossier spécifié, les associe
- * avec leurs fichiers .mmo et .ndx correspondants, puis affiche les
- * informations sur chaque table (nom, longueur d'enregistrement, nombre de champs).
+ * avec leurs fichiers compagnons (.mmo, .ndx, .ftx, .jnl, segments), puis
+ * affiche les informations sur chaque table (nom, longueur d'enregistrement,
+ * nombre de champs, fichiers trouvés et manquants). Les tables déclarées dans
+ * un fichier .rep sans fichier .fic correspondant sont signalées.
  * 
  * @param path - Chemin du dossier à scanner
  * @returns Result<()> - Succès si le scan s'est bien déroulé, erreur sinon
@@ -40,18 +51,52 @@ pub async fn scan_tables(path: PathBuf) -> Result<()> {
     let tables = engine.scan_tables()?;
 
     println!("Tables trouvées: {}", tables.len());
-    for table in &tables {
-        println!("  - {}", table);
-        
-        if let Ok(schema) = engine.get_schema(table) {
-            println!("    Record length: {} bytes", schema.record_length);
-            println!("    Fields: {}", schema.field_count);
+    for database in engine.list_databases() {
+        if !database.name.is_empty() {
+            println!("Base {} ({:?})", database.name, database.path);
+        }
+        for table in &database.tables {
+            print_table(&engine, table);
+        }
+        for missing in &database.missing_tables {
+            println!(
+                "  ! {} introuvable: {} (déclarée dans {:?}, cherchée dans {:?})",
+                missing.name, missing.declared_path, missing.repository, missing.resolved_path
+            );
         }
     }
 
     Ok(())
 }
 
+/**
+ * Affiche le schéma et les fichiers d'une table détectée par scan_tables.
+ *
+ * @param engine - Moteur de stockage ayant scanné le dossier
+ * @param table - Nom qualifié de la table
+ *
+ * Effets de bord :
+ * - Lit le header du fichier .fic
+ * - Affiche des informations sur stdout
+ */
+fn print_table(engine: &StorageEngine, table: &str) {
+    println!("  - {}", table);
+    
+    if let Ok(schema) = engine.get_schema(table) {
+        println!("    Record length: {} bytes", schema.record_length);
+        println!("    Fields: {}", schema.field_count);
+    }
+
+    if let Ok(files) = engine.table_files(table) {
+        for path in files.found_paths() {
+            println!("    Fichier: {}", path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+        }
+        if !files.missing.is_empty() {
+            println!("    Manquants: {}", files.missing.join(", "));
+        }
+    }
+}
+
 /**
  * Exporte une table vers un fichier JSON ou CSV.
  * 
@@ -63,10 +108,12 @@ pub async fn scan_tables(path: PathBuf) -> Result<()> {
  * @param table - Nom de la table à exporter
  * @param format - Format d'export ("json" ou "csv")
  * @param output - Chemin du fichier de sortie (None = stdout)
+ * @param sort - Clés de tri séparées par des virgules (None = ordre physique)
  * @returns Result<()> - Succès si l'export s'est bien déroulé, erreur sinon
  * 
  * Effets de bord :
  * - Lit les données depuis les fichiers .fic/.mmo
+ * - Peut écrire des fichiers temporaires (tri externe des grandes tables)
  * - Écrit les données dans un fichier ou sur stdout
  */
 pub async fn export_table(
@@ -74,57 +121,411 @@ pub async fn export_table(
     table: String,
     format: String,
     output: Option<PathBuf>,
+    sort: Option<String>,
 ) -> Result<()> {
     info!("Export de la table '{}' au format {}", table, format);
 
-    let filters = QueryFilters {
-        limit: None,
-        offset: None,
-        field_filters: std::collections::HashMap::new(),
+    let order_by = sort.as_deref().map(SortKey::parse_list).transpose()?.unwrap_or_default();
+    // Export complet, enregistrement par enregistrement : la table n'est pas chargée en mémoire
+    let open_output = || -> Result<Box<dyn Write>> {
+        Ok(match &output {
+            Some(output_path) => Box::new(std::io::BufWriter::new(File::create(output_path)?)),
+            None => Box::new(std::io::stdout().lock()),
+        })
     };
 
-    let result = engine.select(&table, filters)?;
-
     match format.as_str() {
         "json" => {
-            let json = serde_json::to_string_pretty(&result.records)?;
-            if let Some(output_path) = output {
-                std::fs::write(&output_path, json)?;
-                println!("Exporté vers: {:?}", output_path);
-            } else {
-                println!("{}", json);
+            let mut serializer = serde_json::Serializer::pretty(open_output()?);
+            let mut seq = serializer.serialize_seq(None)?;
+            engine.for_each_sorted(&table, &order_by, |record| Ok(seq.serialize_element(&record)?))?;
+            seq.end()?;
+            let mut writer = serializer.into_inner();
+            if output.is_none() {
+                writeln!(writer)?;
             }
+            writer.flush()?;
         }
         "csv" => {
-            let writer: Box<dyn Write> = if let Some(output_path) = &output {
-                Box::new(File::create(output_path)?)
-            } else {
-                Box::new(std::io::stdout())
-            };
+            let mut wtr = csv::Writer::from_writer(open_output()?);
 
-            let mut wtr = csv::Writer::from_writer(writer);
-            
-            // En-têtes (simplifié)
-            wtr.write_record(&["id"])?;
-            
-            for record in &result.records {
-                wtr.write_record(&[record.id.to_string()])?;
-            }
+            // En-têtes dans l'ordre des rubriques (ordre du schéma)
+            let headers: Vec<String> = std::iter::once("id".to_string())
+                .chain(engine.get_schema(&table)?.fields.into_iter().map(|f| f.name).filter(|name| name != "id"))
+                .collect();
+            wtr.write_record(&headers)?;
+
+            engine.for_each_sorted(&table, &order_by, |record| {
+                wtr.write_record(headers.iter().map(|name| match record.fields.get(name) {
+                    None | Some(FieldValue::Null { .. }) => String::new(),
+                    Some(value) => value.to_string(),
+                }))?;
+                Ok(())
+            })?;
             
             wtr.flush()?;
-            
-            if let Some(output_path) = output {
-                println!("Exporté vers: {:?}", output_path);
-            }
         }
         _ => {
             anyhow::bail!("Format non supporté: {}", format);
         }
     }
 
+    if let Some(output_path) = output {
+        println!("Exporté vers: {:?}", output_path);
+    }
+
+    Ok(())
+}
+
+/**
+ * Reconstruit ou vérifie les index .ndx d'une table.
+ *
+ * Affiche pour chaque index le nombre d'entrées et les différences entre
+ * l'index existant et celui recalculé depuis le fichier .fic.
+ *
+ * @param engine - Moteur de stockage (en écriture sauf pour la vérification)
+ * @param table - Nom de la table
+ * @param verify - Compare sans réécrire les index
+ * @returns Result<()> - Succès, ou erreur si la vérification trouve une incohérence
+ *
+ * Effets de bord :
+ * - Remplace les fichiers .ndx de la table (sauf en vérification)
+ * - Affiche le rapport sur stdout
+ */
+pub async fn reindex_table(engine: StorageEngine, table: String, verify: bool) -> Result<()> {
+    const MAX_DIFF_LINES: usize = 20;
+
+    let reports = engine.reindex(&table, verify)?;
+    if reports.is_empty() {
+        println!("Aucun index à reconstruire pour la table {}", table);
+        return Ok(());
+    }
+
+    for report in &reports {
+        println!("Index {} ({} entrées calculées)", report.index, report.entries);
+        match (&report.existing_entries, &report.existing_error) {
+            (_, Some(error)) => println!("  Index existant illisible: {}", error),
+            (Some(count), None) => println!("  Index existant: {} entrées", count),
+            (None, None) => {}
+        }
+        if let Some(first) = report.skipped_records.first() {
+            println!("  {} enregistrement(s) illisible(s) ignoré(s) (premier: {})", report.skipped_records.len(), first);
+        }
+        for entry in report.missing.iter().take(MAX_DIFF_LINES) {
+            println!("  - manquante: clé {} -> enregistrement {}", entry.key, entry.record_id);
+        }
+        for entry in report.unexpected.iter().take(MAX_DIFF_LINES) {
+            println!("  + en trop:   clé {} -> enregistrement {}", entry.key, entry.record_id);
+        }
+        let hidden = report.missing.len().saturating_sub(MAX_DIFF_LINES)
+            + report.unexpected.len().saturating_sub(MAX_DIFF_LINES);
+        if hidden > 0 {
+            println!("  ... {} différence(s) supplémentaire(s)", hidden);
+        }
+        if report.is_consistent() {
+            println!("  Index cohérent avec les données");
+        }
+        if report.written {
+            println!("  Index réécrit: {:?}", report.path);
+        }
+    }
+
+    let inconsistent = reports.iter().filter(|r| !r.is_consistent()).count();
+    if verify && inconsistent > 0 {
+        anyhow::bail!("{} index incohérent(s) pour la table {}", inconsistent, table);
+    }
+
+    Ok(())
+}
+
+/**
+ * Construit les filtres d'historique à partir des arguments de la ligne de commande.
+ *
+ * @param record - ID de l'enregistrement à suivre
+ * @param since - Date de début incluse (AAAA-MM-JJ[ HH:MM[:SS]])
+ * @param until - Date de fin exclue (AAAA-MM-JJ[ HH:MM[:SS]])
+ * @param operation - Type d'opération (insert, update, delete)
+ * @param user - Utilisateur ayant effectué les modifications
+ * @param limit - Nombre maximum d'entrées
+ * @returns Result<HistoryFilters> - Filtres, ou erreur si une date ou une opération est invalide
+ */
+pub fn history_filters(
+    record: Option<u32>,
+    since: Option<String>,
+    until: Option<String>,
+    operation: Option<String>,
+    user: Option<String>,
+    limit: Option<u32>,
+) -> Result<HistoryFilters> {
+    let operation = match operation {
+        Some(name) => Some(crate::core::JournalOperation::parse(&name)
+            .ok_or_else(|| anyhow::anyhow!("Opération inconnue: {}. Options: insert, update, delete", name))?),
+        None => None,
+    };
+    Ok(HistoryFilters {
+        record_id: record,
+        since: since.as_deref().map(crate::core::jnl::parse_timestamp).transpose()?,
+        until: until.as_deref().map(crate::core::jnl::parse_timestamp).transpose()?,
+        operation,
+        user,
+        limit,
+    })
+}
+
+/**
+ * Affiche l'historique des modifications d'une table.
+ *
+ * Lit les journaux .jnl de la table et affiche les opérations retenues par
+ * les filtres, de la plus récente à la plus ancienne. Pour une modification,
+ * les rubriques changées sont affichées avec leur ancienne et nouvelle valeur.
+ *
+ * @param engine - Moteur de stockage contenant les données
+ * @param table - Nom de la table
+ * @param filters - Filtres (enregistrement, période, opération, utilisateur)
+ * @param format - Format d'affichage ("table" ou "json")
+ * @returns Result<()> - Succès, ou erreur si la table n'a pas de journal
+ *
+ * Effets de bord :
+ * - Lit les fichiers .jnl de la table
+ * - Affiche l'historique sur stdout
+ */
+pub async fn show_history(engine: StorageEngine, table: String, filters: HistoryFilters, format: String) -> Result<()> {
+    let history = engine.history(&table, &filters)?;
+
+    match format.as_str() {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&history)?);
+        }
+        "table" => {
+            println!("Modifications trouvées: {}", history.len());
+            for entry in &history {
+                println!(
+                    "{} {:?} enregistrement {} par {}@{} ({}#{})",
+                    entry.date, entry.operation, entry.record_id, entry.user, entry.station, entry.journal, entry.sequence
+                );
+                for field in &entry.changed_fields {
+                    let value = |record: &Option<crate::storage::Record>| record.as_ref()
+                        .and_then(|r| r.fields.get(field))
+                        .map(display_value)
+                        .unwrap_or_default();
+                    println!("    {}: {} -> {}", field, value(&entry.before), value(&entry.after));
+                }
+            }
+        }
+        _ => {
+            anyhow::bail!("Format non supporté: {}. Options: table, json", format);
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Émet les changements d'une table depuis la dernière exécution (CDC).
+ *
+ * Compare la table au point de reprise, écrit un événement NDJSON par
+ * enregistrement inséré, modifié ou supprimé, puis enregistre le nouveau
+ * point de reprise. Si la livraison échoue, le point de reprise n'est pas
+ * mis à jour : la prochaine exécution émettra à nouveau les mêmes changements.
+ *
+ * @param engine - Moteur de stockage contenant les données
+ * @param table - Nom de la table
+ * @param state - Fichier du point de reprise
+ * @param sink - Destination des événements (stdout, fichier, webhook)
+ * @returns Result<()> - Erreur de lecture, de livraison ou d'écriture du point de reprise
+ *
+ * Effets de bord :
+ * - Lit tous les enregistrements de la table
+ * - Écrit les événements vers la destination et le bilan sur stderr
+ * - Remplace le fichier du point de reprise
+ */
+pub async fn capture_changes(engine: StorageEngine, table: String, state: PathBuf, sink: crate::cdc::CdcSink) -> Result<()> {
+    // Lecture et envoi au webhook bloquants : hors du runtime async
+    let summary = tokio::task::spawn_blocking(move || -> Result<crate::cdc::CdcSummary> {
+        let previous = crate::cdc::CdcCheckpoint::load(&state)?;
+        let mut writer = sink.open()?;
+        let (checkpoint, summary) = crate::cdc::capture(&engine, &table, previous.as_ref(), |event| writer.write(event))?;
+        writer.finish()?;
+        checkpoint.save(&state)?;
+        Ok(summary)
+    })
+    .await??;
+
+    eprintln!(
+        "Exécution {} : {} enregistrement(s), {} insert, {} update, {} delete, {} inchangé(s)",
+        summary.run, summary.records, summary.inserted, summary.updated, summary.deleted, summary.unchanged
+    );
+    Ok(())
+}
+
+/**
+ * Compare deux copies d'une base et écrit le rapport.
+ *
+ * @param left - Dossier de la copie de référence (avant)
+ * @param right - Dossier de la copie comparée (après)
+ * @param options - Tables, clé d'appariement, nombre d'enregistrements détaillés
+ * @param format - Format du rapport ("json" ou "html")
+ * @param output - Fichier du rapport (None = stdout)
+ * @param parallel - Active le multi-threading
+ * @returns Result<()> - Erreur si une copie est illisible ou le format inconnu
+ *
+ * Effets de bord :
+ * - Lit les tables des deux copies
+ * - Écrit le rapport dans un fichier ou sur stdout, et un résumé sur stderr
+ */
+pub async fn diff_copies(
+    left: PathBuf,
+    right: PathBuf,
+    options: crate::diff::DiffOptions,
+    format: String,
+    output: Option<PathBuf>,
+    parallel: bool,
+) -> Result<()> {
+    if !matches!(format.as_str(), "json" | "html") {
+        anyhow::bail!("Format non supporté: {}. Options: json, html", format);
+    }
+    let left_engine = crate::diff::open_copy(&left, parallel)?;
+    let right_engine = crate::diff::open_copy(&right, parallel)?;
+    let report = crate::diff::diff_engines(&left_engine, &right_engine, &options)?;
+
+    let content = if format == "html" {
+        crate::diff::render_html(&report)
+    } else {
+        serde_json::to_string_pretty(&report)?
+    };
+    match &output {
+        Some(path) => std::fs::write(path, content)?,
+        None => println!("{}", content),
+    }
+
+    for table in report.tables.iter().filter(|t| t.status != crate::diff::TableStatus::Unchanged) {
+        eprintln!(
+            "{} ({:?}) : {} ajouté(s), {} supprimé(s), {} modifié(s)",
+            table.table, table.status, table.added, table.removed, table.changed
+        );
+    }
+    if let Some(path) = output {
+        eprintln!("Rapport écrit dans: {:?}", path);
+    }
+    Ok(())
+}
+
+/**
+ * Affiche le profil des rubriques d'une table.
+ *
+ * @param engine - Moteur de stockage contenant les données
+ * @param table - Nom de la table
+ * @param top - Nombre de valeurs les plus fréquentes par rubrique
+ * @param format - Format d'affichage ("table" ou "json")
+ * @returns Result<()> - Erreur si la table est introuvable ou le format inconnu
+ *
+ * Effets de bord :
+ * - Lit tous les enregistrements de la table
+ * - Affiche le profil sur stdout
+ */
+pub async fn show_stats(engine: StorageEngine, table: String, top: usize, format: String) -> Result<()> {
+    if !matches!(format.as_str(), "table" | "json") {
+        anyhow::bail!("Format non supporté: {}. Options: table, json", format);
+    }
+    let profile = tokio::task::spawn_blocking(move || crate::profile::profile_table(&engine, &table, top, &crate::storage::CancelToken::new())).await??;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&profile)?);
+        return Ok(());
+    }
+
+    println!("Table: {} ({} enregistrement(s), {} ms)", profile.table, profile.records, profile.duration_ms);
+    for field in &profile.fields {
+        println!();
+        println!("{} ({:?})", field.name, field.field_type);
+        println!(
+            "    non nulles: {}, nulles: {}, vides: {}, distinctes: {}{}",
+            field.count, field.nulls, field.empty,
+            if field.distinct_exact { "" } else { "~" }, field.distinct
+        );
+        if let (Some(min), Some(max)) = (&field.min, &field.max) {
+            println!("    min: {}, max: {}", display_value(min), display_value(max));
+        }
+        if !field.lengths.is_empty() {
+            let lengths: Vec<String> = field.lengths.iter()
+                .map(|bucket| format!("{}-{}: {}", bucket.min, bucket.max, bucket.count))
+                .collect();
+            println!("    longueurs: {}", lengths.join(", "));
+        }
+        if !field.patterns.is_empty() {
+            let patterns: Vec<String> = field.patterns.iter()
+                .map(|pattern| format!("{:?}: {}", pattern.pattern, pattern.count))
+                .collect();
+            println!("    formats: {}", patterns.join(", "));
+        }
+        for value in &field.top {
+            println!("    {:>8} × {:?}", value.count, value.value);
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Affiche les encodages candidats des textes d'une table.
+ *
+ * @param engine - Moteur de stockage contenant les données
+ * @param table - Nom de la table
+ * @param sample - Nombre d'enregistrements échantillonnés
+ * @param format - Format d'affichage ("table" ou "json")
+ * @returns Result<()> - Erreur si la table est introuvable ou le format inconnu
+ *
+ * Effets de bord :
+ * - Lit les premiers enregistrements de la table
+ * - Affiche les scores sur stdout
+ */
+pub async fn detect_encoding(engine: StorageEngine, table: String, sample: u32, format: String) -> Result<()> {
+    if !matches!(format.as_str(), "table" | "json") {
+        anyhow::bail!("Format non supporté: {}. Options: table, json", format);
+    }
+    let report = tokio::task::spawn_blocking(move || crate::profile::detect_encoding(&engine, &table, sample, &crate::storage::CancelToken::new())).await??;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Table: {} ({} enregistrement(s), {} valeur(s) non ASCII)", report.table, report.records, report.samples);
+    if report.samples == 0 {
+        println!("Aucune valeur non ASCII : tous les encodages donnent le même texte");
+        return Ok(());
+    }
+    for score in &report.scores {
+        println!("    {:<14} {:>8}  invalides: {}", score.encoding.to_string(), score.score, score.invalid_samples);
+    }
+    for field in &report.fields {
+        println!();
+        let detected = field.detected.map_or_else(|| "-".to_string(), |encoding| encoding.to_string());
+        println!(
+            "{} : détecté {}, configuré {} ({} valeur(s) non ASCII)",
+            field.name, detected, field.configured, field.samples
+        );
+    }
     Ok(())
 }
 
+/**
+ * Formate une valeur de rubrique pour l'affichage en console.
+ *
+ * @param value - Valeur typée
+ * @returns String - Représentation lisible (chaînes entre guillemets)
+ */
+fn display_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::String { value } => format!("{:?}", value),
+        FieldValue::Integer { value } => value.to_string(),
+        FieldValue::Float { value } => value.to_string(),
+        FieldValue::Binary { value } => format!("0x{}", value),
+        FieldValue::MemoText { value, .. } => format!("{:?}", value),
+        FieldValue::MemoBinary { size, mime, .. } => format!("<{}, {} octets>", mime, size),
+        FieldValue::Null { .. } => "NULL".to_string(),
+    }
+}
+
 /**
  * Affiche des informations de debug sur un fichier HFSQL.
  * 
@@ -133,7 +534,8 @@ pub async fn export_table(
  * - "header" : Affiche les informations du header
  * - "hex" : Affiche un dump hexadécimal des 64 premiers bytes
  * - "raw" : Affiche un dump hexadécimal complet du header
- * - "records" : Affiche les premiers enregistrements
+ * - "records" : Affiche les premiers enregistrements (pour un .ndx, les
+ *   premières clés décodées selon les rubriques indexées)
  * 
  * @param file - Chemin du fichier à analyser
  * @param dump - Type de dump à effectuer (header, hex, raw, records)
@@ -194,10 +596,39 @@ pub async fn debug_file(file: PathBuf, dump: String) -> Result<()> {
                 // TODO: Dump du contenu
             }
             "ndx" => {
-                let ndx = crate::core::NdxFile::open(&file)?;
-                println!("Entrées dans l'index: {}", ndx.entries().len());
-                for entry in ndx.entries().iter().take(10) {
-                    println!("  Key: {:?}, Record ID: {}", entry.key, entry.record_id);
+                let mut ndx = crate::core::NdxFile::open(&file)?;
+                match dump.as_str() {
+                    "header" => {
+                        println!("{:#?}", ndx.header());
+                    }
+                    "records" => {
+                        let index = index_definition(&file, &ndx);
+                        println!("Entrées dans l'index: {}", ndx.entry_count());
+                        for entry in ndx.iter()?.take(10) {
+                            let entry = entry?;
+                            match crate::core::key::decode_index_key(&entry.key, &index) {
+                                Ok(segments) => {
+                                    let key = segments.iter()
+                                        .enumerate()
+                                        .map(|(i, segment)| format!(
+                                            "{}={}{}",
+                                            segment.field.clone().unwrap_or_else(|| format!("segment{}", i)),
+                                            segment.value,
+                                            if segment.folded { " (transformée)" } else { "" }
+                                        ))
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
+                                    println!("  Key: {}, Record ID: {}", key, entry.record_id);
+                                }
+                                Err(e) => {
+                                    println!("  Key: {} (non décodable: {}), Record ID: {}", hex::encode(&entry.key), e, entry.record_id);
+                                }
+                            }
+                        }
+                    }
+                    _ => {
+                        anyhow::bail!("Type de dump inconnu: {}. Options: header, records", dump);
+                    }
                 }
             }
             _ => {
@@ -211,3 +642,118 @@ pub async fn debug_file(file: PathBuf, dump: String) -> Result<()> {
     Ok(())
 }
 
+
+/**
+ * Retrouve la définition d'un index à partir de son fichier .ndx.
+ *
+ * Le dossier du fichier est scanné pour retrouver la table propriétaire
+ * (celle dont le .fic porte le nom du fichier d'index) : seul son schéma est
+ * construit, pour associer les segments de la clé aux rubriques. Si la table
+ * n'est pas trouvée, les segments sont décrits sans nom de rubrique.
+ *
+ * @param file - Chemin du fichier .ndx
+ * @param ndx - Fichier .ndx ouvert
+ * @returns IndexDefinition - Définition de l'index
+ *
+ * Effets de bord :
+ * - Liste les fichiers du dossier contenant l'index et lit ceux de la table propriétaire
+ */
+fn index_definition(file: &std::path::Path, ndx: &crate::core::NdxFile) -> crate::core::IndexDefinition {
+    let name = file.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // Table propriétaire : même nom de fichier que l'index ({table}.ndx, {table}.ndx0, ...)
+    let stem = file.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let from_schema = file.parent()
+        .and_then(|dir| StorageEngine::new(dir, true).ok())
+        .and_then(|engine| {
+            engine.scan_tables().ok()?;
+            let table = engine.list_tables().into_iter().find(|table| {
+                engine.table_files(table)
+                    .ok()
+                    .and_then(|files| files.fic_path.file_stem().map(|s| s.to_string_lossy().eq_ignore_ascii_case(&stem)))
+                    .unwrap_or(false)
+            })?;
+            engine.get_schema(&table).ok()?
+                .indexes
+                .into_iter()
+                .find(|index| index.name.eq_ignore_ascii_case(&name))
+        });
+
+    from_schema.unwrap_or_else(|| crate::core::IndexDefinition {
+        name,
+        path: file.to_path_buf(),
+        fields: vec![None; ndx.segments().len()],
+        segments: ndx.segments().to_vec(),
+        encodings: Vec::new(),
+        unique: ndx.header().unique,
+    })
+}
+
+/**
+ * Sauvegarde les tables d'un dossier et affiche le contenu du manifeste.
+ *
+ * @param source - Dossier (ou archive) à sauvegarder
+ * @param dest - Dossier dans lequel la sauvegarde horodatée est créée
+ * @param options - Format, tentatives, multi-threading
+ * @returns Result<()> - Erreur si une table change à chaque tentative ou si l'écriture échoue
+ *
+ * Effets de bord :
+ * - Crée la sauvegarde dans `dest`
+ * - Affiche un résumé sur stdout
+ */
+pub async fn backup_database(source: PathBuf, dest: PathBuf, options: crate::backup::BackupOptions) -> Result<()> {
+    let result = crate::backup::create_backup(&source, &dest, &options)?;
+    let mut total = 0u64;
+    for table in &result.manifest.tables {
+        let size: u64 = table.files.iter().map(|file| file.size).sum();
+        total += size;
+        let retried = if table.attempts > 1 { format!(" ({} copies)", table.attempts) } else { String::new() };
+        println!(
+            "  {} : {} enregistrement(s), {} fichier(s), {} octets{}",
+            table.name, table.record_count, table.files.len(), size, retried
+        );
+    }
+    println!("Sauvegarde créée: {:?} ({} table(s), {} octets)", result.path, result.manifest.tables.len(), total);
+    Ok(())
+}
+
+/**
+ * Vérifie une sauvegarde par rapport à son manifeste.
+ *
+ * @param path - Dossier ou archive de la sauvegarde
+ * @param format - Format d'affichage (table, json)
+ * @returns Result<()> - Erreur (code de sortie non nul) si la sauvegarde n'est pas conforme
+ *
+ * Effets de bord :
+ * - Relit tous les fichiers de la sauvegarde
+ * - Affiche le résultat sur stdout
+ */
+pub async fn verify_backup(path: PathBuf, format: String) -> Result<()> {
+    if !matches!(format.as_str(), "table" | "json") {
+        anyhow::bail!("Format non supporté: {}. Options: table, json", format);
+    }
+    let report = crate::backup::verify_backup(&path)?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "Sauvegarde du {} (moteur {}) : {} table(s), {} fichier(s), {} octets relus",
+            report.created_at, report.engine_version, report.tables, report.files, report.bytes
+        );
+        for problem in &report.problems {
+            println!("  ! {} : {}", problem.path, problem.problem);
+        }
+        if report.is_ok() {
+            println!("Sauvegarde conforme au manifeste");
+        }
+    }
+
+    if !report.is_ok() {
+        anyhow::bail!("{} anomalie(s) dans la sauvegarde {:?}", report.problems.len(), path);
+    }
+    Ok(())
+}
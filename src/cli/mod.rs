@@ -8,6 +8,13 @@
  * - export : Exporte une table vers JSON ou CSV
  * - serve : Démarre le serveur API HTTP
  * - debug : Affiche des informations de debug sur un fichier
+ * - reindex : Reconstruit ou vérifie les index .ndx d'une table
+ * - history : Affiche l'historique des modifications lu dans les journaux .jnl
+ * - cdc : Émet les enregistrements insérés, modifiés et supprimés depuis la dernière exécution
+ * - diff : Compare deux copies d'une base (enregistrements ajoutés, supprimés, modifiés)
+ * - stats : Profile les rubriques d'une table (nulls, distincts, min/max, formats)
+ * - encoding : Détecte l'encodage des textes d'une table (scores des encodages candidats)
+ * - backup : Sauvegarde les tables avec un manifeste ; backup verify la contrôle
  * 
  * Liens avec d'autres modules :
  * - Utilise src/storage/engine.rs pour accéder aux données
@@ -31,7 +38,7 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Chemin du dossier contenant les fichiers .fic/.mmo/.ndx
+    /// Chemin du dossier contenant les fichiers .fic/.mmo/.ndx (ou archive .zip, .tar, .tar.gz)
     #[arg(short, long, global = true)]
     pub data_dir: Option<PathBuf>,
 
@@ -59,6 +66,9 @@ pub enum Commands {
         /// Fichier de sortie (stdout si non spécifié)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Ordre des enregistrements (ex: "nom:nocase,age:desc:nulls_last")
+        #[arg(short, long)]
+        sort: Option<String>,
     },
     /// Démarre le serveur API HTTP
     Serve {
@@ -77,6 +87,129 @@ pub enum Commands {
         #[arg(short = 't', long, default_value = "header")]
         dump: String,
     },
+    /// Reconstruit les index .ndx d'une table à partir du fichier .fic
+    Reindex {
+        /// Nom de la table à réindexer
+        table: String,
+        /// Compare les index existants aux données sans les réécrire
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Affiche l'historique des modifications d'une table (journaux .jnl)
+    History {
+        /// Nom de la table
+        table: String,
+        /// ID de l'enregistrement à suivre
+        #[arg(short, long)]
+        record: Option<u32>,
+        /// Date de début incluse (AAAA-MM-JJ ou AAAA-MM-JJ HH:MM[:SS], UTC)
+        #[arg(long)]
+        since: Option<String>,
+        /// Date de fin exclue (AAAA-MM-JJ ou AAAA-MM-JJ HH:MM[:SS], UTC)
+        #[arg(long)]
+        until: Option<String>,
+        /// Type d'opération (insert, update, delete)
+        #[arg(long)]
+        operation: Option<String>,
+        /// Utilisateur ayant effectué les modifications
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Nombre maximum d'entrées (les plus récentes)
+        #[arg(short, long)]
+        limit: Option<u32>,
+        /// Format d'affichage (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Émet en NDJSON les changements d'une table depuis la dernière exécution (CDC)
+    Cdc {
+        /// Nom de la table
+        table: String,
+        /// Fichier du point de reprise (créé à la première exécution)
+        #[arg(short, long)]
+        state: PathBuf,
+        /// Fichier NDJSON de sortie, complété à chaque exécution (défaut : stdout)
+        #[arg(short, long, conflicts_with = "webhook")]
+        output: Option<PathBuf>,
+        /// URL d'un webhook local recevant les événements en POST
+        #[arg(short, long)]
+        webhook: Option<String>,
+    },
+    /// Compare deux copies d'une base (dossiers de données ou sauvegardes)
+    Diff {
+        /// Dossier ou archive de la copie de référence (avant)
+        left: PathBuf,
+        /// Dossier ou archive de la copie comparée (après)
+        right: PathBuf,
+        /// Table à comparer (répétable ; toutes les tables par défaut)
+        #[arg(short, long)]
+        table: Vec<String>,
+        /// Rubriques d'appariement séparées par des virgules (défaut : clé primaire, sinon ID)
+        #[arg(short, long)]
+        key: Option<String>,
+        /// Format du rapport (json, html)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        /// Fichier du rapport (stdout si non spécifié)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Nombre maximum d'enregistrements détaillés par table
+        #[arg(short, long, default_value_t = crate::diff::DEFAULT_MAX_RECORDS)]
+        limit: usize,
+    },
+    /// Profile les rubriques d'une table (nulls, distincts, min/max, formats)
+    Stats {
+        /// Nom de la table
+        table: String,
+        /// Nombre de valeurs les plus fréquentes par rubrique
+        #[arg(short = 'n', long, default_value_t = crate::profile::DEFAULT_TOP)]
+        top: usize,
+        /// Format d'affichage (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Détecte l'encodage des textes d'une table (CP850, Windows-1252, UTF-8…)
+    Encoding {
+        /// Nom de la table
+        table: String,
+        /// Nombre d'enregistrements échantillonnés
+        #[arg(short = 'n', long, default_value_t = crate::profile::encoding::DEFAULT_SAMPLE)]
+        sample: u32,
+        /// Format d'affichage (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+    /// Sauvegarde les tables d'un dossier avec un manifeste (tailles, SHA-256)
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Backup {
+        #[command(subcommand)]
+        action: Option<BackupAction>,
+        /// Dossier (ou archive) à sauvegarder
+        #[arg(required = true)]
+        source: Option<PathBuf>,
+        /// Dossier dans lequel la sauvegarde horodatée est créée
+        #[arg(required = true)]
+        dest: Option<PathBuf>,
+        /// Forme de la sauvegarde (dir, zip)
+        #[arg(short, long, default_value = "dir")]
+        format: String,
+        /// Nouvelles copies d'une table modifiée pendant la copie
+        #[arg(short, long, default_value_t = crate::backup::DEFAULT_RETRIES)]
+        retries: u32,
+    },
+}
+
+/// Actions sur une sauvegarde existante
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// Vérifie une sauvegarde (dossier ou archive) par rapport à son manifeste
+    Verify {
+        /// Dossier ou archive de la sauvegarde
+        path: PathBuf,
+        /// Format d'affichage (table, json)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
 }
 
 impl Cli {
@@ -84,7 +217,7 @@ impl Cli {
      * Exécute la commande CLI sélectionnée par l'utilisateur.
      * 
      * Charge la configuration, détermine le dossier de données, puis
-     * exécute la commande appropriée (scan, export, serve, debug).
+     * exécute la commande appropriée (scan, export, serve, debug, reindex, history, cdc, diff, stats, encoding, backup).
      * 
      * @returns Result<()> - Succès si l'exécution s'est bien déroulée, erreur sinon
      * 
@@ -111,21 +244,88 @@ impl Cli {
             Commands::Scan { path } => {
                 commands::scan_tables(path).await
             }
-            Commands::Export { table, format, output } => {
-                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?;
+            Commands::Export { table, format, output, sort } => {
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
                 engine.scan_tables()?;
-                commands::export_table(engine, table, format, output).await
+                commands::export_table(engine, table, format, output, sort).await
             }
             Commands::Serve { port, host } => {
                 let engine = std::sync::Arc::new(
                     crate::storage::StorageEngine::new_with_parallel(&data_dir, settings.storage.read_only, settings.storage.parallel)?
+                        .with_encodings(settings.storage.encoding.clone())
                 );
                 engine.scan_tables()?;
-                crate::api::start_server(engine, &host, port).await
+                // La surveillance s'arrête avec le serveur (suppression du watcher)
+                let _watcher = settings.storage.watch.then(|| crate::storage::TableWatcher::start(
+                    engine.clone(),
+                    std::time::Duration::from_millis(settings.storage.watch_interval_ms.max(100)),
+                ));
+                let queries = crate::storage::AsyncEngine::new(engine, settings.api.query_threads, settings.api.query_timeout())?;
+                crate::api::start_server(queries, &host, port).await
             }
             Commands::Debug { file, dump } => {
                 commands::debug_file(file, dump).await
             }
+            Commands::Reindex { table, verify } => {
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, verify, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
+                engine.scan_tables()?;
+                commands::reindex_table(engine, table, verify).await
+            }
+            Commands::History { table, record, since, until, operation, user, limit, format } => {
+                let filters = commands::history_filters(record, since, until, operation, user, limit)?;
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
+                engine.scan_tables()?;
+                commands::show_history(engine, table, filters, format).await
+            }
+            Commands::Cdc { table, state, output, webhook } => {
+                let sink = match (output, webhook) {
+                    (Some(path), _) => crate::cdc::CdcSink::File(path),
+                    (None, Some(url)) => crate::cdc::CdcSink::Webhook(url),
+                    (None, None) => crate::cdc::CdcSink::Stdout,
+                };
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
+                engine.scan_tables()?;
+                commands::capture_changes(engine, table, state, sink).await
+            }
+            Commands::Diff { left, right, table, key, format, output, limit } => {
+                let options = crate::diff::DiffOptions {
+                    tables: table,
+                    key: key.map(|key| key.split(',').map(|field| field.trim().to_string()).collect()),
+                    max_records: limit,
+                };
+                commands::diff_copies(left, right, options, format, output, settings.storage.parallel).await
+            }
+            Commands::Stats { table, top, format } => {
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
+                engine.scan_tables()?;
+                commands::show_stats(engine, table, top, format).await
+            }
+            Commands::Encoding { table, sample, format } => {
+                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?
+                    .with_encodings(settings.storage.encoding.clone());
+                engine.scan_tables()?;
+                commands::detect_encoding(engine, table, sample, format).await
+            }
+            Commands::Backup { action: Some(BackupAction::Verify { path, format }), .. } => {
+                commands::verify_backup(path, format).await
+            }
+            Commands::Backup { action: None, source, dest, format, retries } => {
+                let (Some(source), Some(dest)) = (source, dest) else {
+                    anyhow::bail!("Usage: fic backup <dossier> <destination> ou fic backup verify <sauvegarde>");
+                };
+                let options = crate::backup::BackupOptions {
+                    format: format.parse()?,
+                    retries,
+                    parallel: settings.storage.parallel,
+                    ..Default::default()
+                };
+                commands::backup_database(source, dest, options).await
+            }
         }
     }
 }
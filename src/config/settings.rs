@@ -6,8 +6,8 @@
  * 
  * Structure de configuration :
  * - Settings : Configuration principale contenant tous les sous-modules
- * - ApiSettings : Paramètres du serveur HTTP (host, port, CORS)
- * - StorageSettings : Paramètres du moteur de stockage (lecture seule, écriture)
+ * - ApiSettings : Paramètres du serveur HTTP (host, port, CORS, délai et threads des requêtes)
+ * - StorageSettings : Paramètres du moteur de stockage (lecture seule, écriture, surveillance)
  * - LoggingSettings : Paramètres de logging (niveau de log)
  * 
  * Liens avec d'autres modules :
@@ -41,6 +41,23 @@ pub struct ApiSettings {
     pub port: u16,
     /// Active ou désactive le CORS (Cross-Origin Resource Sharing)
     pub cors_enabled: bool,
+    /// Délai d'exécution d'une requête, en millisecondes (0 = illimité)
+    #[serde(default = "default_query_timeout_ms")]
+    pub query_timeout_ms: u64,
+    /// Nombre de threads du pool de lecture (0 = un par cœur)
+    #[serde(default)]
+    pub query_threads: usize,
+}
+
+fn default_query_timeout_ms() -> u64 {
+    crate::storage::async_engine::DEFAULT_QUERY_TIMEOUT.as_millis() as u64
+}
+
+impl ApiSettings {
+    /// Délai d'exécution des requêtes (None = illimité)
+    pub fn query_timeout(&self) -> Option<std::time::Duration> {
+        (self.query_timeout_ms > 0).then(|| std::time::Duration::from_millis(self.query_timeout_ms))
+    }
 }
 
 /// Paramètres de configuration du moteur de stockage
@@ -53,12 +70,29 @@ pub struct StorageSettings {
     /// Active le multi-threading pour la lecture parallèle (améliore les performances)
     #[serde(default = "default_parallel")]
     pub parallel: bool,
+    /// Surveille le dossier de données et met à jour les tables (commande serve)
+    #[serde(default = "default_watch")]
+    pub watch: bool,
+    /// Délai entre deux scans de la surveillance, en millisecondes
+    #[serde(default = "default_watch_interval_ms")]
+    pub watch_interval_ms: u64,
+    /// Encodage des textes (global, par base, par table, par rubrique)
+    #[serde(default)]
+    pub encoding: crate::core::EncodingConfig,
 }
 
 fn default_parallel() -> bool {
     true
 }
 
+fn default_watch() -> bool {
+    true
+}
+
+fn default_watch_interval_ms() -> u64 {
+    crate::storage::watcher::DEFAULT_WATCH_INTERVAL.as_millis() as u64
+}
+
 /// Paramètres de configuration du système de logging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingSettings {
@@ -79,11 +113,16 @@ impl Default for Settings {
                 host: "127.0.0.1".to_string(),
                 port: 8080,
                 cors_enabled: true,
+                query_timeout_ms: default_query_timeout_ms(),
+                query_threads: 0,
             },
             storage: StorageSettings {
                 read_only: false,
                 enable_write: true,
                 parallel: true,
+                watch: default_watch(),
+                watch_interval_ms: default_watch_interval_ms(),
+                encoding: Default::default(),
             },
             logging: LoggingSettings {
                 level: "info".to_string(),
@@ -111,7 +150,10 @@ impl Settings {
      * - FIC__DATA_DIR : Chemin du dossier de données
      * - FIC__API__HOST : Host du serveur API
      * - FIC__API__PORT : Port du serveur API
+     * - FIC__API__QUERY_TIMEOUT_MS : Délai d'exécution des requêtes (0 = illimité)
      * - FIC__STORAGE__READ_ONLY : Mode lecture seule (true/false)
+     * - FIC__STORAGE__WATCH : Surveillance du dossier de données (true/false)
+     * - FIC__STORAGE__ENCODING : Encodage des textes de toutes les tables (ex: cp850)
      */
     pub fn load() -> anyhow::Result<Self> {
         // Tentative de chargement depuis un fichier de configuration
@@ -133,9 +175,18 @@ impl Settings {
         if let Ok(port) = std::env::var("FIC__API__PORT") {
             settings.api.port = port.parse()?;
         }
+        if let Ok(timeout) = std::env::var("FIC__API__QUERY_TIMEOUT_MS") {
+            settings.api.query_timeout_ms = timeout.parse()?;
+        }
         if let Ok(read_only) = std::env::var("FIC__STORAGE__READ_ONLY") {
             settings.storage.read_only = read_only.parse().unwrap_or(false);
         }
+        if let Ok(watch) = std::env::var("FIC__STORAGE__WATCH") {
+            settings.storage.watch = watch.parse().unwrap_or(true);
+        }
+        if let Ok(encoding) = std::env::var("FIC__STORAGE__ENCODING") {
+            settings.storage.encoding.default = encoding.parse()?;
+        }
 
         Ok(settings)
     }
@@ -0,0 +1,545 @@
+/**
+ * Archives ZIP et tar lues comme un dossier de données.
+ *
+ * Les sauvegardes HFSQL sont souvent transmises en .zip (plusieurs Go).
+ * Plutôt que de les extraire, le moteur lit les tables dans l'archive :
+ * - ZIP : le répertoire central est lu à l'ouverture (crate zip, ZIP64
+ *   compris). Une entrée stockée sans compression est lue directement dans
+ *   l'archive (accès aléatoire) ; une entrée compressée (DEFLATE) est
+ *   extraite dans un fichier temporaire à sa première ouverture.
+ * - tar : les en-têtes sont parcourus à l'ouverture (crate tar, noms longs
+ *   GNU et pax compris) et chaque fichier est lu directement dans l'archive.
+ * - tar.gz / tgz : le flux n'est pas adressable ; il est décompressé une
+ *   fois dans un fichier temporaire, puis lu comme un tar.
+ *
+ * Si l'archive ne contient qu'un dossier à sa racine (cas d'un dossier
+ * HFSQL zippé), ce dossier est pris comme racine.
+ *
+ * Les archives ouvertes sont partagées (une par chemin) tant qu'un
+ * fichier ou un moteur les utilise ; elles sont réindexées si le fichier
+ * d'archive change. Les fichiers temporaires sont supprimés quand
+ * l'archive n'est plus utilisée.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise les crates zip, tar et flate2 (décompression gzip)
+ * - Utilisé par src/core/source.rs (chemins virtuels `archive.zip/TABLE.FIC`)
+ */
+
+use crate::logger::{get_logger, LogLevel};
+use flate2::read::MultiGzDecoder;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::SystemTime;
+use zip::{CompressionMethod, ZipArchive};
+
+/// Archives ouvertes, par chemin (réutilisées tant qu'elles sont utilisées)
+static OPEN_ARCHIVES: Lazy<Mutex<HashMap<PathBuf, Weak<Archive>>>> = Lazy::new(Default::default);
+
+/// Numéro des dossiers temporaires d'extraction
+static SPILL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Format d'archive, déduit de l'extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /**
+     * Reconnaît une archive à son extension (.zip, .tar, .tar.gz, .tgz).
+     *
+     * @param path - Chemin du fichier
+     * @returns Option<ArchiveKind> - Format, ou None pour un autre fichier
+     */
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// Emplacement des octets d'une entrée
+#[derive(Debug, Clone)]
+enum EntryData {
+    /// Octets non compressés dans le fichier de données (archive ou tar décompressé)
+    Stored { offset: u64 },
+    /// Entrée ZIP compressée en DEFLATE (position dans l'archive), extraite à la première ouverture
+    Deflated { index: usize },
+    /// Entrée illisible (chiffrée, méthode de compression non supportée)
+    Unsupported(String),
+}
+
+/// Fichier d'une archive
+#[derive(Debug, Clone)]
+struct Entry {
+    size: u64,
+    data: EntryData,
+}
+
+/// Dossier temporaire des entrées extraites, supprimé avec l'archive
+struct SpillDir {
+    path: PathBuf,
+}
+
+impl SpillDir {
+    fn create() -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "fic-archive-{}-{}",
+            std::process::id(),
+            SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Archive indexée : fichiers et dossiers, par chemin relatif à sa racine
+pub struct Archive {
+    path: PathBuf,
+    kind: ArchiveKind,
+    /// Taille et date du fichier d'archive à l'indexation
+    stamp: (u64, Option<SystemTime>),
+    /// Fichier contenant les octets des entrées Stored
+    data_path: PathBuf,
+    /// Dossier racine retiré des noms ("" si aucun)
+    root: String,
+    files: HashMap<String, Entry>,
+    /// Contenu de chaque dossier ("" pour la racine)
+    dirs: HashMap<String, BTreeSet<String>>,
+    /// Archive ZIP ouverte, pour extraire les entrées compressées
+    zip: Option<Mutex<ZipArchive<File>>>,
+    /// Entrées DEFLATE déjà extraites
+    extracted: Mutex<HashMap<String, PathBuf>>,
+    /// Dossier temporaire (créé à la première extraction, ou pour un tar.gz)
+    spill: Mutex<Option<SpillDir>>,
+}
+
+impl std::fmt::Debug for Archive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Archive")
+            .field("path", &self.path)
+            .field("kind", &self.kind)
+            .field("files", &self.files.len())
+            .finish()
+    }
+}
+
+/// Métadonnées d'une entrée d'archive
+#[derive(Debug, Clone, Copy)]
+pub struct EntryMetadata {
+    pub len: u64,
+    pub is_dir: bool,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn file_stamp(path: &Path) -> io::Result<(u64, Option<SystemTime>)> {
+    let metadata = std::fs::metadata(path)?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/**
+ * Normalise un nom d'entrée (séparateurs '/', sans "./" ni '/' de tête ou de fin).
+ *
+ * @param name - Nom lu dans l'archive
+ * @returns Option<String> - Nom relatif, None s'il est vide ou remonte ("..")
+ */
+fn normalize_name(name: &str) -> Option<String> {
+    let parts: Vec<&str> = name.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    if parts.is_empty() || parts.contains(&"..") {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+/// Entrée lue dans l'index d'une archive (nom brut, dossier ou fichier)
+type RawEntry = (String, Option<Entry>);
+
+/**
+ * Lit le répertoire central d'une archive ZIP.
+ *
+ * @param zip - Archive ouverte
+ * @returns io::Result<Vec<RawEntry>> - Entrées (None pour un dossier)
+ */
+fn index_zip(zip: &mut ZipArchive<File>) -> io::Result<Vec<RawEntry>> {
+    let mut entries = Vec::with_capacity(zip.len());
+    for index in 0..zip.len() {
+        let file = zip.by_index_raw(index)?;
+        let name = file.name().to_string();
+        if file.is_dir() || name.ends_with('\\') {
+            entries.push((name, None));
+            continue;
+        }
+        let data = if file.encrypted() {
+            EntryData::Unsupported("entrée chiffrée".to_string())
+        } else {
+            match file.compression() {
+                CompressionMethod::Stored => EntryData::Stored { offset: file.data_start() },
+                CompressionMethod::Deflated => EntryData::Deflated { index },
+                other => EntryData::Unsupported(format!("méthode de compression {} non supportée", other)),
+            }
+        };
+        entries.push((name, Some(Entry { size: file.size(), data })));
+    }
+    Ok(entries)
+}
+
+/**
+ * Parcourt les en-têtes d'une archive tar.
+ *
+ * @param file - Archive tar (non compressée)
+ * @returns io::Result<Vec<RawEntry>> - Entrées (None pour un dossier)
+ */
+fn index_tar(file: File) -> io::Result<Vec<RawEntry>> {
+    let mut archive = tar::Archive::new(file);
+    let mut entries = Vec::new();
+    for entry in archive.entries_with_seek()? {
+        let entry = entry?;
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            entries.push((name, None));
+        } else if kind.is_file() || kind.is_contiguous() {
+            entries.push((name, Some(Entry { size: entry.size(), data: EntryData::Stored { offset: entry.raw_file_position() } })));
+        }
+        // Liens, périphériques : ignorés
+    }
+    Ok(entries)
+}
+
+impl Archive {
+    /**
+     * Ouvre (ou réutilise) une archive.
+     *
+     * @param path - Chemin du fichier d'archive
+     * @returns io::Result<Arc<Archive>> - Archive indexée, ou erreur si le fichier est illisible ou invalide
+     *
+     * Effets de bord :
+     * - Lit l'index de l'archive ; décompresse un tar.gz dans un fichier temporaire
+     */
+    pub fn open(path: &Path) -> io::Result<Arc<Archive>> {
+        let kind = ArchiveKind::detect(path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{:?} n'est pas une archive", path)))?;
+        let stamp = file_stamp(path)?;
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        // Verrou conservé pendant l'indexation : une seule indexation par archive
+        let mut open = OPEN_ARCHIVES.lock().unwrap();
+        if let Some(archive) = open.get(&key).and_then(Weak::upgrade) {
+            if archive.stamp == stamp {
+                return Ok(archive);
+            }
+        }
+        open.retain(|_, archive| archive.strong_count() > 0);
+
+        let archive = Arc::new(Self::index(path, kind, stamp)?);
+        open.insert(key, Arc::downgrade(&archive));
+        get_logger().log_with_source(
+            LogLevel::Info,
+            format!("Archive {:?} indexée: {} fichier(s)", path, archive.files.len()),
+            Some("Archive".to_string()),
+        );
+        Ok(archive)
+    }
+
+    fn index(path: &Path, kind: ArchiveKind, stamp: (u64, Option<SystemTime>)) -> io::Result<Self> {
+        let mut spill = None;
+        let mut zip = None;
+        let (data_path, raw_entries) = match kind {
+            ArchiveKind::Zip => {
+                let mut archive = ZipArchive::new(File::open(path)?)?;
+                let entries = index_zip(&mut archive)?;
+                zip = Some(Mutex::new(archive));
+                (path.to_path_buf(), entries)
+            }
+            ArchiveKind::Tar => (path.to_path_buf(), index_tar(File::open(path)?)?),
+            ArchiveKind::TarGz => {
+                let dir = SpillDir::create()?;
+                let tar_path = dir.path.join("archive.tar");
+                let mut writer = BufWriter::new(File::create(&tar_path)?);
+                let size = io::copy(&mut MultiGzDecoder::new(BufReader::new(File::open(path)?)), &mut writer)?;
+                writer.flush()?;
+                drop(writer);
+                get_logger().log_with_source(
+                    LogLevel::Info,
+                    format!("Archive {:?} décompressée ({} octets) dans {:?}", path, size, dir.path),
+                    Some("Archive".to_string()),
+                );
+                let entries = index_tar(File::open(&tar_path)?)?;
+                spill = Some(dir);
+                (tar_path, entries)
+            }
+        };
+
+        let mut entries: Vec<(String, Option<Entry>)> = raw_entries.into_iter()
+            .filter_map(|(name, entry)| Some((normalize_name(&name)?, entry)))
+            .collect();
+
+        // Dossier racine unique (dossier HFSQL zippé) : pris comme racine
+        let mut root = String::new();
+        while let Some((name, _)) = entries.iter().find(|(_, entry)| entry.is_some()) {
+            let first = name.split('/').next().unwrap().to_string();
+            let prefix = format!("{}/", first);
+            if !entries.iter().all(|(name, entry)| name.starts_with(&prefix) || (entry.is_none() && *name == first)) {
+                break;
+            }
+            entries = entries.into_iter()
+                .filter_map(|(name, entry)| Some((name.strip_prefix(&prefix)?.to_string(), entry)))
+                .collect();
+            root.push_str(&prefix);
+        }
+
+        let mut files = HashMap::new();
+        let mut dirs: HashMap<String, BTreeSet<String>> = HashMap::new();
+        dirs.insert(String::new(), BTreeSet::new());
+        for (name, entry) in entries {
+            // Dossiers parents (implicites dans la plupart des ZIP)
+            let mut parent = String::new();
+            let parts: Vec<&str> = name.split('/').collect();
+            for (depth, part) in parts.iter().enumerate() {
+                dirs.entry(parent.clone()).or_default().insert(part.to_string());
+                if depth + 1 < parts.len() || entry.is_none() {
+                    parent = if parent.is_empty() { part.to_string() } else { format!("{}/{}", parent, part) };
+                    dirs.entry(parent.clone()).or_default();
+                }
+            }
+            if let Some(entry) = entry {
+                files.insert(name, entry);
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            kind,
+            stamp,
+            data_path,
+            root,
+            files,
+            dirs,
+            zip,
+            extracted: Mutex::new(HashMap::new()),
+            spill: Mutex::new(spill),
+        })
+    }
+
+    /// Chemin du fichier d'archive
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Dossier racine retiré des noms ("" si aucun)
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Date de modification du fichier d'archive (celle de toutes ses entrées)
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.stamp.1
+    }
+
+    /**
+     * Métadonnées d'un fichier ou d'un dossier de l'archive.
+     *
+     * @param name - Chemin relatif à la racine ("" pour la racine)
+     * @returns Option<EntryMetadata> - Taille et nature, None si absent
+     */
+    pub fn metadata(&self, name: &str) -> Option<EntryMetadata> {
+        if let Some(entry) = self.files.get(name) {
+            return Some(EntryMetadata { len: entry.size, is_dir: false });
+        }
+        self.dirs.contains_key(name).then_some(EntryMetadata { len: 0, is_dir: true })
+    }
+
+    /**
+     * Liste un dossier de l'archive.
+     *
+     * @param name - Chemin relatif à la racine ("" pour la racine)
+     * @returns Option<Vec<String>> - Noms des fichiers et dossiers, None si le dossier est absent
+     */
+    pub fn read_dir(&self, name: &str) -> Option<Vec<String>> {
+        self.dirs.get(name).map(|children| children.iter().cloned().collect())
+    }
+
+    /**
+     * Ouvre un fichier de l'archive.
+     *
+     * @param name - Chemin relatif à la racine
+     * @returns io::Result<(File, u64, u64)> - Fichier contenant les octets, position et taille
+     *
+     * Effets de bord :
+     * - Extrait une entrée compressée dans le dossier temporaire à sa première ouverture
+     */
+    pub(crate) fn open_entry(&self, name: &str) -> io::Result<(File, u64, u64)> {
+        let entry = self.files.get(name).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("{} absent de l'archive {:?}", name, self.path))
+        })?;
+        match &entry.data {
+            EntryData::Stored { offset } => Ok((File::open(&self.data_path)?, *offset, entry.size)),
+            EntryData::Deflated { index } => {
+                let path = self.extract(name, *index, entry.size)?;
+                Ok((File::open(path)?, 0, entry.size))
+            }
+            EntryData::Unsupported(reason) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} ne peut pas être lu dans l'archive {:?}: {}", name, self.path, reason),
+            )),
+        }
+    }
+
+    /// Décompresse une entrée DEFLATE dans le dossier temporaire (une seule fois, CRC vérifié par la crate zip)
+    fn extract(&self, name: &str, index: usize, size: u64) -> io::Result<PathBuf> {
+        let mut extracted = self.extracted.lock().unwrap();
+        if let Some(path) = extracted.get(name) {
+            return Ok(path.clone());
+        }
+        let mut spill = self.spill.lock().unwrap();
+        if spill.is_none() {
+            *spill = Some(SpillDir::create()?);
+        }
+        let path = spill.as_ref().unwrap().path.join(format!("entry-{}", extracted.len()));
+
+        let mut zip = self.zip.as_ref()
+            .ok_or_else(|| invalid(format!("{}: entrée compressée hors d'une archive ZIP", name)))?
+            .lock().unwrap();
+        let mut writer = BufWriter::new(File::create(&path)?);
+        let result = zip.by_index(index)
+            .map_err(io::Error::from)
+            .and_then(|mut file| io::copy(&mut file, &mut writer))
+            .and_then(|inflated| writer.flush().map(|_| inflated));
+        let checked = match result {
+            Ok(inflated) if inflated == size => Ok(()),
+            Ok(_) => Err(invalid(format!("{}: taille incorrecte après décompression", name))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = checked {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        get_logger().log_with_source(
+            LogLevel::Debug,
+            format!("{} extrait de {:?} ({} octets)", name, self.path, size),
+            Some("Archive".to_string()),
+        );
+        extracted.insert(name.to_string(), path.clone());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{Read, Seek, SeekFrom};
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    /// Fichier d'une archive ZIP de test : nom, contenu, compressé en DEFLATE
+    pub(crate) type ZipFile<'a> = (&'a str, &'a [u8], bool);
+
+    /// Écrit une archive ZIP (entrées stockées, ou DEFLATE)
+    pub(crate) fn write_zip(path: &Path, files: &[ZipFile]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content, deflated) in files {
+            let method = if *deflated { CompressionMethod::Deflated } else { CompressionMethod::Stored };
+            zip.start_file(*name, SimpleFileOptions::default().compression_method(method)).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// Écrit une archive tar (noms longs en extension GNU)
+    pub(crate) fn write_tar<W: Write>(writer: W, files: &[(&str, &[u8])]) {
+        let mut tar = tar::Builder::new(writer);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            tar.append_data(&mut header, name, *content).unwrap();
+        }
+        tar.into_inner().unwrap().flush().unwrap();
+    }
+
+    /// Lit `len` octets à une position
+    fn read_at(file: &mut File, offset: u64, len: usize) -> Vec<u8> {
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        let mut buffer = vec![0; len];
+        file.read_exact(&mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_zip_archive() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sauvegarde.zip");
+        write_zip(&path, &[
+            ("DONNEES/CLIENT.FIC", b"0123456789", false),
+            ("DONNEES/CLIENT.MMO", b"PCS fichier HFSQL", true),
+            ("DONNEES/JNL/CLIENT.JNL", b"jnl", false),
+        ]);
+
+        let archive = Archive::open(&path).unwrap();
+        assert_eq!(archive.root(), "DONNEES/");
+        assert_eq!(archive.read_dir("").unwrap(), vec!["CLIENT.FIC", "CLIENT.MMO", "JNL"]);
+        assert!(archive.metadata("JNL").unwrap().is_dir);
+        assert_eq!(archive.metadata("CLIENT.MMO").unwrap().len, 17);
+        assert!(Arc::ptr_eq(&archive, &Archive::open(&path).unwrap()));
+
+        let (mut file, offset, len) = archive.open_entry("CLIENT.FIC").unwrap();
+        assert_eq!(read_at(&mut file, offset + 2, (len - 2) as usize), b"23456789");
+        let (mut file, offset, len) = archive.open_entry("CLIENT.MMO").unwrap();
+        assert_eq!(read_at(&mut file, offset, len as usize), b"PCS fichier HFSQL");
+        assert!(archive.open_entry("ABSENT.FIC").is_err());
+    }
+
+    #[test]
+    fn test_tar_archive() {
+        let dir = TempDir::new().unwrap();
+        let long_name = format!("COMPTA/{}.FIC", "F".repeat(120));
+        let files: [(&str, &[u8]); 3] = [
+            ("./CLIENT.FIC", b"0123456789"),
+            ("COMPTA/FACTURE.FIC", &[7u8; 600]),
+            (&long_name, b"long"),
+        ];
+        let path = dir.path().join("sauvegarde.tar");
+        write_tar(File::create(&path).unwrap(), &files);
+        let gz_path = dir.path().join("sauvegarde.tgz");
+        write_tar(flate2::write::GzEncoder::new(File::create(&gz_path).unwrap(), Default::default()), &files);
+
+        for path in [path, gz_path] {
+            let archive = Archive::open(&path).unwrap();
+            assert_eq!(archive.root(), "");
+            assert_eq!(archive.read_dir("").unwrap(), vec!["CLIENT.FIC", "COMPTA"]);
+            assert_eq!(archive.read_dir("COMPTA").unwrap().len(), 2);
+            let (mut file, offset, len) = archive.open_entry("COMPTA/FACTURE.FIC").unwrap();
+            assert_eq!(read_at(&mut file, offset, len as usize), vec![7u8; 600]);
+            let (mut file, offset, len) = archive.open_entry(&long_name).unwrap();
+            assert_eq!(read_at(&mut file, offset, len as usize), b"long");
+        }
+        assert_eq!(ArchiveKind::detect(Path::new("a.TAR.GZ")), Some(ArchiveKind::TarGz));
+        assert_eq!(normalize_name("./a\\b/"), Some("a/b".to_string()));
+        assert_eq!(normalize_name("../x"), None);
+    }
+}
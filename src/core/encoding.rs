@@ -0,0 +1,426 @@
+/**
+ * Encodages des textes stockés dans les fichiers HFSQL.
+ *
+ * Les rubriques texte et les mémos sont stockés dans la page de code du
+ * poste qui a créé les données : Windows-1252 pour la plupart des fichiers,
+ * mais CP850/CP437 pour les données HyperFile DOS, ISO-8859-15 ou UTF-8
+ * pour d'autres. L'encodage est configurable (EncodingConfig) pour toutes
+ * les tables, par base, par table et par rubrique ; il est résolu à la
+ * lecture du schéma et porté par chaque FieldInfo.
+ *
+ * L'encodage `auto` reproduit le comportement historique : Windows-1252
+ * pour les rubriques (UTF-8 en secours), UTF-8 valide sinon Windows-1252
+ * pour les mémos.
+ *
+ * rank_encodings aide à choisir : chaque encodage candidat décode des
+ * échantillons et reçoit un score selon la vraisemblance des caractères
+ * obtenus (lettres accentuées dans un mot, caractères de contrôle, dessins
+ * de cadres, séquences UTF-8 mal décodées…).
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (décodage des rubriques, détection)
+ * - Utilisé par src/core/mmo.rs (mémos) et src/core/key.rs (clés d'index)
+ * - Configuré par src/config/settings.rs ([storage.encoding])
+ */
+
+use encoding_rs::{Encoding, ISO_8859_15, MACINTOSH, WINDOWS_1250, WINDOWS_1252};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Caractères 0x80-0xFF de la page de code 437 (DOS US)
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐\
+└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+
+/// Caractères 0x80-0xFF de la page de code 850 (DOS Europe occidentale)
+const CP850_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐\
+└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{ad}±‗¾¶§÷¸°¨·¹³²■\u{a0}";
+
+static CP437: Lazy<Vec<char>> = Lazy::new(|| CP437_HIGH.chars().collect());
+static CP850: Lazy<Vec<char>> = Lazy::new(|| CP850_HIGH.chars().collect());
+
+/// Encodage des textes d'une rubrique ou d'un mémo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextEncoding {
+    /// Comportement historique (Windows-1252 / UTF-8)
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "utf-8", alias = "utf8")]
+    Utf8,
+    #[serde(rename = "windows-1252", alias = "cp1252")]
+    Windows1252,
+    #[serde(rename = "windows-1250", alias = "cp1250")]
+    Windows1250,
+    #[serde(rename = "iso-8859-1", alias = "latin1")]
+    Iso8859_1,
+    #[serde(rename = "iso-8859-15", alias = "latin9")]
+    Iso8859_15,
+    /// DOS Europe occidentale (HyperFile DOS)
+    #[serde(rename = "cp850", alias = "ibm850")]
+    Cp850,
+    /// DOS US
+    #[serde(rename = "cp437", alias = "ibm437")]
+    Cp437,
+    #[serde(rename = "macintosh", alias = "mac-roman")]
+    MacRoman,
+}
+
+impl TextEncoding {
+    /// Encodages comparés par défaut par rank_encodings, par ordre de préférence
+    pub const CANDIDATES: [TextEncoding; 8] = [
+        TextEncoding::Windows1252,
+        TextEncoding::Utf8,
+        TextEncoding::Iso8859_15,
+        TextEncoding::Cp850,
+        TextEncoding::Cp437,
+        TextEncoding::Iso8859_1,
+        TextEncoding::Windows1250,
+        TextEncoding::MacRoman,
+    ];
+
+    /// Encodage encoding_rs correspondant (None pour les tables internes)
+    fn standard(&self) -> Option<&'static Encoding> {
+        match self {
+            TextEncoding::Auto | TextEncoding::Windows1252 => Some(WINDOWS_1252),
+            TextEncoding::Windows1250 => Some(WINDOWS_1250),
+            TextEncoding::Iso8859_15 => Some(ISO_8859_15),
+            TextEncoding::MacRoman => Some(MACINTOSH),
+            TextEncoding::Utf8 | TextEncoding::Iso8859_1 | TextEncoding::Cp850 | TextEncoding::Cp437 => None,
+        }
+    }
+
+    /// Table des caractères 0x80-0xFF d'une page de code DOS
+    fn dos_table(&self) -> Option<&'static [char]> {
+        match self {
+            TextEncoding::Cp437 => Some(&CP437),
+            TextEncoding::Cp850 => Some(&CP850),
+            _ => None,
+        }
+    }
+
+    /**
+     * Décode le texte d'une rubrique.
+     *
+     * @param bytes - Octets du texte (sans les octets nuls de fin)
+     * @returns (String, bool) - Texte décodé et indicateur d'octets invalides
+     *          (remplacés par U+FFFD, ou UTF-8 invalide en mode auto)
+     */
+    pub fn decode(&self, bytes: &[u8]) -> (String, bool) {
+        match self {
+            TextEncoding::Auto => {
+                let (decoded, _, had_errors) = WINDOWS_1252.decode(bytes);
+                if !had_errors {
+                    return (decoded.into_owned(), false);
+                }
+                match std::str::from_utf8(bytes) {
+                    Ok(text) => (text.to_string(), false),
+                    Err(_) => (decoded.into_owned(), true),
+                }
+            }
+            TextEncoding::Utf8 => {
+                let decoded = String::from_utf8_lossy(bytes);
+                let had_errors = matches!(decoded, std::borrow::Cow::Owned(_));
+                (decoded.into_owned(), had_errors)
+            }
+            TextEncoding::Iso8859_1 => (bytes.iter().map(|&b| b as char).collect(), false),
+            TextEncoding::Cp850 | TextEncoding::Cp437 => {
+                let table = self.dos_table().unwrap_or_default();
+                let text = bytes.iter()
+                    .map(|&b| if b < 0x80 { b as char } else { table[(b - 0x80) as usize] })
+                    .collect();
+                (text, false)
+            }
+            _ => {
+                let encoding = self.standard().unwrap_or(WINDOWS_1252);
+                let (decoded, had_errors) = encoding.decode_without_bom_handling(bytes);
+                (decoded.into_owned(), had_errors)
+            }
+        }
+    }
+
+    /**
+     * Décode le contenu textuel d'un bloc mémo.
+     *
+     * En mode auto, un bloc UTF-8 valide est lu en UTF-8, sinon en Windows-1252.
+     *
+     * @param bytes - Données du bloc
+     * @returns String - Texte décodé (octets invalides remplacés)
+     */
+    pub fn decode_memo(&self, bytes: &[u8]) -> String {
+        match (self, std::str::from_utf8(bytes)) {
+            (TextEncoding::Auto, Ok(text)) => text.to_string(),
+            (TextEncoding::Auto, Err(_)) => WINDOWS_1252.decode(bytes).0.into_owned(),
+            _ => self.decode(bytes).0,
+        }
+    }
+
+    /**
+     * Encode un texte dans l'encodage (ex: valeur recherchée dans un index).
+     *
+     * @param text - Texte à encoder
+     * @returns Vec<u8> - Octets ; les caractères non représentables deviennent '?'
+     */
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let single_byte = |to_byte: &dyn Fn(char) -> Option<u8>| -> Vec<u8> {
+            text.chars().map(|c| to_byte(c).unwrap_or(b'?')).collect()
+        };
+        match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Iso8859_1 => single_byte(&|c| u8::try_from(c as u32).ok()),
+            TextEncoding::Cp850 | TextEncoding::Cp437 => {
+                let table = self.dos_table().unwrap_or_default();
+                single_byte(&|c| {
+                    if c.is_ascii() {
+                        Some(c as u8)
+                    } else {
+                        table.iter().position(|&t| t == c).map(|p| 0x80 + p as u8)
+                    }
+                })
+            }
+            _ => {
+                let encoding = self.standard().unwrap_or(WINDOWS_1252);
+                let (encoded, _, had_errors) = encoding.encode(text);
+                if !had_errors {
+                    return encoded.into_owned();
+                }
+                // encoding_rs remplace par des entités HTML : encodage caractère par caractère
+                single_byte(&|c| {
+                    let mut buffer = [0u8; 4];
+                    let (encoded, _, had_errors) = encoding.encode(c.encode_utf8(&mut buffer));
+                    (!had_errors && encoded.len() == 1).then(|| encoded[0])
+                })
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for TextEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "auto" => Ok(TextEncoding::Auto),
+            "utf-8" | "utf8" => Ok(TextEncoding::Utf8),
+            "windows-1252" | "cp1252" => Ok(TextEncoding::Windows1252),
+            "windows-1250" | "cp1250" => Ok(TextEncoding::Windows1250),
+            "iso-8859-1" | "latin1" => Ok(TextEncoding::Iso8859_1),
+            "iso-8859-15" | "latin9" => Ok(TextEncoding::Iso8859_15),
+            "cp850" | "ibm850" => Ok(TextEncoding::Cp850),
+            "cp437" | "ibm437" => Ok(TextEncoding::Cp437),
+            "macintosh" | "mac-roman" => Ok(TextEncoding::MacRoman),
+            _ => anyhow::bail!(
+                "Encodage inconnu: {} (attendu: auto, utf-8, windows-1252, windows-1250, iso-8859-1, iso-8859-15, cp850, cp437, macintosh)",
+                s
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TextEncoding::Auto => "auto",
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Windows1252 => "windows-1252",
+            TextEncoding::Windows1250 => "windows-1250",
+            TextEncoding::Iso8859_1 => "iso-8859-1",
+            TextEncoding::Iso8859_15 => "iso-8859-15",
+            TextEncoding::Cp850 => "cp850",
+            TextEncoding::Cp437 => "cp437",
+            TextEncoding::MacRoman => "macintosh",
+        })
+    }
+}
+
+/**
+ * Encodages configurés : pour toutes les tables, par base, par table et par rubrique.
+ *
+ * Section `[storage.encoding]` de la configuration. Les clés des tables
+ * sont `TABLE` ou `base.TABLE`, celles des rubriques `TABLE.rubrique` ou
+ * `base.TABLE.rubrique` (sans tenir compte de la casse).
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncodingConfig {
+    /// Encodage par défaut
+    #[serde(default)]
+    pub default: TextEncoding,
+    /// Encodage par base (nom de la base)
+    #[serde(default)]
+    pub databases: HashMap<String, TextEncoding>,
+    /// Encodage par table
+    #[serde(default)]
+    pub tables: HashMap<String, TextEncoding>,
+    /// Encodage par rubrique
+    #[serde(default)]
+    pub fields: HashMap<String, TextEncoding>,
+}
+
+impl EncodingConfig {
+    /**
+     * Résout l'encodage d'une rubrique, de la règle la plus précise à la plus générale.
+     *
+     * Ordre : rubrique (qualifiée puis non qualifiée), table (qualifiée puis
+     * non qualifiée), base, défaut.
+     *
+     * @param database - Base de la table (vide pour la base racine)
+     * @param table - Nom de la table, sans la base
+     * @param field - Nom de la rubrique
+     * @returns TextEncoding - Encodage à utiliser
+     */
+    pub fn resolve(&self, database: &str, table: &str, field: &str) -> TextEncoding {
+        let lookup = |rules: &HashMap<String, TextEncoding>, key: &str| {
+            rules.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)).map(|(_, encoding)| *encoding)
+        };
+        let qualified = if database.is_empty() { table.to_string() } else { format!("{}.{}", database, table) };
+        lookup(&self.fields, &format!("{}.{}", qualified, field))
+            .or_else(|| lookup(&self.fields, &format!("{}.{}", table, field)))
+            .or_else(|| lookup(&self.tables, &qualified))
+            .or_else(|| lookup(&self.tables, table))
+            .or_else(|| (!database.is_empty()).then(|| lookup(&self.databases, database)).flatten())
+            .unwrap_or(self.default)
+    }
+}
+
+/// Score d'un encodage candidat sur des échantillons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodingScore {
+    /// Encodage candidat
+    pub encoding: TextEncoding,
+    /// Somme des points (plus élevé = plus vraisemblable)
+    pub score: i64,
+    /// Échantillons contenant des octets invalides pour cet encodage
+    pub invalid_samples: u32,
+}
+
+/// Famille d'un caractère non ASCII, pour le score
+enum CharClass {
+    /// Lettre latine (accentuée)
+    Letter,
+    /// Ponctuation et symboles courants (€, °, «, ’, …)
+    Symbol,
+    /// Dessins de cadres, lettres grecques, symboles mathématiques
+    Unlikely,
+    /// Caractère de contrôle ou de remplacement
+    Invalid,
+}
+
+fn char_class(c: char) -> CharClass {
+    match c {
+        '\u{FFFD}' => CharClass::Invalid,
+        c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => CharClass::Invalid,
+        '\u{370}'..='\u{3FF}' | '\u{2200}'..='\u{22FF}' | '\u{2310}'..='\u{2321}' | '\u{2500}'..='\u{25FF}'
+        | '×' | '÷' | 'ƒ' | '₧' | 'ı' | '‗' | '¤' | '¦' | '¯' | '´' | '¸' | '¨' | '\u{AD}' | 'µ' => CharClass::Unlikely,
+        '\u{C0}'..='\u{24F}' => CharClass::Letter,
+        _ => CharClass::Symbol,
+    }
+}
+
+/**
+ * Score la vraisemblance d'un texte décodé.
+ *
+ * Seuls les caractères non ASCII comptent : une lettre accentuée dans un mot
+ * rapporte 2 points (1 si isolée, 0 si majuscule après une minuscule), un
+ * symbole courant 0 (-1 collé entre deux lettres), un caractère improbable
+ * -2, un caractère de contrôle ou de remplacement -5. Une séquence UTF-8
+ * décodée en page de code (ex: "Ã©") coûte 3 points de plus.
+ */
+fn text_score(text: &str) -> i64 {
+    let chars: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii() {
+            continue;
+        }
+        let previous = i.checked_sub(1).and_then(|p| chars.get(p)).copied();
+        let next = chars.get(i + 1).copied();
+        let letter_before = previous.is_some_and(char::is_alphabetic);
+        let letter_after = next.is_some_and(char::is_alphabetic);
+        score += match char_class(c) {
+            CharClass::Letter if c.is_uppercase() && previous.is_some_and(char::is_lowercase) => 0,
+            CharClass::Letter if letter_before || letter_after => 2,
+            CharClass::Letter => 1,
+            CharClass::Symbol if letter_before && letter_after && c != '’' => -1,
+            CharClass::Symbol => 0,
+            CharClass::Unlikely => -2,
+            CharClass::Invalid => -5,
+        };
+        // Octet de tête UTF-8 suivi d'un octet de continuation lu comme un caractère
+        if matches!(c, 'Ã' | 'Â' | 'Å' | 'Ä') && next.is_some_and(|n| ('\u{80}'..='\u{BF}').contains(&n) || "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ".contains(n)) {
+            score -= 3;
+        }
+    }
+    score
+}
+
+/**
+ * Classe des encodages candidats selon leur vraisemblance sur des échantillons.
+ *
+ * Chaque échantillon est décodé avec chaque candidat et noté (voir
+ * text_score). Les échantillons purement ASCII ne départagent rien et
+ * peuvent être omis. À score égal, l'ordre des candidats est conservé.
+ *
+ * @param samples - Octets bruts de textes (rubriques ou mémos)
+ * @param candidates - Encodages à comparer (ex: TextEncoding::CANDIDATES)
+ * @returns Vec<EncodingScore> - Candidats du plus au moins vraisemblable
+ */
+pub fn rank_encodings(samples: &[&[u8]], candidates: &[TextEncoding]) -> Vec<EncodingScore> {
+    let mut scores: Vec<EncodingScore> = candidates.iter()
+        .map(|&encoding| {
+            let mut score = 0;
+            let mut invalid_samples = 0;
+            for sample in samples {
+                let (text, had_errors) = encoding.decode(sample);
+                score += text_score(&text);
+                invalid_samples += had_errors as u32;
+            }
+            EncodingScore { encoding, score, invalid_samples }
+        })
+        .collect();
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then(a.invalid_samples.cmp(&b.invalid_samples)));
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dos_code_pages() {
+        assert_eq!(CP437.len(), 128);
+        assert_eq!(CP850.len(), 128);
+        let bytes = [0x82, b'l', 0x8A, b'v', b'e'];
+        assert_eq!(TextEncoding::Cp850.decode(&bytes), ("élève".to_string(), false));
+        assert_eq!(TextEncoding::Cp437.encode("élève"), bytes);
+        assert_eq!(TextEncoding::Cp850.decode(&[0xE9]).0, "Ú");
+        assert_eq!(TextEncoding::Cp437.decode(&[0xE9]).0, "Θ");
+        assert_eq!(TextEncoding::Iso8859_15.encode("€"), [0xA4]);
+        assert_eq!(TextEncoding::Iso8859_1.encode("€a"), b"?a");
+        assert_eq!("LATIN9".parse::<TextEncoding>().unwrap(), TextEncoding::Iso8859_15);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut config = EncodingConfig { default: TextEncoding::Windows1252, ..Default::default() };
+        config.databases.insert("DOS".to_string(), TextEncoding::Cp850);
+        config.tables.insert("dos.ARCHIVE".to_string(), TextEncoding::Cp437);
+        config.fields.insert("ARCHIVE.notes".to_string(), TextEncoding::Utf8);
+
+        assert_eq!(config.resolve("", "CLIENT", "nom"), TextEncoding::Windows1252);
+        assert_eq!(config.resolve("DOS", "CLIENT", "nom"), TextEncoding::Cp850);
+        assert_eq!(config.resolve("DOS", "ARCHIVE", "nom"), TextEncoding::Cp437);
+        assert_eq!(config.resolve("DOS", "ARCHIVE", "NOTES"), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_rank_encodings() {
+        let best = |encoding: TextEncoding| {
+            let samples = ["À bientôt", "crème brûlée", "Hôtel de l'Étoile"].map(|text| encoding.encode(text));
+            let samples: Vec<&[u8]> = samples.iter().map(Vec::as_slice).collect();
+            rank_encodings(&samples, &TextEncoding::CANDIDATES)[0].encoding
+        };
+        assert_eq!(best(TextEncoding::Windows1252), TextEncoding::Windows1252);
+        assert_eq!(best(TextEncoding::Utf8), TextEncoding::Utf8);
+        assert_eq!(best(TextEncoding::Cp850), TextEncoding::Cp850);
+    }
+}
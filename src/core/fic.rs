@@ -25,7 +25,7 @@ use crate::logger::{get_logger, LogLevel};
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use crate::core::source::{self, SourceFile};
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use rayon::prelude::*;
@@ -72,7 +72,7 @@ pub struct FicFile {
     /// Header du fichier (lu au moment de l'ouverture)
     header: FicHeader,
     /// Handle du fichier ouvert (Option pour permettre la fermeture explicite)
-    file: Option<File>,
+    file: Option<SourceFile>,
 }
 
 impl FicFile {
@@ -91,7 +91,7 @@ impl FicFile {
      */
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let mut file = File::open(&path)
+        let mut file = source::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
 
         let header = Self::read_header(&mut file)?;
@@ -493,9 +493,8 @@ impl FicFile {
      * - Lit depuis le fichier (si possible)
      */
     pub fn dump_header_hex(&self) -> String {
-        use std::fs::File;
         use std::io::Read;
-        if let Ok(mut f) = File::open(&self.path) {
+        if let Ok(mut f) = source::open(&self.path) {
             let mut buffer = vec![0u8; 64];
             if f.read_exact(&mut buffer).is_ok() {
                 let mut result = String::new();
@@ -539,6 +538,7 @@ impl FicFile {
             offset,
             length: 4,
             field_type: crate::core::FieldType::Integer,
+            encoding: Default::default(),
         });
         offset += 4;
 
@@ -548,6 +548,7 @@ impl FicFile {
             offset,
             length: 1,
             field_type: crate::core::FieldType::Integer,
+            encoding: Default::default(),
         });
         offset += 1;
 
@@ -558,6 +559,7 @@ impl FicFile {
                 offset,
                 length: self.header.record_length - offset,
                 field_type: crate::core::FieldType::Binary,
+                encoding: Default::default(),
             });
         }
 
@@ -572,11 +574,38 @@ impl Drop for FicFile {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    /**
+     * Écrit un .fic minimal pour les tests : header PCS de 20 octets puis un
+     * enregistrement par entrée (byte de flags, octets nuls, puis la valeur
+     * dans le dernier octet).
+     *
+     * @param path - Chemin du fichier à créer
+     * @param record_length - Longueur d'un enregistrement (byte de flags inclus)
+     * @param records - (supprimé, valeur du dernier octet) de chaque enregistrement
+     */
+    pub(crate) fn write_fic(path: &Path, record_length: u16, records: &[(bool, u8)]) -> Result<()> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"PCS\0");
+        data.extend_from_slice(&1u16.to_le_bytes()); // Version
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&record_length.to_le_bytes());
+        data.extend_from_slice(&(records.len() as u16).to_le_bytes());
+        data.extend_from_slice(&[0u8; 8]);
+        for (deleted, value) in records {
+            let mut record = vec![0u8; record_length as usize];
+            record[0] = *deleted as u8;
+            record[record_length as usize - 1] = *value;
+            data.extend(record);
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
     fn create_test_fic_file() -> Result<NamedTempFile> {
         let mut file = NamedTempFile::new()?;
         
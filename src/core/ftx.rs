@@ -0,0 +1,505 @@
+/**
+ * Gestionnaire de fichiers .ftx (index full-text HFSQL).
+ *
+ * Ce fichier contient les structures et fonctions pour lire les index
+ * full-text, qui associent chaque mot des rubriques indexées aux
+ * enregistrements qui le contiennent (index inversé).
+ *
+ * Structure d'un fichier .ftx :
+ * - 0x00 Magic (4 bytes) : "FTX\0" ou "PCS\0"
+ * - 0x04 Version (u16), 0x06 Options (u16) :
+ *   bit 0 = insensible à la casse, bit 1 = insensible aux accents
+ * - 0x08 Nombre d'enregistrements indexés (u32)
+ * - 0x0C Nombre de mots du dictionnaire (u32)
+ * - 0x10 Offset du dictionnaire (u32)
+ * - 0x14 Nombre de rubriques indexées (u16), 0x16 réservé (u16)
+ * - 0x18 Rubriques (4 bytes chacune) : offset (u16), longueur (u16)
+ * - Dictionnaire, trié par mot : longueur (u8), mot (encodage des rubriques),
+ *   nombre d'enregistrements (u32), offset de la liste d'occurrences (u32)
+ * - Listes d'occurrences : (record_id u32, nombre d'occurrences u16)
+ *
+ * Le dictionnaire est chargé à l'ouverture ; les listes d'occurrences sont
+ * lues à la demande. Les mots sont stockés déjà transformés (majuscules,
+ * sans accents) selon les options de l'index.
+ *
+ * Fonctionnalités :
+ * - Lecture du header et du dictionnaire
+ * - Recherche de mots exacts ou par préfixe ("client*")
+ * - Classement des enregistrements par pertinence (TF-IDF)
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/key.rs pour les transformations de texte
+ * - Utilisé par src/storage/engine.rs pour les recherches full-text
+ */
+
+use crate::core::key::fold_text;
+use crate::core::TextEncoding;
+use anyhow::{Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::core::source::{self, SourceFile};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Taille de la partie fixe du header (avant les descripteurs de rubriques)
+const HEADER_FIXED_SIZE: u64 = 0x18;
+/// Taille d'une occurrence dans une liste (record_id + fréquence)
+const POSTING_SIZE: u64 = 6;
+
+/// Index insensible à la casse (mots stockés en majuscules)
+pub const FTX_CASE_INSENSITIVE: u16 = 0x0001;
+/// Index insensible aux accents (mots stockés sans diacritiques)
+pub const FTX_ACCENT_INSENSITIVE: u16 = 0x0002;
+
+/// Header d'un fichier .ftx
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FtxHeader {
+    /// Magic bytes identifiant le format
+    pub magic: u32,
+    /// Version du format de fichier
+    pub version: u16,
+    /// Options de l'index (FTX_CASE_INSENSITIVE, FTX_ACCENT_INSENSITIVE)
+    pub flags: u16,
+    /// Nombre d'enregistrements indexés
+    pub document_count: u32,
+    /// Nombre de mots du dictionnaire
+    pub term_count: u32,
+    /// Offset du dictionnaire dans le fichier
+    pub dictionary_offset: u32,
+    /// Rubriques indexées (offset, longueur), byte de flags inclus
+    pub fields: Vec<FtxField>,
+}
+
+impl FtxHeader {
+    /// Indique si l'index ignore la casse
+    pub fn is_case_insensitive(&self) -> bool {
+        self.flags & FTX_CASE_INSENSITIVE != 0
+    }
+
+    /// Indique si l'index ignore les accents
+    pub fn is_accent_insensitive(&self) -> bool {
+        self.flags & FTX_ACCENT_INSENSITIVE != 0
+    }
+}
+
+/// Rubrique couverte par un index full-text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtxField {
+    /// Offset de la rubrique dans l'enregistrement (byte de flags inclus)
+    pub offset: u32,
+    /// Longueur de la rubrique
+    pub length: u32,
+}
+
+/// Mot du dictionnaire
+#[derive(Debug, Clone)]
+struct FtxTerm {
+    /// Mot transformé, dans l'encodage des rubriques indexées
+    term: Vec<u8>,
+    /// Nombre d'enregistrements contenant le mot
+    document_frequency: u32,
+    /// Offset de la liste d'occurrences
+    postings_offset: u32,
+}
+
+/// Enregistrement trouvé par une recherche full-text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullTextHit {
+    /// ID de l'enregistrement dans le fichier .fic
+    pub record_id: u32,
+    /// Score de pertinence (plus élevé = plus pertinent)
+    pub score: f64,
+}
+
+/// Gestionnaire de fichier d'index full-text .ftx
+pub struct FtxFile {
+    /// Chemin du fichier
+    #[allow(dead_code)]
+    path: std::path::PathBuf,
+    /// Header du fichier
+    header: FtxHeader,
+    /// Dictionnaire trié
+    terms: Vec<FtxTerm>,
+    /// Encodage des mots du dictionnaire
+    encoding: TextEncoding,
+    /// Handle du fichier ouvert
+    file: Option<SourceFile>,
+}
+
+impl FtxFile {
+    /**
+     * Ouvre un fichier .ftx et charge son dictionnaire.
+     *
+     * @param path - Chemin vers le fichier .ftx
+     * @returns Result<FtxFile> - Gestionnaire de fichier ou erreur
+     *
+     * Effets de bord :
+     * - Ouvre le fichier en lecture
+     * - Lit le header et le dictionnaire
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_as(path, TextEncoding::Auto)
+    }
+
+    /**
+     * Ouvre un fichier .ftx dont les mots sont stockés dans un encodage donné.
+     *
+     * @param path - Chemin vers le fichier .ftx
+     * @param encoding - Encodage des rubriques indexées
+     * @returns Result<FtxFile> - Gestionnaire de fichier ou erreur
+     *
+     * Effets de bord :
+     * - Ouvre le fichier en lecture
+     * - Lit le header et le dictionnaire
+     */
+    pub fn open_as<P: AsRef<Path>>(path: P, encoding: TextEncoding) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = source::open(&path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+        let file_size = file.size()?;
+
+        let mut reader = BufReader::new(file);
+        let header = Self::read_header(&mut reader)
+            .with_context(|| format!("Header full-text invalide: {:?}", path))?;
+        let terms = Self::read_dictionary(&mut reader, &header, file_size)
+            .with_context(|| format!("Dictionnaire full-text invalide: {:?}", path))?;
+
+        Ok(Self {
+            path,
+            header,
+            terms,
+            encoding,
+            file: Some(reader.into_inner()),
+        })
+    }
+
+    /**
+     * Lit et valide le header d'un fichier .ftx.
+     */
+    fn read_header<R: Read + Seek>(reader: &mut R) -> Result<FtxHeader> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic_bytes = [0u8; 4];
+        reader.read_exact(&mut magic_bytes)?;
+        if &magic_bytes[0..3] != b"FTX" && &magic_bytes[0..3] != b"PCS" {
+            anyhow::bail!("Magic bytes invalides: {:?} (attendu: FTX ou PCS)", magic_bytes);
+        }
+
+        let version = reader.read_u16::<LittleEndian>()?;
+        let flags = reader.read_u16::<LittleEndian>()?;
+        let document_count = reader.read_u32::<LittleEndian>()?;
+        let term_count = reader.read_u32::<LittleEndian>()?;
+        let dictionary_offset = reader.read_u32::<LittleEndian>()?;
+        let field_count = reader.read_u16::<LittleEndian>()?;
+        let _reserved = reader.read_u16::<LittleEndian>()?;
+
+        let mut fields = Vec::with_capacity(field_count as usize);
+        for _ in 0..field_count {
+            let offset = reader.read_u16::<LittleEndian>()? as u32;
+            let length = reader.read_u16::<LittleEndian>()? as u32;
+            fields.push(FtxField { offset, length });
+        }
+
+        if (dictionary_offset as u64) < HEADER_FIXED_SIZE + field_count as u64 * 4 {
+            anyhow::bail!("Offset du dictionnaire {} dans le header", dictionary_offset);
+        }
+
+        Ok(FtxHeader {
+            magic: u32::from_le_bytes(magic_bytes),
+            version,
+            flags,
+            document_count,
+            term_count,
+            dictionary_offset,
+            fields,
+        })
+    }
+
+    /**
+     * Lit le dictionnaire et vérifie qu'il est trié et que les listes
+     * d'occurrences sont dans le fichier.
+     */
+    fn read_dictionary<R: Read + Seek>(reader: &mut R, header: &FtxHeader, file_size: u64) -> Result<Vec<FtxTerm>> {
+        reader.seek(SeekFrom::Start(header.dictionary_offset as u64))?;
+
+        // Chaque mot occupe au moins 10 bytes : borne la préallocation sur un header corrompu
+        let max_terms = (file_size / 10) as usize;
+        let mut terms: Vec<FtxTerm> = Vec::with_capacity((header.term_count as usize).min(max_terms));
+        for i in 0..header.term_count {
+            let length = reader.read_u8()? as usize;
+            let mut term = vec![0u8; length];
+            reader.read_exact(&mut term)
+                .with_context(|| format!("Mot {} tronqué", i))?;
+            let document_frequency = reader.read_u32::<LittleEndian>()?;
+            let postings_offset = reader.read_u32::<LittleEndian>()?;
+
+            let end = postings_offset as u64 + document_frequency as u64 * POSTING_SIZE;
+            if end > file_size {
+                anyhow::bail!("Liste d'occurrences du mot {} hors du fichier", i);
+            }
+            if let Some(previous) = terms.last() {
+                if previous.term >= term {
+                    anyhow::bail!("Dictionnaire non trié au mot {}", i);
+                }
+            }
+
+            terms.push(FtxTerm {
+                term,
+                document_frequency,
+                postings_offset,
+            });
+        }
+        Ok(terms)
+    }
+
+    /**
+     * Retourne une référence vers le header du fichier.
+     *
+     * @returns &FtxHeader - Référence vers le header
+     */
+    pub fn header(&self) -> &FtxHeader {
+        &self.header
+    }
+
+    /**
+     * Retourne les mots du dictionnaire (forme stockée, décodée).
+     *
+     * @returns Vec<String> - Mots, dans l'ordre du dictionnaire
+     */
+    pub fn terms(&self) -> Vec<String> {
+        self.terms.iter()
+            .map(|t| self.encoding.decode(&t.term).0)
+            .collect()
+    }
+
+    /**
+     * Applique à un mot les transformations de l'index (casse, accents).
+     *
+     * @param word - Mot saisi
+     * @returns Vec<u8> - Mot sous sa forme stockée (encodage du dictionnaire)
+     */
+    pub fn normalize(&self, word: &str) -> Vec<u8> {
+        let encoded = self.encoding.encode(word);
+        fold_text(&encoded, self.header.is_case_insensitive(), self.header.is_accent_insensitive(), self.encoding)
+    }
+
+    /**
+     * Lit la liste d'occurrences d'un mot du dictionnaire.
+     *
+     * @param term_index - Position du mot dans le dictionnaire
+     * @returns Result<Vec<(u32, u16)>> - Couples (record_id, nombre d'occurrences)
+     *
+     * Effets de bord :
+     * - Lit depuis le fichier (position modifiée)
+     */
+    fn postings(&mut self, term_index: usize) -> Result<Vec<(u32, u16)>> {
+        let term = &self.terms[term_index];
+        let file = self.file.as_mut()
+            .context("Fichier non ouvert")?;
+
+        let mut buffer = vec![0u8; term.document_frequency as usize * POSTING_SIZE as usize];
+        file.seek(SeekFrom::Start(term.postings_offset as u64))?;
+        file.read_exact(&mut buffer)
+            .with_context(|| format!("Impossible de lire les occurrences du mot {}", term_index))?;
+
+        Ok(buffer.chunks_exact(POSTING_SIZE as usize)
+            .map(|p| (u32::from_le_bytes([p[0], p[1], p[2], p[3]]), u16::from_le_bytes([p[4], p[5]])))
+            .collect())
+    }
+
+    /**
+     * Retourne les positions des mots correspondant à un mot de la requête.
+     *
+     * Un mot terminé par '*' correspond à tous les mots de même préfixe.
+     */
+    fn matching_terms(&self, word: &str) -> std::ops::Range<usize> {
+        match word.strip_suffix('*') {
+            Some(prefix) => {
+                let prefix = self.normalize(prefix);
+                let start = self.terms.partition_point(|t| t.term < prefix);
+                let end = start + self.terms[start..].partition_point(|t| t.term.starts_with(&prefix));
+                start..end
+            }
+            None => {
+                let term = self.normalize(word);
+                let start = self.terms.partition_point(|t| t.term < term);
+                let end = if self.terms.get(start).map(|t| t.term == term).unwrap_or(false) { start + 1 } else { start };
+                start..end
+            }
+        }
+    }
+
+    /**
+     * Recherche les enregistrements contenant tous les mots d'une requête.
+     *
+     * Chaque mot de la requête doit être présent (ET implicite). Les résultats
+     * sont classés par score TF-IDF décroissant : somme, pour chaque mot, du
+     * nombre d'occurrences pondéré par la rareté du mot dans l'index.
+     *
+     * @param query - Mots recherchés, séparés par des espaces ("client*" = préfixe)
+     * @returns Result<Vec<FullTextHit>> - Enregistrements classés par pertinence
+     *
+     * Effets de bord :
+     * - Lit les listes d'occurrences depuis le fichier
+     */
+    pub fn search(&mut self, query: &str) -> Result<Vec<FullTextHit>> {
+        let words = tokenize_query(query);
+        if words.is_empty() {
+            anyhow::bail!("Requête full-text vide");
+        }
+
+        let documents = self.header.document_count.max(1) as f64;
+        let mut scores: Option<HashMap<u32, f64>> = None;
+
+        for word in &words {
+            let mut word_scores: HashMap<u32, f64> = HashMap::new();
+            for term_index in self.matching_terms(word) {
+                let frequency = self.terms[term_index].document_frequency.max(1) as f64;
+                let idf = (1.0 + documents / frequency).ln();
+                for (record_id, occurrences) in self.postings(term_index)? {
+                    *word_scores.entry(record_id).or_insert(0.0) += occurrences as f64 * idf;
+                }
+            }
+
+            scores = Some(match scores {
+                None => word_scores,
+                Some(previous) => previous.into_iter()
+                    .filter_map(|(id, score)| word_scores.get(&id).map(|s| (id, score + s)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<FullTextHit> = scores.unwrap_or_default()
+            .into_iter()
+            .map(|(record_id, score)| FullTextHit { record_id, score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.record_id.cmp(&b.record_id)));
+        Ok(hits)
+    }
+}
+
+/**
+ * Découpe une requête full-text en mots.
+ *
+ * Les séparateurs sont les caractères non alphanumériques, sauf '*'
+ * en fin de mot (recherche par préfixe).
+ *
+ * @param query - Requête saisie
+ * @returns Vec<String> - Mots de la requête
+ *
+ * Effets de bord : Aucun
+ */
+pub fn tokenize_query(query: &str) -> Vec<String> {
+    query.split(|c: char| !c.is_alphanumeric() && c != '*')
+        .map(|word| word.trim_start_matches('*'))
+        .filter(|word| !word.trim_end_matches('*').is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// Occurrences d'un mot : (record_id, nombre d'occurrences)
+    type Postings = Vec<(u32, u16)>;
+
+    /// Index insensible à la casse et aux accents sur 3 enregistrements
+    fn create_test_ftx_file() -> Result<NamedTempFile> {
+        write_ftx(FTX_CASE_INSENSITIVE | FTX_ACCENT_INSENSITIVE, &[
+            (b"CLIENT", vec![(0, 1), (1, 3)]),
+            (b"CLIENTELE", vec![(2, 1)]),
+            (b"ELEVE", vec![(1, 1)]),
+            (b"PARIS", vec![(0, 1), (2, 2)]),
+        ])
+    }
+
+    /// Écrit un index full-text sur 3 enregistrements (mots triés, déjà transformés)
+    fn write_ftx(flags: u16, dictionary: &[(&[u8], Postings)]) -> Result<NamedTempFile> {
+        let dictionary_offset = 0x18 + 4;
+        let dictionary_size: usize = dictionary.iter().map(|(t, _)| 1 + t.len() + 8).sum();
+        let mut postings_offset = (dictionary_offset + dictionary_size) as u32;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"FTX\0");
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&flags.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&(dictionary.len() as u32).to_le_bytes());
+        data.extend_from_slice(&(dictionary_offset as u32).to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes()); // Rubrique: offset
+        data.extend_from_slice(&40u16.to_le_bytes()); // Rubrique: longueur
+
+        for (term, postings) in dictionary {
+            data.push(term.len() as u8);
+            data.extend_from_slice(term);
+            data.extend_from_slice(&(postings.len() as u32).to_le_bytes());
+            data.extend_from_slice(&postings_offset.to_le_bytes());
+            postings_offset += postings.len() as u32 * 6;
+        }
+        for (_, postings) in dictionary {
+            for (record_id, occurrences) in postings {
+                data.extend_from_slice(&record_id.to_le_bytes());
+                data.extend_from_slice(&occurrences.to_le_bytes());
+            }
+        }
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&data)?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_read_dictionary() -> Result<()> {
+        let test_file = create_test_ftx_file()?;
+        let ftx = FtxFile::open(test_file.path())?;
+
+        assert_eq!(ftx.header().document_count, 3);
+        assert_eq!(ftx.header().fields, vec![FtxField { offset: 5, length: 40 }]);
+        assert_eq!(ftx.terms(), vec!["CLIENT", "CLIENTELE", "ELEVE", "PARIS"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_ranking() -> Result<()> {
+        let test_file = create_test_ftx_file()?;
+        let mut ftx = FtxFile::open(test_file.path())?;
+
+        let ids: Vec<u32> = ftx.search("client")?.iter().map(|h| h.record_id).collect();
+        assert_eq!(ids, vec![1, 0]);
+
+        let ids: Vec<u32> = ftx.search("Client Paris")?.iter().map(|h| h.record_id).collect();
+        assert_eq!(ids, vec![0]);
+
+        let ids: Vec<u32> = ftx.search("client*")?.iter().map(|h| h.record_id).collect();
+        assert_eq!(ids, vec![1, 2, 0]);
+
+        let ids: Vec<u32> = ftx.search("élève")?.iter().map(|h| h.record_id).collect();
+        assert_eq!(ids, vec![1]);
+
+        assert!(ftx.search("inconnu")?.is_empty());
+        assert!(ftx.search("  ").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dictionary_encoding() -> Result<()> {
+        // Index insensible à la casse seulement, mots en CP850 : "ÉLÈVE"
+        let test_file = write_ftx(FTX_CASE_INSENSITIVE, &[(b"\x90L\xd4VE", vec![(2, 1)])])?;
+        let mut ftx = FtxFile::open_as(test_file.path(), TextEncoding::Cp850)?;
+
+        assert_eq!(ftx.terms(), vec!["ÉLÈVE"]);
+        let ids: Vec<u32> = ftx.search("élève")?.iter().map(|h| h.record_id).collect();
+        assert_eq!(ids, vec![2]);
+        assert!(ftx.search("eleve")?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,495 @@
+/**
+ * Gestionnaire de fichiers journal HFSQL (.jnl).
+ *
+ * Lorsque la journalisation est activée, HFSQL Classic enregistre chaque
+ * modification d'une table dans un fichier journal séparé. Ce fichier
+ * contient les structures et fonctions pour lire ces journaux.
+ *
+ * Structure d'un fichier journal :
+ * - 0x00 Magic (4 bytes) : "JNL\0" ou "PCS\0"
+ * - 0x04 Version (u16), 0x06 réservé (u16)
+ * - 0x08 Taille des images d'enregistrement (u32, sans le byte de flags)
+ * - 0x0C Nombre d'entrées (u32)
+ * - 0x10 Offset de la première entrée (u32)
+ * - Entrées (taille variable, à la suite) :
+ *   - 0x00 Taille totale de l'entrée (u32)
+ *   - 0x04 Opération (u8) : 1 = ajout, 2 = modification, 3 = suppression
+ *   - 0x05 Options (u8) : bit 0 = image avant présente, bit 1 = image après présente
+ *   - 0x06 réservé (u16)
+ *   - 0x08 Date de l'opération (i64, secondes depuis 1970-01-01 UTC)
+ *   - 0x10 ID de l'enregistrement (u32)
+ *   - Utilisateur : longueur (u8) + texte (Windows-1252)
+ *   - Poste : longueur (u8) + texte (Windows-1252)
+ *   - Image avant, puis image après (si présentes)
+ *
+ * Les entrées sont lues à la demande, dans l'ordre du journal.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs pour l'historique des modifications
+ */
+
+use anyhow::{Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use encoding_rs::WINDOWS_1252;
+use serde::{Deserialize, Serialize};
+use crate::core::source::{self, SourceFile};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Taille de la partie fixe d'une entrée (avant utilisateur et poste)
+const ENTRY_FIXED_SIZE: u32 = 0x14;
+/// L'entrée contient l'image de l'enregistrement avant l'opération
+const ENTRY_HAS_BEFORE: u8 = 0x01;
+/// L'entrée contient l'image de l'enregistrement après l'opération
+const ENTRY_HAS_AFTER: u8 = 0x02;
+
+/// Type d'opération journalisée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalOperation {
+    /// Ajout d'un enregistrement
+    Insert,
+    /// Modification d'un enregistrement
+    Update,
+    /// Suppression d'un enregistrement
+    Delete,
+    /// Code d'opération non reconnu
+    Unknown(u8),
+}
+
+impl JournalOperation {
+    /**
+     * Convertit le code d'opération stocké dans une entrée.
+     *
+     * @param code - Code lu dans le journal
+     * @returns JournalOperation - Opération correspondante
+     */
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x01 => JournalOperation::Insert,
+            0x02 => JournalOperation::Update,
+            0x03 => JournalOperation::Delete,
+            other => JournalOperation::Unknown(other),
+        }
+    }
+
+    /**
+     * Convertit un nom d'opération saisi (insert, update, delete).
+     *
+     * @param name - Nom de l'opération (insensible à la casse)
+     * @returns Option<JournalOperation> - Opération ou None si inconnue
+     */
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "insert" | "ajout" => Some(JournalOperation::Insert),
+            "update" | "modification" => Some(JournalOperation::Update),
+            "delete" | "suppression" => Some(JournalOperation::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// Header d'un fichier journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JnlHeader {
+    /// Magic bytes identifiant le format
+    pub magic: u32,
+    /// Version du format de fichier
+    pub version: u16,
+    /// Taille des images d'enregistrement (sans le byte de flags)
+    pub image_length: u32,
+    /// Nombre d'entrées déclaré
+    pub entry_count: u32,
+    /// Offset de la première entrée
+    pub data_offset: u32,
+}
+
+/// Entrée du journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Position de l'entrée dans le journal (0-based)
+    pub sequence: u32,
+    /// Opération effectuée
+    pub operation: JournalOperation,
+    /// Date de l'opération (secondes depuis 1970-01-01 UTC)
+    pub timestamp: i64,
+    /// ID de l'enregistrement concerné
+    pub record_id: u32,
+    /// Utilisateur ayant effectué l'opération
+    pub user: String,
+    /// Poste ayant effectué l'opération
+    pub station: String,
+    /// Données de l'enregistrement avant l'opération (sans le byte de flags)
+    pub before: Option<Vec<u8>>,
+    /// Données de l'enregistrement après l'opération (sans le byte de flags)
+    pub after: Option<Vec<u8>>,
+}
+
+/// Gestionnaire de fichier journal
+pub struct JnlFile {
+    /// Chemin du fichier
+    #[allow(dead_code)]
+    path: std::path::PathBuf,
+    /// Header du fichier
+    header: JnlHeader,
+    /// Taille du fichier (pour valider les entrées)
+    file_size: u64,
+    /// Reader sur le fichier ouvert
+    reader: BufReader<SourceFile>,
+}
+
+impl JnlFile {
+    /**
+     * Ouvre un fichier journal et lit son header.
+     *
+     * @param path - Chemin vers le fichier journal
+     * @returns Result<JnlFile> - Gestionnaire de fichier ou erreur
+     *
+     * Effets de bord :
+     * - Ouvre le fichier en lecture
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = source::open(&path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+        let file_size = file.size()?;
+
+        let mut reader = BufReader::new(file);
+        let header = Self::read_header(&mut reader)
+            .with_context(|| format!("Header de journal invalide: {:?}", path))?;
+        if header.data_offset as u64 > file_size {
+            anyhow::bail!("Offset des entrées {} au-delà de la fin du journal {:?}", header.data_offset, path);
+        }
+
+        Ok(Self {
+            path,
+            header,
+            file_size,
+            reader,
+        })
+    }
+
+    /**
+     * Lit et valide le header d'un fichier journal.
+     */
+    fn read_header<R: Read + Seek>(reader: &mut R) -> Result<JnlHeader> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic_bytes = [0u8; 4];
+        reader.read_exact(&mut magic_bytes)?;
+        if &magic_bytes[0..3] != b"JNL" && &magic_bytes[0..3] != b"PCS" {
+            anyhow::bail!("Magic bytes invalides: {:?} (attendu: JNL ou PCS)", magic_bytes);
+        }
+
+        let version = reader.read_u16::<LittleEndian>()?;
+        let _reserved = reader.read_u16::<LittleEndian>()?;
+        let image_length = reader.read_u32::<LittleEndian>()?;
+        let entry_count = reader.read_u32::<LittleEndian>()?;
+        let data_offset = reader.read_u32::<LittleEndian>()?;
+
+        if data_offset < 0x14 {
+            anyhow::bail!("Offset des entrées {} dans le header", data_offset);
+        }
+
+        Ok(JnlHeader {
+            magic: u32::from_le_bytes(magic_bytes),
+            version,
+            image_length,
+            entry_count,
+            data_offset,
+        })
+    }
+
+    /**
+     * Retourne une référence vers le header du fichier.
+     *
+     * @returns &JnlHeader - Référence vers le header
+     */
+    pub fn header(&self) -> &JnlHeader {
+        &self.header
+    }
+
+    /**
+     * Parcourt les entrées du journal dans l'ordre.
+     *
+     * @returns Result<JnlEntries> - Itérateur sur les entrées
+     *
+     * Effets de bord :
+     * - Repositionne la lecture au début des entrées
+     */
+    pub fn entries(&mut self) -> Result<JnlEntries<'_>> {
+        self.reader.seek(SeekFrom::Start(self.header.data_offset as u64))?;
+        Ok(JnlEntries {
+            position: self.header.data_offset as u64,
+            sequence: 0,
+            done: false,
+            jnl: self,
+        })
+    }
+
+    /**
+     * Lit une entrée à la position courante.
+     *
+     * @param position - Offset de l'entrée dans le fichier
+     * @param sequence - Numéro de l'entrée
+     * @returns Result<(JournalEntry, u64)> - Entrée et offset de l'entrée suivante
+     */
+    fn read_entry(&mut self, position: u64, sequence: u32) -> Result<(JournalEntry, u64)> {
+        let image_length = self.header.image_length as usize;
+        let reader = &mut self.reader;
+
+        let entry_length = reader.read_u32::<LittleEndian>()?;
+        if entry_length < ENTRY_FIXED_SIZE + 2 || position + entry_length as u64 > self.file_size {
+            anyhow::bail!("Taille d'entrée {} invalide à l'offset {}", entry_length, position);
+        }
+
+        let mut buffer = vec![0u8; entry_length as usize - 4];
+        reader.read_exact(&mut buffer)?;
+
+        let operation = JournalOperation::from_code(buffer[0]);
+        let flags = buffer[1];
+        let timestamp = i64::from_le_bytes(buffer[4..12].try_into()?);
+        let record_id = u32::from_le_bytes(buffer[12..16].try_into()?);
+
+        let mut cursor = 16usize;
+        let read_text = |cursor: &mut usize| -> Result<String> {
+            let length = *buffer.get(*cursor).context("Entrée tronquée")? as usize;
+            let bytes = buffer.get(*cursor + 1..*cursor + 1 + length).context("Entrée tronquée")?;
+            *cursor += 1 + length;
+            Ok(WINDOWS_1252.decode(bytes).0.trim_end_matches('\0').to_string())
+        };
+        let user = read_text(&mut cursor)?;
+        let station = read_text(&mut cursor)?;
+
+        let read_image = |present: bool, cursor: &mut usize| -> Result<Option<Vec<u8>>> {
+            if !present {
+                return Ok(None);
+            }
+            let image = buffer.get(*cursor..*cursor + image_length)
+                .context("Image d'enregistrement tronquée")?
+                .to_vec();
+            *cursor += image_length;
+            Ok(Some(image))
+        };
+        let before = read_image(flags & ENTRY_HAS_BEFORE != 0, &mut cursor)
+            .with_context(|| format!("Entrée {} (offset {})", sequence, position))?;
+        let after = read_image(flags & ENTRY_HAS_AFTER != 0, &mut cursor)
+            .with_context(|| format!("Entrée {} (offset {})", sequence, position))?;
+
+        Ok((
+            JournalEntry {
+                sequence,
+                operation,
+                timestamp,
+                record_id,
+                user,
+                station,
+                before,
+                after,
+            },
+            position + entry_length as u64,
+        ))
+    }
+}
+
+/// Itérateur sur les entrées d'un journal
+pub struct JnlEntries<'a> {
+    /// Journal parcouru
+    jnl: &'a mut JnlFile,
+    /// Offset de la prochaine entrée
+    position: u64,
+    /// Numéro de la prochaine entrée
+    sequence: u32,
+    /// Parcours terminé (fin de fichier ou erreur)
+    done: bool,
+}
+
+impl<'a> Iterator for JnlEntries<'a> {
+    type Item = Result<JournalEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done
+            || self.sequence >= self.jnl.header.entry_count
+            || self.position + 4 > self.jnl.file_size
+        {
+            return None;
+        }
+
+        match self.jnl.read_entry(self.position, self.sequence) {
+            Ok((entry, next)) => {
+                self.position = next;
+                self.sequence += 1;
+                Some(Ok(entry))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/**
+ * Convertit une date saisie en secondes depuis 1970-01-01 UTC.
+ *
+ * Formats acceptés : "AAAA-MM-JJ", "AAAA-MM-JJTHH:MM:SS" ou
+ * "AAAA-MM-JJ HH:MM:SS".
+ *
+ * @param value - Date saisie
+ * @returns Result<i64> - Timestamp Unix ou erreur de format
+ *
+ * Effets de bord : Aucun
+ */
+pub fn parse_timestamp(value: &str) -> Result<i64> {
+    let value = value.trim();
+    let (date, time) = match value.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        anyhow::bail!("Date invalide: {} (attendu: AAAA-MM-JJ)", value);
+    };
+    let year: i64 = year.parse().with_context(|| format!("Année invalide: {}", value))?;
+    let month: u32 = month.parse().with_context(|| format!("Mois invalide: {}", value))?;
+    let day: u32 = day.parse().with_context(|| format!("Jour invalide: {}", value))?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        anyhow::bail!("Date invalide: {}", value);
+    }
+
+    let seconds = match time {
+        Some(time) => {
+            let fields: Vec<u32> = time.split(':')
+                .map(|f| f.parse::<u32>())
+                .collect::<std::result::Result<_, _>>()
+                .with_context(|| format!("Heure invalide: {}", value))?;
+            match fields.as_slice() {
+                [h, m] if *h < 24 && *m < 60 => (h * 3600 + m * 60) as i64,
+                [h, m, s] if *h < 24 && *m < 60 && *s < 60 => (h * 3600 + m * 60 + s) as i64,
+                _ => anyhow::bail!("Heure invalide: {}", value),
+            }
+        }
+        None => 0,
+    };
+
+    Ok(days_from_civil(year, month, day) * 86400 + seconds)
+}
+
+/**
+ * Formate un timestamp Unix en "AAAA-MM-JJ HH:MM:SS" (UTC).
+ *
+ * @param timestamp - Secondes depuis 1970-01-01 UTC
+ * @returns String - Date formatée
+ *
+ * Effets de bord : Aucun
+ */
+pub fn format_timestamp(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, seconds / 3600, (seconds % 3600) / 60, seconds % 60
+    )
+}
+
+/// Nombre de jours depuis 1970-01-01 pour une date du calendrier grégorien
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Date du calendrier grégorien pour un nombre de jours depuis 1970-01-01
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn entry(operation: u8, timestamp: i64, record_id: u32, before: Option<&[u8]>, after: Option<&[u8]>) -> Vec<u8> {
+        let mut body = vec![operation, 0, 0, 0];
+        if before.is_some() {
+            body[1] |= ENTRY_HAS_BEFORE;
+        }
+        if after.is_some() {
+            body[1] |= ENTRY_HAS_AFTER;
+        }
+        body.extend_from_slice(&timestamp.to_le_bytes());
+        body.extend_from_slice(&record_id.to_le_bytes());
+        body.push(5);
+        body.extend_from_slice(b"admin");
+        body.push(4);
+        body.extend_from_slice(b"PC01");
+        body.extend_from_slice(before.unwrap_or_default());
+        body.extend_from_slice(after.unwrap_or_default());
+
+        let mut data = ((body.len() + 4) as u32).to_le_bytes().to_vec();
+        data.extend(body);
+        data
+    }
+
+    fn create_test_jnl_file() -> Result<NamedTempFile> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"JNL\0");
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&4u32.to_le_bytes()); // Taille des images
+        data.extend_from_slice(&3u32.to_le_bytes()); // Nombre d'entrées
+        data.extend_from_slice(&0x14u32.to_le_bytes()); // Offset des entrées
+        data.extend(entry(1, 1_760_000_000, 7, None, Some(b"abcd")));
+        data.extend(entry(2, 1_760_000_100, 7, Some(b"abcd"), Some(b"abce")));
+        data.extend(entry(3, 1_760_000_200, 7, Some(b"abce"), None));
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&data)?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_read_entries() -> Result<()> {
+        let test_file = create_test_jnl_file()?;
+        let mut jnl = JnlFile::open(test_file.path())?;
+        assert_eq!(jnl.header().entry_count, 3);
+
+        let entries = jnl.entries()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].operation, JournalOperation::Update);
+        assert_eq!(entries[1].user, "admin");
+        assert_eq!(entries[1].station, "PC01");
+        assert_eq!(entries[1].before.as_deref(), Some(&b"abcd"[..]));
+        assert_eq!(entries[1].after.as_deref(), Some(&b"abce"[..]));
+        assert_eq!(entries[2].operation, JournalOperation::Delete);
+        assert!(entries[2].after.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_timestamps() -> Result<()> {
+        assert_eq!(parse_timestamp("1970-01-01")?, 0);
+        assert_eq!(parse_timestamp("2024-02-29T12:30:00")?, 1_709_209_800);
+        assert_eq!(format_timestamp(1_709_209_800), "2024-02-29 12:30:00");
+        assert!(parse_timestamp("2024-13-01").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+/**
+ * Encodage et décodage des clés d'index HFSQL.
+ *
+ * Les clés des fichiers .ndx sont stockées sous une forme comparable octet
+ * par octet. Ce fichier contient les fonctions qui transforment une valeur
+ * (saisie par l'utilisateur ou lue dans un enregistrement) vers cette forme,
+ * et l'opération inverse pour afficher une clé lue dans un index :
+ *
+ * - Entiers signés : big-endian avec bit de signe inversé
+ * - Entiers non signés : big-endian
+ * - Réels : représentation IEEE 754 rendue triable
+ * - Chaînes : dans l'encodage de la rubrique (Windows-1252 par défaut),
+ *   complétées par des zéros, éventuellement converties en majuscules et/ou
+ *   sans accents selon les options du segment
+ * - Segments descendants : tous les octets sont inversés
+ *
+ * Le décodage des chaînes est exact sauf pour les segments insensibles à la
+ * casse ou aux accents : la clé ne contient alors que la forme transformée
+ * (ex: "ELEVE" pour "Élève"), signalée par DecodedSegment::folded.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/ndx.rs pour les descripteurs de segments
+ * - Utilisé par src/storage/planner.rs pour construire les bornes de recherche
+ * - Utilisé par src/cli/commands.rs pour afficher les clés d'un index
+ * - Utilisé par src/storage/engine.rs pour reconstruire les index (reindex)
+ */
+
+use crate::core::ndx::{KeyKind, KeySegment};
+use crate::core::{IndexDefinition, TextEncoding};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Valeur typée d'un segment de clé décodé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum KeyValue {
+    /// Chaîne de caractères (zéros de fin retirés)
+    String(String),
+    /// Entier signé
+    Integer(i64),
+    /// Entier non signé
+    UnsignedInteger(u64),
+    /// Nombre décimal
+    Float(f64),
+    /// Date au format AAAA-MM-JJ
+    Date(String),
+    /// Données binaires (hexadécimal)
+    Binary(String),
+}
+
+impl fmt::Display for KeyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyValue::String(s) => write!(f, "\"{}\"", s),
+            KeyValue::Integer(i) => write!(f, "{}", i),
+            KeyValue::UnsignedInteger(u) => write!(f, "{}", u),
+            KeyValue::Float(x) => write!(f, "{}", x),
+            KeyValue::Date(d) => write!(f, "{}", d),
+            KeyValue::Binary(h) => write!(f, "0x{}", h),
+        }
+    }
+}
+
+/// Segment d'une clé décodé et rattaché à sa rubrique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedSegment {
+    /// Rubrique couverte par le segment (None si aucune ne correspond)
+    pub field: Option<String>,
+    /// Valeur décodée
+    pub value: KeyValue,
+    /// La valeur est une forme transformée (majuscules et/ou sans accents)
+    /// et peut différer de la valeur stockée dans l'enregistrement
+    pub folded: bool,
+}
+
+/**
+ * Transforme les bytes bruts d'une rubrique en segment de clé triable.
+ *
+ * @param raw - Bytes de la rubrique tels que stockés dans l'enregistrement
+ * @param segment - Descripteur du segment de clé
+ * @param encoding - Encodage des textes de la rubrique
+ * @returns Vec<u8> - Segment de clé de longueur segment.length
+ *
+ * Effets de bord : Aucun
+ */
+pub fn encode_segment(raw: &[u8], segment: &KeySegment, encoding: TextEncoding) -> Vec<u8> {
+    let mut key = match segment.kind {
+        KeyKind::Integer => {
+            let mut bytes: Vec<u8> = raw.iter().rev().copied().collect();
+            if let Some(first) = bytes.first_mut() {
+                *first ^= 0x80;
+            }
+            bytes
+        }
+        KeyKind::UnsignedInteger => raw.iter().rev().copied().collect(),
+        KeyKind::Float => sortable_float(raw),
+        KeyKind::String => fold_text(raw, segment.is_case_insensitive(), segment.is_accent_insensitive(), encoding),
+        KeyKind::Date | KeyKind::Binary | KeyKind::Unknown(_) => raw.to_vec(),
+    };
+
+    key.resize(segment.length as usize, 0);
+
+    if segment.is_descending() {
+        for byte in key.iter_mut() {
+            *byte = !*byte;
+        }
+    }
+
+    key
+}
+
+/**
+ * Construit la clé d'index d'un enregistrement à partir de ses données brutes.
+ *
+ * Les offsets des segments incluent le byte de flags de l'enregistrement,
+ * absent de FicRecord::data. Les rubriques qui dépassent de l'enregistrement
+ * sont complétées par des zéros.
+ *
+ * @param data - Données de l'enregistrement (sans le byte de flags)
+ * @param segments - Descripteurs des segments de la clé
+ * @param encodings - Encodage de chaque segment (auto si absent)
+ * @returns Vec<u8> - Clé sous sa forme stockée
+ *
+ * Effets de bord : Aucun
+ */
+pub fn record_key(data: &[u8], segments: &[KeySegment], encodings: &[TextEncoding]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(segments.iter().map(|s| s.length as usize).sum());
+    for (i, segment) in segments.iter().enumerate() {
+        let start = segment.offset as usize;
+        let end = start + segment.length as usize;
+        let raw: Vec<u8> = (start..end)
+            .map(|position| match position {
+                0 => 0,
+                _ => data.get(position - 1).copied().unwrap_or(0),
+            })
+            .collect();
+        key.extend(encode_segment(&raw, segment, encodings.get(i).copied().unwrap_or_default()));
+    }
+    key
+}
+
+/**
+ * Encode une valeur textuelle (ex: valeur d'une clause WHERE) en segment de clé.
+ *
+ * La valeur est d'abord convertie dans la représentation binaire de la
+ * rubrique (entier little-endian, chaîne Windows-1252, ...), puis transformée
+ * avec encode_segment.
+ *
+ * @param value - Valeur à encoder
+ * @param segment - Descripteur du segment de clé
+ * @returns Result<Vec<u8>> - Segment de clé ou erreur si la valeur n'est pas convertible
+ *
+ * Effets de bord : Aucun
+ */
+pub fn encode_value(value: &str, segment: &KeySegment) -> Result<Vec<u8>> {
+    encode_value_as(value, segment, TextEncoding::Auto)
+}
+
+/**
+ * Encode une valeur en segment de clé, les chaînes dans l'encodage de la rubrique.
+ *
+ * @param value - Valeur à encoder
+ * @param segment - Descripteur du segment de clé
+ * @param encoding - Encodage des textes de la rubrique indexée
+ * @returns Result<Vec<u8>> - Segment de clé ou erreur si la valeur n'est pas convertible
+ */
+pub fn encode_value_as(value: &str, segment: &KeySegment, encoding: TextEncoding) -> Result<Vec<u8>> {
+    let length = segment.length as usize;
+    let raw = match segment.kind {
+        KeyKind::Integer => {
+            let parsed: i64 = value.trim().parse()
+                .with_context(|| format!("Valeur entière invalide pour la clé: {}", value))?;
+            let bytes = parsed.to_le_bytes();
+            let fits = length >= 8 || (length > 0 && {
+                let bits = (length * 8) as u32;
+                let min = -(1i64 << (bits - 1));
+                let max = (1i64 << (bits - 1)) - 1;
+                (min..=max).contains(&parsed)
+            });
+            if !fits {
+                anyhow::bail!("Valeur {} hors limites pour une clé entière de {} bytes", parsed, length);
+            }
+            bytes[..length.min(8)].to_vec()
+        }
+        KeyKind::UnsignedInteger => {
+            let parsed: u64 = value.trim().parse()
+                .with_context(|| format!("Valeur entière non signée invalide pour la clé: {}", value))?;
+            if length == 0 || (length < 8 && parsed >> (length * 8) != 0) {
+                anyhow::bail!("Valeur {} hors limites pour une clé non signée de {} bytes", parsed, length);
+            }
+            parsed.to_le_bytes()[..length.min(8)].to_vec()
+        }
+        KeyKind::Float => {
+            let parsed: f64 = value.trim().parse()
+                .with_context(|| format!("Valeur décimale invalide pour la clé: {}", value))?;
+            match length {
+                4 => (parsed as f32).to_le_bytes().to_vec(),
+                8 => parsed.to_le_bytes().to_vec(),
+                _ => anyhow::bail!("Longueur {} non supportée pour une clé réelle", length),
+            }
+        }
+        KeyKind::Binary => hex::decode(value.trim())
+            .with_context(|| format!("Valeur hexadécimale invalide pour la clé: {}", value))?,
+        KeyKind::String | KeyKind::Date | KeyKind::Unknown(_) => encoding.encode(value),
+    };
+
+    Ok(encode_segment(&raw, segment, encoding))
+}
+
+/**
+ * Décode une clé d'index en valeurs typées, segment par segment.
+ *
+ * @param key - Clé telle que stockée dans le fichier .ndx
+ * @param segments - Descripteurs des segments de la clé
+ * @param encodings - Encodage de chaque segment (auto si absent)
+ * @returns Result<Vec<KeyValue>> - Une valeur par segment, ou erreur si la
+ *          clé est plus courte que la somme des segments
+ *
+ * Effets de bord : Aucun
+ */
+pub fn decode_key(key: &[u8], segments: &[KeySegment], encodings: &[TextEncoding]) -> Result<Vec<KeyValue>> {
+    let mut values = Vec::with_capacity(segments.len());
+    let mut position = 0usize;
+    for (i, segment) in segments.iter().enumerate() {
+        let end = position + segment.length as usize;
+        let bytes = key.get(position..end).with_context(|| format!(
+            "Clé trop courte ({} bytes) pour le segment {} (bytes {}..{})",
+            key.len(), i, position, end
+        ))?;
+        values.push(decode_segment(bytes, segment, encodings.get(i).copied().unwrap_or_default()));
+        position = end;
+    }
+    Ok(values)
+}
+
+/**
+ * Décode une clé d'index et associe chaque segment à sa rubrique.
+ *
+ * @param key - Clé telle que stockée dans le fichier .ndx
+ * @param index - Définition de l'index (segments et rubriques couvertes)
+ * @returns Result<Vec<DecodedSegment>> - Segments décodés
+ *
+ * Effets de bord : Aucun
+ */
+pub fn decode_index_key(key: &[u8], index: &IndexDefinition) -> Result<Vec<DecodedSegment>> {
+    let values = decode_key(key, &index.segments, &index.encodings)?;
+    Ok(values.into_iter()
+        .zip(&index.segments)
+        .enumerate()
+        .map(|(i, (value, segment))| DecodedSegment {
+            field: index.fields.get(i).cloned().flatten(),
+            folded: segment.kind == KeyKind::String
+                && (segment.is_case_insensitive() || segment.is_accent_insensitive()),
+            value,
+        })
+        .collect())
+}
+
+/**
+ * Décode un segment de clé (opération inverse de encode_segment).
+ *
+ * Les longueurs non standard (ex: réel sur 3 bytes) sont rendues en binaire.
+ */
+fn decode_segment(bytes: &[u8], segment: &KeySegment, encoding: TextEncoding) -> KeyValue {
+    let bytes: Vec<u8> = if segment.is_descending() {
+        bytes.iter().map(|b| !b).collect()
+    } else {
+        bytes.to_vec()
+    };
+
+    match segment.kind {
+        KeyKind::Integer if (1..=8).contains(&bytes.len()) => {
+            let mut value = (bytes[0] ^ 0x80) as i8 as i64;
+            for &b in &bytes[1..] {
+                value = (value << 8) | b as i64;
+            }
+            KeyValue::Integer(value)
+        }
+        KeyKind::UnsignedInteger if (1..=8).contains(&bytes.len()) => {
+            KeyValue::UnsignedInteger(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+        }
+        KeyKind::Float if bytes.len() == 4 || bytes.len() == 8 => {
+            let mut raw = bytes.clone();
+            if raw[0] & 0x80 != 0 {
+                raw[0] ^= 0x80;
+            } else {
+                for byte in raw.iter_mut() {
+                    *byte = !*byte;
+                }
+            }
+            if raw.len() == 4 {
+                KeyValue::Float(f32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64)
+            } else {
+                let mut array = [0u8; 8];
+                array.copy_from_slice(&raw);
+                KeyValue::Float(f64::from_be_bytes(array))
+            }
+        }
+        KeyKind::String => {
+            let end = bytes.iter().rposition(|&b| b != 0).map(|p| p + 1).unwrap_or(0);
+            KeyValue::String(encoding.decode(&bytes[..end]).0)
+        }
+        KeyKind::Date if bytes.len() == 8 && bytes.iter().all(u8::is_ascii_digit) => {
+            let text = String::from_utf8_lossy(&bytes);
+            KeyValue::Date(format!("{}-{}-{}", &text[0..4], &text[4..6], &text[6..8]))
+        }
+        _ => KeyValue::Binary(hex::encode(&bytes)),
+    }
+}
+
+/**
+ * Rend une représentation IEEE 754 little-endian triable octet par octet.
+ *
+ * Les nombres positifs ont leur bit de signe inversé, les négatifs
+ * ont tous leurs bits inversés.
+ */
+fn sortable_float(raw: &[u8]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = raw.iter().rev().copied().collect();
+    let negative = bytes.first().map(|b| b & 0x80 != 0).unwrap_or(false);
+    if negative {
+        for byte in bytes.iter_mut() {
+            *byte = !*byte;
+        }
+    } else if let Some(first) = bytes.first_mut() {
+        *first ^= 0x80;
+    }
+    bytes
+}
+
+/**
+ * Applique les transformations de texte HFSQL (sans accents, majuscules)
+ * à une chaîne stockée dans l'encodage de sa rubrique.
+ *
+ * Les encodages mono-octet sont transformés octet par octet (la longueur
+ * est conservée) : un caractère dont la forme transformée n'existe pas dans
+ * l'encodage est laissé tel quel. En UTF-8, les séquences invalides (ex:
+ * caractère coupé en fin de rubrique) sont recopiées sans changement.
+ *
+ * @param raw - Texte encodé
+ * @param case_insensitive - Conversion en majuscules
+ * @param accent_insensitive - Suppression des diacritiques
+ * @param encoding - Encodage du texte
+ * @returns Vec<u8> - Texte transformé, dans le même encodage
+ */
+pub(crate) fn fold_text(raw: &[u8], case_insensitive: bool, accent_insensitive: bool, encoding: TextEncoding) -> Vec<u8> {
+    if !case_insensitive && !accent_insensitive {
+        return raw.to_vec();
+    }
+    let fold = |c: char| {
+        let c = if accent_insensitive { strip_accent(c) } else { c };
+        if case_insensitive { to_upper(c) } else { c }
+    };
+
+    if encoding == TextEncoding::Utf8 {
+        let mut folded = Vec::with_capacity(raw.len());
+        for chunk in raw.utf8_chunks() {
+            folded.extend(chunk.valid().chars().map(fold).collect::<String>().into_bytes());
+            folded.extend_from_slice(chunk.invalid());
+        }
+        return folded;
+    }
+
+    raw.iter()
+        .map(|&b| {
+            if b.is_ascii() {
+                return if case_insensitive { b.to_ascii_uppercase() } else { b };
+            }
+            let (decoded, _) = encoding.decode(&[b]);
+            let mut chars = decoded.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return b;
+            };
+            let folded = fold(c);
+            if folded == c {
+                return b;
+            }
+            match encoding.encode(folded.encode_utf8(&mut [0u8; 4])).as_slice() {
+                [byte] if *byte != b'?' => *byte,
+                _ => b,
+            }
+        })
+        .collect()
+}
+
+/// Convertit un caractère en majuscule (inchangé si la majuscule tient sur plusieurs caractères)
+fn to_upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+/// Retire le diacritique d'une lettre latine (la casse est conservée)
+fn strip_accent(c: char) -> char {
+    match c {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' => 'A',
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'Ç' | 'Ć' | 'Č' => 'C',
+        'ç' | 'ć' | 'č' => 'c',
+        'Ď' | 'Đ' => 'D',
+        'ď' | 'đ' => 'd',
+        'È'..='Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => 'E',
+        'è'..='ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'Ì'..='Ï' | 'Ī' | 'Į' | 'İ' => 'I',
+        'ì'..='ï' | 'ī' | 'į' | 'ı' => 'i',
+        'Ĺ' | 'Ľ' | 'Ł' => 'L',
+        'ĺ' | 'ľ' | 'ł' => 'l',
+        'Ñ' | 'Ń' | 'Ň' => 'N',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'Ò'..='Ö' | 'Ø' | 'Ő' => 'O',
+        'ò'..='ö' | 'ø' | 'ő' => 'o',
+        'Ŕ' | 'Ř' => 'R',
+        'ŕ' | 'ř' => 'r',
+        'Ś' | 'Ş' | 'Š' => 'S',
+        'ś' | 'ş' | 'š' => 's',
+        'Ţ' | 'Ť' => 'T',
+        'ţ' | 'ť' => 't',
+        'Ù'..='Ü' | 'Ů' | 'Ű' => 'U',
+        'ù'..='ü' | 'ů' | 'ű' => 'u',
+        'Ý' | 'Ÿ' => 'Y',
+        'ý' | 'ÿ' => 'y',
+        'Ź' | 'Ż' | 'Ž' => 'Z',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ndx::{SEGMENT_ACCENT_INSENSITIVE, SEGMENT_CASE_INSENSITIVE, SEGMENT_DESCENDING};
+
+    fn segment(kind: KeyKind, length: u32, flags: u8) -> KeySegment {
+        KeySegment { offset: 0, length, kind, flags }
+    }
+
+    #[test]
+    fn test_decode_composite_key() -> Result<()> {
+        let segments = vec![
+            segment(KeyKind::Integer, 2, SEGMENT_DESCENDING),
+            segment(KeyKind::String, 6, 0),
+            segment(KeyKind::Float, 8, 0),
+        ];
+        let mut key = encode_value("-300", &segments[0])?;
+        key.extend(encode_value("Lyon", &segments[1])?);
+        key.extend(encode_value("-2.5", &segments[2])?);
+
+        let values = decode_key(&key, &segments, &[])?;
+        assert_eq!(values, vec![
+            KeyValue::Integer(-300),
+            KeyValue::String("Lyon".to_string()),
+            KeyValue::Float(-2.5),
+        ]);
+        assert!(decode_key(&key[..10], &segments, &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_folded_string() -> Result<()> {
+        let index = IndexDefinition {
+            name: "CLIENT.NDX".to_string(),
+            path: "CLIENT.NDX".into(),
+            fields: vec![Some("nom".to_string())],
+            segments: vec![segment(KeyKind::String, 8, SEGMENT_CASE_INSENSITIVE | SEGMENT_ACCENT_INSENSITIVE)],
+            encodings: Vec::new(),
+            unique: false,
+        };
+        let key = encode_value("Élève", &index.segments[0])?;
+        let decoded = decode_index_key(&key, &index)?;
+        assert_eq!(decoded[0].field.as_deref(), Some("nom"));
+        assert_eq!(decoded[0].value, KeyValue::String("ELEVE".to_string()));
+        assert!(decoded[0].folded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_keys_follow_encoding() -> Result<()> {
+        let folded = segment(KeyKind::String, 8, SEGMENT_CASE_INSENSITIVE | SEGMENT_ACCENT_INSENSITIVE);
+        for encoding in [TextEncoding::Windows1252, TextEncoding::Cp850, TextEncoding::Utf8] {
+            let key = encode_value_as("Élève", &folded, encoding)?;
+            assert_eq!(&key[..5], b"ELEVE", "{}", encoding);
+        }
+        // Majuscule hors de l'encodage (µ -> Μ) : octet conservé
+        assert_eq!(fold_text(&[0xFF, 0xB5], true, false, TextEncoding::Windows1252), vec![0x9F, 0xB5]);
+        // UTF-8 coupé en fin de rubrique : octets invalides recopiés
+        assert_eq!(fold_text(&[b'e', 0xC3], true, false, TextEncoding::Utf8), vec![b'E', 0xC3]);
+
+        let exact = segment(KeyKind::String, 6, 0);
+        let key = encode_value_as("Gérard", &exact, TextEncoding::Cp850)?;
+        assert_eq!(key[1], 0x82);
+        assert_eq!(decode_key(&key, &[exact], &[TextEncoding::Cp850])?, vec![KeyValue::String("Gérard".to_string())]);
+        Ok(())
+    }
+}
@@ -12,7 +12,8 @@
  * 
  * Fonctionnalités :
  * - Lecture de blocs mémo par offset
- * - Décodage automatique en texte (Windows-1252 puis UTF-8)
+ * - Détection des blocs binaires (signature connue ou octets de contrôle)
+ * - Décodage automatique en texte (UTF-8 puis Windows-1252)
  * - Lecture de données brutes
  * 
  * Liens avec d'autres modules :
@@ -20,11 +21,11 @@
  * - Les offsets sont fournis par les enregistrements FicRecord
  */
 
+use crate::core::TextEncoding;
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
-use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use crate::core::source::{self, SourceFile};
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
@@ -39,6 +40,42 @@ pub struct MmoBlock {
     pub data: Vec<u8>,
     /// Texte décodé (si le bloc contient du texte)
     pub text: Option<String>,
+    /// Type MIME d'un bloc binaire (None pour un texte)
+    #[serde(default)]
+    pub mime: Option<String>,
+}
+
+/// Signatures des formats binaires courants dans les mémos
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\xd0\xcf\x11\xe0", "application/x-ole-storage"),
+];
+
+/**
+ * Détermine si un bloc mémo est binaire et son type MIME.
+ *
+ * Un bloc est binaire s'il commence par une signature connue, ou s'il n'est
+ * pas de l'UTF-8 valide et contient des octets de contrôle (hors tabulation
+ * et fins de ligne), absents d'un texte Windows-1252.
+ *
+ * @param data - Données du bloc
+ * @returns Option<&'static str> - Type MIME si le bloc est binaire
+ */
+pub fn detect_binary(data: &[u8]) -> Option<&'static str> {
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(signature, _)| data.starts_with(signature)) {
+        return Some(mime);
+    }
+    let control = |b: &u8| *b < 0x20 && !matches!(*b, b'\t' | b'\n' | b'\r');
+    if std::str::from_utf8(data).is_err() && data.iter().any(control) {
+        return Some("application/octet-stream");
+    }
+    None
 }
 
 /// Gestionnaire de fichier .mmo permettant la lecture des blocs mémo
@@ -47,7 +84,7 @@ pub struct MmoFile {
     #[allow(dead_code)]
     path: std::path::PathBuf,
     /// Handle du fichier ouvert
-    file: Option<File>,
+    file: Option<SourceFile>,
 }
 
 impl MmoFile {
@@ -62,7 +99,7 @@ impl MmoFile {
      */
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let file = File::open(&path)
+        let file = source::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
 
         Ok(Self {
@@ -85,6 +122,20 @@ impl MmoFile {
      * - Lit depuis le fichier (position modifiée)
      */
     pub fn read_block(&mut self, offset: u32) -> Result<MmoBlock> {
+        self.read_block_as(offset, TextEncoding::Auto)
+    }
+
+    /**
+     * Lit un bloc mémo et décode son texte dans l'encodage de la rubrique.
+     *
+     * @param offset - Offset du bloc dans le fichier (en bytes)
+     * @param encoding - Encodage du texte (auto : UTF-8 valide, sinon Windows-1252)
+     * @returns Result<MmoBlock> - Bloc mémo lu ou erreur
+     *
+     * Effets de bord :
+     * - Lit depuis le fichier (position modifiée)
+     */
+    pub fn read_block_as(&mut self, offset: u32, encoding: TextEncoding) -> Result<MmoBlock> {
         let file = self.file.as_mut()
             .context("Fichier non ouvert")?;
 
@@ -98,13 +149,12 @@ impl MmoFile {
         let mut data = vec![0u8; length as usize];
         file.read_exact(&mut data)?;
 
-        // Tentative de décodage en texte (UTF-8 ou Windows-1252)
-        let text = if let Ok(utf8_str) = std::str::from_utf8(&data) {
-            Some(utf8_str.to_string())
+        // Décodage en texte sauf bloc binaire
+        let mime = detect_binary(&data);
+        let text = if mime.is_some() {
+            None
         } else {
-            // Essayer Windows-1252 (CP1252) pour les fichiers français avec accents
-            let (decoded, _, _) = WINDOWS_1252.decode(&data);
-            Some(decoded.into_owned())
+            Some(encoding.decode_memo(&data))
         };
 
         Ok(MmoBlock {
@@ -112,6 +162,7 @@ impl MmoFile {
             length,
             data,
             text,
+            mime: mime.map(str::to_string),
         })
     }
 
@@ -150,3 +201,18 @@ impl Drop for MmoFile {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_binary() {
+        assert_eq!(detect_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        assert_eq!(detect_binary(b"%PDF-1.4\n"), Some("application/pdf"));
+        assert_eq!(detect_binary(&[0x01, 0xe9, 0x00, 0x02]), Some("application/octet-stream"));
+        // Texte Windows-1252 (é = 0xe9) et UTF-8
+        assert_eq!(detect_binary(b"Client r\xe9gulier\r\n"), None);
+        assert_eq!(detect_binary("à rappeler".as_bytes()), None);
+    }
+}
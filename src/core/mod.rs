@@ -7,6 +7,13 @@
  * - fic.rs : Gestion des fichiers .fic (données principales)
  * - mmo.rs : Gestion des fichiers .mmo (données mémo/blobs)
  * - ndx.rs : Gestion des fichiers .ndx (index)
+ * - ftx.rs : Gestion des fichiers .ftx (index full-text)
+ * - jnl.rs : Gestion des fichiers journal .jnl (historique des modifications)
+ * - rep.rs : Lecture des fichiers .rep (emplacement des tables d'un projet)
+ * - key.rs : Encodage et décodage des clés d'index (forme triable)
+ * - encoding.rs : Encodages des textes (pages de code, configuration, détection)
+ * - source.rs : Ouverture des fichiers sur disque ou dans une archive
+ * - archive.rs : Lecture des archives ZIP et tar (sauvegardes)
  * 
  * Il définit également les structures de schéma (TableSchema, FieldInfo)
  * utilisées pour représenter la structure des tables.
@@ -14,18 +21,37 @@
  * Exports :
  * - FicFile, FicHeader, FicRecord : Structures pour les fichiers .fic
  * - MmoFile, MmoBlock : Structures pour les fichiers .mmo
- * - NdxFile, NdxEntry : Structures pour les fichiers .ndx
- * - TableSchema, FieldInfo, FieldType : Structures de schéma
+ * - NdxFile, NdxEntry, KeySegment : Structures pour les fichiers .ndx
+ * - KeyValue, DecodedSegment : Clés d'index décodées
+ * - FtxFile, FullTextHit : Structures pour les index full-text
+ * - JnlFile, JournalEntry, JournalOperation : Structures pour les journaux
+ * - RepFile, RepEntry : Tables déclarées dans un fichier .rep
+ * - TableSchema, FieldInfo, FieldType, IndexDefinition, FullTextDefinition : Structures de schéma
  * - TableFiles : Représentation d'un ensemble de fichiers liés
+ * - TextEncoding, EncodingConfig, EncodingScore : Encodages des textes
+ * - SourceFile : Fichier ouvert sur disque ou dans une archive
  */
 
+pub mod archive;
+pub mod encoding;
 pub mod fic;
+pub mod ftx;
+pub mod jnl;
+pub mod key;
 pub mod mmo;
 pub mod ndx;
+pub mod rep;
+pub mod source;
 
+pub use encoding::{EncodingConfig, EncodingScore, TextEncoding};
 pub use fic::{FicFile, FicHeader, FicRecord};
+pub use ftx::{FtxFile, FullTextHit};
+pub use jnl::{JnlFile, JournalEntry, JournalOperation};
+pub use key::{DecodedSegment, KeyValue};
 pub use mmo::{MmoFile, MmoBlock};
-pub use ndx::{NdxFile, NdxEntry};
+pub use ndx::{KeyKind, KeySegment, NdxEntry, NdxFile, NdxHeader};
+pub use rep::{RepEntry, RepFile};
+pub use source::SourceFile;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -41,6 +67,44 @@ pub struct TableSchema {
     pub field_count: u32,
     /// Liste des champs avec leurs métadonnées
     pub fields: Vec<FieldInfo>,
+    /// Index (.ndx) disponibles pour la table
+    #[serde(default)]
+    pub indexes: Vec<IndexDefinition>,
+    /// Index full-text (.ftx) disponibles pour la table
+    #[serde(default)]
+    pub full_text: Vec<FullTextDefinition>,
+}
+
+/// Définition d'un index de la table, lue depuis le header du fichier .ndx
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    /// Nom de l'index (nom du fichier .ndx)
+    pub name: String,
+    /// Chemin vers le fichier .ndx
+    pub path: PathBuf,
+    /// Rubriques du schéma couvertes par chaque segment (None si aucune ne correspond)
+    pub fields: Vec<Option<String>>,
+    /// Segments composant la clé
+    pub segments: Vec<KeySegment>,
+    /// Encodage des textes de chaque segment (celui de sa rubrique, auto à défaut)
+    #[serde(default)]
+    pub encodings: Vec<TextEncoding>,
+    /// Index sans doublons
+    pub unique: bool,
+}
+
+/// Définition d'un index full-text de la table, lue depuis le header du fichier .ftx
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullTextDefinition {
+    /// Nom de l'index (nom du fichier .ftx)
+    pub name: String,
+    /// Chemin vers le fichier .ftx
+    pub path: PathBuf,
+    /// Rubriques du schéma couvertes par l'index (None si aucune ne correspond)
+    pub fields: Vec<Option<String>>,
+    /// Encodage des mots du dictionnaire (celui des rubriques indexées)
+    #[serde(default)]
+    pub encoding: TextEncoding,
 }
 
 /// Informations sur un champ d'une table
@@ -54,6 +118,9 @@ pub struct FieldInfo {
     pub length: u32,
     /// Type de données du champ
     pub field_type: FieldType,
+    /// Encodage des textes de la rubrique (résolu depuis la configuration)
+    #[serde(default)]
+    pub encoding: TextEncoding,
 }
 
 /// Types de données supportés pour les champs
@@ -80,11 +147,38 @@ pub enum FieldType {
 pub struct TableFiles {
     /// Nom de la table
     pub name: String,
+    /// Base de données (dossier) contenant la table, vide pour la base racine
+    pub database: String,
     /// Chemin vers le fichier .fic principal
     pub fic_path: PathBuf,
     /// Chemin vers le fichier .mmo (optionnel)
     pub mmo_path: Option<PathBuf>,
     /// Chemins vers les fichiers d'index .ndx (peut y en avoir plusieurs)
     pub ndx_paths: Vec<PathBuf>,
+    /// Chemins vers les fichiers d'index full-text .ftx
+    pub ftx_paths: Vec<PathBuf>,
+    /// Chemins vers les fichiers journal .jnl (dossier de données ou sous-dossier JNL)
+    pub journal_paths: Vec<PathBuf>,
+    /// Segments de continuation (.fi1, .mm1, .nd1…) des fichiers de plus de 2 Go, non lus
+    pub segment_paths: Vec<PathBuf>,
+    /// Noms des fichiers compagnons attendus mais absents (.mmo ou .ndx dont des segments existent, trous de numérotation)
+    pub missing: Vec<String>,
+}
+
+impl TableFiles {
+    /**
+     * Liste tous les fichiers trouvés pour la table, .fic compris.
+     *
+     * @returns Vec<&PathBuf> - Chemins dans l'ordre fic, mmo, ndx, ftx, jnl, segments
+     */
+    pub fn found_paths(&self) -> Vec<&PathBuf> {
+        std::iter::once(&self.fic_path)
+            .chain(self.mmo_path.iter())
+            .chain(self.ndx_paths.iter())
+            .chain(self.ftx_paths.iter())
+            .chain(self.journal_paths.iter())
+            .chain(self.segment_paths.iter())
+            .collect()
+    }
 }
 
@@ -1,33 +1,74 @@
 /**
  * Gestionnaire de fichiers .ndx (fichiers d'index HFSQL).
- * 
- * Ce fichier contient les structures et fonctions pour lire les fichiers .ndx
- * qui contiennent des index permettant de rechercher rapidement des enregistrements
+ *
+ * Ce fichier contient les structures et fonctions pour lire et reconstruire
+ * les fichiers .ndx qui contiennent des index permettant de rechercher rapidement des enregistrements
  * par clé dans les fichiers .fic.
- * 
- * Structure d'un fichier .ndx (simplifiée) :
- * - Header : Magic bytes, nombre d'entrées, longueur de clé
- * - Entrées : Liste de (clé, record_id) permettant de mapper une clé à un ID d'enregistrement
- * 
+ *
+ * Structure d'un fichier .ndx :
+ * - Page 0 : Header (magic, taille de page, page racine, descripteurs de clé)
+ * - Pages suivantes : Nœuds du B-tree, de taille fixe (page_size bytes)
+ *   - Nœuds internes : pointeur vers le fils le plus à gauche, puis des couples
+ *     (clé séparatrice, page fille) où la page fille contient les clés >= séparateur
+ *   - Feuilles : couples (clé, record_id) triés, chaînés par un pointeur vers
+ *     la feuille suivante pour les parcours ordonnés
+ *
+ * Les clés sont stockées sous une forme directement comparable octet par octet
+ * (entiers en big-endian avec bit de signe inversé, segments descendants inversés,
+ * etc.), ce qui permet de comparer les clés sans connaître leur type.
+ *
+ * Le format .ndx de HFSQL n'est pas documenté : cette structure est une hypothèse
+ * de travail, qui n'a pas été vérifiée sur des index produits par HFSQL. C'est celle
+ * qu'écrit NdxWriter. Le header est donc validé strictement (magic, version, options,
+ * types de segments, taille du fichier) : un fichier qui ne correspond pas est refusé
+ * avec une erreur explicite plutôt que parcouru.
+ *
  * Fonctionnalités :
- * - Lecture de l'index complet au chargement
- * - Recherche par clé
- * - Recherche par record_id
- * 
- * Note : Cette implémentation est simplifiée et suppose un format d'index linéaire.
- * Les vrais fichiers .ndx HFSQL utilisent généralement une structure B-tree.
- * 
+ * - Lecture du header et des descripteurs de segments de clé
+ * - Recherche ponctuelle par clé (descente dans l'arbre)
+ * - Parcours ordonné d'un intervalle de clés sans charger tout l'index, dans les deux sens
+ * - Recherche par record_id (parcours complet des feuilles)
+ * - Écriture d'un index complet à partir d'entrées (NdxWriter)
+ *
  * Liens avec d'autres modules :
- * - Utilisé par src/storage/engine.rs pour les recherches indexées
+ * - Utilisé par src/storage/engine.rs pour les recherches indexées et la réindexation
  */
 
 use anyhow::{Context, Result};
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use serde::{Deserialize, Serialize};
+use crate::core::source::{self, SourceFile};
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Bound;
 use std::path::Path;
 
+/// Taille de la partie fixe du header (avant les descripteurs de segments)
+const HEADER_FIXED_SIZE: u32 = 0x1C;
+/// Taille d'un descripteur de segment de clé dans le header
+const SEGMENT_DESCRIPTOR_SIZE: u32 = 8;
+/// Taille de l'en-tête d'une page de nœud
+const NODE_HEADER_SIZE: u32 = 8;
+/// Profondeur maximale acceptée lors de la descente (protection contre les cycles)
+const MAX_TREE_DEPTH: usize = 64;
+/// Taille de page par défaut des index écrits par NdxWriter
+pub const DEFAULT_PAGE_SIZE: u32 = 4096;
+/// Version du format écrite dans les index reconstruits
+const WRITER_VERSION: u16 = 1;
+/// Versions du format acceptées à la lecture
+const SUPPORTED_VERSIONS: &[u16] = &[WRITER_VERSION];
+/// Option du header : l'index interdit les doublons
+const HEADER_UNIQUE: u16 = 0x0001;
+
+/// Segment ignorant la casse (clé stockée en majuscules)
+pub const SEGMENT_CASE_INSENSITIVE: u8 = 0x01;
+/// Segment ignorant les accents (clé stockée sans diacritiques)
+pub const SEGMENT_ACCENT_INSENSITIVE: u8 = 0x02;
+/// Segment trié par ordre décroissant (octets inversés)
+pub const SEGMENT_DESCENDING: u8 = 0x04;
+/// Options de segment connues
+const SEGMENT_KNOWN_FLAGS: u8 = SEGMENT_CASE_INSENSITIVE | SEGMENT_ACCENT_INSENSITIVE | SEGMENT_DESCENDING;
+
 /// Entrée dans un index .ndx
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NdxEntry {
@@ -39,126 +80,1070 @@ pub struct NdxEntry {
     pub offset: u64,
 }
 
+/// Type de donnée d'un segment de clé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyKind {
+    /// Chaîne de caractères (complétée par des zéros)
+    String,
+    /// Entier signé
+    Integer,
+    /// Entier non signé
+    UnsignedInteger,
+    /// Nombre décimal (IEEE 754)
+    Float,
+    /// Date au format AAAAMMJJ
+    Date,
+    /// Données binaires
+    Binary,
+    /// Code de type non reconnu
+    Unknown(u8),
+}
+
+impl KeyKind {
+    /**
+     * Convertit le code de type stocké dans le descripteur de segment.
+     *
+     * @param code - Code de type lu dans le header
+     * @returns KeyKind - Type correspondant
+     */
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x01 => KeyKind::String,
+            0x02 => KeyKind::Integer,
+            0x03 => KeyKind::UnsignedInteger,
+            0x04 => KeyKind::Float,
+            0x05 => KeyKind::Date,
+            0x06 => KeyKind::Binary,
+            other => KeyKind::Unknown(other),
+        }
+    }
+
+    /**
+     * Retourne le code de type tel qu'il est stocké dans le header.
+     *
+     * @returns u8 - Code de type
+     */
+    pub fn code(&self) -> u8 {
+        match self {
+            KeyKind::String => 0x01,
+            KeyKind::Integer => 0x02,
+            KeyKind::UnsignedInteger => 0x03,
+            KeyKind::Float => 0x04,
+            KeyKind::Date => 0x05,
+            KeyKind::Binary => 0x06,
+            KeyKind::Unknown(code) => *code,
+        }
+    }
+}
+
+/// Descripteur d'un segment de clé (une rubrique indexée)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeySegment {
+    /// Offset de la rubrique dans l'enregistrement (en bytes, byte de flags inclus)
+    pub offset: u32,
+    /// Longueur du segment dans la clé (en bytes)
+    pub length: u32,
+    /// Type de donnée du segment
+    pub kind: KeyKind,
+    /// Options du segment (SEGMENT_CASE_INSENSITIVE, SEGMENT_ACCENT_INSENSITIVE, SEGMENT_DESCENDING)
+    pub flags: u8,
+}
+
+impl KeySegment {
+    /// Indique si le segment ignore la casse
+    pub fn is_case_insensitive(&self) -> bool {
+        self.flags & SEGMENT_CASE_INSENSITIVE != 0
+    }
+
+    /// Indique si le segment ignore les accents
+    pub fn is_accent_insensitive(&self) -> bool {
+        self.flags & SEGMENT_ACCENT_INSENSITIVE != 0
+    }
+
+    /// Indique si le segment est trié par ordre décroissant
+    pub fn is_descending(&self) -> bool {
+        self.flags & SEGMENT_DESCENDING != 0
+    }
+}
+
+/// Header d'un fichier .ndx
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NdxHeader {
+    /// Magic bytes identifiant le format ("PCS\0" ou "NDX\0")
+    pub magic: u32,
+    /// Version du format de fichier
+    pub version: u16,
+    /// Indique si l'index interdit les doublons
+    pub unique: bool,
+    /// Taille d'une page (nœud) en bytes
+    pub page_size: u32,
+    /// Numéro de la page racine du B-tree
+    pub root_page: u32,
+    /// Nombre total de pages (header inclus)
+    pub page_count: u32,
+    /// Nombre total d'entrées dans les feuilles
+    pub entry_count: u32,
+    /// Longueur totale d'une clé en bytes
+    pub key_length: u32,
+    /// Descripteurs des segments composant la clé
+    pub segments: Vec<KeySegment>,
+}
+
+/// Type d'un nœud du B-tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NdxNodeKind {
+    /// Nœud interne (clés séparatrices et pages filles)
+    Internal,
+    /// Feuille (clés et record_id)
+    Leaf,
+}
+
+/// Nœud du B-tree lu depuis une page
+#[derive(Debug, Clone)]
+pub struct NdxNode {
+    /// Numéro de la page
+    pub page: u32,
+    /// Type du nœud
+    pub kind: NdxNodeKind,
+    /// Fils le plus à gauche (nœud interne) ou feuille suivante (feuille, 0 = aucune)
+    pub link: u32,
+    /// Clés du nœud, triées
+    pub keys: Vec<Vec<u8>>,
+    /// Pages filles (nœud interne) ou record_id (feuille), un par clé
+    pub pointers: Vec<u32>,
+}
+
+impl NdxNode {
+    /**
+     * Retourne la page d'un fils d'un nœud interne.
+     *
+     * @param child - Position du fils (0 = fils le plus à gauche, i = fils de la clé i - 1)
+     * @returns u32 - Numéro de la page fille
+     */
+    fn child(&self, child: usize) -> u32 {
+        if child == 0 { self.link } else { self.pointers[child - 1] }
+    }
+}
+
 /// Gestionnaire de fichier d'index .ndx
 pub struct NdxFile {
     /// Chemin du fichier
     #[allow(dead_code)]
     path: std::path::PathBuf,
+    /// Header du fichier (lu au moment de l'ouverture)
+    header: NdxHeader,
     /// Handle du fichier ouvert
-    #[allow(dead_code)]
-    file: Option<File>,
-    /// Liste de toutes les entrées de l'index (chargée en mémoire)
-    entries: Vec<NdxEntry>,
+    file: Option<SourceFile>,
 }
 
 impl NdxFile {
     /**
-     * Ouvre un fichier .ndx en lecture et charge toutes les entrées.
-     * 
+     * Ouvre un fichier .ndx en lecture et lit son header.
+     *
+     * Seul le header est chargé : les nœuds sont lus à la demande
+     * lors des recherches et des parcours.
+     *
      * @param path - Chemin vers le fichier .ndx
      * @returns Result<NdxFile> - Gestionnaire de fichier ou erreur
-     * 
+     *
      * Effets de bord :
      * - Ouvre le fichier en lecture
-     * - Lit toutes les entrées de l'index en mémoire
+     * - Lit le header et les descripteurs de segments
      */
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let mut file = File::open(&path)
+        let mut file = source::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
 
-        // Lecture de l'index (structure simplifiée)
-        let entries = Self::read_index(&mut file)?;
+        let header = Self::read_header(&mut file)
+            .with_context(|| format!("Header d'index invalide: {:?}", path))?;
 
         Ok(Self {
             path,
+            header,
             file: Some(file),
-            entries,
         })
     }
 
     /**
-     * Lit la structure d'index depuis le fichier.
-     * 
-     * Parse le header puis lit toutes les entrées de l'index.
-     * Cette implémentation suppose un format linéaire simplifié.
-     * 
-     * @param reader - Reader positionné au début du fichier
-     * @returns Result<Vec<NdxEntry>> - Liste des entrées de l'index
-     * 
+     * Lit et valide le header d'un fichier .ndx.
+     *
+     * Tout ce qui sort de la structure connue (magic, version, options, type
+     * de segment, fichier plus court que les pages déclarées) est refusé.
+     *
+     * @param reader - Reader positionné n'importe où dans le fichier
+     * @returns Result<NdxHeader> - Header parsé ou erreur si le format n'est pas reconnu
+     *
      * Effets de bord :
-     * - Lit toutes les entrées depuis le reader
+     * - Lit depuis le reader (position modifiée)
      */
-    fn read_index<R: Read + Seek>(reader: &mut R) -> Result<Vec<NdxEntry>> {
+    fn read_header<R: Read + Seek>(reader: &mut R) -> Result<NdxHeader> {
+        let file_length = reader.seek(SeekFrom::End(0))?;
         reader.seek(SeekFrom::Start(0))?;
 
-        // Header hypothétique:
-        // - 4 bytes: magic
-        // - 4 bytes: nombre d'entrées
-        // - 4 bytes: longueur de clé
-        let _magic = reader.read_u32::<LittleEndian>()?;
-        let entry_count = reader.read_u32::<LittleEndian>()?;
-        let key_length = reader.read_u32::<LittleEndian>()?;
+        let mut magic_bytes = [0u8; 4];
+        reader.read_exact(&mut magic_bytes)
+            .context("Fichier trop court pour contenir un header d'index")?;
+        if &magic_bytes != b"PCS\0" && &magic_bytes != b"NDX\0" {
+            anyhow::bail!("Magic bytes invalides: {:?} (attendu: PCS\\0 ou NDX\\0)", magic_bytes);
+        }
+        let magic = u32::from_le_bytes(magic_bytes);
 
-        let mut entries = Vec::new();
-        let mut offset = 12u64; // Header size
+        let version = reader.read_u16::<LittleEndian>()?;
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            anyhow::bail!("Version d'index {} non reconnue (versions lues: {:?})", version, SUPPORTED_VERSIONS);
+        }
+        let flags = reader.read_u16::<LittleEndian>()?;
+        if flags & !HEADER_UNIQUE != 0 {
+            anyhow::bail!("Options d'index inconnues: 0x{:04x}", flags);
+        }
+        let page_size = reader.read_u32::<LittleEndian>()?;
+        let root_page = reader.read_u32::<LittleEndian>()?;
+        let page_count = reader.read_u32::<LittleEndian>()?;
+        let entry_count = reader.read_u32::<LittleEndian>()?;
+        let key_length = reader.read_u16::<LittleEndian>()? as u32;
+        let segment_count = reader.read_u16::<LittleEndian>()? as u32;
 
-        for _i in 0..entry_count {
-            let mut key = vec![0u8; key_length as usize];
-            reader.read_exact(&mut key)?;
-            
-            let record_id = reader.read_u32::<LittleEndian>()?;
+        if page_size < HEADER_FIXED_SIZE + segment_count * SEGMENT_DESCRIPTOR_SIZE {
+            anyhow::bail!("Taille de page {} trop petite pour {} segments", page_size, segment_count);
+        }
+        if key_length == 0 || NODE_HEADER_SIZE + key_length + 4 > page_size {
+            anyhow::bail!("Longueur de clé {} incompatible avec la taille de page {}", key_length, page_size);
+        }
+        if root_page == 0 || root_page >= page_count {
+            anyhow::bail!("Page racine {} invalide (pages: {})", root_page, page_count);
+        }
+        if (page_count as u64) * (page_size as u64) > file_length {
+            anyhow::bail!("Fichier tronqué: {} pages de {} bytes déclarées pour {} bytes", page_count, page_size, file_length);
+        }
 
-            entries.push(NdxEntry {
-                key,
-                record_id,
+        let mut segments = Vec::with_capacity(segment_count as usize);
+        for _ in 0..segment_count {
+            let offset = reader.read_u16::<LittleEndian>()? as u32;
+            let length = reader.read_u16::<LittleEndian>()? as u32;
+            let kind = KeyKind::from_code(reader.read_u8()?);
+            if let KeyKind::Unknown(code) = kind {
+                anyhow::bail!("Type de segment inconnu 0x{:02x}", code);
+            }
+            let segment_flags = reader.read_u8()?;
+            if segment_flags & !SEGMENT_KNOWN_FLAGS != 0 {
+                anyhow::bail!("Options de segment inconnues: 0x{:02x}", segment_flags);
+            }
+            let _reserved = reader.read_u16::<LittleEndian>()?;
+            segments.push(KeySegment {
                 offset,
+                length,
+                kind,
+                flags: segment_flags,
             });
+        }
+
+        let segments_length: u32 = segments.iter().map(|s| s.length).sum();
+        if !segments.is_empty() && segments_length != key_length {
+            anyhow::bail!("Les segments ({} bytes) ne couvrent pas la clé ({} bytes)", segments_length, key_length);
+        }
+
+        Ok(NdxHeader {
+            magic,
+            version,
+            unique: flags & HEADER_UNIQUE != 0,
+            page_size,
+            root_page,
+            page_count,
+            entry_count,
+            key_length,
+            segments,
+        })
+    }
+
+    /**
+     * Retourne une référence vers le header du fichier.
+     *
+     * @returns &NdxHeader - Référence vers le header
+     */
+    pub fn header(&self) -> &NdxHeader {
+        &self.header
+    }
+
+    /**
+     * Retourne les descripteurs des segments de la clé.
+     *
+     * @returns &[KeySegment] - Segments de la clé, dans l'ordre
+     */
+    pub fn segments(&self) -> &[KeySegment] {
+        &self.header.segments
+    }
+
+    /**
+     * Retourne le nombre d'entrées déclaré dans le header.
+     *
+     * @returns u32 - Nombre d'entrées
+     */
+    pub fn entry_count(&self) -> u32 {
+        self.header.entry_count
+    }
+
+    /**
+     * Lit un nœud du B-tree.
+     *
+     * @param page - Numéro de la page à lire (> 0)
+     * @returns Result<NdxNode> - Nœud lu ou erreur si la page est invalide
+     *
+     * Effets de bord :
+     * - Lit depuis le fichier (position modifiée)
+     */
+    pub fn read_node(&mut self, page: u32) -> Result<NdxNode> {
+        if page == 0 || page >= self.header.page_count {
+            anyhow::bail!("Page {} hors limites (pages: {})", page, self.header.page_count);
+        }
+
+        let page_size = self.header.page_size;
+        let key_length = self.header.key_length as usize;
+        let file = self.file.as_mut()
+            .context("Fichier non ouvert")?;
+
+        let mut buffer = vec![0u8; page_size as usize];
+        file.seek(SeekFrom::Start(page as u64 * page_size as u64))?;
+        file.read_exact(&mut buffer)
+            .with_context(|| format!("Impossible de lire la page {}", page))?;
+
+        let kind = match buffer[0] {
+            0x00 => NdxNodeKind::Leaf,
+            0x01 => NdxNodeKind::Internal,
+            other => anyhow::bail!("Type de nœud inconnu 0x{:02x} à la page {}", other, page),
+        };
+        let key_count = u16::from_le_bytes([buffer[2], buffer[3]]) as usize;
+        let link = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
+
+        let entry_size = key_length + 4;
+        if NODE_HEADER_SIZE as usize + key_count * entry_size > buffer.len() {
+            anyhow::bail!("Page {} corrompue: {} clés ne tiennent pas dans {} bytes", page, key_count, page_size);
+        }
+
+        let mut keys = Vec::with_capacity(key_count);
+        let mut pointers = Vec::with_capacity(key_count);
+        for i in 0..key_count {
+            let start = NODE_HEADER_SIZE as usize + i * entry_size;
+            keys.push(buffer[start..start + key_length].to_vec());
+            let ptr = &buffer[start + key_length..start + entry_size];
+            pointers.push(u32::from_le_bytes([ptr[0], ptr[1], ptr[2], ptr[3]]));
+        }
+
+        Ok(NdxNode {
+            page,
+            kind,
+            link,
+            keys,
+            pointers,
+        })
+    }
+
+    /**
+     * Descend depuis la racine jusqu'à la feuille pouvant contenir une clé.
+     *
+     * Dans chaque nœud interne, on suit le fils situé avant le premier
+     * séparateur >= clé, afin de ne pas manquer les doublons qui débutent
+     * dans la feuille précédente.
+     *
+     * @param key - Clé recherchée (None = feuille la plus à gauche)
+     * @returns Result<NdxNode> - Feuille atteinte
+     */
+    fn descend(&mut self, key: Option<&[u8]>) -> Result<NdxNode> {
+        let mut page = self.header.root_page;
+        for _ in 0..MAX_TREE_DEPTH {
+            let node = self.read_node(page)?;
+            if node.kind == NdxNodeKind::Leaf {
+                return Ok(node);
+            }
+            let before = match key {
+                Some(key) => node.keys.partition_point(|k| k.as_slice() < key),
+                None => 0,
+            };
+            page = node.child(before);
+        }
+        anyhow::bail!("Profondeur maximale dépassée: l'arbre de l'index est probablement corrompu")
+    }
+
+    /**
+     * Parcourt les entrées dont la clé est comprise dans un intervalle.
+     *
+     * Les entrées sont retournées dans l'ordre de l'index. Seules les feuilles
+     * nécessaires sont lues, au fur et à mesure de l'itération.
+     *
+     * @param lower - Borne inférieure de l'intervalle
+     * @param upper - Borne supérieure de l'intervalle
+     * @returns Result<NdxRange> - Itérateur sur les entrées
+     *
+     * Effets de bord :
+     * - Lit les pages de l'index depuis le fichier
+     */
+    pub fn range(&mut self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> Result<NdxRange<'_>> {
+        let start_key = match lower {
+            Bound::Included(k) | Bound::Excluded(k) => Some(k),
+            Bound::Unbounded => None,
+        };
+        let leaf = self.descend(start_key)?;
+        let position = match lower {
+            Bound::Included(k) => leaf.keys.partition_point(|key| key.as_slice() < k),
+            Bound::Excluded(k) => leaf.keys.partition_point(|key| key.as_slice() <= k),
+            Bound::Unbounded => 0,
+        };
+
+        Ok(NdxRange {
+            lower: lower.map(|k| k.to_vec()),
+            upper: upper.map(|k| k.to_vec()),
+            leaf: Some(leaf),
+            position,
+            visited_leaves: 1,
+            ndx: self,
+        })
+    }
 
-            offset += (key_length + 4) as u64;
+    /**
+     * Parcourt les entrées dont la clé est comprise dans un intervalle, de la
+     * plus grande à la plus petite.
+     *
+     * Les feuilles n'étant chaînées que vers la suivante, le chemin depuis la
+     * racine est conservé pour remonter à la feuille précédente. Comme pour
+     * range, seules les feuilles nécessaires sont lues.
+     *
+     * @param lower - Borne inférieure de l'intervalle
+     * @param upper - Borne supérieure de l'intervalle (point de départ du parcours)
+     * @returns Result<NdxRevRange> - Itérateur sur les entrées, dans l'ordre inverse de l'index
+     *
+     * Effets de bord :
+     * - Lit les pages de l'index depuis le fichier
+     */
+    pub fn rev_range(&mut self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> Result<NdxRevRange<'_>> {
+        // Dernier fils pouvant contenir des clés de l'intervalle : les doublons
+        // d'un séparateur peuvent se trouver dans le fils qui le précède
+        let last_child = |node: &NdxNode| match upper {
+            Bound::Included(k) => node.keys.partition_point(|key| key.as_slice() <= k),
+            Bound::Excluded(k) => node.keys.partition_point(|key| key.as_slice() < k),
+            Bound::Unbounded => node.keys.len(),
+        };
+        let mut path = Vec::new();
+        let mut page = self.header.root_page;
+        let leaf = loop {
+            if path.len() >= MAX_TREE_DEPTH {
+                anyhow::bail!("Profondeur maximale dépassée: l'arbre de l'index est probablement corrompu");
+            }
+            let node = self.read_node(page)?;
+            if node.kind == NdxNodeKind::Leaf {
+                break node;
+            }
+            let child = last_child(&node);
+            page = node.child(child);
+            path.push((node, child));
+        };
+
+        Ok(NdxRevRange {
+            lower: lower.map(|k| k.to_vec()),
+            upper: upper.map(|k| k.to_vec()),
+            position: leaf.keys.len(),
+            leaf: Some(leaf),
+            path,
+            visited_leaves: 1,
+            ndx: self,
+        })
+    }
+
+    /**
+     * Construit l'entrée située à une position d'une feuille.
+     *
+     * @param leaf - Feuille lue
+     * @param index - Position de l'entrée dans la feuille
+     * @returns NdxEntry - Clé, record_id et offset de l'entrée dans le fichier
+     */
+    fn leaf_entry(&self, leaf: &NdxNode, index: usize) -> NdxEntry {
+        let entry_size = (self.header.key_length + 4) as u64;
+        let offset = leaf.page as u64 * self.header.page_size as u64
+            + NODE_HEADER_SIZE as u64
+            + index as u64 * entry_size;
+        NdxEntry {
+            key: leaf.keys[index].clone(),
+            record_id: leaf.pointers[index],
+            offset,
         }
+    }
 
-        Ok(entries)
+    /**
+     * Parcourt toutes les entrées de l'index dans l'ordre des clés.
+     *
+     * @returns Result<NdxRange> - Itérateur sur toutes les entrées
+     */
+    pub fn iter(&mut self) -> Result<NdxRange<'_>> {
+        self.range(Bound::Unbounded, Bound::Unbounded)
     }
 
     /**
-     * Recherche une entrée par sa clé.
-     * 
-     * Effectue une recherche linéaire dans l'index. Pour de meilleures
-     * performances, cette fonction devrait être optimisée avec une recherche
-     * binaire si les entrées sont triées.
-     * 
-     * @param key - Clé à rechercher
-     * @returns Option<&NdxEntry> - Entrée trouvée ou None
-     * 
-     * Effets de bord : Aucun
+     * Recherche la première entrée ayant exactement cette clé.
+     *
+     * @param key - Clé à rechercher (forme stockée, longueur complète)
+     * @returns Result<Option<NdxEntry>> - Entrée trouvée ou None
+     *
+     * Effets de bord :
+     * - Lit les pages traversées depuis le fichier
      */
-    pub fn find(&self, key: &[u8]) -> Option<&NdxEntry> {
-        // Recherche linéaire (à optimiser avec binaire si trié)
-        self.entries.iter().find(|e| e.key == key)
+    pub fn find(&mut self, key: &[u8]) -> Result<Option<NdxEntry>> {
+        self.range(Bound::Included(key), Bound::Included(key))?
+            .next()
+            .transpose()
     }
 
     /**
-     * Retourne toutes les entrées de l'index.
-     * 
-     * @returns &[NdxEntry] - Référence vers toutes les entrées
+     * Recherche toutes les entrées ayant exactement cette clé (index avec doublons).
+     *
+     * @param key - Clé à rechercher (forme stockée, longueur complète)
+     * @returns Result<Vec<NdxEntry>> - Entrées trouvées, dans l'ordre de l'index
      */
-    pub fn entries(&self) -> &[NdxEntry] {
-        &self.entries
+    pub fn find_all(&mut self, key: &[u8]) -> Result<Vec<NdxEntry>> {
+        self.range(Bound::Included(key), Bound::Included(key))?
+            .collect()
     }
 
     /**
      * Recherche toutes les entrées associées à un record_id.
-     * 
+     *
+     * L'index n'étant pas trié par record_id, cette recherche parcourt
+     * toutes les feuilles.
+     *
      * @param record_id - ID de l'enregistrement à rechercher
-     * @returns Vec<&NdxEntry> - Liste des entrées trouvées (peut y en avoir plusieurs)
-     * 
-     * Effets de bord : Aucun
+     * @returns Result<Vec<NdxEntry>> - Liste des entrées trouvées (peut y en avoir plusieurs)
      */
-    pub fn find_by_record_id(&self, record_id: u32) -> Vec<&NdxEntry> {
-        self.entries.iter()
-            .filter(|e| e.record_id == record_id)
-            .collect()
+    pub fn find_by_record_id(&mut self, record_id: u32) -> Result<Vec<NdxEntry>> {
+        let mut found = Vec::new();
+        for entry in self.iter()? {
+            let entry = entry?;
+            if entry.record_id == record_id {
+                found.push(entry);
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// Itérateur ordonné sur un intervalle de clés d'un index .ndx
+pub struct NdxRange<'a> {
+    /// Index parcouru
+    ndx: &'a mut NdxFile,
+    /// Borne inférieure (les doublons d'une feuille précédente peuvent la précéder)
+    lower: Bound<Vec<u8>>,
+    /// Borne supérieure de l'intervalle
+    upper: Bound<Vec<u8>>,
+    /// Feuille courante (None = parcours terminé)
+    leaf: Option<NdxNode>,
+    /// Position dans la feuille courante
+    position: usize,
+    /// Nombre de feuilles lues (protection contre les chaînages cycliques)
+    visited_leaves: u32,
+}
+
+impl<'a> Iterator for NdxRange<'a> {
+    type Item = Result<NdxEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let leaf = self.leaf.as_ref()?;
+
+            if self.position >= leaf.keys.len() {
+                let next_page = leaf.link;
+                if next_page == 0 {
+                    self.leaf = None;
+                    return None;
+                }
+                self.visited_leaves += 1;
+                if self.visited_leaves > self.ndx.header.page_count {
+                    self.leaf = None;
+                    return Some(Err(anyhow::anyhow!("Chaînage des feuilles cyclique à la page {}", next_page)));
+                }
+                match self.ndx.read_node(next_page) {
+                    Ok(node) if node.kind == NdxNodeKind::Leaf => {
+                        self.leaf = Some(node);
+                        self.position = 0;
+                        continue;
+                    }
+                    Ok(_) => {
+                        self.leaf = None;
+                        return Some(Err(anyhow::anyhow!("La page {} chaînée n'est pas une feuille", next_page)));
+                    }
+                    Err(e) => {
+                        self.leaf = None;
+                        return Some(Err(e));
+                    }
+                }
+            }
+
+            let index = self.position;
+            self.position += 1;
+            let key = &leaf.keys[index];
+
+            if !after_lower(key, &self.lower) {
+                continue;
+            }
+            if !before_upper(key, &self.upper) {
+                self.leaf = None;
+                return None;
+            }
+
+            return Some(Ok(self.ndx.leaf_entry(leaf, index)));
+        }
+    }
+}
+
+/// Itérateur sur un intervalle de clés d'un index .ndx, dans l'ordre inverse
+pub struct NdxRevRange<'a> {
+    /// Index parcouru
+    ndx: &'a mut NdxFile,
+    /// Borne inférieure de l'intervalle (fin du parcours)
+    lower: Bound<Vec<u8>>,
+    /// Borne supérieure (les doublons d'une feuille suivante peuvent la dépasser)
+    upper: Bound<Vec<u8>>,
+    /// Nœuds internes de la racine à la feuille courante, avec la position du fils suivi
+    path: Vec<(NdxNode, usize)>,
+    /// Feuille courante (None = parcours terminé)
+    leaf: Option<NdxNode>,
+    /// Nombre d'entrées restant à lire dans la feuille courante
+    position: usize,
+    /// Nombre de feuilles lues (protection contre les arbres incohérents)
+    visited_leaves: u32,
+}
+
+impl NdxRevRange<'_> {
+    /**
+     * Remonte à la feuille précédente : on remonte le chemin jusqu'au premier
+     * nœud ayant un fils à gauche de celui suivi, puis on descend par les
+     * fils les plus à droite.
+     *
+     * @returns Result<Option<NdxNode>> - Feuille précédente, ou None en début d'index
+     */
+    fn previous_leaf(&mut self) -> Result<Option<NdxNode>> {
+        let mut page = loop {
+            let Some((node, child)) = self.path.last_mut() else {
+                return Ok(None);
+            };
+            if *child > 0 {
+                *child -= 1;
+                break node.child(*child);
+            }
+            self.path.pop();
+        };
+        loop {
+            if self.path.len() >= MAX_TREE_DEPTH {
+                anyhow::bail!("Profondeur maximale dépassée: l'arbre de l'index est probablement corrompu");
+            }
+            let node = self.ndx.read_node(page)?;
+            if node.kind == NdxNodeKind::Leaf {
+                return Ok(Some(node));
+            }
+            let child = node.keys.len();
+            page = node.child(child);
+            self.path.push((node, child));
+        }
+    }
+}
+
+impl<'a> Iterator for NdxRevRange<'a> {
+    type Item = Result<NdxEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.leaf.as_ref()?;
+
+            if self.position == 0 {
+                self.visited_leaves += 1;
+                if self.visited_leaves > self.ndx.header.page_count {
+                    self.leaf = None;
+                    return Some(Err(anyhow::anyhow!("Parcours inverse de l'index incohérent: plus de feuilles que de pages")));
+                }
+                match self.previous_leaf() {
+                    Ok(Some(node)) => {
+                        self.position = node.keys.len();
+                        self.leaf = Some(node);
+                        continue;
+                    }
+                    Ok(None) => {
+                        self.leaf = None;
+                        return None;
+                    }
+                    Err(e) => {
+                        self.leaf = None;
+                        return Some(Err(e));
+                    }
+                }
+            }
+
+            self.position -= 1;
+            let leaf = self.leaf.as_ref()?;
+            let key = &leaf.keys[self.position];
+
+            if !before_upper(key, &self.upper) {
+                continue;
+            }
+            if !after_lower(key, &self.lower) {
+                self.leaf = None;
+                return None;
+            }
+
+            return Some(Ok(self.ndx.leaf_entry(leaf, self.position)));
+        }
+    }
+}
+
+/// Indique si une clé est au-dessus de la borne inférieure d'un intervalle
+fn after_lower(key: &[u8], lower: &Bound<Vec<u8>>) -> bool {
+    match lower {
+        Bound::Included(k) => key >= k.as_slice(),
+        Bound::Excluded(k) => key > k.as_slice(),
+        Bound::Unbounded => true,
+    }
+}
+
+/// Indique si une clé est en dessous de la borne supérieure d'un intervalle
+fn before_upper(key: &[u8], upper: &Bound<Vec<u8>>) -> bool {
+    match upper {
+        Bound::Included(k) => key <= k.as_slice(),
+        Bound::Excluded(k) => key < k.as_slice(),
+        Bound::Unbounded => true,
     }
 }
 
+/**
+ * Écrivain d'index .ndx.
+ *
+ * Construit un B-tree complet à partir de la liste des entrées (clé, record_id),
+ * par chargement ascendant : les entrées triées remplissent les feuilles, puis
+ * chaque niveau interne référence le niveau inférieur jusqu'à n'avoir qu'une
+ * racine. Le résultat est lisible par NdxFile.
+ */
+pub struct NdxWriter {
+    /// Segments composant la clé
+    segments: Vec<KeySegment>,
+    /// Index sans doublons
+    unique: bool,
+    /// Taille d'une page en bytes
+    page_size: u32,
+}
+
+impl NdxWriter {
+    /**
+     * Crée un écrivain pour un index dont la clé est définie par ses segments.
+     *
+     * @param segments - Segments de la clé (dans l'ordre)
+     * @param unique - Index sans doublons
+     * @returns NdxWriter - Écrivain avec la taille de page par défaut
+     */
+    pub fn new(segments: Vec<KeySegment>, unique: bool) -> Self {
+        Self {
+            segments,
+            unique,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /**
+     * Modifie la taille des pages écrites (ex: pour reproduire celle de l'index d'origine).
+     *
+     * @param page_size - Taille d'une page en bytes
+     * @returns NdxWriter - Écrivain modifié
+     */
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /**
+     * Écrit l'index dans un fichier.
+     *
+     * Les entrées sont triées par clé puis par record_id. Le fichier est d'abord
+     * écrit à côté de la destination puis renommé, pour ne jamais laisser un
+     * index à moitié écrit.
+     *
+     * @param path - Chemin du fichier .ndx à créer ou remplacer
+     * @param entries - Couples (clé, record_id), clés sous leur forme stockée
+     * @returns Result<NdxHeader> - Header de l'index écrit, ou erreur si une clé
+     *          n'a pas la bonne longueur ou si un index unique contient un doublon
+     *
+     * Effets de bord :
+     * - Crée un fichier temporaire puis remplace le fichier de destination
+     */
+    pub fn write<P: AsRef<Path>>(&self, path: P, mut entries: Vec<(Vec<u8>, u32)>) -> Result<NdxHeader> {
+        let path = path.as_ref();
+        let key_length: u32 = self.segments.iter().map(|s| s.length).sum();
+        let header_size = HEADER_FIXED_SIZE + self.segments.len() as u32 * SEGMENT_DESCRIPTOR_SIZE;
+        if key_length == 0 || key_length > u16::MAX as u32 {
+            anyhow::bail!("Longueur de clé {} invalide", key_length);
+        }
+        if header_size > self.page_size || NODE_HEADER_SIZE + 2 * (key_length + 4) > self.page_size {
+            anyhow::bail!("Taille de page {} trop petite pour une clé de {} bytes", self.page_size, key_length);
+        }
+        if let Some((key, record_id)) = entries.iter().find(|(key, _)| key.len() != key_length as usize) {
+            anyhow::bail!("Clé de {} bytes pour l'enregistrement {} (attendu: {})", key.len(), record_id, key_length);
+        }
+
+        entries.sort();
+        if self.unique {
+            if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                anyhow::bail!(
+                    "Doublon dans un index unique: enregistrements {} et {} (clé {})",
+                    pair[0].1, pair[1].1, hex::encode(&pair[0].0)
+                );
+            }
+        }
+
+        let capacity = ((self.page_size - NODE_HEADER_SIZE) / (key_length + 4)) as usize;
+        let mut pages: Vec<Vec<u8>> = Vec::new();
+
+        // Feuilles : pages 1..=n, chaînées dans l'ordre
+        let chunks: Vec<&[(Vec<u8>, u32)]> = if entries.is_empty() {
+            vec![&entries[..]]
+        } else {
+            entries.chunks(capacity).collect()
+        };
+        let leaf_count = chunks.len();
+        let mut level: Vec<(Vec<u8>, u32)> = Vec::with_capacity(leaf_count);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let page_number = pages.len() as u32 + 1;
+            let next = if i + 1 < leaf_count { page_number + 1 } else { 0 };
+            pages.push(self.node_page(0x00, next, chunk));
+            let first_key = chunk.first()
+                .map(|(key, _)| key.clone())
+                .unwrap_or_else(|| vec![0u8; key_length as usize]);
+            level.push((first_key, page_number));
+        }
+
+        // Niveaux internes : lien = premier fils, séparateurs = première clé des fils suivants
+        while level.len() > 1 {
+            let mut parents = Vec::new();
+            for group in level.chunks(capacity + 1) {
+                let page_number = pages.len() as u32 + 1;
+                pages.push(self.node_page(0x01, group[0].1, &group[1..]));
+                parents.push((group[0].0.clone(), page_number));
+            }
+            level = parents;
+        }
+
+        let header = NdxHeader {
+            magic: u32::from_le_bytes(*b"NDX\0"),
+            version: WRITER_VERSION,
+            unique: self.unique,
+            page_size: self.page_size,
+            root_page: level[0].1,
+            page_count: pages.len() as u32 + 1,
+            entry_count: entries.len() as u32,
+            key_length,
+            segments: self.segments.clone(),
+        };
+
+        let temp_path = path.with_extension("ndx.tmp");
+        {
+            let file = File::create(&temp_path)
+                .with_context(|| format!("Impossible de créer le fichier: {:?}", temp_path))?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&self.header_page(&header)?)?;
+            for page in &pages {
+                writer.write_all(page)?;
+            }
+            writer.flush()?;
+        }
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Impossible de remplacer l'index: {:?}", path))?;
+
+        Ok(header)
+    }
+
+    /// Sérialise la page 0 (header et descripteurs de segments)
+    fn header_page(&self, header: &NdxHeader) -> Result<Vec<u8>> {
+        let mut page = Vec::with_capacity(self.page_size as usize);
+        page.write_u32::<LittleEndian>(header.magic)?;
+        page.write_u16::<LittleEndian>(header.version)?;
+        page.write_u16::<LittleEndian>(if header.unique { HEADER_UNIQUE } else { 0 })?;
+        page.write_u32::<LittleEndian>(header.page_size)?;
+        page.write_u32::<LittleEndian>(header.root_page)?;
+        page.write_u32::<LittleEndian>(header.page_count)?;
+        page.write_u32::<LittleEndian>(header.entry_count)?;
+        page.write_u16::<LittleEndian>(header.key_length as u16)?;
+        page.write_u16::<LittleEndian>(header.segments.len() as u16)?;
+        for segment in &header.segments {
+            page.write_u16::<LittleEndian>(segment.offset as u16)?;
+            page.write_u16::<LittleEndian>(segment.length as u16)?;
+            page.write_u8(segment.kind.code())?;
+            page.write_u8(segment.flags)?;
+            page.write_u16::<LittleEndian>(0)?;
+        }
+        page.resize(self.page_size as usize, 0);
+        Ok(page)
+    }
+
+    /// Sérialise une page de nœud (feuille ou nœud interne)
+    fn node_page(&self, kind: u8, link: u32, entries: &[(Vec<u8>, u32)]) -> Vec<u8> {
+        let mut page = vec![kind, 0];
+        page.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        page.extend_from_slice(&link.to_le_bytes());
+        for (key, pointer) in entries {
+            page.extend_from_slice(key);
+            page.extend_from_slice(&pointer.to_le_bytes());
+        }
+        page.resize(self.page_size as usize, 0);
+        page
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const PAGE_SIZE: usize = 64;
+
+    fn page(kind: u8, link: u32, entries: &[(u32, u32)]) -> Vec<u8> {
+        let mut page = vec![kind, 0];
+        page.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        page.extend_from_slice(&link.to_le_bytes());
+        for (key, pointer) in entries {
+            page.extend_from_slice(&key.to_be_bytes());
+            page.extend_from_slice(&pointer.to_le_bytes());
+        }
+        page.resize(PAGE_SIZE, 0);
+        page
+    }
+
+    /// Index à deux niveaux : trois feuilles (la clé 3 est à cheval) et une racine
+    fn create_test_ndx_file() -> Result<NamedTempFile> {
+        let mut header = Vec::new();
+        header.extend_from_slice(b"NDX\0");
+        header.extend_from_slice(&1u16.to_le_bytes()); // Version
+        header.extend_from_slice(&0u16.to_le_bytes()); // Flags (doublons autorisés)
+        header.extend_from_slice(&(PAGE_SIZE as u32).to_le_bytes());
+        header.extend_from_slice(&4u32.to_le_bytes()); // Page racine
+        header.extend_from_slice(&5u32.to_le_bytes()); // Nombre de pages
+        header.extend_from_slice(&8u32.to_le_bytes()); // Nombre d'entrées
+        header.extend_from_slice(&4u16.to_le_bytes()); // Longueur de clé
+        header.extend_from_slice(&1u16.to_le_bytes()); // Nombre de segments
+        header.extend_from_slice(&1u16.to_le_bytes()); // Segment: offset
+        header.extend_from_slice(&4u16.to_le_bytes()); // Segment: longueur
+        header.push(KeyKind::UnsignedInteger.code());
+        header.push(0);
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.resize(PAGE_SIZE, 0);
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&header)?;
+        file.write_all(&page(0x00, 2, &[(1, 10), (2, 20), (3, 30)]))?;
+        file.write_all(&page(0x00, 3, &[(3, 31), (4, 40), (5, 50)]))?;
+        file.write_all(&page(0x00, 0, &[(7, 70), (8, 80)]))?;
+        file.write_all(&page(0x01, 1, &[(3, 2), (7, 3)]))?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_read_header() -> Result<()> {
+        let test_file = create_test_ndx_file()?;
+        let ndx = NdxFile::open(test_file.path())?;
+
+        assert_eq!(ndx.header().root_page, 4);
+        assert_eq!(ndx.entry_count(), 8);
+        assert_eq!(ndx.segments().len(), 1);
+        assert_eq!(ndx.segments()[0].kind, KeyKind::UnsignedInteger);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_unknown_header() -> Result<()> {
+        let valid = std::fs::read(create_test_ndx_file()?.path())?;
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            ([b"NDX1".as_slice(), &valid[4..]].concat(), "Magic"),
+            ([&valid[..4], &2u16.to_le_bytes(), &valid[6..]].concat(), "Version"),
+            ([&valid[..6], &0x8000u16.to_le_bytes(), &valid[8..]].concat(), "Options d'index"),
+            ([&valid[..0x20], &[0x7F], &valid[0x21..]].concat(), "Type de segment"),
+            (valid[..valid.len() - PAGE_SIZE].to_vec(), "tronqué"),
+        ];
+
+        for (bytes, expected) in cases {
+            let mut file = NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            let error = format!("{:#}", NdxFile::open(file.path()).err().expect(expected));
+            assert!(error.contains(expected), "{}", error);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_duplicates_across_leaves() -> Result<()> {
+        let test_file = create_test_ndx_file()?;
+        let mut ndx = NdxFile::open(test_file.path())?;
+
+        let ids: Vec<u32> = ndx.find_all(&3u32.to_be_bytes())?
+            .iter()
+            .map(|e| e.record_id)
+            .collect();
+        assert_eq!(ids, vec![30, 31]);
+        assert!(ndx.find(&6u32.to_be_bytes())?.is_none());
+        assert_eq!(ndx.find(&7u32.to_be_bytes())?.map(|e| e.record_id), Some(70));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_scan() -> Result<()> {
+        let test_file = create_test_ndx_file()?;
+        let mut ndx = NdxFile::open(test_file.path())?;
+
+        let lower = 3u32.to_be_bytes();
+        let upper = 7u32.to_be_bytes();
+        let ids: Vec<u32> = ndx.range(Bound::Excluded(&lower), Bound::Included(&upper))?
+            .map(|e| e.map(|e| e.record_id))
+            .collect::<Result<_>>()?;
+        assert_eq!(ids, vec![40, 50, 70]);
+
+        let all = ndx.iter()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(all.len(), 8);
+
+        let ids: Vec<u32> = ndx.rev_range(Bound::Excluded(&lower), Bound::Included(&upper))?
+            .map(|e| e.map(|e| e.record_id))
+            .collect::<Result<_>>()?;
+        assert_eq!(ids, vec![70, 50, 40]);
+        // Doublons à cheval sur deux feuilles, parcourus depuis la fin
+        let ids: Vec<u32> = ndx.rev_range(Bound::Included(&lower), Bound::Included(&lower))?
+            .map(|e| e.map(|e| e.record_id))
+            .collect::<Result<_>>()?;
+        assert_eq!(ids, vec![31, 30]);
+        let all = ndx.rev_range(Bound::Unbounded, Bound::Unbounded)?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(all.iter().map(|e| e.record_id).collect::<Vec<_>>(), vec![80, 70, 50, 40, 31, 30, 20, 10]);
+        assert_eq!(all[0].offset, 3 * PAGE_SIZE as u64 + 8 + 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_writer_round_trip() -> Result<()> {
+        let segments = vec![KeySegment { offset: 1, length: 4, kind: KeyKind::UnsignedInteger, flags: 0 }];
+        let entries: Vec<(Vec<u8>, u32)> = (0..50u32).rev()
+            .map(|i| ((i / 2).to_be_bytes().to_vec(), i))
+            .collect();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("TEST.NDX");
+        let header = NdxWriter::new(segments.clone(), false).page_size(64).write(&path, entries)?;
+        assert!(header.root_page > 1);
+
+        let mut ndx = NdxFile::open(&path)?;
+        assert_eq!(ndx.entry_count(), 50);
+        let all = ndx.iter()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(all.len(), 50);
+        assert!(all.windows(2).all(|pair| pair[0].key <= pair[1].key));
+        let reversed = ndx.rev_range(Bound::Unbounded, Bound::Unbounded)?.collect::<Result<Vec<_>>>()?;
+        assert!(reversed.iter().rev().map(|e| (&e.key, e.record_id)).eq(all.iter().map(|e| (&e.key, e.record_id))));
+        let ids: Vec<u32> = ndx.find_all(&12u32.to_be_bytes())?.iter().map(|e| e.record_id).collect();
+        assert_eq!(ids, vec![24, 25]);
+
+        let duplicates = vec![(1u32.to_be_bytes().to_vec(), 1), (1u32.to_be_bytes().to_vec(), 2)];
+        assert!(NdxWriter::new(segments, true).write(&path, duplicates).is_err());
+
+        Ok(())
+    }
+}
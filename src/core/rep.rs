@@ -0,0 +1,280 @@
+/**
+ * Gestionnaire de fichiers de description des répertoires HFSQL (.rep).
+ *
+ * Un projet WinDev peut déclarer l'emplacement de ses fichiers de données
+ * dans un fichier .rep : chaque table logique y est associée à un chemin
+ * physique, absolu ou relatif au dossier du .rep. Les tables d'une même
+ * application ne sont donc pas toujours rangées dans le même dossier.
+ *
+ * Structure d'un fichier .rep :
+ * - 0x00 Magic (4 bytes) : "REP\0" ou "PCS\0"
+ * - 0x04 Version (u16), 0x06 réservé (u16)
+ * - 0x08 Nombre d'entrées (u32)
+ * - 0x0C Offset de la première entrée (u32)
+ * - Entrées (taille variable, à la suite) :
+ *   - 0x00 Taille totale de l'entrée (u16)
+ *   - 0x02 Options (u8) : bit 0 = entrée désactivée
+ *   - 0x03 Nom logique : longueur (u8) + texte (Windows-1252)
+ *   - Chemin physique : longueur (u16) + texte (Windows-1252), fichier .fic
+ *     ou dossier le contenant
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs pour enregistrer les tables déclarées
+ */
+
+use anyhow::{Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use encoding_rs::WINDOWS_1252;
+use serde::{Deserialize, Serialize};
+use crate::core::source;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// L'entrée est désactivée (table retirée du projet)
+const ENTRY_DISABLED: u8 = 0x01;
+
+/// Header d'un fichier .rep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepHeader {
+    /// Magic bytes identifiant le format
+    pub magic: u32,
+    /// Version du format de fichier
+    pub version: u16,
+    /// Nombre d'entrées déclaré
+    pub entry_count: u32,
+    /// Offset de la première entrée
+    pub data_offset: u32,
+}
+
+/// Table déclarée dans un fichier .rep
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepEntry {
+    /// Nom logique de la table
+    pub name: String,
+    /// Chemin physique tel qu'écrit dans le fichier (fichier .fic ou dossier)
+    pub path: String,
+    /// Entrée désactivée
+    pub disabled: bool,
+}
+
+impl RepEntry {
+    /**
+     * Convertit le chemin déclaré en chemin local.
+     *
+     * Les séparateurs Windows sont convertis ; un chemin relatif est résolu
+     * depuis le dossier du fichier .rep. Un chemin avec lettre de lecteur
+     * (`C:\Donnees`) est conservé tel quel.
+     *
+     * @param base_dir - Dossier contenant le fichier .rep
+     * @returns PathBuf - Chemin du fichier .fic ou du dossier déclaré
+     */
+    pub fn resolve(&self, base_dir: &Path) -> PathBuf {
+        let declared = self.path.trim().replace('\\', "/");
+        let has_drive = declared.as_bytes().get(1) == Some(&b':');
+        if declared.starts_with('/') || has_drive {
+            return PathBuf::from(declared);
+        }
+        declared
+            .split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+            .fold(base_dir.to_path_buf(), |path, part| path.join(part))
+    }
+}
+
+/// Fichier de description des répertoires
+pub struct RepFile {
+    /// Chemin du fichier
+    path: PathBuf,
+    /// Header du fichier
+    header: RepHeader,
+    /// Entrées lues
+    entries: Vec<RepEntry>,
+}
+
+impl RepFile {
+    /**
+     * Ouvre un fichier .rep et lit toutes ses entrées.
+     *
+     * @param path - Chemin vers le fichier .rep
+     * @returns Result<RepFile> - Fichier lu ou erreur si le header ou une entrée est invalide
+     *
+     * Effets de bord :
+     * - Lit le fichier en entier
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = source::open(&path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+        let file_size = file.size()?;
+
+        let mut reader = BufReader::new(file);
+        let header = Self::read_header(&mut reader)
+            .with_context(|| format!("Header de fichier .rep invalide: {:?}", path))?;
+        if header.data_offset as u64 > file_size {
+            anyhow::bail!("Offset des entrées {} au-delà de la fin du fichier {:?}", header.data_offset, path);
+        }
+
+        reader.seek(SeekFrom::Start(header.data_offset as u64))?;
+        let mut position = header.data_offset as u64;
+        let mut entries = Vec::with_capacity(header.entry_count.min(4096) as usize);
+        for index in 0..header.entry_count {
+            let (entry, length) = Self::read_entry(&mut reader, position, file_size)
+                .with_context(|| format!("Entrée {} de {:?}", index, path))?;
+            entries.push(entry);
+            position += length;
+        }
+
+        Ok(Self {
+            path,
+            header,
+            entries,
+        })
+    }
+
+    /**
+     * Lit et valide le header d'un fichier .rep.
+     */
+    fn read_header<R: Read + Seek>(reader: &mut R) -> Result<RepHeader> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut magic_bytes = [0u8; 4];
+        reader.read_exact(&mut magic_bytes)?;
+        if &magic_bytes[0..3] != b"REP" && &magic_bytes[0..3] != b"PCS" {
+            anyhow::bail!("Magic bytes invalides: {:?} (attendu: REP ou PCS)", magic_bytes);
+        }
+
+        let version = reader.read_u16::<LittleEndian>()?;
+        let _reserved = reader.read_u16::<LittleEndian>()?;
+        let entry_count = reader.read_u32::<LittleEndian>()?;
+        let data_offset = reader.read_u32::<LittleEndian>()?;
+
+        if data_offset < 0x10 {
+            anyhow::bail!("Offset des entrées {} dans le header", data_offset);
+        }
+
+        Ok(RepHeader {
+            magic: u32::from_le_bytes(magic_bytes),
+            version,
+            entry_count,
+            data_offset,
+        })
+    }
+
+    /**
+     * Lit une entrée à la position courante.
+     *
+     * @returns Result<(RepEntry, u64)> - Entrée et taille lue
+     */
+    fn read_entry<R: Read>(reader: &mut R, position: u64, file_size: u64) -> Result<(RepEntry, u64)> {
+        let entry_length = reader.read_u16::<LittleEndian>()?;
+        if entry_length < 6 || position + entry_length as u64 > file_size {
+            anyhow::bail!("Taille d'entrée {} invalide à l'offset {}", entry_length, position);
+        }
+
+        let mut buffer = vec![0u8; entry_length as usize - 2];
+        reader.read_exact(&mut buffer)?;
+
+        let flags = buffer[0];
+        let name_length = buffer[1] as usize;
+        let name = buffer.get(2..2 + name_length).context("Nom logique tronqué")?;
+        let path_start = 2 + name_length;
+        let path_length = buffer.get(path_start..path_start + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+            .context("Chemin tronqué")?;
+        let path = buffer.get(path_start + 2..path_start + 2 + path_length).context("Chemin tronqué")?;
+
+        let decode = |bytes: &[u8]| WINDOWS_1252.decode(bytes).0.trim_end_matches('\0').trim().to_string();
+        Ok((
+            RepEntry {
+                name: decode(name),
+                path: decode(path),
+                disabled: flags & ENTRY_DISABLED != 0,
+            },
+            entry_length as u64,
+        ))
+    }
+
+    /**
+     * Retourne une référence vers le header du fichier.
+     *
+     * @returns &RepHeader - Référence vers le header
+     */
+    pub fn header(&self) -> &RepHeader {
+        &self.header
+    }
+
+    /**
+     * Retourne les entrées du fichier, désactivées comprises.
+     *
+     * @returns &[RepEntry] - Entrées dans l'ordre du fichier
+     */
+    pub fn entries(&self) -> &[RepEntry] {
+        &self.entries
+    }
+
+    /**
+     * Retourne le dossier du fichier .rep, base des chemins relatifs.
+     *
+     * @returns &Path - Dossier contenant le fichier
+     */
+    pub fn base_dir(&self) -> &Path {
+        self.path.parent().unwrap_or_else(|| Path::new("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn entry(flags: u8, name: &str, path: &str) -> Vec<u8> {
+        let mut body = vec![flags, name.len() as u8];
+        body.extend_from_slice(name.as_bytes());
+        body.extend_from_slice(&(path.len() as u16).to_le_bytes());
+        body.extend_from_slice(path.as_bytes());
+
+        let mut data = ((body.len() + 2) as u16).to_le_bytes().to_vec();
+        data.extend(body);
+        data
+    }
+
+    fn create_test_rep_file() -> Result<NamedTempFile> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"REP\0");
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes()); // Nombre d'entrées
+        data.extend_from_slice(&0x10u32.to_le_bytes()); // Offset des entrées
+        data.extend(entry(0, "Client", r".\Compta\CLIENT.FIC"));
+        data.extend(entry(0, "Produit", r"C:\Donnees\Stock"));
+        data.extend(entry(ENTRY_DISABLED, "Ancien", r"..\Archives"));
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&data)?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_read_entries() -> Result<()> {
+        let test_file = create_test_rep_file()?;
+        let rep = RepFile::open(test_file.path())?;
+        assert_eq!(rep.header().entry_count, 3);
+        assert_eq!(rep.entries().len(), 3);
+        assert_eq!(rep.entries()[0].name, "Client");
+        assert_eq!(rep.entries()[1].path, r"C:\Donnees\Stock");
+        assert!(rep.entries()[2].disabled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_paths() {
+        let base = Path::new("/data/projet");
+        let entry = |path: &str| RepEntry { name: "T".to_string(), path: path.to_string(), disabled: false };
+        assert_eq!(entry(r".\Compta\CLIENT.FIC").resolve(base), PathBuf::from("/data/projet/Compta/CLIENT.FIC"));
+        assert_eq!(entry(r"..\Archives").resolve(base), PathBuf::from("/data/projet/../Archives"));
+        assert_eq!(entry(r"C:\Donnees\Stock").resolve(base), PathBuf::from("C:/Donnees/Stock"));
+        assert_eq!(entry("/srv/hfsql").resolve(base), PathBuf::from("/srv/hfsql"));
+    }
+}
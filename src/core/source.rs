@@ -0,0 +1,274 @@
+/**
+ * Source des fichiers HFSQL : dossier sur disque ou archive.
+ *
+ * Les lecteurs (.fic, .mmo, .ndx, .ftx, .jnl, .rep) et le moteur passent
+ * par ce module plutôt que par std::fs. Un chemin qui traverse une
+ * archive (`sauvegarde.zip/CLIENT.FIC`, `sauvegarde.tar.gz/COMPTA`) est
+ * résolu dans l'archive ; tout autre chemin désigne un fichier sur disque.
+ * Un fichier d'archive (.zip, .tar, .tar.gz, .tgz) est vu comme un dossier,
+ * ce qui permet de pointer `data_dir` (ou `--data-dir`) sur une sauvegarde.
+ *
+ * Les archives sont en lecture seule : les écritures (reconstruction
+ * d'index, cache de schémas) restent sur disque.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/archive.rs
+ * - Utilisé par les lecteurs de src/core/, src/storage/engine.rs et src/storage/cache.rs
+ */
+
+use crate::core::archive::{Archive, ArchiveKind};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Fichier ouvert : fichier sur disque, ou fenêtre d'un fichier dans une archive
+#[derive(Debug)]
+pub enum SourceFile {
+    Plain(File),
+    Entry {
+        file: File,
+        /// Position du contenu dans `file`
+        start: u64,
+        len: u64,
+        /// Position courante, relative au contenu
+        pos: u64,
+        /// Archive conservée ouverte tant que le fichier l'est
+        archive: Arc<Archive>,
+    },
+}
+
+impl SourceFile {
+    /**
+     * Taille du fichier.
+     *
+     * @returns io::Result<u64> - Taille en octets
+     */
+    pub fn size(&self) -> io::Result<u64> {
+        match self {
+            SourceFile::Plain(file) => Ok(file.metadata()?.len()),
+            SourceFile::Entry { len, .. } => Ok(*len),
+        }
+    }
+
+    /// Archive d'origine (None pour un fichier sur disque)
+    pub fn archive(&self) -> Option<&Arc<Archive>> {
+        match self {
+            SourceFile::Plain(_) => None,
+            SourceFile::Entry { archive, .. } => Some(archive),
+        }
+    }
+}
+
+impl Read for SourceFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            SourceFile::Plain(file) => file.read(buf),
+            SourceFile::Entry { file, start, len, pos, .. } => {
+                if *pos >= *len {
+                    return Ok(0);
+                }
+                let wanted = buf.len().min((*len - *pos) as usize);
+                file.seek(SeekFrom::Start(*start + *pos))?;
+                let read = file.read(&mut buf[..wanted])?;
+                *pos += read as u64;
+                Ok(read)
+            }
+        }
+    }
+}
+
+impl Seek for SourceFile {
+    fn seek(&mut self, target: SeekFrom) -> io::Result<u64> {
+        match self {
+            SourceFile::Plain(file) => file.seek(target),
+            SourceFile::Entry { len, pos, .. } => {
+                let next = match target {
+                    SeekFrom::Start(offset) => Some(offset),
+                    SeekFrom::End(delta) => len.checked_add_signed(delta),
+                    SeekFrom::Current(delta) => pos.checked_add_signed(delta),
+                };
+                *pos = next.ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "Position négative dans une entrée d'archive")
+                })?;
+                Ok(*pos)
+            }
+        }
+    }
+}
+
+/// Métadonnées d'un fichier ou d'un dossier, sur disque ou dans une archive
+#[derive(Debug, Clone, Copy)]
+pub struct SourceMetadata {
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+    pub is_dir: bool,
+}
+
+/// Emplacement résolu d'un chemin
+enum Location {
+    Disk(PathBuf),
+    /// Archive et chemin relatif à sa racine ("" pour la racine)
+    Archive(Arc<Archive>, String),
+}
+
+/**
+ * Indique si un chemin désigne un fichier d'archive reconnu sur disque.
+ *
+ * @param path - Chemin à tester
+ * @returns bool - true pour un .zip, .tar, .tar.gz ou .tgz existant
+ */
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveKind::detect(path).is_some() && path.is_file()
+}
+
+/**
+ * Résout un chemin sur disque ou dans une archive.
+ *
+ * @param path - Chemin (éventuellement à travers une archive)
+ * @returns io::Result<Location> - Emplacement, ou erreur si l'archive est illisible
+ */
+fn locate(path: &Path) -> io::Result<Location> {
+    if path.exists() {
+        if is_archive(path) {
+            return Ok(Location::Archive(Archive::open(path)?, String::new()));
+        }
+        return Ok(Location::Disk(path.to_path_buf()));
+    }
+    for ancestor in path.ancestors().skip(1) {
+        if is_archive(ancestor) {
+            let inner = path.strip_prefix(ancestor).unwrap_or(path);
+            let name = inner.components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            return Ok(Location::Archive(Archive::open(ancestor)?, name));
+        }
+        if ancestor.exists() {
+            break;
+        }
+    }
+    Ok(Location::Disk(path.to_path_buf()))
+}
+
+/**
+ * Ouvre un fichier en lecture.
+ *
+ * @param path - Chemin du fichier (éventuellement dans une archive)
+ * @returns io::Result<SourceFile> - Fichier ouvert
+ *
+ * Effets de bord :
+ * - Peut indexer l'archive, ou extraire une entrée compressée dans un fichier temporaire
+ */
+pub fn open(path: &Path) -> io::Result<SourceFile> {
+    match locate(path)? {
+        Location::Disk(path) => Ok(SourceFile::Plain(File::open(path)?)),
+        Location::Archive(archive, name) => {
+            let (file, start, len) = archive.open_entry(&name)?;
+            Ok(SourceFile::Entry { file, start, len, pos: 0, archive })
+        }
+    }
+}
+
+/**
+ * Métadonnées d'un chemin.
+ *
+ * Les entrées d'archive portent la date de modification de l'archive.
+ *
+ * @param path - Chemin (éventuellement dans une archive)
+ * @returns io::Result<SourceMetadata> - Taille, date et nature
+ */
+pub fn metadata(path: &Path) -> io::Result<SourceMetadata> {
+    match locate(path)? {
+        Location::Disk(path) => {
+            let metadata = std::fs::metadata(path)?;
+            Ok(SourceMetadata { len: metadata.len(), modified: metadata.modified().ok(), is_dir: metadata.is_dir() })
+        }
+        Location::Archive(archive, name) => {
+            let entry = archive.metadata(&name).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("{:?} absent de l'archive {:?}", name, archive.path()))
+            })?;
+            Ok(SourceMetadata { len: entry.len, modified: archive.modified(), is_dir: entry.is_dir })
+        }
+    }
+}
+
+/// Indique si un chemin existe (sur disque ou dans une archive)
+pub fn exists(path: &Path) -> bool {
+    metadata(path).is_ok()
+}
+
+/// Indique si un chemin est un fichier (sur disque ou dans une archive)
+pub fn is_file(path: &Path) -> bool {
+    metadata(path).is_ok_and(|metadata| !metadata.is_dir)
+}
+
+/// Indique si un chemin est un dossier (dossier, archive, ou dossier d'une archive)
+pub fn is_dir(path: &Path) -> bool {
+    metadata(path).is_ok_and(|metadata| metadata.is_dir)
+}
+
+/**
+ * Liste un dossier.
+ *
+ * @param path - Dossier, archive, ou dossier d'une archive
+ * @returns io::Result<Vec<PathBuf>> - Chemins des fichiers et sous-dossiers
+ */
+pub fn read_dir(path: &Path) -> io::Result<Vec<PathBuf>> {
+    match locate(path)? {
+        Location::Disk(dir) => std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect(),
+        Location::Archive(archive, name) => {
+            let children = archive.read_dir(&name).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("{:?} absent de l'archive {:?}", name, archive.path()))
+            })?;
+            Ok(children.into_iter().map(|child| path.join(child)).collect())
+        }
+    }
+}
+
+/**
+ * Archive contenant un chemin.
+ *
+ * @param path - Chemin à résoudre
+ * @returns io::Result<Option<Arc<Archive>>> - Archive, None pour un chemin sur disque
+ */
+pub fn archive_containing(path: &Path) -> io::Result<Option<Arc<Archive>>> {
+    Ok(match locate(path)? {
+        Location::Disk(_) => None,
+        Location::Archive(archive, _) => Some(archive),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::archive::tests::write_zip;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_paths() {
+        let dir = TempDir::new().unwrap();
+        let zip = dir.path().join("sauvegarde.zip");
+        write_zip(&zip, &[("CLIENT.FIC", b"0123456789", false), ("COMPTA/FACTURE.FIC", b"abc", false)]);
+        std::fs::write(dir.path().join("LOCAL.FIC"), b"local").unwrap();
+
+        assert!(is_archive(&zip) && is_dir(&zip));
+        assert!(is_file(&zip.join("CLIENT.FIC")));
+        assert!(is_dir(&zip.join("COMPTA")));
+        assert!(!exists(&zip.join("ABSENT.FIC")));
+        assert_eq!(read_dir(&zip.join("COMPTA")).unwrap(), vec![zip.join("COMPTA/FACTURE.FIC")]);
+        assert!(archive_containing(&dir.path().join("LOCAL.FIC")).unwrap().is_none());
+
+        let mut file = open(&zip.join("CLIENT.FIC")).unwrap();
+        assert_eq!(file.size().unwrap(), 10);
+        file.seek(SeekFrom::End(-3)).unwrap();
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, b"789");
+        assert!(file.archive().is_some());
+        assert!(matches!(open(&dir.path().join("LOCAL.FIC")).unwrap(), SourceFile::Plain(_)));
+    }
+}
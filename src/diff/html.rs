@@ -0,0 +1,117 @@
+/**
+ * Rendu HTML d'un rapport de comparaison.
+ *
+ * Produit une page autonome (styles intégrés, sans script) que l'on peut
+ * joindre à un ticket : un résumé par table, puis le détail des
+ * enregistrements ajoutés, supprimés et modifiés avec les valeurs avant/après.
+ */
+
+use crate::diff::{DiffReport, FieldChange, RecordChange, TableStatus};
+use std::fmt::Write;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f0f0f0}\
+.added{background:#e6ffed}.removed{background:#ffeef0}.changed{background:#fff8e1}\
+.before{color:#b31d28;text-decoration:line-through}.after{color:#22863a}\
+code{font-size:0.9em}";
+
+/// Échappe les caractères spéciaux HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Valeur d'une rubrique pour l'affichage (vide si absente)
+fn value(field: &Option<crate::storage::engine::FieldValue>) -> String {
+    field.as_ref().map(|v| escape(&v.to_string())).unwrap_or_default()
+}
+
+/// Cellule des valeurs d'un enregistrement différent
+fn field_cell(change: RecordChange, fields: &[FieldChange]) -> String {
+    let mut cell = String::new();
+    for field in fields {
+        let _ = match change {
+            RecordChange::Changed => write!(
+                cell,
+                "<div><code>{}</code> : <span class=\"before\">{}</span> → <span class=\"after\">{}</span></div>",
+                escape(&field.field), value(&field.before), value(&field.after)
+            ),
+            RecordChange::Added => write!(cell, "<div><code>{}</code> : {}</div>", escape(&field.field), value(&field.after)),
+            RecordChange::Removed => write!(cell, "<div><code>{}</code> : {}</div>", escape(&field.field), value(&field.before)),
+        };
+    }
+    cell
+}
+
+/**
+ * Produit la page HTML d'un rapport de comparaison.
+ *
+ * @param report - Rapport produit par diff_engines
+ * @returns String - Document HTML complet
+ */
+pub fn render_html(report: &DiffReport) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"fr\"><head><meta charset=\"utf-8\"><title>Comparaison HFSQL</title><style>{}</style></head><body>\n\
+         <h1>Comparaison HFSQL</h1>\n<p>Référence : <code>{}</code><br>Comparée : <code>{}</code></p>\n",
+        STYLE,
+        escape(&report.left.display().to_string()),
+        escape(&report.right.display().to_string()),
+    );
+
+    html.push_str("<h2>Résumé</h2>\n<table><tr><th>Table</th><th>État</th><th>Clé</th><th>Avant</th><th>Après</th><th>Ajoutés</th><th>Supprimés</th><th>Modifiés</th></tr>\n");
+    for table in &report.tables {
+        let class = match table.status {
+            TableStatus::Added => "added",
+            TableStatus::Removed => "removed",
+            TableStatus::Changed => "changed",
+            TableStatus::Unchanged => "",
+        };
+        let key = if table.key.is_empty() { "ID".to_string() } else { table.key.join(", ") };
+        let _ = writeln!(
+            html,
+            "<tr class=\"{}\"><td><a href=\"#{}\">{}</a></td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            class, escape(&table.table), escape(&table.table), table.status, escape(&key),
+            table.left_records, table.right_records, table.added, table.removed, table.changed
+        );
+    }
+    html.push_str("</table>\n");
+
+    for table in report.tables.iter().filter(|t| !t.records.is_empty()) {
+        let _ = writeln!(html, "<h2 id=\"{}\">{}</h2>", escape(&table.table), escape(&table.table));
+        if table.duplicate_keys > 0 {
+            let _ = writeln!(html, "<p>{} clé(s) en double ignorée(s).</p>", table.duplicate_keys);
+        }
+        html.push_str("<table><tr><th>Différence</th><th>Clé</th><th>ID avant</th><th>ID après</th><th>Rubriques</th></tr>\n");
+        for record in &table.records {
+            let (class, label) = match record.change {
+                RecordChange::Added => ("added", "Ajouté"),
+                RecordChange::Removed => ("removed", "Supprimé"),
+                RecordChange::Changed => ("changed", "Modifié"),
+            };
+            let id = |id: Option<u32>| id.map(|id| id.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr class=\"{}\"><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                class, label, escape(&record.key), id(record.left_id), id(record.right_id),
+                field_cell(record.change, &record.fields)
+            );
+        }
+        html.push_str("</table>\n");
+        if table.truncated {
+            let _ = writeln!(
+                html,
+                "<p>Seuls les {} premiers enregistrements sont détaillés.</p>",
+                table.records.len()
+            );
+        }
+    }
+
+    html.push_str("</body></html>\n");
+    html
+}
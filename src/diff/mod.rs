@@ -0,0 +1,431 @@
+/**
+ * Module de comparaison de deux copies d'une base HFSQL.
+ *
+ * Quand des données « ont disparu entre lundi et aujourd'hui », on compare
+ * deux copies de la même base (dossiers de données ou sauvegardes). Ce module
+ * ouvre un StorageEngine par copie et rapporte, table par table, les
+ * enregistrements ajoutés, supprimés et modifiés, avec l'ancienne et la
+ * nouvelle valeur de chaque rubrique changée.
+ *
+ * Les enregistrements sont appariés par clé primaire (premier index sans
+ * doublons dont toutes les rubriques sont connues, ou rubriques choisies par
+ * l'utilisateur), à défaut par ID d'enregistrement. La comparaison se fait
+ * en deux passes pour borner la mémoire : une empreinte par enregistrement
+ * de chaque côté, puis relecture des seuls enregistrements différents.
+ *
+ * - mod.rs : Comparaison et structures du rapport
+ * - html.rs : Rendu HTML du rapport
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs pour lire les tables des deux copies
+ * - Utilise src/cdc/capture.rs pour les empreintes d'enregistrements
+ * - Utilisé par la commande `fic diff` (src/cli/commands.rs)
+ */
+
+pub mod html;
+
+pub use html::render_html;
+
+use crate::cdc::capture::fingerprint;
+use crate::core::{source, TableSchema};
+use crate::storage::engine::FieldValue;
+use crate::storage::{Fields, Record, StorageEngine};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Nombre d'enregistrements détaillés par table par défaut
+pub const DEFAULT_MAX_RECORDS: usize = 1000;
+
+/// Options de comparaison
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Tables à comparer (vide = toutes les tables des deux copies)
+    pub tables: Vec<String>,
+    /// Rubriques de la clé d'appariement (None = clé primaire détectée, sinon ID)
+    pub key: Option<Vec<String>>,
+    /// Nombre maximum d'enregistrements détaillés par table (les compteurs restent complets)
+    pub max_records: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            tables: Vec::new(),
+            key: None,
+            max_records: DEFAULT_MAX_RECORDS,
+        }
+    }
+}
+
+/// Rapport de comparaison de deux copies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffReport {
+    /// Dossier de la copie de référence (avant)
+    pub left: PathBuf,
+    /// Dossier de la copie comparée (après)
+    pub right: PathBuf,
+    /// Date de la comparaison (secondes depuis l'époque Unix)
+    pub timestamp: u64,
+    /// Résultat par table, trié par nom
+    pub tables: Vec<TableDiff>,
+}
+
+/// État d'une table entre les deux copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStatus {
+    /// Table présente seulement dans la copie comparée
+    Added,
+    /// Table présente seulement dans la copie de référence
+    Removed,
+    /// Au moins un enregistrement diffère
+    Changed,
+    /// Contenu identique
+    Unchanged,
+}
+
+/// Nature de la différence d'un enregistrement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordChange {
+    /// Présent seulement dans la copie comparée
+    Added,
+    /// Présent seulement dans la copie de référence
+    Removed,
+    /// Présent des deux côtés avec des valeurs différentes
+    Changed,
+}
+
+/// Différences d'une table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDiff {
+    /// Nom qualifié de la table
+    pub table: String,
+    /// État de la table
+    pub status: TableStatus,
+    /// Rubriques de la clé d'appariement (vide = ID d'enregistrement)
+    pub key: Vec<String>,
+    /// Enregistrements actifs dans la copie de référence
+    pub left_records: u32,
+    /// Enregistrements actifs dans la copie comparée
+    pub right_records: u32,
+    /// Enregistrements ajoutés
+    pub added: u32,
+    /// Enregistrements supprimés
+    pub removed: u32,
+    /// Enregistrements modifiés
+    pub changed: u32,
+    /// Enregistrements ignorés car leur clé est en double (de chaque côté)
+    pub duplicate_keys: u32,
+    /// Détail des enregistrements différents (au plus max_records)
+    pub records: Vec<RecordDiff>,
+    /// Vrai si des enregistrements différents ne sont pas détaillés
+    pub truncated: bool,
+}
+
+/// Différence d'un enregistrement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordDiff {
+    /// Nature de la différence
+    pub change: RecordChange,
+    /// Valeur de la clé d'appariement
+    pub key: String,
+    /// ID dans la copie de référence
+    pub left_id: Option<u32>,
+    /// ID dans la copie comparée
+    pub right_id: Option<u32>,
+    /// Rubriques changées (toutes les rubriques pour un ajout ou une suppression)
+    pub fields: Vec<FieldChange>,
+}
+
+/// Valeur d'une rubrique avant et après
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Nom de la rubrique (ou du mémo)
+    pub field: String,
+    /// Valeur dans la copie de référence
+    pub before: Option<FieldValue>,
+    /// Valeur dans la copie comparée
+    pub after: Option<FieldValue>,
+}
+
+/// Empreinte d'un enregistrement, par valeur de clé
+struct Fingerprints {
+    records: BTreeMap<String, (u32, u64)>,
+    duplicates: BTreeSet<String>,
+    count: u32,
+}
+
+/**
+ * Ouvre une copie de base en lecture seule et scanne ses tables.
+ *
+ * @param path - Dossier de données de la copie (ou archive de sauvegarde)
+ * @param parallel - Active le multi-threading
+ * @returns Result<StorageEngine> - Moteur prêt à être comparé
+ */
+pub fn open_copy(path: &Path, parallel: bool) -> Result<StorageEngine> {
+    if !source::is_dir(path) {
+        anyhow::bail!("Le chemin n'est pas un dossier ni une archive: {:?}", path);
+    }
+    let engine = StorageEngine::new_with_parallel(path, true, parallel)?;
+    engine.scan_tables()?;
+    Ok(engine)
+}
+
+/**
+ * Compare deux copies d'une base.
+ *
+ * @param left - Copie de référence (avant)
+ * @param right - Copie comparée (après)
+ * @param options - Tables, clé d'appariement et nombre d'enregistrements détaillés
+ * @returns Result<DiffReport> - Différences par table
+ *
+ * Effets de bord :
+ * - Lit deux fois les enregistrements des tables des deux copies (empreintes, puis détail)
+ */
+pub fn diff_engines(left: &StorageEngine, right: &StorageEngine, options: &DiffOptions) -> Result<DiffReport> {
+    let tables: BTreeSet<String> = if options.tables.is_empty() {
+        left.list_tables().into_iter().chain(right.list_tables()).collect()
+    } else {
+        for table in &options.tables {
+            if !left.has_table(table) && !right.has_table(table) {
+                anyhow::bail!("Table '{}' absente des deux copies", table);
+            }
+        }
+        options.tables.iter().cloned().collect()
+    };
+
+    let tables = tables.iter()
+        .map(|table| diff_table(left, right, table, options)
+            .with_context(|| format!("Comparaison de la table '{}' impossible", table)))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(DiffReport {
+        left: left.data_dir().to_path_buf(),
+        right: right.data_dir().to_path_buf(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        tables,
+    })
+}
+
+/**
+ * Compare une table entre les deux copies.
+ *
+ * @param left - Copie de référence
+ * @param right - Copie comparée
+ * @param table - Nom qualifié de la table
+ * @param options - Clé d'appariement et nombre d'enregistrements détaillés
+ * @returns Result<TableDiff> - Différences de la table
+ */
+fn diff_table(left: &StorageEngine, right: &StorageEngine, table: &str, options: &DiffOptions) -> Result<TableDiff> {
+    let left_schema = left.has_table(table).then(|| left.get_schema(table)).transpose()?;
+    let right_schema = right.has_table(table).then(|| right.get_schema(table)).transpose()?;
+    let key = match (&options.key, &left_schema, &right_schema) {
+        (Some(key), _, _) => {
+            for schema in left_schema.iter().chain(right_schema.iter()) {
+                if let Some(missing) = key.iter().find(|field| !schema.fields.iter().any(|f| &f.name == *field)) {
+                    anyhow::bail!("Rubrique de clé '{}' absente de la table '{}'", missing, table);
+                }
+            }
+            key.clone()
+        }
+        (None, Some(schema), other) => primary_key(schema, other.as_ref()),
+        (None, None, Some(schema)) => primary_key(schema, None),
+        (None, None, None) => Vec::new(),
+    };
+
+    let empty = || Fingerprints { records: BTreeMap::new(), duplicates: BTreeSet::new(), count: 0 };
+    let before = match &left_schema {
+        Some(_) => fingerprints(left, table, &key)?,
+        None => empty(),
+    };
+    let after = match &right_schema {
+        Some(_) => fingerprints(right, table, &key)?,
+        None => empty(),
+    };
+
+    let mut diff = TableDiff {
+        table: table.to_string(),
+        status: match (&left_schema, &right_schema) {
+            (None, _) => TableStatus::Added,
+            (_, None) => TableStatus::Removed,
+            _ => TableStatus::Unchanged,
+        },
+        key: key.clone(),
+        left_records: before.count,
+        right_records: after.count,
+        added: 0,
+        removed: 0,
+        changed: 0,
+        duplicate_keys: (before.duplicates.len() + after.duplicates.len()) as u32,
+        records: Vec::new(),
+        truncated: false,
+    };
+
+    let mut pending: Vec<(RecordChange, &String, Option<u32>, Option<u32>)> = Vec::new();
+    for (value, (left_id, left_hash)) in &before.records {
+        match after.records.get(value) {
+            None => {
+                diff.removed += 1;
+                pending.push((RecordChange::Removed, value, Some(*left_id), None));
+            }
+            Some((right_id, right_hash)) if right_hash != left_hash => {
+                diff.changed += 1;
+                pending.push((RecordChange::Changed, value, Some(*left_id), Some(*right_id)));
+            }
+            Some(_) => {}
+        }
+    }
+    for (value, (right_id, _)) in &after.records {
+        if !before.records.contains_key(value) {
+            diff.added += 1;
+            pending.push((RecordChange::Added, value, None, Some(*right_id)));
+        }
+    }
+    if diff.status == TableStatus::Unchanged && diff.added + diff.removed + diff.changed > 0 {
+        diff.status = TableStatus::Changed;
+    }
+
+    diff.truncated = pending.len() > options.max_records;
+    for (change, value, left_id, right_id) in pending.into_iter().take(options.max_records) {
+        let before = left_id.map(|id| left.get_by_id(table, id)).transpose()?;
+        let after = right_id.map(|id| right.get_by_id(table, id)).transpose()?;
+        diff.records.push(RecordDiff {
+            change,
+            key: value.clone(),
+            left_id,
+            right_id,
+            fields: field_changes(before.as_ref(), after.as_ref(), !key.is_empty()),
+        });
+    }
+    Ok(diff)
+}
+
+/**
+ * Choisit la clé primaire d'une table : premier index sans doublons dont
+ * toutes les rubriques existent dans les deux copies.
+ *
+ * @param schema - Schéma de la copie où la table existe (référence en priorité)
+ * @param other - Schéma de l'autre copie, si la table y existe
+ * @returns Vec<String> - Rubriques de la clé (vide = appariement par ID)
+ */
+fn primary_key(schema: &TableSchema, other: Option<&TableSchema>) -> Vec<String> {
+    let known = |field: &String| other.is_none_or(|other| other.fields.iter().any(|f| &f.name == field));
+    schema.indexes.iter()
+        .filter(|index| index.unique && !index.fields.is_empty())
+        .find_map(|index| {
+            let fields: Option<Vec<String>> = index.fields.iter().cloned().collect();
+            fields.filter(|fields| fields.iter().all(known))
+        })
+        .unwrap_or_default()
+}
+
+/**
+ * Calcule l'empreinte de chaque enregistrement d'une table, par valeur de clé.
+ *
+ * Avec une clé primaire, l'ID n'entre pas dans l'empreinte : une fiche
+ * déplacée dans le .fic (réorganisation) n'est pas une modification.
+ * Les clés en double sont écartées et comptées.
+ */
+fn fingerprints(engine: &StorageEngine, table: &str, key: &[String]) -> Result<Fingerprints> {
+    let mut result = Fingerprints { records: BTreeMap::new(), duplicates: BTreeSet::new(), count: 0 };
+    result.count = engine.for_each_record(table, |mut record| {
+        let value = key_value(&record, key);
+        if !key.is_empty() {
+            record.fields.remove("id");
+        }
+        let hash = fingerprint(&record);
+        if result.duplicates.contains(&value) {
+            return Ok(());
+        }
+        if result.records.insert(value.clone(), (record.id, hash)).is_some() {
+            result.records.remove(&value);
+            result.duplicates.insert(value);
+        }
+        Ok(())
+    })?;
+    Ok(result)
+}
+
+/// Valeur de la clé d'appariement d'un enregistrement (rubriques séparées par " | ")
+fn key_value(record: &Record, key: &[String]) -> String {
+    if key.is_empty() {
+        return record.id.to_string();
+    }
+    key.iter()
+        .map(|field| record.fields.get(field).map(FieldValue::to_string).unwrap_or_else(|| "NULL".to_string()))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/**
+ * Liste les rubriques (mémos compris) qui diffèrent entre deux versions d'un enregistrement.
+ *
+ * @param before - Version de référence (None pour un ajout)
+ * @param after - Version comparée (None pour une suppression)
+ * @param skip_id - Ignore la rubrique "id" (appariement par clé primaire)
+ * @returns Vec<FieldChange> - Rubriques dans l'ordre du schéma
+ */
+fn field_changes(before: Option<&Record>, after: Option<&Record>, skip_id: bool) -> Vec<FieldChange> {
+    let empty = Fields::new();
+    let before = before.map_or(&empty, |record| &record.fields);
+    let after = after.map_or(&empty, |record| &record.fields);
+    // Rubriques de la version comparée, puis celles qui n'y figurent plus
+    let names = after.keys().chain(before.keys().filter(|name| !after.contains_key(name)));
+    names
+        .filter(|name| !(skip_id && name.as_str() == "id"))
+        .filter(|name| before.get(name) != after.get(name))
+        .map(|name| FieldChange {
+            field: name.clone(),
+            before: before.get(name).cloned(),
+            after: after.get(name).cloned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_by_record_id() -> Result<()> {
+        let (monday, today) = (TempDir::new()?, TempDir::new()?);
+        write_fic(&monday.path().join("CLIENT.FIC"), 8, &[(false, 1), (false, 2), (false, 3)])?;
+        write_fic(&today.path().join("CLIENT.FIC"), 8, &[(false, 1), (false, 9), (true, 3), (false, 4)])?;
+        write_fic(&monday.path().join("ARCHIVE.FIC"), 8, &[(false, 1)])?;
+        write_fic(&today.path().join("COMMANDE.FIC"), 8, &[(false, 1)])?;
+
+        let report = diff_engines(&open_copy(monday.path(), false)?, &open_copy(today.path(), false)?, &DiffOptions::default())?;
+        let status: Vec<(&str, TableStatus)> = report.tables.iter().map(|t| (t.table.as_str(), t.status)).collect();
+        assert_eq!(status, vec![
+            ("ARCHIVE", TableStatus::Removed),
+            ("CLIENT", TableStatus::Changed),
+            ("COMMANDE", TableStatus::Added),
+        ]);
+
+        let client = &report.tables[1];
+        assert!(client.key.is_empty());
+        assert_eq!((client.added, client.removed, client.changed), (1, 1, 1));
+        let changed = client.records.iter().find(|r| r.change == RecordChange::Changed).unwrap();
+        assert_eq!(changed.key, "1");
+        assert_eq!(changed.fields.len(), 1);
+        assert_eq!(changed.fields[0].field, "data");
+        assert_eq!(changed.fields[0].before, Some(FieldValue::binary(vec![0, 0, 2])));
+        assert_eq!(changed.fields[0].after, Some(FieldValue::binary(vec![0, 0, 9])));
+
+        let limited = DiffOptions { max_records: 1, ..Default::default() };
+        let report = diff_engines(&open_copy(monday.path(), false)?, &open_copy(today.path(), false)?, &limited)?;
+        assert!(report.tables[1].truncated);
+        assert_eq!(report.tables[1].records.len(), 1);
+        assert!(render_html(&report).contains("CLIENT"));
+        Ok(())
+    }
+}
@@ -6,9 +6,13 @@
  * spécialisés :
  * 
  * - api : Serveur HTTP REST et handlers pour l'accès aux données
+ * - backup : Sauvegarde cohérente d'une base avec manifeste (tailles, SHA-256)
+ * - cdc : Capture des changements d'une table entre deux exécutions
  * - cli : Interface en ligne de commande pour les opérations de maintenance
  * - config : Gestion de la configuration (fichiers, variables d'environnement)
  * - core : Traitement bas niveau des fichiers HFSQL (lecture, parsing)
+ * - diff : Comparaison de deux copies d'une base, enregistrement par enregistrement
+ * - profile : Profilage des rubriques d'une table (nulls, distincts, formats)
  * - sql : Parser et exécuteur SQL pour requêter les données
  * - storage : Moteur de stockage haut niveau (tables, schémas, requêtes)
  * 
@@ -20,12 +24,16 @@
 pub mod activity;
 pub mod ai;
 pub mod api;
+pub mod backup;
+pub mod cdc;
 pub mod cli;
 pub mod config;
 pub mod core;
+pub mod diff;
 pub mod dsn;
 pub mod logger;
 pub mod migration;
+pub mod profile;
 pub mod sql;
 pub mod storage;
 
@@ -0,0 +1,176 @@
+/**
+ * Détection de l'encodage des textes d'une table.
+ *
+ * Les octets bruts des rubriques texte et des mémos texte d'un échantillon
+ * d'enregistrements sont décodés avec chaque encodage candidat
+ * (TextEncoding::CANDIDATES) et notés par rank_encodings. Seules les
+ * valeurs contenant des octets non ASCII sont retenues : les autres se
+ * décodent à l'identique dans toutes les pages de code.
+ *
+ * Le résultat sert à renseigner [storage.encoding] ; il ne modifie pas
+ * la configuration.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/encoding.rs (scores) et src/storage/engine.rs (lecture)
+ * - Utilisé par la commande `fic encoding` et par GET /tables/:table/encoding
+ */
+
+use crate::core::encoding::rank_encodings;
+use crate::core::{EncodingScore, FieldType, TableSchema, TextEncoding};
+use crate::storage::{CancelToken, FieldValue, QueryFilters, RawSource, Record, StorageEngine};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Nombre d'enregistrements échantillonnés par défaut
+pub const DEFAULT_SAMPLE: u32 = 1000;
+
+/// Encodages candidats d'une table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodingReport {
+    /// Nom de la table
+    pub table: String,
+    /// Enregistrements échantillonnés
+    pub records: u32,
+    /// Valeurs non ASCII analysées (toutes rubriques)
+    pub samples: u32,
+    /// Candidats du plus au moins vraisemblable, toutes rubriques confondues
+    pub scores: Vec<EncodingScore>,
+    /// Détail des rubriques texte et mémo, dans l'ordre du schéma
+    pub fields: Vec<FieldEncoding>,
+}
+
+/// Encodage candidat d'une rubrique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldEncoding {
+    /// Nom de la rubrique
+    pub name: String,
+    /// Encodage configuré ([storage.encoding])
+    pub configured: TextEncoding,
+    /// Valeurs non ASCII analysées
+    pub samples: u32,
+    /// Encodage le plus vraisemblable (None sans valeur non ASCII)
+    pub detected: Option<TextEncoding>,
+    /// Candidats du plus au moins vraisemblable (vide sans valeur non ASCII)
+    pub scores: Vec<EncodingScore>,
+}
+
+/**
+ * Extrait les octets de texte d'une rubrique s'ils contiennent des octets non ASCII.
+ *
+ * @param record - Enregistrement lu avec ses octets bruts
+ * @param name - Nom de la rubrique
+ * @param field_type - Type de la rubrique
+ * @returns Option<&[u8]> - Octets du texte (sans le remplissage NUL), None si ASCII ou non textuel
+ */
+fn text_sample<'a>(record: &'a Record, name: &str, field_type: &FieldType) -> Option<&'a [u8]> {
+    let raw = record.fields.raw(name)?;
+    let bytes = match (field_type, raw.source) {
+        (FieldType::String | FieldType::Date | FieldType::Unknown, RawSource::Fic) => {
+            let bytes = raw.bytes();
+            &bytes[..bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len())]
+        }
+        (FieldType::Memo, RawSource::Mmo) if matches!(record.fields.get(name), Some(FieldValue::MemoText { .. })) => raw.bytes(),
+        _ => return None,
+    };
+    (!bytes.is_ascii()).then_some(bytes)
+}
+
+/**
+ * Classe les encodages candidats sur des enregistrements déjà lus.
+ *
+ * @param table - Nom de la table
+ * @param schema - Schéma de la table (encodages configurés compris)
+ * @param records - Enregistrements avec leurs octets bruts
+ * @returns EncodingReport - Scores globaux et par rubrique
+ */
+pub fn encoding_report(table: &str, schema: &TableSchema, records: &[Record]) -> EncodingReport {
+    let mut all_samples: Vec<&[u8]> = Vec::new();
+    let mut fields = Vec::new();
+    for field in &schema.fields {
+        if !matches!(field.field_type, FieldType::String | FieldType::Date | FieldType::Unknown | FieldType::Memo) {
+            continue;
+        }
+        let samples: Vec<&[u8]> = records.iter()
+            .filter_map(|record| text_sample(record, &field.name, &field.field_type))
+            .collect();
+        let scores = if samples.is_empty() {
+            Vec::new()
+        } else {
+            rank_encodings(&samples, &TextEncoding::CANDIDATES)
+        };
+        fields.push(FieldEncoding {
+            name: field.name.clone(),
+            configured: field.encoding,
+            samples: samples.len() as u32,
+            detected: scores.first().map(|score| score.encoding),
+            scores,
+        });
+        all_samples.extend(samples);
+    }
+
+    EncodingReport {
+        table: table.to_string(),
+        records: records.len() as u32,
+        samples: all_samples.len() as u32,
+        scores: if all_samples.is_empty() { Vec::new() } else { rank_encodings(&all_samples, &TextEncoding::CANDIDATES) },
+        fields,
+    }
+}
+
+/**
+ * Détecte l'encodage des textes d'une table sur un échantillon.
+ *
+ * @param engine - Moteur de stockage (tables déjà scannées)
+ * @param table - Nom de la table
+ * @param sample - Nombre d'enregistrements lus (les premiers de la table)
+ * @param cancel - Jeton vérifié à chaque enregistrement (annulation ou délai dépassé)
+ * @returns Result<EncodingReport> - Scores des encodages candidats
+ *
+ * Effets de bord :
+ * - Lit les `sample` premiers enregistrements (et leurs mémos)
+ */
+pub fn detect_encoding(engine: &StorageEngine, table: &str, sample: u32, cancel: &CancelToken) -> Result<EncodingReport> {
+    let schema = engine.get_schema(table)?;
+    let filters = QueryFilters { limit: Some(sample), ..Default::default() };
+    let result = engine.select_cancellable(table, filters, cancel)?;
+    Ok(encoding_report(table, &schema, &result.records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FieldInfo;
+    use crate::storage::{Fields, RawRef};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_encoding_report() {
+        let field = |name: &str, field_type| FieldInfo { name: name.to_string(), offset: 0, length: 0, field_type, encoding: Default::default() };
+        let schema = TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: 0,
+            field_count: 2,
+            fields: vec![field("id", FieldType::Integer), field("nom", FieldType::String)],
+            indexes: Vec::new(),
+            full_text: Vec::new(),
+        };
+        // "Hélène" et "Crème brûlée" en CP850, complétés par des NUL
+        let names: [&[u8]; 3] = [b"H\x82l\x8ane\0\0", b"Cr\x8ame br\x96l\x82e\0", b"Dupont\0\0"];
+        let records: Vec<Record> = names.iter().enumerate()
+            .map(|(id, bytes)| {
+                let buffer: Arc<[u8]> = Arc::from(*bytes);
+                let mut fields = Fields::new();
+                fields.insert("id", FieldValue::integer(id as i64));
+                fields.insert_raw("nom", FieldValue::string(String::new()), Some(RawRef::new(RawSource::Fic, 0, buffer.clone(), 0..buffer.len())));
+                Record { id: id as u32, fields }
+            })
+            .collect();
+
+        let report = encoding_report("CLIENT", &schema, &records);
+        assert_eq!((report.records, report.samples), (3, 2));
+        assert_eq!(report.fields.len(), 1);
+        assert_eq!(report.fields[0].configured, TextEncoding::Auto);
+        assert_eq!(report.fields[0].detected, Some(TextEncoding::Cp850));
+        assert_eq!(report.scores[0].encoding, TextEncoding::Cp850);
+    }
+}
@@ -0,0 +1,432 @@
+/**
+ * Module de profilage des rubriques d'une table.
+ *
+ * Avant une migration, on veut savoir ce que contient une table. Ce module
+ * parcourt tous les enregistrements et calcule, pour chaque rubrique du
+ * TableSchema :
+ * - Le nombre de valeurs nulles et vides (chaîne vide, binaire à zéro)
+ * - Le nombre de valeurs distinctes (exact jusqu'à TRACKED_VALUES_MAX,
+ *   estimé au-delà par HyperLogLog)
+ * - Le minimum et le maximum (nombres, textes, dates)
+ * - Les valeurs les plus fréquentes
+ * - L'histogramme des longueurs (textes, mémos, binaires)
+ * - Les formats reconnus (email, téléphone, SIRET, date)
+ *
+ * La mémoire est bornée : au plus TRACKED_VALUES_MAX valeurs comptées par
+ * rubrique et 4096 registres HyperLogLog.
+ *
+ * - mod.rs : Parcours et statistiques
+ * - patterns.rs : Détection des formats
+ * - encoding.rs : Détection de l'encodage des textes
+ * - relations.rs : Inférence des relations entre tables
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs pour parcourir les enregistrements
+ * - Utilisé par la commande `fic stats` et par GET /tables/:table/stats
+ */
+
+pub mod encoding;
+pub mod patterns;
+pub mod relations;
+
+pub use encoding::{detect_encoding, EncodingReport, FieldEncoding};
+pub use patterns::Pattern;
+pub use relations::{infer_relations, InferredRelation, NameMatch, RelationReport};
+
+use crate::core::{FieldType, TableSchema};
+use crate::storage::engine::FieldValue;
+use crate::storage::{CancelToken, Record, StorageEngine};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+
+/// Nombre de valeurs les plus fréquentes retournées par défaut
+pub const DEFAULT_TOP: usize = 10;
+
+/// Nombre maximum de valeurs distinctes comptées exactement par rubrique
+pub const TRACKED_VALUES_MAX: usize = 10_000;
+
+/// Bits de précision de l'estimateur HyperLogLog (2^12 registres, ~1,6 % d'erreur)
+const HLL_PRECISION: u32 = 12;
+
+/// Profil d'une table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableProfile {
+    /// Nom de la table
+    pub table: String,
+    /// Enregistrements actifs parcourus
+    pub records: u32,
+    /// Durée du profilage en millisecondes
+    pub duration_ms: u64,
+    /// Profil de chaque rubrique, dans l'ordre du schéma
+    pub fields: Vec<FieldProfile>,
+}
+
+/// Profil d'une rubrique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldProfile {
+    /// Nom de la rubrique
+    pub name: String,
+    /// Type de la rubrique
+    pub field_type: FieldType,
+    /// Valeurs non nulles
+    pub count: u64,
+    /// Valeurs nulles (ou mémo absent)
+    pub nulls: u64,
+    /// Valeurs vides (chaîne vide, binaire rempli de zéros)
+    pub empty: u64,
+    /// Nombre de valeurs distinctes non nulles
+    pub distinct: u64,
+    /// Vrai si `distinct` est exact, faux si estimé
+    pub distinct_exact: bool,
+    /// Plus petite valeur (nombres, textes, dates)
+    pub min: Option<FieldValue>,
+    /// Plus grande valeur (nombres, textes, dates)
+    pub max: Option<FieldValue>,
+    /// Valeurs les plus fréquentes, par nombre décroissant
+    pub top: Vec<ValueCount>,
+    /// Vrai si les fréquences sont exactes (moins de TRACKED_VALUES_MAX valeurs distinctes)
+    pub top_exact: bool,
+    /// Histogramme des longueurs (textes, mémos, binaires)
+    pub lengths: Vec<LengthBucket>,
+    /// Formats reconnus et nombre de valeurs correspondantes
+    pub patterns: Vec<PatternCount>,
+}
+
+/// Fréquence d'une valeur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueCount {
+    /// Valeur (forme texte)
+    pub value: String,
+    /// Nombre d'occurrences
+    pub count: u64,
+}
+
+/// Tranche de l'histogramme des longueurs (bornes incluses)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthBucket {
+    /// Longueur minimale de la tranche
+    pub min: usize,
+    /// Longueur maximale de la tranche
+    pub max: usize,
+    /// Nombre de valeurs
+    pub count: u64,
+}
+
+/// Nombre de valeurs d'un format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternCount {
+    /// Format reconnu
+    pub pattern: Pattern,
+    /// Nombre de valeurs ayant ce format
+    pub count: u64,
+}
+
+/// Estimateur HyperLogLog du nombre de valeurs distinctes
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self { registers: vec![0; 1 << HLL_PRECISION] }
+    }
+
+    fn insert(&mut self, value: &str) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        // Correction pour les petites cardinalités (comptage linéaire)
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// Statistiques en cours de calcul pour une rubrique
+struct FieldAccumulator {
+    name: String,
+    field_type: FieldType,
+    count: u64,
+    nulls: u64,
+    empty: u64,
+    hll: HyperLogLog,
+    values: HashMap<String, u64>,
+    values_exact: bool,
+    min: Option<FieldValue>,
+    max: Option<FieldValue>,
+    lengths: BTreeMap<usize, u64>,
+    patterns: BTreeMap<Pattern, u64>,
+}
+
+/// Compare deux valeurs de même nature (None si non comparables)
+fn compare(a: &FieldValue, b: &FieldValue) -> Option<Ordering> {
+    match (a, b) {
+        (FieldValue::Integer { value: a }, FieldValue::Integer { value: b }) => Some(a.cmp(b)),
+        (FieldValue::Float { value: a }, FieldValue::Float { value: b }) => a.partial_cmp(b),
+        _ => Some(a.as_text()?.cmp(b.as_text()?)),
+    }
+}
+
+/// Tranche de l'histogramme : 0, 1, 2-3, 4-7, 8-15…
+fn length_bucket(length: usize) -> usize {
+    if length == 0 { 0 } else { usize::BITS as usize - length.leading_zeros() as usize }
+}
+
+impl FieldAccumulator {
+    fn new(name: String, field_type: FieldType) -> Self {
+        Self {
+            name,
+            field_type,
+            count: 0,
+            nulls: 0,
+            empty: 0,
+            hll: HyperLogLog::new(),
+            values: HashMap::new(),
+            values_exact: true,
+            min: None,
+            max: None,
+            lengths: BTreeMap::new(),
+            patterns: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, record: &Record) {
+        let value = match record.fields.get(&self.name) {
+            Some(FieldValue::Null { .. }) | None => {
+                self.nulls += 1;
+                return;
+            }
+            Some(value) => value,
+        };
+        self.count += 1;
+
+        let length = match value {
+            FieldValue::String { value } | FieldValue::MemoText { value, .. } => Some(value.chars().count()),
+            FieldValue::Binary { value } => Some(value.len() / 2),
+            FieldValue::MemoBinary { size, .. } => Some(*size as usize),
+            _ => None,
+        };
+        let is_empty = match value {
+            FieldValue::String { value } | FieldValue::MemoText { value, .. } => value.is_empty(),
+            FieldValue::Binary { value } => value.bytes().all(|b| b == b'0'),
+            _ => false,
+        };
+        if is_empty {
+            self.empty += 1;
+        }
+        if let Some(length) = length {
+            *self.lengths.entry(length_bucket(length)).or_default() += 1;
+        }
+
+        if compare(value, value).is_some() && !is_empty {
+            if self.min.as_ref().is_none_or(|min| compare(value, min) == Some(Ordering::Less)) {
+                self.min = Some(value.clone());
+            }
+            if self.max.as_ref().is_none_or(|max| compare(value, max) == Some(Ordering::Greater)) {
+                self.max = Some(value.clone());
+            }
+        }
+
+        let text = value.to_string();
+        if (value.as_text().is_some() || matches!(value, FieldValue::Integer { .. })) && !is_empty {
+            for pattern in Pattern::ALL {
+                if patterns::matches(pattern, &text) {
+                    *self.patterns.entry(pattern).or_default() += 1;
+                }
+            }
+        }
+        self.hll.insert(&text);
+        if let Some(count) = self.values.get_mut(&text) {
+            *count += 1;
+        } else if self.values.len() < TRACKED_VALUES_MAX {
+            self.values.insert(text, 1);
+        } else {
+            self.values_exact = false;
+        }
+    }
+
+    fn finish(self, top: usize) -> FieldProfile {
+        let mut values: Vec<(String, u64)> = self.values.into_iter().collect();
+        let distinct = if self.values_exact { values.len() as u64 } else { self.hll.estimate().max(values.len() as u64) };
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        FieldProfile {
+            name: self.name,
+            field_type: self.field_type,
+            count: self.count,
+            nulls: self.nulls,
+            empty: self.empty,
+            distinct,
+            distinct_exact: self.values_exact,
+            min: self.min,
+            max: self.max,
+            top: values.into_iter()
+                .take(top)
+                .map(|(value, count)| ValueCount { value, count })
+                .collect(),
+            top_exact: self.values_exact,
+            lengths: self.lengths.into_iter()
+                .map(|(bucket, count)| match bucket {
+                    0 => LengthBucket { min: 0, max: 0, count },
+                    _ => LengthBucket { min: 1 << (bucket - 1), max: (1 << bucket) - 1, count },
+                })
+                .collect(),
+            patterns: self.patterns.into_iter()
+                .map(|(pattern, count)| PatternCount { pattern, count })
+                .collect(),
+        }
+    }
+}
+
+/// Un accumulateur par rubrique du schéma
+fn accumulators(schema: &TableSchema) -> Vec<FieldAccumulator> {
+    schema.fields.iter()
+        .map(|field| FieldAccumulator::new(field.name.clone(), field.field_type.clone()))
+        .collect()
+}
+
+/**
+ * Calcule le profil des rubriques à partir d'enregistrements.
+ *
+ * @param schema - Schéma de la table (rubriques profilées, dans l'ordre)
+ * @param records - Enregistrements à analyser
+ * @param top - Nombre de valeurs les plus fréquentes par rubrique
+ * @returns Result<(Vec<FieldProfile>, u32)> - Profils et nombre d'enregistrements lus
+ */
+pub fn profile_records(
+    schema: &TableSchema,
+    records: impl Iterator<Item = Result<Record>>,
+    top: usize,
+) -> Result<(Vec<FieldProfile>, u32)> {
+    let mut accumulators = accumulators(schema);
+    let mut count = 0u32;
+    for record in records {
+        let record = record?;
+        for accumulator in &mut accumulators {
+            accumulator.add(&record);
+        }
+        count += 1;
+    }
+    Ok((accumulators.into_iter().map(|a| a.finish(top)).collect(), count))
+}
+
+/**
+ * Profile toutes les rubriques d'une table.
+ *
+ * @param engine - Moteur de stockage (tables déjà scannées)
+ * @param table - Nom de la table
+ * @param top - Nombre de valeurs les plus fréquentes par rubrique
+ * @param cancel - Jeton vérifié à chaque enregistrement (annulation ou délai dépassé)
+ * @returns Result<TableProfile> - Statistiques par rubrique
+ *
+ * Effets de bord :
+ * - Lit tous les enregistrements de la table
+ */
+pub fn profile_table(engine: &StorageEngine, table: &str, top: usize, cancel: &CancelToken) -> Result<TableProfile> {
+    let started = Instant::now();
+    let schema = engine.get_schema(table)?;
+    let mut accumulators = accumulators(&schema);
+    let records = engine.for_each_record(table, |record| {
+        cancel.check()?;
+        for accumulator in &mut accumulators {
+            accumulator.add(&record);
+        }
+        Ok(())
+    })?;
+    Ok(TableProfile {
+        table: table.to_string(),
+        records,
+        duration_ms: started.elapsed().as_millis() as u64,
+        fields: accumulators.into_iter().map(|a| a.finish(top)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FieldInfo;
+    use crate::storage::Fields;
+
+    fn schema() -> TableSchema {
+        let field = |name: &str, field_type| FieldInfo { name: name.to_string(), offset: 0, length: 0, field_type, encoding: Default::default() };
+        TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: 0,
+            field_count: 3,
+            fields: vec![field("id", FieldType::Integer), field("email", FieldType::String), field("notes", FieldType::Memo)],
+            indexes: Vec::new(),
+            full_text: Vec::new(),
+        }
+    }
+
+    fn record(id: u32, email: Option<&str>) -> Result<Record> {
+        let mut fields = Fields::new();
+        fields.insert("id", FieldValue::integer(id as i64));
+        fields.insert("email", email.map_or_else(FieldValue::null, |e| FieldValue::string(e.to_string())));
+        fields.insert("notes", if id.is_multiple_of(2) {
+            FieldValue::memo_text("à rappeler".to_string(), 11)
+        } else {
+            FieldValue::null()
+        });
+        Ok(Record { id, fields })
+    }
+
+    #[test]
+    fn test_profile_fields() -> Result<()> {
+        let records = vec![
+            record(0, Some("a@exemple.fr")),
+            record(1, Some("b@exemple.fr")),
+            record(2, Some("a@exemple.fr")),
+            record(3, Some("")),
+            record(4, None),
+        ];
+        let (profiles, count) = profile_records(&schema(), records.into_iter(), 1)?;
+        assert_eq!(count, 5);
+
+        let id = &profiles[0];
+        assert_eq!((id.min.clone(), id.max.clone()), (Some(FieldValue::integer(0)), Some(FieldValue::integer(4))));
+        assert_eq!(id.distinct, 5);
+
+        let email = &profiles[1];
+        assert_eq!((email.count, email.nulls, email.empty, email.distinct), (4, 1, 1, 3));
+        assert_eq!(email.top[0].value, "a@exemple.fr");
+        assert_eq!(email.top[0].count, 2);
+        assert_eq!(email.patterns[0].pattern, Pattern::Email);
+        assert_eq!(email.patterns[0].count, 3);
+        assert_eq!(email.lengths, vec![
+            LengthBucket { min: 0, max: 0, count: 1 },
+            LengthBucket { min: 8, max: 15, count: 3 },
+        ]);
+
+        let notes = &profiles[2];
+        assert_eq!((notes.count, notes.nulls), (3, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_distinct_estimate() -> Result<()> {
+        let records = (0..50_000).map(|id| record(id, None));
+        let (profiles, _) = profile_records(&schema(), records, 0)?;
+        let id = &profiles[0];
+        assert!(!id.distinct_exact);
+        assert!((47_500..=52_500).contains(&id.distinct), "estimation: {}", id.distinct);
+        Ok(())
+    }
+}
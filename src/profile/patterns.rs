@@ -0,0 +1,101 @@
+/**
+ * Détection des formats de valeurs courants dans les données françaises.
+ *
+ * - Email : adresse électronique
+ * - Phone : numéro de téléphone français (0X XX XX XX XX, +33, 0033)
+ * - Siret : 14 chiffres avec clé de Luhn valide (espaces tolérés)
+ * - Date : AAAA-MM-JJ, JJ/MM/AAAA ou AAAAMMJJ (forme des dates HFSQL)
+ */
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Format reconnu dans une valeur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pattern {
+    /// Adresse électronique
+    Email,
+    /// Numéro de téléphone français
+    Phone,
+    /// Numéro SIRET (14 chiffres, clé de Luhn)
+    Siret,
+    /// Date
+    Date,
+}
+
+impl Pattern {
+    /// Tous les formats, dans l'ordre de détection
+    pub const ALL: [Pattern; 4] = [Pattern::Email, Pattern::Phone, Pattern::Siret, Pattern::Date];
+}
+
+static EMAIL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[A-Za-z]{2,}$").unwrap());
+static PHONE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:\+33\s?|0033\s?|0)[1-9](?:[\s.\-]?\d{2}){4}$").unwrap());
+static ISO_DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})(?:[T ]\d{2}:\d{2}(?::\d{2})?)?$").unwrap());
+static FR_DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{2})/(\d{2})/(\d{4})$").unwrap());
+static HFSQL_DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{4})(\d{2})(\d{2})$").unwrap());
+
+/**
+ * Indique si une valeur correspond à un format.
+ *
+ * @param pattern - Format recherché
+ * @param value - Valeur texte (sans espaces de fin)
+ * @returns bool - true si la valeur a ce format
+ */
+pub fn matches(pattern: Pattern, value: &str) -> bool {
+    let value = value.trim();
+    match pattern {
+        Pattern::Email => EMAIL.is_match(value),
+        Pattern::Phone => PHONE.is_match(value),
+        Pattern::Siret => {
+            let digits: String = value.chars().filter(|c| *c != ' ').collect();
+            digits.len() == 14 && digits.chars().all(|c| c.is_ascii_digit()) && luhn(&digits)
+        }
+        Pattern::Date => {
+            let parts = |captures: regex::Captures, year: usize, month: usize, day: usize| {
+                let number = |i: usize| captures[i].parse::<u32>().unwrap_or(0);
+                (number(year), number(month), number(day))
+            };
+            let date = ISO_DATE.captures(value).map(|c| parts(c, 1, 2, 3))
+                .or_else(|| FR_DATE.captures(value).map(|c| parts(c, 3, 2, 1)))
+                .or_else(|| HFSQL_DATE.captures(value).map(|c| parts(c, 1, 2, 3)));
+            date.is_some_and(|(year, month, day)| (1800..=2200).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day))
+        }
+    }
+}
+
+/// Clé de Luhn (SIREN, SIRET)
+fn luhn(digits: &str) -> bool {
+    let sum: u32 = digits.chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match (i % 2 == 1, digit * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        assert!(matches(Pattern::Email, "jean.dupont@exemple.fr"));
+        assert!(!matches(Pattern::Email, "jean.dupont@exemple"));
+        assert!(matches(Pattern::Phone, "01 23 45 67 89"));
+        assert!(matches(Pattern::Phone, "+33 6.12.34.56.78"));
+        assert!(!matches(Pattern::Phone, "12 34 56 78 90"));
+        assert!(matches(Pattern::Siret, "732 829 320 00074"));
+        assert!(!matches(Pattern::Siret, "73282932000075"));
+        assert!(matches(Pattern::Date, "2025-09-30"));
+        assert!(matches(Pattern::Date, "30/09/2025"));
+        assert!(matches(Pattern::Date, "20250930"));
+        assert!(!matches(Pattern::Date, "20251330"));
+    }
+}
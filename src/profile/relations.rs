@@ -0,0 +1,517 @@
+/**
+ * Inférence des relations (clés étrangères) entre les tables d'une base.
+ *
+ * Les fichiers .fic ne décrivent pas les liaisons de l'analyse WinDev :
+ * sans DSN ODBC, aucune relation n'est connue. Ce module propose des
+ * relations probables entre les tables d'une même base, en trois temps :
+ *
+ * 1. Noms : une rubrique source est rapprochée d'une rubrique cible si elle
+ *    suit la convention ID+table (`FACTURE.IDCLIENT` → `CLIENT.IDCLIENT` ou
+ *    `CLIENT.ID`), porte le même nom qu'elle, ou contient le nom de la table
+ *    cible (`LIGNE.NUMFACTURE` → `FACTURE.IDFACTURE`).
+ * 2. Types : les deux rubriques doivent être de la même famille (nombre,
+ *    texte, date).
+ * 3. Valeurs : les valeurs distinctes des premiers enregistrements de la
+ *    table source sont recherchées parmi celles de la rubrique cible, qui
+ *    doit être sans doublon (clé).
+ *
+ * Le score (0 à 1) combine la convention de nommage (40 %) et le
+ * recouvrement des valeurs (60 %). Un entier 0 et une chaîne vide sont
+ * considérés comme « pas de lien » et ignorés.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs (schémas, lecture projetée des rubriques)
+ * - Utilisé par GET /relations (src/api/handlers.rs)
+ */
+
+use crate::core::{FieldType, TableSchema};
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::{CancelToken, FieldValue, QueryFilters, Record, StorageEngine};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Instant;
+
+/// Nombre d'enregistrements échantillonnés par défaut dans chaque table source
+pub const DEFAULT_SAMPLE: u32 = 1000;
+
+/// Score minimal par défaut d'une relation retournée
+pub const DEFAULT_MIN_SCORE: f64 = 0.5;
+
+/// Nombre maximum de valeurs lues dans une rubrique cible (au-delà, le recouvrement est sous-estimé)
+pub const TARGET_VALUES_MAX: u32 = 200_000;
+
+/// Poids de la convention de nommage dans le score (le reste revient au recouvrement)
+const NAME_WEIGHT: f64 = 0.4;
+
+/// Rapprochement des noms ayant produit une relation candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMatch {
+    /// Convention ID+table (IDCLIENT → CLIENT.IDCLIENT ou CLIENT.ID)
+    Convention,
+    /// Même nom de rubrique des deux côtés
+    SameName,
+    /// Le nom de la rubrique source contient le nom de la table cible
+    TableName,
+}
+
+impl NameMatch {
+    /// Part du score de nommage (0 à 1)
+    fn weight(self) -> f64 {
+        match self {
+            NameMatch::Convention => 1.0,
+            NameMatch::SameName => 0.7,
+            NameMatch::TableName => 0.5,
+        }
+    }
+}
+
+/// Relation probable entre deux tables (mêmes champs que TableRelation, plus le score)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferredRelation {
+    /// Table qui référence (nom qualifié)
+    pub from_table: String,
+    /// Rubrique qui référence
+    pub from_column: String,
+    /// Table référencée (nom qualifié)
+    pub to_table: String,
+    /// Rubrique référencée (sans doublon)
+    pub to_column: String,
+    /// Vraisemblance de la relation (0 à 1)
+    pub score: f64,
+    /// Rapprochement des noms
+    pub name_match: NameMatch,
+    /// Part des valeurs échantillonnées trouvées dans la cible (None sans valeur)
+    pub overlap: Option<f64>,
+    /// Valeurs distinctes échantillonnées dans la rubrique source
+    pub sampled: u32,
+}
+
+/// Résultat de l'inférence des relations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationReport {
+    /// Tables examinées
+    pub tables: usize,
+    /// Durée de l'inférence en millisecondes
+    pub duration_ms: u64,
+    /// Relations retenues, par score décroissant
+    pub relations: Vec<InferredRelation>,
+}
+
+/// Table examinée
+struct TableInfo {
+    /// Nom qualifié (utilisé par le moteur)
+    name: String,
+    /// Nom sans la base, pour les conventions de nommage
+    bare: String,
+    schema: TableSchema,
+}
+
+/// Rubrique d'une table (indices dans la liste des tables et dans le schéma)
+type Column = (usize, usize);
+
+/// Relation candidate, avant vérification des valeurs
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    from: Column,
+    to: Column,
+    name_match: NameMatch,
+}
+
+/// Valeurs distinctes lues dans une rubrique
+#[derive(Debug, Default)]
+struct ColumnValues {
+    distinct: HashSet<String>,
+    /// Aucune valeur n'apparaît deux fois
+    unique: bool,
+}
+
+/// Famille de types pour la compatibilité des rubriques
+#[derive(PartialEq)]
+enum TypeFamily {
+    Number,
+    Text,
+    Date,
+}
+
+fn type_family(field_type: &FieldType) -> Option<TypeFamily> {
+    match field_type {
+        FieldType::Integer | FieldType::Float => Some(TypeFamily::Number),
+        FieldType::String | FieldType::Unknown => Some(TypeFamily::Text),
+        FieldType::Date => Some(TypeFamily::Date),
+        FieldType::Memo | FieldType::Binary => None,
+    }
+}
+
+/// Nom normalisé pour les comparaisons (majuscules, sans séparateurs)
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | ' ' | '-'))
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Noms de la clé d'une table selon la convention (ID, IDCLIENT, CLIENTID)
+fn key_names(table: &str) -> [String; 3] {
+    let table = normalize(table);
+    ["ID".to_string(), format!("ID{}", table), format!("{}ID", table)]
+}
+
+/**
+ * Rapproche les rubriques des tables par leur nom et leur type.
+ *
+ * @param tables - Tables d'une même base
+ * @returns Vec<Candidate> - Relations candidates (une rubrique source peut en avoir plusieurs)
+ */
+fn candidates(tables: &[TableInfo]) -> Vec<Candidate> {
+    let keys: Vec<[String; 3]> = tables.iter().map(|table| key_names(&table.bare)).collect();
+    let mut found = Vec::new();
+
+    for (from_table, source) in tables.iter().enumerate() {
+        for (from_field, field) in source.schema.fields.iter().enumerate() {
+            let Some(family) = type_family(&field.field_type) else { continue };
+            let name = normalize(&field.name);
+            // Clé de la table source elle-même
+            if keys[from_table].contains(&name) {
+                continue;
+            }
+
+            for (to_table, target) in tables.iter().enumerate() {
+                if to_table == from_table {
+                    continue;
+                }
+                let target_table = normalize(&target.bare);
+                let references_table = keys[to_table][1..].contains(&name);
+                let unique_fields: Vec<&str> = target.schema.indexes.iter()
+                    .filter(|index| index.unique && index.fields.len() == 1)
+                    .filter_map(|index| index.fields[0].as_deref())
+                    .collect();
+
+                for (to_field, key) in target.schema.fields.iter().enumerate() {
+                    if type_family(&key.field_type).as_ref() != Some(&family) {
+                        continue;
+                    }
+                    let key_name = normalize(&key.name);
+                    let is_key = keys[to_table].contains(&key_name)
+                        || unique_fields.iter().any(|unique| unique.eq_ignore_ascii_case(&key.name));
+
+                    let name_match = if references_table && is_key {
+                        NameMatch::Convention
+                    } else if name == key_name && key_name != "ID" {
+                        NameMatch::SameName
+                    } else if is_key && target_table.len() >= 3 && name.contains(&target_table) {
+                        NameMatch::TableName
+                    } else {
+                        continue;
+                    };
+                    found.push(Candidate { from: (from_table, from_field), to: (to_table, to_field), name_match });
+                }
+            }
+        }
+    }
+    found
+}
+
+/**
+ * Clé de comparaison d'une valeur (None pour « pas de lien »).
+ *
+ * @param value - Valeur décodée
+ * @returns Option<String> - Représentation commune aux deux tables
+ */
+fn value_key(value: &FieldValue) -> Option<String> {
+    match value {
+        FieldValue::Integer { value } => (*value != 0).then(|| value.to_string()),
+        FieldValue::Float { value } if *value == 0.0 || !value.is_finite() => None,
+        FieldValue::Float { value } if value.fract() == 0.0 => Some((*value as i64).to_string()),
+        FieldValue::Float { value } => Some(value.to_string()),
+        FieldValue::String { value } => {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        }
+        _ => None,
+    }
+}
+
+/**
+ * Collecte les valeurs distinctes d'une rubrique.
+ *
+ * @param records - Enregistrements lus
+ * @param field - Nom de la rubrique
+ * @returns ColumnValues - Valeurs distinctes et absence de doublon
+ */
+fn column_values(records: &[Record], field: &str) -> ColumnValues {
+    let mut values = ColumnValues { unique: true, ..Default::default() };
+    for key in records.iter().filter_map(|record| record.fields.get(field).and_then(value_key)) {
+        if !values.distinct.insert(key) {
+            values.unique = false;
+        }
+    }
+    values
+}
+
+/// Score d'une relation à partir du nommage et du recouvrement des valeurs
+fn relation_score(name_match: NameMatch, overlap: Option<f64>) -> f64 {
+    let score = NAME_WEIGHT * name_match.weight() + (1.0 - NAME_WEIGHT) * overlap.unwrap_or(0.0);
+    (score * 1000.0).round() / 1000.0
+}
+
+/**
+ * Lit les valeurs des rubriques demandées, table par table.
+ *
+ * @param engine - Moteur de stockage
+ * @param tables - Tables de la base
+ * @param columns - Rubriques à lire, par table
+ * @param limit - Enregistrements lus par table
+ * @param cancel - Jeton vérifié à chaque enregistrement
+ * @returns Result<HashMap<Column, ColumnValues>> - Valeurs par rubrique
+ */
+fn read_columns(
+    engine: &StorageEngine,
+    tables: &[TableInfo],
+    columns: &BTreeMap<usize, BTreeSet<usize>>,
+    limit: u32,
+    cancel: &CancelToken,
+) -> Result<HashMap<Column, ColumnValues>> {
+    let mut values = HashMap::new();
+    for (&table, fields) in columns {
+        let info = &tables[table];
+        let names: Vec<String> = fields.iter().map(|&field| info.schema.fields[field].name.clone()).collect();
+        let filters = QueryFilters { limit: Some(limit), projection: Some(names.clone()), ..Default::default() };
+        let records = engine.select_cancellable(&info.name, filters, cancel)?.records;
+        for (&field, name) in fields.iter().zip(&names) {
+            values.insert((table, field), column_values(&records, name));
+        }
+    }
+    Ok(values)
+}
+
+/**
+ * Infère les relations entre les tables d'une même base.
+ *
+ * @param engine - Moteur de stockage
+ * @param tables - Tables de la base
+ * @param sample - Enregistrements échantillonnés par table source
+ * @param min_score - Score minimal d'une relation retenue
+ * @param cancel - Jeton vérifié à chaque enregistrement
+ * @returns Result<Vec<InferredRelation>> - Meilleure relation par rubrique source et table cible
+ */
+fn infer_database(
+    engine: &StorageEngine,
+    tables: &[TableInfo],
+    sample: u32,
+    min_score: f64,
+    cancel: &CancelToken,
+) -> Result<Vec<InferredRelation>> {
+    let candidates = candidates(tables);
+    let mut sources: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    let mut targets: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for candidate in &candidates {
+        sources.entry(candidate.from.0).or_default().insert(candidate.from.1);
+        targets.entry(candidate.to.0).or_default().insert(candidate.to.1);
+    }
+    let source_values = read_columns(engine, tables, &sources, sample, cancel)?;
+    let target_values = read_columns(engine, tables, &targets, TARGET_VALUES_MAX, cancel)?;
+
+    let mut best: HashMap<(Column, usize), InferredRelation> = HashMap::new();
+    for candidate in candidates {
+        let target = &target_values[&candidate.to];
+        if !target.unique {
+            continue;
+        }
+        let source = &source_values[&candidate.from];
+        let overlap = (!source.distinct.is_empty()).then(|| {
+            source.distinct.iter().filter(|value| target.distinct.contains(*value)).count() as f64
+                / source.distinct.len() as f64
+        });
+        if overlap == Some(0.0) {
+            continue;
+        }
+        let score = relation_score(candidate.name_match, overlap);
+        if score < min_score {
+            continue;
+        }
+
+        let (from_table, to_table) = (&tables[candidate.from.0], &tables[candidate.to.0]);
+        let relation = InferredRelation {
+            from_table: from_table.name.clone(),
+            from_column: from_table.schema.fields[candidate.from.1].name.clone(),
+            to_table: to_table.name.clone(),
+            to_column: to_table.schema.fields[candidate.to.1].name.clone(),
+            score,
+            name_match: candidate.name_match,
+            overlap,
+            sampled: source.distinct.len() as u32,
+        };
+        match best.entry((candidate.from, candidate.to.0)) {
+            Entry::Occupied(mut entry) if entry.get().score < score => {
+                entry.insert(relation);
+            }
+            Entry::Occupied(_) => {}
+            Entry::Vacant(entry) => {
+                entry.insert(relation);
+            }
+        }
+    }
+    Ok(best.into_values().collect())
+}
+
+/**
+ * Infère les relations probables entre les tables de chaque base.
+ *
+ * Les relations ne relient que des tables d'une même base.
+ *
+ * @param engine - Moteur de stockage (tables déjà scannées)
+ * @param database - Base à examiner ("" pour la base racine, None pour toutes)
+ * @param sample - Enregistrements échantillonnés par table source
+ * @param min_score - Score minimal d'une relation retenue (0 à 1)
+ * @param cancel - Jeton vérifié à chaque enregistrement (annulation ou délai dépassé)
+ * @returns Result<RelationReport> - Relations par score décroissant, ou erreur si la base est inconnue
+ *
+ * Effets de bord :
+ * - Lit les rubriques candidates des tables (projection)
+ */
+pub fn infer_relations(
+    engine: &StorageEngine,
+    database: Option<&str>,
+    sample: u32,
+    min_score: f64,
+    cancel: &CancelToken,
+) -> Result<RelationReport> {
+    let started = Instant::now();
+    let databases: Vec<_> = engine.list_databases().into_iter()
+        .filter(|info| database.is_none_or(|name| info.name.eq_ignore_ascii_case(name)))
+        .collect();
+    if let (Some(name), true) = (database, databases.is_empty()) {
+        anyhow::bail!("Base '{}' non trouvée", name);
+    }
+
+    let mut table_count = 0;
+    let mut relations = Vec::new();
+    for info in databases {
+        let prefix = format!("{}.", info.name);
+        let tables: Vec<TableInfo> = info.tables.iter()
+            .filter_map(|name| match engine.get_schema(name) {
+                Ok(schema) => Some(TableInfo {
+                    name: name.clone(),
+                    bare: name.strip_prefix(&prefix).unwrap_or(name).to_string(),
+                    schema,
+                }),
+                Err(e) => {
+                    get_logger().log_with_source(
+                        LogLevel::Warn,
+                        format!("Table {} ignorée pour les relations: {}", name, e),
+                        Some("Relations".to_string()),
+                    );
+                    None
+                }
+            })
+            .collect();
+        table_count += tables.len();
+        relations.extend(infer_database(engine, &tables, sample, min_score, cancel)?);
+    }
+
+    relations.sort_by(|a, b| {
+        b.score.total_cmp(&a.score)
+            .then_with(|| a.from_table.cmp(&b.from_table))
+            .then_with(|| a.from_column.cmp(&b.from_column))
+            .then_with(|| a.to_table.cmp(&b.to_table))
+    });
+    get_logger().log_with_source(
+        LogLevel::Info,
+        format!("{} relation(s) inférée(s) entre {} table(s)", relations.len(), table_count),
+        Some("Relations".to_string()),
+    );
+    Ok(RelationReport {
+        tables: table_count,
+        duration_ms: started.elapsed().as_millis() as u64,
+        relations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FieldInfo, IndexDefinition};
+    use crate::storage::Fields;
+
+    fn table(name: &str, fields: &[(&str, FieldType)]) -> TableInfo {
+        TableInfo {
+            name: format!("compta.{}", name),
+            bare: name.to_string(),
+            schema: TableSchema {
+                name: name.to_string(),
+                record_length: 0,
+                field_count: fields.len() as u32,
+                fields: fields.iter()
+                    .map(|(name, field_type)| FieldInfo {
+                        name: name.to_string(),
+                        offset: 0,
+                        length: 0,
+                        field_type: field_type.clone(),
+                        encoding: Default::default(),
+                    })
+                    .collect(),
+                indexes: Vec::new(),
+                full_text: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut pays = table("PAYS", &[("CODE_PAYS", FieldType::String), ("LIBELLE", FieldType::String)]);
+        pays.schema.indexes.push(IndexDefinition {
+            name: "PAYS_CODE".to_string(),
+            path: Default::default(),
+            fields: vec![Some("CODE_PAYS".to_string())],
+            segments: Vec::new(),
+            encodings: Vec::new(),
+            unique: true,
+        });
+        let tables = vec![
+            table("CLIENT", &[("IDCLIENT", FieldType::Integer), ("CODEPAYS", FieldType::String), ("LIBELLE", FieldType::String)]),
+            table("FACTURE", &[("ID", FieldType::Integer), ("IDCLIENT", FieldType::Integer)]),
+            table("LIGNE", &[("IDLIGNE", FieldType::Integer), ("NUMFACTURE", FieldType::Integer), ("IDCLIENT", FieldType::String)]),
+            pays,
+        ];
+        let found: Vec<(Column, Column, NameMatch)> = candidates(&tables).into_iter()
+            .map(|candidate| (candidate.from, candidate.to, candidate.name_match))
+            .collect();
+
+        assert!(found.contains(&((1, 1), (0, 0), NameMatch::Convention)));
+        assert!(found.contains(&((2, 1), (1, 0), NameMatch::TableName)));
+        assert!(found.contains(&((0, 1), (3, 0), NameMatch::SameName)));
+        // Même nom mais cible qui n'est pas une clé : vérifié ensuite sur les valeurs
+        assert!(found.contains(&((0, 2), (3, 1), NameMatch::SameName)));
+        // Types incompatibles (texte → entier) et clés propres des tables sources
+        assert!(!found.iter().any(|(from, _, _)| *from == (2, 2)));
+        assert!(!found.iter().any(|(from, _, _)| *from == (0, 0) || *from == (1, 0) || *from == (2, 0)));
+    }
+
+    #[test]
+    fn test_column_values_and_score() {
+        let record = |id: u32, value: FieldValue| {
+            let mut fields = Fields::new();
+            fields.insert("IDCLIENT", value);
+            Record { id, fields }
+        };
+        let records = vec![
+            record(0, FieldValue::integer(12)),
+            record(1, FieldValue::float(13.0)),
+            record(2, FieldValue::integer(0)),
+            record(3, FieldValue::null()),
+        ];
+        let values = column_values(&records, "IDCLIENT");
+        assert!(values.unique);
+        assert_eq!(values.distinct, HashSet::from(["12".to_string(), "13".to_string()]));
+
+        let duplicated = column_values(&[record(0, FieldValue::string(" A ".to_string())), record(1, FieldValue::string("A".to_string()))], "IDCLIENT");
+        assert!(!duplicated.unique);
+
+        assert_eq!(relation_score(NameMatch::Convention, Some(1.0)), 1.0);
+        assert_eq!(relation_score(NameMatch::TableName, Some(0.5)), 0.5);
+        assert_eq!(relation_score(NameMatch::SameName, None), 0.28);
+    }
+}
@@ -6,7 +6,9 @@
  * en appels aux méthodes du moteur de stockage.
  * 
  * Fonctionnalités :
- * - Exécution de SELECT avec filtres et pagination
+ * - Exécution de SELECT avec filtres, tri et pagination
+ * - Recherche full-text (MATCH/CONTAINS) via les index .ftx
+ * - Échantillon aléatoire (TABLESAMPLE RESERVOIR / STRATIFIED)
  * - Exécution de INSERT, UPDATE, DELETE
  * - Conversion des valeurs SQL en FieldValue
  * - Filtrage des colonnes pour SELECT
@@ -17,17 +19,18 @@
  * - Utilisé par src/sql/server.rs pour exécuter les requêtes HTTP
  */
 
-use crate::storage::{QueryFilters, Record, StorageEngine};
+use crate::storage::{CancelToken, FieldFilter, Fields, FilterOperator, NullsOrder, QueryFilters, Record, SampleSpec, SortKey, StorageEngine};
 use crate::storage::engine::FieldValue;
 use crate::sql::parser::*;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Exécuteur SQL qui traduit les requêtes SQL en opérations sur StorageEngine
 pub struct SqlExecutor {
     /// Moteur de stockage partagé
     engine: Arc<StorageEngine>,
+    /// Jeton vérifié pendant la lecture des tables
+    cancel: CancelToken,
 }
 
 impl SqlExecutor {
@@ -38,7 +41,18 @@ impl SqlExecutor {
      * @returns SqlExecutor - Exécuteur créé
      */
     pub fn new(engine: Arc<StorageEngine>) -> Self {
-        Self { engine }
+        Self { engine, cancel: CancelToken::new() }
+    }
+
+    /**
+     * Associe un jeton d'annulation aux requêtes de l'exécuteur.
+     *
+     * @param cancel - Jeton vérifié à chaque enregistrement lu (annulation ou délai dépassé)
+     * @returns SqlExecutor - Exécuteur dont les lectures s'arrêtent avec le jeton
+     */
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
     }
 
     /**
@@ -63,46 +77,59 @@ impl SqlExecutor {
     }
 
     fn execute_select(&self, select: &SelectStatement) -> Result<SqlResult> {
-        // Convertir la clause WHERE en QueryFilters
-        let mut filters = QueryFilters {
+        // Convertir les clauses WHERE et ORDER BY en QueryFilters
+        let filters = QueryFilters {
             limit: select.limit,
             offset: select.offset,
-            field_filters: HashMap::new(),
+            field_filters: self.field_filters(&select.where_clause),
+            order_by: select.order_by
+                .iter()
+                .map(|item| {
+                    let mut key = SortKey::new(item.column.clone(), item.descending);
+                    key.nulls = item.nulls_first.map(|first| if first { NullsOrder::First } else { NullsOrder::Last });
+                    if let Some(collation) = &item.collation {
+                        key.collation = collation.parse()?;
+                    }
+                    Ok(key)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            // Seules les colonnes du SELECT (et celles du WHERE / ORDER BY) sont décodées
+            projection: (!select.columns.is_empty()).then(|| select.columns.clone()),
+            sample: select.sample.as_ref()
+                .map(|sample| -> Result<SampleSpec> {
+                    let spec = SampleSpec {
+                        method: sample.method.parse()?,
+                        size: sample.size,
+                        strata: sample.strata,
+                        seed: sample.seed,
+                    };
+                    spec.validate()?;
+                    Ok(spec)
+                })
+                .transpose()?,
+            cursor: None,
         };
 
-        if let Some(where_clause) = &select.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&select.table, filters)
+        let query_result = self.engine.select_cancellable(&select.table, filters, &self.cancel)
             .with_context(|| format!("Erreur lors de la sélection depuis la table {}", select.table))?;
 
-        // Filtrer les colonnes si nécessaire
+        // Colonnes dans l'ordre du SELECT (le moteur les retourne dans l'ordre du schéma)
         let records = if select.columns.is_empty() {
             query_result.records
         } else {
             query_result.records
                 .into_iter()
                 .map(|record| {
-                    let mut filtered_fields = HashMap::new();
+                    // Octets bruts conservés
+                    let mut filtered_fields = Fields::with_capacity(select.columns.len());
                     for col in &select.columns {
-                        if let Some(value) = record.fields.get(col) {
-                            filtered_fields.insert(col.clone(), value.clone());
+                        if let Some(field) = record.fields.entry(col) {
+                            filtered_fields.insert_raw(col.clone(), field.value.clone(), field.raw.clone());
                         }
                     }
                     Record {
                         id: record.id,
                         fields: filtered_fields,
-                        memo_data: record.memo_data,
                     }
                 })
                 .collect()
@@ -123,7 +150,7 @@ impl SqlExecutor {
 
     fn execute_insert(&self, insert: &InsertStatement) -> Result<SqlResult> {
         // Créer un Record à partir des valeurs
-        let mut fields = HashMap::new();
+        let mut fields = Fields::new();
         for (i, col) in insert.columns.iter().enumerate() {
             if i < insert.values.len() {
                 let value = self.sql_value_to_field_value(&insert.values[i])?;
@@ -134,7 +161,6 @@ impl SqlExecutor {
         let record = Record {
             id: 0, // Sera assigné par l'engine
             fields,
-            memo_data: HashMap::new(),
         };
 
         let id = self.engine.insert(&insert.table, record)
@@ -145,26 +171,14 @@ impl SqlExecutor {
 
     fn execute_update(&self, update: &UpdateStatement) -> Result<SqlResult> {
         // Pour UPDATE, on doit d'abord trouver les enregistrements à mettre à jour
-        let mut filters = QueryFilters {
+        let filters = QueryFilters {
             limit: None,
             offset: None,
-            field_filters: HashMap::new(),
+            field_filters: self.field_filters(&update.where_clause),
+            ..Default::default()
         };
 
-        if let Some(where_clause) = &update.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&update.table, filters)
+        let query_result = self.engine.select_cancellable(&update.table, filters, &self.cancel)
             .with_context(|| format!("Erreur lors de la sélection pour UPDATE dans la table {}", update.table))?;
 
         let mut updated_count = 0;
@@ -186,26 +200,14 @@ impl SqlExecutor {
 
     fn execute_delete(&self, delete: &DeleteStatement) -> Result<SqlResult> {
         // Pour DELETE, on doit d'abord trouver les enregistrements à supprimer
-        let mut filters = QueryFilters {
+        let filters = QueryFilters {
             limit: None,
             offset: None,
-            field_filters: HashMap::new(),
+            field_filters: self.field_filters(&delete.where_clause),
+            ..Default::default()
         };
 
-        if let Some(where_clause) = &delete.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&delete.table, filters)
+        let query_result = self.engine.select_cancellable(&delete.table, filters, &self.cancel)
             .with_context(|| format!("Erreur lors de la sélection pour DELETE dans la table {}", delete.table))?;
 
         let mut deleted_count = 0;
@@ -218,6 +220,46 @@ impl SqlExecutor {
         Ok(SqlResult::Delete { count: deleted_count })
     }
 
+    /**
+     * Convertit une clause WHERE en conditions pour le moteur de stockage.
+     *
+     * @param where_clause - Clause WHERE optionnelle
+     * @returns Vec<FieldFilter> - Conditions (combinées par AND)
+     */
+    fn field_filters(&self, where_clause: &Option<WhereClause>) -> Vec<FieldFilter> {
+        let Some(where_clause) = where_clause else {
+            return Vec::new();
+        };
+        where_clause.conditions
+            .iter()
+            .map(|condition| {
+                let value = match &condition.value {
+                    SqlValue::String(s) => s.clone(),
+                    SqlValue::Integer(i) => i.to_string(),
+                    SqlValue::Float(f) => f.to_string(),
+                    SqlValue::Boolean(b) => b.to_string(),
+                    SqlValue::Null => "".to_string(),
+                };
+                let operator = match condition.operator {
+                    ComparisonOperator::Equal => FilterOperator::Equal,
+                    ComparisonOperator::NotEqual => FilterOperator::NotEqual,
+                    ComparisonOperator::GreaterThan => FilterOperator::GreaterThan,
+                    ComparisonOperator::LessThan => FilterOperator::LessThan,
+                    ComparisonOperator::GreaterThanOrEqual => FilterOperator::GreaterThanOrEqual,
+                    ComparisonOperator::LessThanOrEqual => FilterOperator::LessThanOrEqual,
+                    ComparisonOperator::Like => FilterOperator::Like,
+                    ComparisonOperator::NotLike => FilterOperator::NotLike,
+                    ComparisonOperator::Match => FilterOperator::Match,
+                };
+                FieldFilter {
+                    field: condition.column.clone(),
+                    operator,
+                    value,
+                }
+            })
+            .collect()
+    }
+
     fn sql_value_to_field_value(&self, sql_value: &SqlValue) -> Result<FieldValue> {
         match sql_value {
             SqlValue::String(s) => Ok(FieldValue::string(s.clone())),
@@ -7,14 +7,17 @@
  * de données typées représentant la requête.
  * 
  * Fonctionnalités supportées :
- * - SELECT avec colonnes, WHERE, LIMIT, OFFSET
+ * - SELECT avec colonnes, WHERE, ORDER BY, LIMIT, OFFSET
+ * - Opérateurs de WHERE : =, !=, <, <=, >, >=, LIKE, NOT LIKE (mots-clés sans casse)
+ * - Échantillon aléatoire : FROM table TABLESAMPLE RESERVOIR (n) | STRATIFIED (n[, strates]) [REPEATABLE (graine)]
+ * - Recherche full-text dans WHERE : MATCH(col) AGAINST('mots'), CONTAINS(col, 'mots')
  * - INSERT avec colonnes et valeurs
  * - UPDATE avec SET et WHERE
  * - DELETE avec WHERE
  * 
  * Limitations :
  * - Parser basique (pas de sous-requêtes, JOIN, etc.)
- * - Support limité des opérateurs (AND uniquement dans WHERE)
+ * - Support limité des opérateurs (AND uniquement dans WHERE, hors des littéraux entre quotes)
  * 
  * Liens avec d'autres modules :
  * - Utilisé par src/sql/executor.rs pour exécuter les requêtes
@@ -42,11 +45,44 @@ pub enum SqlStatement {
 pub struct SelectStatement {
     pub table: String,
     pub columns: Vec<String>, // Vide = SELECT *
+    /// TABLESAMPLE : échantillon aléatoire de la table
+    #[serde(default)]
+    pub sample: Option<TableSample>,
     pub where_clause: Option<WhereClause>,
+    #[serde(default)]
+    pub order_by: Vec<OrderByItem>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
 
+/// Élément de la clause ORDER BY
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderByItem {
+    pub column: String,
+    pub descending: bool,
+    /// NULLS FIRST (true) / NULLS LAST (false), None = position par défaut
+    #[serde(default)]
+    pub nulls_first: Option<bool>,
+    /// COLLATE BINARY | NOCASE | NOACCENT
+    #[serde(default)]
+    pub collation: Option<String>,
+}
+
+/// Clause TABLESAMPLE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableSample {
+    /// Méthode (RESERVOIR ou STRATIFIED)
+    pub method: String,
+    /// Nombre d'enregistrements à tirer
+    pub size: u32,
+    /// Nombre de strates (STRATIFIED)
+    #[serde(default)]
+    pub strata: Option<u32>,
+    /// REPEATABLE (graine)
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
 /// Requête INSERT
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsertStatement {
@@ -94,6 +130,9 @@ pub enum ComparisonOperator {
     GreaterThanOrEqual,
     LessThanOrEqual,
     Like,
+    NotLike,
+    /// Recherche full-text : MATCH(col) AGAINST('mots') ou CONTAINS(col, 'mots')
+    Match,
 }
 
 /// Clause SET dans UPDATE
@@ -152,10 +191,10 @@ impl SqlParser {
     }
 
     fn parse_select(sql: &str) -> Result<SqlStatement> {
-        // Parser simple pour SELECT * FROM table [WHERE ...] [LIMIT ...] [OFFSET ...]
+        // Parser simple pour SELECT * FROM table [TABLESAMPLE ...] [WHERE ...] [ORDER BY ...] [LIMIT ...] [OFFSET ...]
         // Note: regex plus flexible pour gérer les cas simples
         let re = regex::Regex::new(
-            r"(?i)^SELECT\s+(.+?)\s+FROM\s+(\w+)(?:\s+WHERE\s+(.+?))?(?:\s+LIMIT\s+(\d+))?(?:\s+OFFSET\s+(\d+))?$"
+            r"(?i)^SELECT\s+(.+?)\s+FROM\s+(\w+(?:\.\w+)*)(?:\s+TABLESAMPLE\s+(\w+)\s*\(([^)]*)\)(?:\s+REPEATABLE\s*\(\s*(\d+)\s*\))?)?(?:\s+WHERE\s+(.+?))?(?:\s+ORDER\s+BY\s+(.+?))?(?:\s+LIMIT\s+(\d+))?(?:\s+OFFSET\s+(\d+))?$"
         ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
         
         if let Some(caps) = re.captures(sql) {
@@ -167,14 +206,20 @@ impl SqlParser {
                 columns_str.split(',').map(|s| s.trim().to_string()).collect()
             };
             
-            let where_clause = caps.get(3).map(|m| Self::parse_where(m.as_str())).transpose()?;
-            let limit = caps.get(4).and_then(|m| m.as_str().parse().ok());
-            let offset = caps.get(5).and_then(|m| m.as_str().parse().ok());
+            let sample = caps.get(3)
+                .map(|method| Self::parse_table_sample(method.as_str(), &caps[4], caps.get(5).map(|m| m.as_str())))
+                .transpose()?;
+            let where_clause = caps.get(6).map(|m| Self::parse_where(m.as_str())).transpose()?;
+            let order_by = caps.get(7).map(|m| Self::parse_order_by(m.as_str())).transpose()?.unwrap_or_default();
+            let limit = caps.get(8).and_then(|m| m.as_str().parse().ok());
+            let offset = caps.get(9).and_then(|m| m.as_str().parse().ok());
             
             Ok(SqlStatement::Select(SelectStatement {
                 table,
                 columns,
+                sample,
                 where_clause,
+                order_by,
                 limit,
                 offset,
             }))
@@ -186,7 +231,7 @@ impl SqlParser {
     fn parse_insert(sql: &str) -> Result<SqlStatement> {
         // INSERT INTO table (col1, col2) VALUES (val1, val2)
         let re = regex::Regex::new(
-            r"(?i)^INSERT\s+INTO\s+(\w+)\s*\((.+?)\)\s+VALUES\s*\((.+?)\)$"
+            r"(?i)^INSERT\s+INTO\s+(\w+(?:\.\w+)*)\s*\((.+?)\)\s+VALUES\s*\((.+?)\)$"
         ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
         
         if let Some(caps) = re.captures(sql) {
@@ -216,7 +261,7 @@ impl SqlParser {
     fn parse_update(sql: &str) -> Result<SqlStatement> {
         // UPDATE table SET col1=val1, col2=val2 [WHERE ...]
         let re = regex::Regex::new(
-            r"(?i)^UPDATE\s+(\w+)\s+SET\s+(.+?)(?:\s+WHERE\s+(.+?))?$"
+            r"(?i)^UPDATE\s+(\w+(?:\.\w+)*)\s+SET\s+(.+?)(?:\s+WHERE\s+(.+?))?$"
         ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
         
         if let Some(caps) = re.captures(sql) {
@@ -238,7 +283,7 @@ impl SqlParser {
     fn parse_delete(sql: &str) -> Result<SqlStatement> {
         // DELETE FROM table [WHERE ...]
         let re = regex::Regex::new(
-            r"(?i)^DELETE\s+FROM\s+(\w+)(?:\s+WHERE\s+(.+?))?$"
+            r"(?i)^DELETE\s+FROM\s+(\w+(?:\.\w+)*)(?:\s+WHERE\s+(.+?))?$"
         ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
         
         if let Some(caps) = re.captures(sql) {
@@ -256,33 +301,26 @@ impl SqlParser {
 
     fn parse_where(where_str: &str) -> Result<WhereClause> {
         // Parser simple: col = val AND col2 = val2
-        let conditions: Result<Vec<Condition>> = where_str
-            .split("AND")
+        let conditions: Result<Vec<Condition>> = Self::split_conditions(where_str)
+            .into_iter()
             .map(|cond| {
-                let cond = cond.trim();
-                // Support: col = val, col != val, col > val, etc.
-                for op in ["!=", ">=", "<=", "=", ">", "<", "LIKE"] {
-                    if cond.contains(op) {
-                        let parts: Vec<&str> = cond.split(op).collect();
-                        if parts.len() == 2 {
-                            let column = parts[0].trim().to_string();
-                            let value_str = parts[1].trim();
-                            let value = Self::parse_value(value_str)?;
-                            let operator = match op {
-                                "=" => ComparisonOperator::Equal,
-                                "!=" => ComparisonOperator::NotEqual,
-                                ">" => ComparisonOperator::GreaterThan,
-                                "<" => ComparisonOperator::LessThan,
-                                ">=" => ComparisonOperator::GreaterThanOrEqual,
-                                "<=" => ComparisonOperator::LessThanOrEqual,
-                                "LIKE" => ComparisonOperator::Like,
-                                _ => ComparisonOperator::Equal,
-                            };
-                            return Ok(Condition { column, operator, value });
-                        }
-                    }
+                if let Some(condition) = Self::parse_full_text(cond)? {
+                    return Ok(condition);
+                }
+                // Support: col = val, col != val, col > val, col LIKE val, etc.
+                let Some((start, end, operator)) = Self::find_operator(cond) else {
+                    anyhow::bail!("Condition invalide: {}", cond);
+                };
+                let column = cond[..start].trim();
+                let value_str = cond[end..].trim();
+                if column.is_empty() || value_str.is_empty() {
+                    anyhow::bail!("Condition invalide: {}", cond);
                 }
-                anyhow::bail!("Condition invalide: {}", cond)
+                Ok(Condition {
+                    column: column.to_string(),
+                    operator,
+                    value: Self::parse_value(value_str)?,
+                })
             })
             .collect();
         
@@ -291,6 +329,189 @@ impl SqlParser {
         })
     }
 
+    /**
+     * Découpe une clause WHERE sur le mot-clé AND.
+     *
+     * AND est reconnu sans tenir compte de la casse, comme mot entier et
+     * hors des littéraux entre quotes : 'SANDWICH' ou nom = 'GRANDE' ne sont
+     * pas coupés.
+     *
+     * @param where_str - Contenu de la clause WHERE
+     * @returns Vec<&str> - Conditions, dans l'ordre
+     */
+    fn split_conditions(where_str: &str) -> Vec<&str> {
+        let outside = Self::outside_quotes(where_str);
+        let mut conditions = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < outside.len() {
+            if outside[i] && Self::keyword_at(where_str, i, "AND") {
+                conditions.push(where_str[start..i].trim());
+                start = i + 3;
+                i += 3;
+            } else {
+                i += 1;
+            }
+        }
+        conditions.push(where_str[start..].trim());
+        conditions
+    }
+
+    /**
+     * Repère l'opérateur de comparaison d'une condition, hors des littéraux
+     * entre quotes.
+     *
+     * LIKE et NOT LIKE (sans tenir compte de la casse, mots entiers) sont
+     * cherchés avant les opérateurs symboliques : nom LIKE 'a=b' est un LIKE.
+     *
+     * @param cond - Condition (col op valeur)
+     * @returns Option<(usize, usize, ComparisonOperator)> - Début et fin de
+     *          l'opérateur dans la condition, et opérateur reconnu
+     */
+    fn find_operator(cond: &str) -> Option<(usize, usize, ComparisonOperator)> {
+        let outside = Self::outside_quotes(cond);
+        let bytes = cond.as_bytes();
+
+        for i in (0..bytes.len()).filter(|&i| outside[i]) {
+            if Self::keyword_at(cond, i, "LIKE") {
+                return Some((i, i + 4, ComparisonOperator::Like));
+            }
+            if Self::keyword_at(cond, i, "NOT") {
+                let next = i + 3 + bytes[i + 3..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+                if next > i + 3 && Self::keyword_at(cond, next, "LIKE") {
+                    return Some((i, next + 4, ComparisonOperator::NotLike));
+                }
+            }
+        }
+
+        for i in (0..bytes.len()).filter(|&i| outside[i]) {
+            let operator = match (bytes[i], bytes.get(i + 1)) {
+                (b'!', Some(b'=')) => (2, ComparisonOperator::NotEqual),
+                (b'>', Some(b'=')) => (2, ComparisonOperator::GreaterThanOrEqual),
+                (b'<', Some(b'=')) => (2, ComparisonOperator::LessThanOrEqual),
+                (b'=', _) => (1, ComparisonOperator::Equal),
+                (b'>', _) => (1, ComparisonOperator::GreaterThan),
+                (b'<', _) => (1, ComparisonOperator::LessThan),
+                _ => continue,
+            };
+            return Some((i, i + operator.0, operator.1));
+        }
+        None
+    }
+
+    /**
+     * Indique, pour chaque octet, s'il est hors d'un littéral entre quotes
+     * ('...' ou "..."). Les quotes elles-mêmes font partie du littéral ; une
+     * quote doublée ('') ferme puis rouvre le littéral.
+     */
+    fn outside_quotes(text: &str) -> Vec<bool> {
+        let mut quote: Option<u8> = None;
+        text.bytes()
+            .map(|byte| match quote {
+                Some(q) => {
+                    if byte == q {
+                        quote = None;
+                    }
+                    false
+                }
+                None if byte == b'\'' || byte == b'"' => {
+                    quote = Some(byte);
+                    false
+                }
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Indique si le mot-clé commence à la position `i` (sans tenir compte de la casse, mot entier)
+    fn keyword_at(text: &str, i: usize, keyword: &str) -> bool {
+        let bytes = text.as_bytes();
+        let is_word = |i: usize| bytes.get(i).is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_');
+        bytes.get(i..i + keyword.len()).is_some_and(|word| word.eq_ignore_ascii_case(keyword.as_bytes()))
+            && (i == 0 || !is_word(i - 1))
+            && !is_word(i + keyword.len())
+    }
+
+    fn parse_full_text(cond: &str) -> Result<Option<Condition>> {
+        // MATCH(col) AGAINST('mots') ou CONTAINS(col, 'mots'), col = * pour tous les index
+        let re = regex::Regex::new(
+            r"(?i)^(?:MATCH\s*\(\s*(\w+|\*)\s*\)\s*AGAINST\s*\((.+)\)|CONTAINS\s*\(\s*(\w+|\*)\s*,(.+)\))$"
+        ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
+
+        let Some(caps) = re.captures(cond) else {
+            return Ok(None);
+        };
+        let column = caps.get(1).or_else(|| caps.get(3)).unwrap().as_str().to_string();
+        let value = match Self::parse_value(caps.get(2).or_else(|| caps.get(4)).unwrap().as_str())? {
+            SqlValue::String(s) => SqlValue::String(s),
+            _ => anyhow::bail!("Recherche full-text invalide (texte entre quotes attendu): {}", cond),
+        };
+        Ok(Some(Condition {
+            column,
+            operator: ComparisonOperator::Match,
+            value,
+        }))
+    }
+
+    fn parse_table_sample(method: &str, arguments: &str, seed: Option<&str>) -> Result<TableSample> {
+        // RESERVOIR (n [ROWS]) ou STRATIFIED (n [ROWS] [, strates]), REPEATABLE (graine)
+        let mut arguments = arguments.split(',').map(|argument| {
+            let words: Vec<&str> = argument.split_whitespace().collect();
+            match words.as_slice() {
+                [number] => number.parse::<u32>().ok(),
+                [number, rows] if rows.eq_ignore_ascii_case("ROWS") => number.parse::<u32>().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| anyhow::anyhow!("Clause TABLESAMPLE invalide (nombre attendu): {}", argument.trim()))
+        });
+        let size = arguments.next().transpose()?
+            .ok_or_else(|| anyhow::anyhow!("Taille d'échantillon attendue dans TABLESAMPLE {}", method))?;
+        let strata = arguments.next().transpose()?;
+        if arguments.next().is_some() {
+            anyhow::bail!("Clause TABLESAMPLE invalide: trop d'arguments pour {}", method);
+        }
+        let seed = seed
+            .map(|seed| seed.parse::<u64>().map_err(|_| anyhow::anyhow!("Graine REPEATABLE invalide: {}", seed)))
+            .transpose()?;
+        Ok(TableSample { method: method.to_string(), size, strata, seed })
+    }
+
+    fn parse_order_by(order_str: &str) -> Result<Vec<OrderByItem>> {
+        // col1 [COLLATE nom] [ASC|DESC] [NULLS FIRST|LAST], col2 ...
+        order_str
+            .split(',')
+            .map(|item| {
+                let mut parts = item.split_whitespace();
+                let Some(column) = parts.next() else {
+                    anyhow::bail!("Clause ORDER BY invalide: {}", item.trim());
+                };
+                let mut order = OrderByItem {
+                    column: column.to_string(),
+                    descending: false,
+                    nulls_first: None,
+                    collation: None,
+                };
+                while let Some(word) = parts.next() {
+                    match word.to_uppercase().as_str() {
+                        "ASC" => order.descending = false,
+                        "DESC" => order.descending = true,
+                        "NULLS" => match parts.next().map(|w| w.to_uppercase()).as_deref() {
+                            Some("FIRST") => order.nulls_first = Some(true),
+                            Some("LAST") => order.nulls_first = Some(false),
+                            _ => anyhow::bail!("NULLS FIRST ou NULLS LAST attendu: {}", item.trim()),
+                        },
+                        "COLLATE" => match parts.next() {
+                            Some(collation) => order.collation = Some(collation.to_string()),
+                            None => anyhow::bail!("Nom de collation attendu après COLLATE: {}", item.trim()),
+                        },
+                        _ => anyhow::bail!("Clause ORDER BY invalide: {}", item.trim()),
+                    }
+                }
+                Ok(order)
+            })
+            .collect()
+    }
+
     fn parse_set_clauses(set_str: &str) -> Result<Vec<SetClause>> {
         set_str
             .split(',')
@@ -339,3 +560,68 @@ impl SqlParser {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(sql: &str) -> Vec<Condition> {
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => select.where_clause.map(|w| w.conditions).unwrap_or_default(),
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+    }
+
+    fn text(condition: &Condition) -> &str {
+        match &condition.value {
+            SqlValue::String(s) => s,
+            other => panic!("texte attendu: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_and_inside_literals() {
+        let found = conditions("SELECT * FROM CLIENT WHERE MATCH(nom) AGAINST('SANDWICH')");
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0].operator, ComparisonOperator::Match));
+        assert_eq!(text(&found[0]), "SANDWICH");
+
+        let found = conditions("SELECT * FROM CLIENT WHERE nom = 'GRANDE'");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].column, "nom");
+        assert_eq!(text(&found[0]), "GRANDE");
+
+        let found = conditions("SELECT * FROM CLIENT WHERE ville = 'Rennes and Brest' AND code > 3");
+        assert_eq!(found.len(), 2);
+        assert_eq!(text(&found[0]), "Rennes and Brest");
+        assert_eq!(found[1].column, "code");
+    }
+
+    #[test]
+    fn test_and_is_case_insensitive() {
+        let found = conditions("SELECT * FROM CLIENT WHERE code = 1 and brand = 'x' AnD ANDRE = 2");
+        let columns: Vec<&str> = found.iter().map(|c| c.column.as_str()).collect();
+        assert_eq!(columns, ["code", "brand", "ANDRE"]);
+    }
+
+    #[test]
+    fn test_operators_outside_quotes() {
+        let found = conditions("SELECT * FROM CLIENT WHERE nom like 'A%' AND ville Not Like '%x' AND code >= 3");
+        assert!(matches!(found[0].operator, ComparisonOperator::Like));
+        assert_eq!(text(&found[0]), "A%");
+        assert!(matches!(found[1].operator, ComparisonOperator::NotLike));
+        assert_eq!(found[1].column, "ville");
+        assert!(matches!(found[2].operator, ComparisonOperator::GreaterThanOrEqual));
+
+        let found = conditions("SELECT * FROM CLIENT WHERE nom LIKE 'a=b'");
+        assert!(matches!(found[0].operator, ComparisonOperator::Like));
+        assert_eq!(text(&found[0]), "a=b");
+
+        let found = conditions("SELECT * FROM CLIENT WHERE nom = 'x LIKE y' AND likes != 2");
+        assert!(matches!(found[0].operator, ComparisonOperator::Equal));
+        assert_eq!(text(&found[0]), "x LIKE y");
+        assert_eq!(found[1].column, "likes");
+        assert!(matches!(found[1].operator, ComparisonOperator::NotEqual));
+
+        assert!(SqlParser::parse("SELECT * FROM CLIENT WHERE nom 'Dupont'").is_err());
+    }
+}
@@ -19,6 +19,7 @@
 use crate::api::server::AppState;
 use crate::logger::{get_logger, LogLevel};
 use crate::sql::{SqlExecutor, SqlParser};
+use crate::storage::Interrupted;
 use anyhow::Result;
 use axum::{
     extract::State,
@@ -43,13 +44,45 @@ pub struct SqlResponse {
     /// Indique si l'exécution a réussi
     pub success: bool,
     /// Données retournées (pour SELECT) ou métadonnées (pour INSERT/UPDATE/DELETE)
-    pub data: Option<serde_json::Value>,
+    pub data: Option<SqlData>,
     /// Message d'erreur si l'exécution a échoué
     pub error: Option<String>,
     /// Nombre de lignes affectées (pour INSERT/UPDATE/DELETE)
     pub rows_affected: Option<usize>,
 }
 
+/// Données d'une réponse SQL
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum SqlData {
+    /// Résultat d'un SELECT sur le moteur FIC (rubriques dans l'ordre des colonnes)
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<SqlRow>,
+    },
+    /// Autres données (ID inséré, résultats ODBC)
+    Json(serde_json::Value),
+}
+
+/// Ligne d'un SELECT : l'ID (s'il n'est pas sélectionné), puis les rubriques dans l'ordre
+pub struct SqlRow(crate::storage::Record);
+
+impl Serialize for SqlRow {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let record = &self.0;
+        let with_id = !record.fields.contains_key("id");
+        let mut map = serializer.serialize_map(Some(record.fields.len() + with_id as usize))?;
+        if with_id {
+            map.serialize_entry("id", &record.id)?;
+        }
+        for (name, value) in record.fields.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
 /**
  * Handler POST /sql - Exécute une requête SQL.
  * 
@@ -63,6 +96,7 @@ pub struct SqlResponse {
  * Effets de bord :
  * - Peut lire/écrire des données selon la requête SQL
  * - Peut se connecter à une base de données ODBC
+ * - Arrête la requête FIC si le client se déconnecte ; 504 si le délai est dépassé
  */
 pub async fn execute_sql(
     State(state): State<AppState>,
@@ -73,34 +107,25 @@ pub async fn execute_sql(
         return execute_sql_odbc(dsn, &request.sql).await;
     }
 
-    // Sinon, utiliser le moteur FIC
-    let executor = SqlExecutor::new(state.engine.clone());
-    
+    // Sinon, utiliser le moteur FIC, sur le pool de lecture (annulable, avec délai)
     match SqlParser::parse(&request.sql) {
         Ok(statement) => {
-            match executor.execute(&statement) {
+            let result = state.queries.query(move |engine, cancel| {
+                SqlExecutor::new(engine.clone())
+                    .with_cancel(cancel.clone())
+                    .execute(&statement)
+            })
+            .await;
+            match result {
                 Ok(result) => {
                     match result {
                         crate::sql::executor::SqlResult::Select { columns, rows } => {
-                            let data: Vec<serde_json::Value> = rows
-                                .into_iter()
-                                .map(|record| {
-                                    let mut row = serde_json::Map::new();
-                                    row.insert("id".to_string(), serde_json::Value::Number(record.id.into()));
-                                    for (key, value) in record.fields {
-                                        row.insert(key, serde_json::to_value(value).unwrap_or(serde_json::Value::Null));
-                                    }
-                                    serde_json::Value::Object(row)
-                                })
-                                .collect();
-                            
-                            let mut response = serde_json::Map::new();
-                            response.insert("columns".to_string(), serde_json::to_value(columns).unwrap());
-                            response.insert("rows".to_string(), serde_json::Value::Array(data));
-                            
                             Ok(Json(SqlResponse {
                                 success: true,
-                                data: Some(serde_json::Value::Object(response)),
+                                data: Some(SqlData::Rows {
+                                    columns,
+                                    rows: rows.into_iter().map(SqlRow).collect(),
+                                }),
                                 error: None,
                                 rows_affected: None,
                             }))
@@ -108,7 +133,7 @@ pub async fn execute_sql(
                         crate::sql::executor::SqlResult::Insert { id } => {
                             Ok(Json(SqlResponse {
                                 success: true,
-                                data: Some(serde_json::json!({ "id": id })),
+                                data: Some(SqlData::Json(serde_json::json!({ "id": id }))),
                                 error: None,
                                 rows_affected: Some(1),
                             }))
@@ -132,8 +157,12 @@ pub async fn execute_sql(
                     }
                 }
                 Err(e) => {
+                    let status = match Interrupted::find(&e) {
+                        Some(Interrupted::TimedOut(_)) => StatusCode::GATEWAY_TIMEOUT,
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
                     Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
+                        status,
                         Json(SqlResponse {
                             success: false,
                             data: None,
@@ -208,7 +237,7 @@ async fn execute_sql_odbc(
             
             Ok(Json(SqlResponse {
                 success: true,
-                data: Some(serde_json::Value::Object(response)),
+                data: Some(SqlData::Json(serde_json::Value::Object(response))),
                 error: None,
                 rows_affected: odbc_result.rows_affected,
             }))
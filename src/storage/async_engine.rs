@@ -0,0 +1,264 @@
+/**
+ * Façade asynchrone du moteur de stockage pour le serveur HTTP.
+ *
+ * Les méthodes du StorageEngine sont bloquantes (lecture de fichiers,
+ * décodage parallèle avec rayon) : appelées directement depuis un handler,
+ * elles occupent un thread du runtime tokio et retardent les autres requêtes.
+ * AsyncEngine exécute ces lectures sur un pool de threads dédié et rend la
+ * main au runtime en attendant le résultat.
+ *
+ * Chaque requête reçoit un CancelToken :
+ * - annulé si le future est abandonné avant la fin (client HTTP déconnecté :
+ *   Axum abandonne alors le handler) ;
+ * - expiré après le délai configuré (query) ; l'appelant reçoit aussitôt
+ *   une erreur Interrupted::TimedOut et la lecture s'arrête au prochain
+ *   enregistrement.
+ *
+ * Les opérations qui ne vérifient pas le jeton (recherche, historique)
+ * continuent en arrière-plan après un délai dépassé, mais n'occupent que le
+ * pool dédié.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/storage/engine.rs et src/storage/cancel.rs
+ * - Utilisé par src/api/handlers.rs et src/sql/server.rs (AppState.queries)
+ */
+
+use crate::core::FullTextHit;
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::cancel::{CancelToken, Interrupted};
+use crate::storage::{HistoryEntry, HistoryFilters, QueryFilters, QueryResult, Record, StorageEngine};
+use anyhow::{Context, Result};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Délai d'exécution par défaut d'une requête de l'API
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Moteur de stockage dont les lectures s'exécutent sur un pool dédié
+#[derive(Clone)]
+pub struct AsyncEngine {
+    /// Moteur partagé
+    engine: Arc<StorageEngine>,
+    /// Pool de threads des lectures (le décodage parallèle y reste aussi)
+    pool: Arc<rayon::ThreadPool>,
+    /// Délai d'exécution des requêtes (None = illimité)
+    timeout: Option<Duration>,
+}
+
+/// Annule le jeton d'une requête si son future est abandonné avant la fin
+struct CancelOnDrop {
+    cancel: CancelToken,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if self.armed {
+            self.cancel.cancel();
+            get_logger().log_with_source(LogLevel::Info, "Requête abandonnée par le client : lecture annulée".to_string(), Some("Storage".to_string()));
+        }
+    }
+}
+
+impl AsyncEngine {
+    /**
+     * Crée la façade et son pool de threads.
+     *
+     * @param engine - Moteur de stockage partagé
+     * @param threads - Nombre de threads du pool (0 = un par cœur)
+     * @param timeout - Délai d'exécution des requêtes (None = illimité)
+     * @returns Result<AsyncEngine> - Façade, ou erreur si le pool ne peut pas être créé
+     *
+     * Effets de bord :
+     * - Démarre les threads du pool (arrêtés quand le dernier clone est supprimé)
+     */
+    pub fn new(engine: Arc<StorageEngine>, threads: usize, timeout: Option<Duration>) -> Result<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("fic-query-{}", index))
+            .build()
+            .context("Impossible de créer le pool de lecture")?;
+        Ok(Self {
+            engine,
+            pool: Arc::new(pool),
+            timeout,
+        })
+    }
+
+    /// Moteur sous-jacent (pour les opérations non bloquantes : tables, bases, cache)
+    pub fn engine(&self) -> &Arc<StorageEngine> {
+        &self.engine
+    }
+
+    /// Délai d'exécution des requêtes (None = illimité)
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /**
+     * Exécute une lecture sur le pool, avec le délai configuré.
+     *
+     * @param job - Lecture ; doit vérifier le jeton pour s'arrêter au plus tôt
+     * @returns Result<T> - Résultat de la lecture, ou erreur Interrupted::TimedOut
+     *
+     * Effets de bord :
+     * - Annule la lecture si le future est abandonné ou si le délai est dépassé
+     */
+    pub async fn query<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Arc<StorageEngine>, &CancelToken) -> Result<T> + Send + 'static,
+    {
+        self.execute(self.timeout, job).await
+    }
+
+    /**
+     * Exécute une opération longue sur le pool, sans délai (scan, migration).
+     *
+     * @param job - Opération à exécuter
+     * @returns Result<T> - Résultat de l'opération
+     *
+     * Effets de bord :
+     * - Annule le jeton si le future est abandonné avant la fin
+     */
+    pub async fn run<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Arc<StorageEngine>, &CancelToken) -> Result<T> + Send + 'static,
+    {
+        self.execute(None, job).await
+    }
+
+    async fn execute<T, F>(&self, timeout: Option<Duration>, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Arc<StorageEngine>, &CancelToken) -> Result<T> + Send + 'static,
+    {
+        let cancel = timeout.map(CancelToken::with_timeout).unwrap_or_default();
+        let mut guard = CancelOnDrop { cancel: cancel.clone(), armed: true };
+        let (sender, receiver) = oneshot::channel();
+        let engine = self.engine.clone();
+        self.pool.spawn(move || {
+            // Requête annulée pendant l'attente d'un thread : rien à lire
+            let result = cancel.check().and_then(|_| {
+                panic::catch_unwind(AssertUnwindSafe(|| job(&engine, &cancel)))
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("La lecture s'est arrêtée sur une erreur interne (panic)")))
+            });
+            let _ = sender.send(result);
+        });
+
+        let received = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, receiver).await {
+                Ok(received) => received,
+                Err(_) => {
+                    guard.armed = false;
+                    guard.cancel.cancel();
+                    get_logger().log_with_source(
+                        LogLevel::Warn,
+                        format!("Requête arrêtée après {} ms (délai dépassé)", timeout.as_millis()),
+                        Some("Storage".to_string()),
+                    );
+                    return Err(Interrupted::TimedOut(timeout).into());
+                }
+            },
+            None => receiver.await,
+        };
+        guard.armed = false;
+        received.map_err(|_| anyhow::anyhow!("Lecture interrompue : la tâche a été abandonnée"))?
+    }
+
+    /**
+     * Version asynchrone de StorageEngine::select (annulable, avec délai).
+     *
+     * @param table - Nom de la table
+     * @param filters - Filtres de requête
+     * @returns Result<QueryResult> - Résultats ou erreur
+     */
+    pub async fn select(&self, table: String, filters: QueryFilters) -> Result<QueryResult> {
+        self.query(move |engine, cancel| engine.select_cancellable(&table, filters, cancel)).await
+    }
+
+    /**
+     * Version asynchrone de StorageEngine::get_by_id.
+     *
+     * @param table - Nom de la table
+     * @param id - ID de l'enregistrement
+     * @returns Result<Record> - Enregistrement décodé ou erreur
+     */
+    pub async fn get_by_id(&self, table: String, id: u32) -> Result<Record> {
+        self.query(move |engine, _| engine.get_by_id(&table, id)).await
+    }
+
+    /**
+     * Version asynchrone de StorageEngine::search.
+     *
+     * @param table - Nom de la table
+     * @param query - Mots recherchés
+     * @param field - Rubrique à interroger (tous les index full-text si None)
+     * @returns Result<Vec<FullTextHit>> - Enregistrements trouvés, par pertinence
+     */
+    pub async fn search(&self, table: String, query: String, field: Option<String>) -> Result<Vec<FullTextHit>> {
+        self.query(move |engine, _| engine.search(&table, &query, field.as_deref())).await
+    }
+
+    /**
+     * Version asynchrone de StorageEngine::history.
+     *
+     * @param table - Nom de la table
+     * @param filters - Filtres de l'historique
+     * @returns Result<Vec<HistoryEntry>> - Modifications, les plus récentes en premier
+     */
+    pub async fn history(&self, table: String, filters: HistoryFilters) -> Result<Vec<HistoryEntry>> {
+        self.query(move |engine, _| engine.history(&table, &filters)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    fn engine(dir: &TempDir) -> Arc<StorageEngine> {
+        Arc::new(StorageEngine::new_with_parallel(dir.path(), true, false).unwrap())
+    }
+
+    /// Lecture qui ne se termine qu'à l'annulation du jeton
+    fn wait_cancelled(cancel: &CancelToken) -> Result<()> {
+        loop {
+            cancel.check()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout() {
+        let dir = TempDir::new().unwrap();
+        let queries = AsyncEngine::new(engine(&dir), 1, Some(Duration::from_millis(20))).unwrap();
+
+        assert_eq!(queries.query(|_, _| Ok(42)).await.unwrap(), 42);
+        let error = queries.query(|_, cancel| wait_cancelled(cancel)).await.unwrap_err();
+        assert_eq!(Interrupted::find(&error), Some(Interrupted::TimedOut(Duration::from_millis(20))));
+        // Sans délai : la capture de la trace du panic (RUST_BACKTRACE) peut dépasser 20 ms
+        let error = queries.run(|_, _| -> Result<()> { panic!("test") }).await.unwrap_err();
+        assert!(error.to_string().contains("panic"));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_query_is_cancelled() {
+        let dir = TempDir::new().unwrap();
+        let queries = AsyncEngine::new(engine(&dir), 1, None).unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        let query = queries.run(move |_, cancel| {
+            let result = wait_cancelled(cancel);
+            let _ = sender.send(result.as_ref().err().and_then(Interrupted::find));
+            result
+        });
+        // Le future est abandonné avant la fin, comme un handler dont le client se déconnecte
+        assert!(tokio::time::timeout(Duration::from_millis(20), query).await.is_err());
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Some(Interrupted::Cancelled));
+    }
+}
@@ -0,0 +1,380 @@
+/**
+ * Cache des schémas et des fichiers ouverts, par table.
+ *
+ * Le tableau de bord interroge les mêmes tables de nombreuses fois par page.
+ * Sans cache, chaque requête rouvre le .fic pour en déduire le schéma, relit
+ * les headers des index, puis rouvre le .fic et le .mmo pour la lecture.
+ *
+ * Pour chaque table (clé : chemin du .fic), le cache conserve :
+ * - Le schéma et le header du .fic
+ * - Des fichiers .fic et .mmo déjà ouverts, rendus au cache après usage
+ *   (au plus MAX_POOLED_HANDLES de chaque par table)
+ * - La taille et la date de modification des fichiers lus (.fic, .mmo,
+ *   .ndx, .ftx) au moment du chargement
+ *
+ * À chaque accès, la taille et la date de modification sont comparées à
+ * celles du disque : si un fichier a changé, apparu ou disparu, l'entrée
+ * est rechargée et les fichiers ouverts de l'ancienne entrée sont fermés.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (get_schema, select, get_by_id)
+ * - Invalidé par la surveillance des fichiers (src/storage/watcher.rs)
+ * - Statistiques exposées par GET /cache/stats (src/api/handlers.rs)
+ */
+
+use crate::core::source;
+use crate::core::{FicFile, FicHeader, MmoFile, TableFiles, TableSchema};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+/// Nombre maximum de fichiers ouverts conservés par table et par type
+pub const MAX_POOLED_HANDLES: usize = 8;
+
+/// Taille et date de modification d'un fichier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    /// Lit l'état d'un fichier (None s'il n'existe plus)
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let metadata = source::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len,
+            modified: metadata.modified,
+        })
+    }
+}
+
+/// Compteurs du cache (partagés avec les entrées pour les fichiers ouverts)
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+    handle_reuses: AtomicU64,
+    handle_opens: AtomicU64,
+}
+
+/// Statistiques d'utilisation du cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Nombre de tables en cache
+    pub tables: usize,
+    /// Schémas servis depuis le cache
+    pub hits: u64,
+    /// Schémas chargés depuis le disque (premier accès ou fichier modifié)
+    pub misses: u64,
+    /// Entrées rechargées parce qu'un fichier a changé
+    pub invalidations: u64,
+    /// Proportion des accès servis depuis le cache (0.0 à 1.0)
+    pub hit_rate: f64,
+    /// Fichiers .fic/.mmo réutilisés
+    pub handle_reuses: u64,
+    /// Fichiers .fic/.mmo ouverts
+    pub handle_opens: u64,
+}
+
+/// Fichiers ouverts disponibles pour une table
+type Pool<T> = Arc<Mutex<Vec<T>>>;
+
+/// Table en cache
+pub struct CachedTable {
+    /// État des fichiers lus au chargement, dans l'ordre fic, mmo, ndx, ftx
+    stamps: Vec<(PathBuf, Option<FileStamp>)>,
+    /// Schéma de la table
+    pub schema: TableSchema,
+    /// Header du fichier .fic
+    pub header: FicHeader,
+    /// Chemin du fichier .fic
+    fic_path: PathBuf,
+    /// Chemin du fichier .mmo (optionnel)
+    mmo_path: Option<PathBuf>,
+    fic_pool: Pool<FicFile>,
+    mmo_pool: Pool<MmoFile>,
+    counters: Arc<CacheCounters>,
+}
+
+/**
+ * Fichier ouvert emprunté au cache, rendu à la table à la fin de son usage.
+ *
+ * Un fichier emprunté à une entrée invalidée entre-temps est simplement fermé.
+ */
+pub struct PooledHandle<T> {
+    slot: Option<T>,
+    pool: Option<Pool<T>>,
+}
+
+/// Cache des tables, par chemin du fichier .fic
+#[derive(Default)]
+pub struct TableCache {
+    tables: RwLock<HashMap<PathBuf, Arc<CachedTable>>>,
+    counters: Arc<CacheCounters>,
+}
+
+/// Chemins surveillés pour une table
+fn watched_paths(files: &TableFiles) -> Vec<PathBuf> {
+    std::iter::once(&files.fic_path)
+        .chain(files.mmo_path.iter())
+        .chain(files.ndx_paths.iter())
+        .chain(files.ftx_paths.iter())
+        .cloned()
+        .collect()
+}
+
+impl TableCache {
+    /**
+     * Retourne l'entrée d'une table, chargée ou rechargée si nécessaire.
+     *
+     * @param files - Fichiers de la table
+     * @param load - Lit le schéma à partir du .fic ouvert (appelé en cas d'absence ou de modification)
+     * @returns Result<Arc<CachedTable>> - Entrée à jour
+     *
+     * Effets de bord :
+     * - Lit la taille et la date de modification des fichiers de la table
+     * - Ouvre le fichier .fic si l'entrée doit être (re)chargée
+     */
+    pub fn get_or_load(
+        &self,
+        files: &TableFiles,
+        load: impl FnOnce(&FicFile) -> Result<TableSchema>,
+    ) -> Result<Arc<CachedTable>> {
+        let paths = watched_paths(files);
+        let stamps: Vec<(PathBuf, Option<FileStamp>)> = paths.into_iter()
+            .map(|path| {
+                let stamp = FileStamp::read(&path);
+                (path, stamp)
+            })
+            .collect();
+
+        let existing = self.tables.read().unwrap().get(&files.fic_path).cloned();
+        if let Some(cached) = &existing {
+            if cached.stamps == stamps {
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached.clone());
+            }
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+
+        // L'état des fichiers est lu avant le chargement : une modification
+        // pendant la lecture sera détectée au prochain accès
+        let fic = FicFile::open(&files.fic_path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier .fic: {:?}", files.fic_path))?;
+        self.counters.handle_opens.fetch_add(1, Ordering::Relaxed);
+        let schema = load(&fic)?;
+        let cached = Arc::new(CachedTable {
+            stamps,
+            schema,
+            header: fic.header().clone(),
+            fic_path: files.fic_path.clone(),
+            mmo_path: files.mmo_path.clone(),
+            fic_pool: Arc::new(Mutex::new(vec![fic])),
+            mmo_pool: Arc::new(Mutex::new(Vec::new())),
+            counters: self.counters.clone(),
+        });
+        self.tables.write().unwrap().insert(files.fic_path.clone(), cached.clone());
+        Ok(cached)
+    }
+
+    /**
+     * Retire les tables dont le fichier .fic n'est plus enregistré.
+     *
+     * @param keep - Indique si un chemin de .fic est encore utilisé
+     */
+    pub fn retain(&self, keep: impl Fn(&Path) -> bool) {
+        self.tables.write().unwrap().retain(|path, _| keep(path));
+    }
+
+    /**
+     * Retire une table du cache (rechargée au prochain accès).
+     *
+     * @param fic_path - Chemin du fichier .fic de la table
+     */
+    pub fn invalidate(&self, fic_path: &Path) {
+        if self.tables.write().unwrap().remove(fic_path).is_some() {
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /**
+     * Retourne les statistiques d'utilisation du cache.
+     *
+     * @returns CacheStats - Compteurs depuis le démarrage du moteur
+     */
+    pub fn stats(&self) -> CacheStats {
+        let counter = |value: &AtomicU64| value.load(Ordering::Relaxed);
+        let (hits, misses) = (counter(&self.counters.hits), counter(&self.counters.misses));
+        CacheStats {
+            tables: self.tables.read().unwrap().len(),
+            hits,
+            misses,
+            invalidations: counter(&self.counters.invalidations),
+            hit_rate: if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+            handle_reuses: counter(&self.counters.handle_reuses),
+            handle_opens: counter(&self.counters.handle_opens),
+        }
+    }
+}
+
+impl CachedTable {
+    /**
+     * Emprunte un fichier .fic ouvert (ouvert s'il n'y en a aucun de libre).
+     *
+     * @returns Result<PooledHandle<FicFile>> - Fichier ouvert, rendu à la fin de son usage
+     */
+    pub fn fic(&self) -> Result<PooledHandle<FicFile>> {
+        let pooled = self.fic_pool.lock().unwrap().pop();
+        let fic = match pooled {
+            Some(fic) => {
+                self.counters.handle_reuses.fetch_add(1, Ordering::Relaxed);
+                fic
+            }
+            None => {
+                self.counters.handle_opens.fetch_add(1, Ordering::Relaxed);
+                FicFile::open(&self.fic_path)
+                    .with_context(|| format!("Impossible d'ouvrir le fichier .fic: {:?}", self.fic_path))?
+            }
+        };
+        Ok(PooledHandle {
+            slot: Some(fic),
+            pool: Some(self.fic_pool.clone()),
+        })
+    }
+
+    /**
+     * Emprunte un fichier .mmo ouvert.
+     *
+     * @returns Result<PooledHandle<MmoFile>> - Fichier ouvert, ou emplacement vide si la table n'a pas de .mmo
+     */
+    pub fn mmo(&self) -> Result<PooledHandle<MmoFile>> {
+        let Some(mmo_path) = &self.mmo_path else {
+            return Ok(PooledHandle::empty());
+        };
+        let pooled = self.mmo_pool.lock().unwrap().pop();
+        let mmo = match pooled {
+            Some(mmo) => {
+                self.counters.handle_reuses.fetch_add(1, Ordering::Relaxed);
+                mmo
+            }
+            None => {
+                self.counters.handle_opens.fetch_add(1, Ordering::Relaxed);
+                MmoFile::open(mmo_path)
+                    .with_context(|| format!("Impossible d'ouvrir le fichier .mmo: {:?}", mmo_path))?
+            }
+        };
+        Ok(PooledHandle {
+            slot: Some(mmo),
+            pool: Some(self.mmo_pool.clone()),
+        })
+    }
+}
+
+impl<T> PooledHandle<T> {
+    /// Emplacement vide (fichier absent ou inutile pour la requête)
+    pub fn empty() -> Self {
+        Self { slot: None, pool: None }
+    }
+
+    /**
+     * Accès au fichier sous forme d'option (forme attendue par le décodage
+     * des enregistrements pour le .mmo).
+     *
+     * @returns &mut Option<T> - Fichier emprunté, None si la table n'en a pas
+     */
+    pub fn slot(&mut self) -> &mut Option<T> {
+        &mut self.slot
+    }
+}
+
+impl<T> Deref for PooledHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.slot.as_ref().expect("fichier emprunté absent")
+    }
+}
+
+impl<T> DerefMut for PooledHandle<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.slot.as_mut().expect("fichier emprunté absent")
+    }
+}
+
+impl<T> Drop for PooledHandle<T> {
+    fn drop(&mut self) {
+        if let (Some(item), Some(pool)) = (self.slot.take(), &self.pool) {
+            let mut pool = pool.lock().unwrap();
+            if pool.len() < MAX_POOLED_HANDLES {
+                pool.push(item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use tempfile::TempDir;
+
+    fn table_files(fic_path: PathBuf) -> TableFiles {
+        TableFiles {
+            name: "CLIENT".to_string(),
+            database: String::new(),
+            fic_path,
+            mmo_path: None,
+            ndx_paths: Vec::new(),
+            ftx_paths: Vec::new(),
+            journal_paths: Vec::new(),
+            segment_paths: Vec::new(),
+            missing: Vec::new(),
+        }
+    }
+
+    fn load(fic: &FicFile) -> Result<TableSchema> {
+        Ok(TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: fic.header().record_length,
+            field_count: 0,
+            fields: Vec::new(),
+            indexes: Vec::new(),
+            full_text: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_hits_and_invalidation() -> Result<()> {
+        let dir = TempDir::new()?;
+        let fic_path = dir.path().join("CLIENT.FIC");
+        write_fic(&fic_path, 16, &[(false, 0); 2])?;
+        let files = table_files(fic_path.clone());
+        let cache = TableCache::default();
+
+        let first = cache.get_or_load(&files, load)?;
+        assert_eq!(first.header.record_count, 2);
+        {
+            let _fic = first.fic()?; // Fichier ouvert au chargement, réutilisé
+        }
+        let second = cache.get_or_load(&files, load)?;
+        assert!(Arc::ptr_eq(&first, &second));
+
+        write_fic(&fic_path, 16, &[(false, 0); 3])?; // Taille modifiée
+        let reloaded = cache.get_or_load(&files, load)?;
+        assert_eq!(reloaded.header.record_count, 3);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.invalidations), (1, 2, 1));
+        assert_eq!(stats.handle_reuses, 1);
+        assert_eq!(stats.tables, 1);
+        Ok(())
+    }
+}
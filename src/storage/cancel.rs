@@ -0,0 +1,124 @@
+/**
+ * Annulation et délai d'exécution des requêtes.
+ *
+ * Un CancelToken est partagé entre la tâche qui exécute une requête et
+ * celle qui l'a demandée. Le moteur le vérifie à chaque enregistrement lu
+ * (StorageEngine::select_cancellable, profilage) et s'arrête dès que le
+ * jeton est annulé ou que son délai est dépassé, avec une erreur Interrupted.
+ *
+ * Liens avec d'autres modules :
+ * - Vérifié par src/storage/engine.rs, src/profile/mod.rs et src/sql/executor.rs
+ * - Créé par src/storage/async_engine.rs pour chaque requête de l'API
+ */
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cause de l'arrêt d'une requête
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    /// Annulée par le demandeur (client HTTP déconnecté)
+    Cancelled,
+    /// Délai d'exécution dépassé
+    TimedOut(Duration),
+}
+
+impl Interrupted {
+    /**
+     * Recherche une interruption dans la chaîne des causes d'une erreur.
+     *
+     * @param error - Erreur retournée par le moteur (éventuellement avec contexte)
+     * @returns Option<Interrupted> - Cause de l'arrêt, None pour une autre erreur
+     */
+    pub fn find(error: &anyhow::Error) -> Option<Interrupted> {
+        error.chain().find_map(|cause| cause.downcast_ref::<Interrupted>()).copied()
+    }
+}
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interrupted::Cancelled => write!(f, "Requête annulée"),
+            Interrupted::TimedOut(timeout) => write!(f, "Délai d'exécution dépassé ({} ms)", timeout.as_millis()),
+        }
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Jeton d'annulation d'une requête (clonable, partagé entre threads)
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    /// Positionné par cancel()
+    cancelled: Arc<AtomicBool>,
+    /// Échéance et délai d'origine (None = pas de délai)
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl CancelToken {
+    /**
+     * Crée un jeton sans délai, annulé uniquement par cancel().
+     *
+     * @returns CancelToken - Jeton actif
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Crée un jeton qui expire après un délai.
+     *
+     * @param timeout - Durée maximale d'exécution, à partir de maintenant
+     * @returns CancelToken - Jeton actif jusqu'à l'échéance
+     */
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            deadline: Some((Instant::now() + timeout, timeout)),
+        }
+    }
+
+    /**
+     * Annule la requête : les prochaines vérifications échouent.
+     *
+     * Effets de bord :
+     * - Visible par tous les clones du jeton
+     */
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /**
+     * Vérifie que la requête peut continuer.
+     *
+     * @returns Result<()> - Erreur Interrupted si le jeton est annulé ou expiré
+     */
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(Interrupted::Cancelled.into());
+        }
+        match self.deadline {
+            Some((deadline, timeout)) if Instant::now() >= deadline => Err(Interrupted::TimedOut(timeout).into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_token() {
+        let token = CancelToken::new();
+        assert!(token.check().is_ok());
+        token.clone().cancel();
+        let error = token.check().unwrap_err();
+        assert_eq!(Interrupted::find(&error.context("lecture")), Some(Interrupted::Cancelled));
+
+        let expired = CancelToken::with_timeout(Duration::ZERO);
+        assert_eq!(Interrupted::find(&expired.check().unwrap_err()), Some(Interrupted::TimedOut(Duration::ZERO)));
+        assert!(Interrupted::find(&anyhow::anyhow!("autre")).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+/**
+ * Curseurs de pagination (keyset) des sélections.
+ *
+ * La pagination par offset relit et ignore tous les enregistrements des
+ * pages précédentes. Un curseur mémorise l'endroit où la page s'est
+ * arrêtée : la page suivante reprend directement à l'enregistrement
+ * suivant, lu par accès direct (FicFile::read_record).
+ *
+ * - Ordre physique (parcours complet) : ID du dernier enregistrement
+ *   retourné ; la page suivante commence à l'ID suivant
+ * - Ordre d'un index : clé (forme stockée) et ID du dernier enregistrement
+ *   retourné ; la page suivante reprend dans l'index à l'entrée qui suit
+ *   (clé, ID), même si des entrées ont été ajoutées ou retirées entre-temps
+ *
+ * Le jeton est opaque pour le client (hexadécimal). Il porte une empreinte
+ * de la table, des conditions et du tri : il est refusé pour une autre
+ * requête.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise la crate crc32fast (CRC-32 de l'empreinte)
+ * - Utilisé par src/storage/engine.rs (select)
+ */
+
+use crate::storage::engine::QueryFilters;
+use anyhow::{Context, Result};
+
+/// Version du format des jetons
+const CURSOR_VERSION: u8 = 2;
+
+/// Point de reprise d'une sélection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorPosition {
+    /// Ordre physique du .fic : reprise après cet ID
+    Fic { last_id: u32 },
+    /// Ordre d'un index : reprise après l'entrée (`last_key`, `last_id`)
+    Index { last_key: Vec<u8>, last_id: u32 },
+}
+
+impl CursorPosition {
+    /// ID du dernier enregistrement retourné
+    pub fn last_id(&self) -> u32 {
+        match self {
+            CursorPosition::Fic { last_id } | CursorPosition::Index { last_id, .. } => *last_id,
+        }
+    }
+}
+
+/// Curseur d'une sélection : point de reprise et empreinte de la requête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub position: CursorPosition,
+    pub fingerprint: u32,
+}
+
+impl Cursor {
+    /**
+     * Encode le curseur en jeton opaque.
+     *
+     * @returns String - Jeton (hexadécimal)
+     */
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![CURSOR_VERSION];
+        match &self.position {
+            CursorPosition::Fic { last_id } => {
+                bytes.push(0);
+                bytes.extend(self.fingerprint.to_le_bytes());
+                bytes.extend(last_id.to_le_bytes());
+            }
+            CursorPosition::Index { last_key, last_id } => {
+                bytes.push(1);
+                bytes.extend(self.fingerprint.to_le_bytes());
+                bytes.extend(last_id.to_le_bytes());
+                bytes.extend(last_key);
+            }
+        }
+        hex::encode(bytes)
+    }
+
+    /**
+     * Décode un jeton.
+     *
+     * @param token - Jeton retourné par une page précédente (QueryResult.next_cursor)
+     * @returns Result<Cursor> - Curseur, ou erreur si le jeton est invalide
+     */
+    pub fn decode(token: &str) -> Result<Self> {
+        let bytes = hex::decode(token.trim()).with_context(|| format!("Curseur invalide: {}", token))?;
+        let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let position = match bytes.as_slice() {
+            [CURSOR_VERSION, 0, ..] if bytes.len() == 10 => CursorPosition::Fic { last_id: u32_at(6) },
+            [CURSOR_VERSION, 1, ..] if bytes.len() >= 10 => CursorPosition::Index { last_key: bytes[10..].to_vec(), last_id: u32_at(6) },
+            _ => anyhow::bail!("Curseur invalide: {}", token),
+        };
+        Ok(Cursor { position, fingerprint: u32_at(2) })
+    }
+}
+
+/**
+ * Empreinte d'une requête : table, conditions et tri.
+ *
+ * La projection, la limite et l'offset n'en font pas partie : ils peuvent
+ * changer d'une page à l'autre sans invalider le curseur.
+ *
+ * @param table - Nom de la table
+ * @param filters - Filtres de la requête
+ * @returns u32 - Empreinte (CRC-32)
+ */
+pub fn fingerprint(table: &str, filters: &QueryFilters) -> u32 {
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(table.to_lowercase().as_bytes());
+    crc.update(&serde_json::to_vec(&filters.field_filters).unwrap_or_default());
+    crc.update(&serde_json::to_vec(&filters.order_by).unwrap_or_default());
+    crc.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::engine::{FieldFilter, FilterOperator, SortKey};
+
+    #[test]
+    fn test_cursor_round_trip() -> Result<()> {
+        for position in [CursorPosition::Fic { last_id: 41 }, CursorPosition::Index { last_key: b"DUPONT\0\0".to_vec(), last_id: 1_000_000 }] {
+            let cursor = Cursor { position, fingerprint: 0xDEAD_BEEF };
+            assert_eq!(Cursor::decode(&cursor.encode())?, cursor);
+        }
+        assert!(Cursor::decode("pas un curseur").is_err());
+        assert!(Cursor::decode("0100").is_err());
+        assert!(Cursor::decode("0300efbeadde29000000").is_err());
+        // Jeton de l'ancien format (position dans l'index) : refusé
+        assert!(Cursor::decode("0101efbeadde40420f0007000000").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_follows_query() {
+        let mut filters = QueryFilters::default();
+        let base = fingerprint("CLIENT", &filters);
+        assert_eq!(base, fingerprint("client", &filters));
+        assert_ne!(base, fingerprint("FACTURE", &filters));
+
+        filters.limit = Some(10);
+        filters.projection = Some(vec!["nom".to_string()]);
+        assert_eq!(fingerprint("CLIENT", &filters), base);
+
+        filters.field_filters.push(FieldFilter { field: "ville".to_string(), operator: FilterOperator::Equal, value: "Paris".to_string() });
+        let filtered = fingerprint("CLIENT", &filters);
+        assert_ne!(filtered, base);
+        filters.order_by.push(SortKey::new("nom", false));
+        assert_ne!(fingerprint("CLIENT", &filters), filtered);
+    }
+}
@@ -7,12 +7,15 @@
  * - Détection automatique des tables dans un dossier
  * - Lecture et écriture d'enregistrements
  * - Conversion des données brutes en structures typées (Record, FieldValue)
- * - Requêtes avec filtres et pagination
+ * - Requêtes avec filtres, tri, pagination et échantillonnage aléatoire
  * - Gestion des schémas de tables
  * - Décodage automatique des champs (entiers, flottants, chaînes, binaires, mémos)
  * 
- * Le moteur maintient un cache des tables détectées et peut fonctionner
- * en mode lecture seule ou avec écriture.
+ * Le moteur maintient un cache des tables détectées, un cache des schémas
+ * et des fichiers ouverts (src/storage/cache.rs), et peut fonctionner
+ * en mode lecture seule ou avec écriture. Chaque scan est comparé au
+ * précédent et les tables ajoutées, supprimées ou modifiées sont publiées
+ * aux abonnés (src/storage/watcher.rs).
  * 
  * Liens avec d'autres modules :
  * - Utilise src/core/ pour lire les fichiers .fic/.mmo/.ndx
@@ -20,24 +23,242 @@
  * - Utilisé par src/sql/executor.rs pour les requêtes SQL
  */
 
-use crate::core::{FicFile, FicRecord, MmoFile, TableFiles, TableSchema};
+use crate::core::key::record_key;
+use crate::core::ndx::{NdxEntry, NdxWriter, DEFAULT_PAGE_SIZE};
+use crate::core::jnl::format_timestamp;
+use crate::core::archive::Archive;
+use crate::core::rep::RepFile;
+use crate::core::source;
+use crate::core::{EncodingConfig, FicFile, FicRecord, FtxFile, FullTextDefinition, FullTextHit, IndexDefinition, JnlFile, JournalOperation, KeySegment, MmoFile, NdxFile, TableFiles, TableSchema, TextEncoding};
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::cache::{CacheStats, CachedTable, PooledHandle, TableCache};
+use crate::storage::cancel::CancelToken;
+use crate::storage::cursor::{self, Cursor, CursorPosition};
+use crate::storage::keydefs;
+use crate::storage::planner::{self, FullTextAccess, IndexAccess, QueryPlan};
+use crate::storage::predicate::{self, Predicate};
+pub use crate::storage::record::{FieldValue, Record};
+use crate::storage::record::{Fields, RawRef, RawSource};
+use crate::storage::sample::{self, Candidates, SampleRng, SampleSpec};
+use crate::storage::sort::{RecordSorter, SortedPage};
+use crate::storage::watcher::{self, TableChange, TableState, CHANGE_CHANNEL_CAPACITY};
 use anyhow::{Context, Result};
-use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use rayon::prelude::*;
+use tokio::sync::broadcast;
 
 /// Filtres pour les requêtes de sélection
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QueryFilters {
     /// Nombre maximum d'enregistrements à retourner
     pub limit: Option<u32>,
     /// Nombre d'enregistrements à ignorer (pagination)
     pub offset: Option<u32>,
-    /// Filtres par champ (nom_champ -> valeur)
-    pub field_filters: HashMap<String, String>,
+    /// Conditions par champ (combinées par AND)
+    #[serde(default)]
+    pub field_filters: Vec<FieldFilter>,
+    /// Ordre de tri demandé (ORDER BY)
+    #[serde(default)]
+    pub order_by: Vec<SortKey>,
+    /// Rubriques à retourner (None = toutes) ; seules celles-ci et les
+    /// rubriques filtrées ou triées sont décodées. L'ID est toujours retourné.
+    #[serde(default)]
+    pub projection: Option<Vec<String>>,
+    /// Échantillon aléatoire (TABLESAMPLE) : seuls les enregistrements tirés
+    /// sont lus, puis triés et paginés (limit vaut par défaut la taille de l'échantillon)
+    #[serde(default)]
+    pub sample: Option<SampleSpec>,
+    /// Curseur retourné par la page précédente (QueryResult.next_cursor) :
+    /// la page reprend juste après, sans relire les précédentes (remplace offset)
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// Condition sur un champ (ex: nom = 'Dupont')
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldFilter {
+    /// Nom du champ
+    pub field: String,
+    /// Opérateur de comparaison
+    pub operator: FilterOperator,
+    /// Valeur de comparaison (convertie selon le type du champ)
+    pub value: String,
+}
+
+/// Opérateurs de comparaison supportés par les filtres
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LessThan,
+    GreaterThanOrEqual,
+    LessThanOrEqual,
+    Like,
+    /// Négation de LIKE (NULL exclu, comme pour LIKE)
+    NotLike,
+    /// Recherche full-text (MATCH / CONTAINS), rubrique "*" = tous les index .ftx
+    Match,
+}
+
+/// Clé de tri
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    /// Nom du champ
+    pub field: String,
+    /// Tri décroissant
+    #[serde(default)]
+    pub descending: bool,
+    /// Position des valeurs NULL (None = en dernier en ASC, en premier en DESC)
+    #[serde(default)]
+    pub nulls: Option<NullsOrder>,
+    /// Règle de comparaison des chaînes
+    #[serde(default)]
+    pub collation: Collation,
+}
+
+/// Position des valeurs NULL dans un tri
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// Règle de comparaison des chaînes dans un tri
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collation {
+    /// Octet par octet (Windows-1252), sensible à la casse et aux accents
+    #[default]
+    Binary,
+    /// Insensible à la casse
+    NoCase,
+    /// Insensible à la casse et aux accents
+    NoAccent,
+}
+
+impl Collation {
+    /**
+     * Indique les transformations appliquées au texte avant comparaison.
+     *
+     * @returns (bool, bool) - (insensible à la casse, insensible aux accents),
+     *          comme les options d'un segment d'index HFSQL
+     */
+    pub fn folding(&self) -> (bool, bool) {
+        match self {
+            Collation::Binary => (false, false),
+            Collation::NoCase => (true, false),
+            Collation::NoAccent => (true, true),
+        }
+    }
+}
+
+impl std::str::FromStr for Collation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "binary" => Ok(Collation::Binary),
+            "nocase" | "ci" => Ok(Collation::NoCase),
+            "noaccent" | "ai" => Ok(Collation::NoAccent),
+            _ => anyhow::bail!("Collation inconnue: {} (attendu: binary, nocase, noaccent)", s),
+        }
+    }
+}
+
+impl std::fmt::Display for Collation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Collation::Binary => "BINARY",
+            Collation::NoCase => "NOCASE",
+            Collation::NoAccent => "NOACCENT",
+        })
+    }
+}
+
+impl SortKey {
+    /**
+     * Crée une clé de tri avec la position des NULL et la collation par défaut.
+     *
+     * @param field - Nom du champ
+     * @param descending - Tri décroissant
+     * @returns SortKey - Clé de tri
+     */
+    pub fn new(field: impl Into<String>, descending: bool) -> Self {
+        Self {
+            field: field.into(),
+            descending,
+            nulls: None,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Indique si les valeurs NULL sont placées en premier
+    pub fn nulls_first(&self) -> bool {
+        self.nulls.map(|nulls| nulls == NullsOrder::First).unwrap_or(self.descending)
+    }
+
+    /**
+     * Lit une liste de clés de tri séparées par des virgules
+     * (ex: "nom:nocase,age:desc:nulls_last").
+     *
+     * @param list - Clés au format de SortKey::from_str
+     * @returns Result<Vec<SortKey>> - Clés par priorité décroissante, ou erreur si une clé est invalide
+     */
+    pub fn parse_list(list: &str) -> Result<Vec<SortKey>> {
+        list.split(',')
+            .filter(|key| !key.trim().is_empty())
+            .map(|key| key.parse())
+            .collect()
+    }
+}
+
+/**
+ * Lit une clé de tri au format `champ[:asc|desc][:nulls_first|nulls_last][:collation]`
+ * (paramètre `sort` de l'API REST, option `--sort` du CLI).
+ */
+impl std::str::FromStr for SortKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().split(':');
+        let field = parts.next().unwrap_or_default().trim();
+        if field.is_empty() {
+            anyhow::bail!("Clé de tri vide");
+        }
+        let mut key = SortKey::new(field, false);
+        for option in parts.map(|p| p.trim().to_lowercase()) {
+            match option.as_str() {
+                "asc" => key.descending = false,
+                "desc" => key.descending = true,
+                "nulls_first" => key.nulls = Some(NullsOrder::First),
+                "nulls_last" => key.nulls = Some(NullsOrder::Last),
+                other => key.collation = other.parse()
+                    .with_context(|| format!("Option de tri invalide pour {}: {}", field, other))?,
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl std::fmt::Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.field)?;
+        if self.collation != Collation::Binary {
+            write!(f, " COLLATE {}", self.collation)?;
+        }
+        write!(f, " {}", if self.descending { "DESC" } else { "ASC" })?;
+        if let Some(nulls) = self.nulls {
+            write!(f, " NULLS {}", if nulls == NullsOrder::First { "FIRST" } else { "LAST" })?;
+        }
+        Ok(())
+    }
 }
 
 /// Résultat d'une requête de sélection
@@ -45,84 +266,345 @@ pub struct QueryFilters {
 pub struct QueryResult {
     /// Liste des enregistrements retournés
     pub records: Vec<Record>,
-    /// Nombre total d'enregistrements (avant pagination)
-    pub total: u32,
+    /// Nombre total d'enregistrements retenus (avant pagination), quand il est
+    /// connu : None si la lecture s'est arrêtée à la page demandée (accès indexé
+    /// ou full-text avec des enregistrements après la page)
+    pub total: Option<u32>,
     /// Offset utilisé
     pub offset: u32,
     /// Limite utilisée
     pub limit: u32,
+    /// Chemin d'accès utilisé (parcours complet ou index)
+    pub plan: QueryPlan,
+    /// Curseur de la page suivante (None s'il n'y a plus d'enregistrement, ou si
+    /// l'ordre n'est ni l'ordre physique ni celui d'un index)
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
-/// Représente un enregistrement avec ses données décodées et typées
+/// Filtres pour l'historique des modifications (journaux)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryFilters {
+    /// Ne garder que les modifications de cet enregistrement
+    pub record_id: Option<u32>,
+    /// Date minimale incluse (secondes depuis 1970-01-01 UTC)
+    pub since: Option<i64>,
+    /// Date maximale exclue (secondes depuis 1970-01-01 UTC)
+    pub until: Option<i64>,
+    /// Ne garder que ce type d'opération
+    pub operation: Option<JournalOperation>,
+    /// Ne garder que les modifications de cet utilisateur (insensible à la casse)
+    pub user: Option<String>,
+    /// Nombre maximum d'entrées (les plus récentes)
+    pub limit: Option<u32>,
+}
+
+/// Modification lue dans un journal, décodée selon le schéma de la table
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Record {
-    /// Identifiant de l'enregistrement
-    pub id: u32,
-    /// Champs de l'enregistrement (nom -> valeur typée)
-    pub fields: HashMap<String, FieldValue>,
-    /// Données mémo décodées (nom_champ_mémo -> contenu texte)
-    pub memo_data: HashMap<String, String>,
+pub struct HistoryEntry {
+    /// Nom du fichier journal
+    pub journal: String,
+    /// Position de l'entrée dans le journal
+    pub sequence: u32,
+    /// Opération effectuée
+    pub operation: JournalOperation,
+    /// Date de l'opération (secondes depuis 1970-01-01 UTC)
+    pub timestamp: i64,
+    /// Date lisible (AAAA-MM-JJ HH:MM:SS, UTC)
+    pub date: String,
+    /// ID de l'enregistrement concerné
+    pub record_id: u32,
+    /// Utilisateur ayant effectué l'opération
+    pub user: String,
+    /// Poste ayant effectué l'opération
+    pub station: String,
+    /// Enregistrement avant l'opération
+    pub before: Option<Record>,
+    /// Enregistrement après l'opération
+    pub after: Option<Record>,
+    /// Rubriques modifiées, dans l'ordre du schéma (modifications uniquement)
+    pub changed_fields: Vec<String>,
 }
 
+/// Résultat de la reconstruction (ou de la vérification) d'un index
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum FieldValue {
-    #[serde(rename = "string")]
-    String { value: String },
-    #[serde(rename = "integer")]
-    Integer { value: i64 },
-    #[serde(rename = "float")]
-    Float { value: f64 },
-    #[serde(rename = "binary")]
-    Binary { value: String }, // hex string
-    #[serde(rename = "null")]
-    Null { #[serde(serialize_with = "serialize_null")] value: () },
+pub struct ReindexReport {
+    /// Nom de l'index
+    pub index: String,
+    /// Chemin du fichier .ndx
+    pub path: PathBuf,
+    /// Nombre d'entrées calculées depuis le fichier .fic
+    pub entries: usize,
+    /// Nombre d'entrées lues dans l'index existant (None s'il est illisible)
+    pub existing_entries: Option<usize>,
+    /// Erreur rencontrée en lisant l'index existant
+    pub existing_error: Option<String>,
+    /// Entrées attendues absentes de l'index existant
+    pub missing: Vec<IndexDiffEntry>,
+    /// Entrées de l'index existant qui ne correspondent à aucun enregistrement
+    pub unexpected: Vec<IndexDiffEntry>,
+    /// Enregistrements illisibles du .fic, absents des entrées calculées
+    pub skipped_records: Vec<u32>,
+    /// L'index a été réécrit sur disque
+    pub written: bool,
 }
 
-// Helpers pour créer les valeurs plus facilement
-impl FieldValue {
-    pub fn string(s: String) -> Self {
-        FieldValue::String { value: s }
-    }
-    
-    pub fn integer(i: i64) -> Self {
-        FieldValue::Integer { value: i }
+impl ReindexReport {
+    /// Indique si l'index existant correspond exactement aux données
+    pub fn is_consistent(&self) -> bool {
+        self.existing_error.is_none() && self.missing.is_empty() && self.unexpected.is_empty() && self.skipped_records.is_empty()
     }
-    
-    pub fn float(f: f64) -> Self {
-        FieldValue::Float { value: f }
+}
+
+/// Entrée d'index sous forme (clé stockée, record_id)
+type KeyEntry = (Vec<u8>, u32);
+
+/// Parcours d'un accès indexé, lu au fil de l'itération
+type IndexScan<'a> = Box<dyn Iterator<Item = Result<NdxEntry>> + 'a>;
+
+/// Page lue à partir de candidats parcourus au fil de la lecture
+#[derive(Default)]
+struct CandidatePage {
+    /// Enregistrements de la page
+    records: Vec<Record>,
+    /// Entrée (clé d'index, ID) du dernier enregistrement de la page
+    last: Option<KeyEntry>,
+    /// Enregistrements retenus parmi les candidats lus (offset compris)
+    matched: u32,
+    /// Un enregistrement retenu suit la page (la lecture s'est arrêtée avant la fin)
+    more: bool,
+}
+
+impl CandidatePage {
+    /// Total retenu, connu seulement si tous les candidats ont été lus
+    fn total(&self) -> Option<u32> {
+        (!self.more).then_some(self.matched)
     }
-    
-    pub fn binary(bytes: Vec<u8>) -> Self {
-        let hex_string: String = bytes.iter()
-            .map(|b| format!("{:02x}", b))
-            .collect();
-        FieldValue::Binary { value: hex_string }
+}
+
+/// Candidats d'une reprise de curseur, dans l'ordre de parcours
+type ResumeCandidates<'a> = Box<dyn Iterator<Item = Result<KeyEntry>> + 'a>;
+
+/// Entrée d'index présente d'un seul côté de la comparaison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexDiffEntry {
+    /// Clé (hexadécimal, forme stockée)
+    pub key: String,
+    /// ID de l'enregistrement associé
+    pub record_id: u32,
+}
+
+/// Base de données HFSQL enregistrée dans le moteur (un dossier de fichiers .fic)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    /// Nom de la base ("" pour la base racine, "compta", "compta.archives"…)
+    pub name: String,
+    /// Dossier de la base
+    pub path: PathBuf,
+    /// Noms qualifiés des tables de la base, triés
+    pub tables: Vec<String>,
+    /// Base utilisée pour les noms de tables non qualifiés
+    pub current: bool,
+    /// Tables déclarées dans un fichier .rep dont le fichier est introuvable
+    pub missing_tables: Vec<MissingTable>,
+}
+
+/// Table déclarée dans un fichier .rep dont le fichier .fic est introuvable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingTable {
+    /// Nom logique de la table
+    pub name: String,
+    /// Fichier .rep déclarant la table
+    pub repository: PathBuf,
+    /// Chemin tel qu'écrit dans le fichier .rep
+    pub declared_path: String,
+    /// Chemin local recherché
+    pub resolved_path: PathBuf,
+}
+
+/// Base enregistrée dans le moteur
+#[derive(Debug, Clone)]
+struct DatabaseEntry {
+    /// Dossier de la base
+    path: PathBuf,
+    /// Préfixe du dossier scanné qui a détecté la base ("" = dossier de données)
+    source: String,
+    /// Tables déclarées dans un .rep mais introuvables
+    missing_tables: Vec<MissingTable>,
+}
+
+/// Base détectée par un scan, avant son enregistrement
+struct ScannedDatabase {
+    /// Nom de la base
+    name: String,
+    /// Dossier et tables manquantes
+    entry: DatabaseEntry,
+    /// Tables trouvées (nom, fichiers)
+    tables: Vec<(String, TableFiles)>,
+}
+
+/// Profondeur maximale de l'exploration récursive des dossiers
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// Chemin canonique d'un dossier, ou le chemin tel quel s'il n'est pas résolu (archive)
+fn canonical_dir(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/**
+ * Retrouve un chemin sans tenir compte de la casse de ses composants.
+ *
+ * Utile pour les chemins écrits sous Windows (`.\DONNEES\Client.fic`) et
+ * lus sur un système de fichiers sensible à la casse.
+ *
+ * @param path - Chemin recherché
+ * @returns Option<PathBuf> - Chemin existant correspondant, ou None
+ */
+fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
+    if source::exists(path) {
+        return Some(path.to_path_buf());
     }
-    
-    pub fn null() -> Self {
-        FieldValue::Null { value: () }
+    let mut current = PathBuf::new();
+    for component in path.components() {
+        let std::path::Component::Normal(name) = component else {
+            current.push(component.as_os_str());
+            continue;
+        };
+        let exact = current.join(name);
+        if source::exists(&exact) {
+            current = exact;
+            continue;
+        }
+        let parent = if current.as_os_str().is_empty() { Path::new(".") } else { current.as_path() };
+        let name = name.to_string_lossy();
+        current = source::read_dir(parent).ok()?
+            .into_iter()
+            .find(|entry| entry.file_name().is_some_and(|entry| entry.to_string_lossy().eq_ignore_ascii_case(&name)))?;
     }
+    Some(current)
+}
+
+/// Contenu d'un dossier de données, indexé par nom de fichier en minuscules
+struct DirectoryFiles {
+    /// Fichiers du dossier de données
+    files: HashMap<String, PathBuf>,
+    /// Fichiers du sous-dossier JNL (journaux)
+    journals: HashMap<String, PathBuf>,
 }
 
-fn serialize_null<S>(_value: &(), serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-{
-    // Sérialiser explicitement comme null pour correspondre au format frontend
-    serializer.serialize_some(&serde_json::Value::Null)
+impl DirectoryFiles {
+    /**
+     * Liste un dossier de données et son éventuel sous-dossier JNL.
+     *
+     * @param data_dir - Dossier de données
+     * @returns Result<DirectoryFiles> - Fichiers trouvés, ou erreur si le dossier est illisible
+     *
+     * Effets de bord :
+     * - Lit le contenu du dossier
+     */
+    fn read(data_dir: &Path) -> Result<Self> {
+        let files = Self::list(data_dir)
+            .with_context(|| format!("Impossible de lire le dossier: {:?}", data_dir))?;
+        let journals = files.iter()
+            .find(|(name, path)| name.as_str() == "jnl" && source::is_dir(path))
+            .map(|(_, path)| Self::list(path))
+            .transpose()
+            .with_context(|| format!("Impossible de lire le dossier des journaux de {:?}", data_dir))?
+            .unwrap_or_default();
+        Ok(Self { files, journals })
+    }
+
+    fn list(dir: &Path) -> std::io::Result<HashMap<String, PathBuf>> {
+        let mut files = HashMap::new();
+        for path in source::read_dir(dir)? {
+            if let Some(name) = path.file_name() {
+                files.insert(name.to_string_lossy().to_lowercase(), path);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Cherche un fichier du dossier de données (nom en minuscules)
+    fn find(&self, name: &str) -> Option<PathBuf> {
+        self.files.get(name).filter(|path| source::is_file(path)).cloned()
+    }
+
+    /// Cherche un fichier du sous-dossier JNL (nom en minuscules)
+    fn find_journal(&self, name: &str) -> Option<PathBuf> {
+        self.journals.get(name).filter(|path| source::is_file(path)).cloned()
+    }
+
+    /**
+     * Retourne les fichiers numérotés `{stem}.{prefix}N`, triés par numéro.
+     *
+     * Les numéros absents entre `first` et le plus grand numéro trouvé sont
+     * ajoutés à `missing`.
+     *
+     * @param stem - Nom de la table en minuscules
+     * @param prefix - Début de l'extension ("ndx", "fi", "mm"…)
+     * @param first - Premier numéro attendu
+     * @param missing - Liste des fichiers manquants à compléter
+     * @param expected - Formate le nom d'un fichier attendu à partir de son extension
+     * @returns Vec<PathBuf> - Fichiers trouvés
+     */
+    fn numbered(
+        &self,
+        stem: &str,
+        prefix: &str,
+        first: u32,
+        missing: &mut Vec<String>,
+        expected: &dyn Fn(String) -> String,
+    ) -> Vec<PathBuf> {
+        let start = format!("{}.{}", stem, prefix);
+        let mut found: Vec<(u32, PathBuf)> = self.files.iter()
+            .filter(|(_, path)| source::is_file(path))
+            .filter_map(|(name, path)| {
+                let digits = name.strip_prefix(&start)?;
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                Some((digits.parse().ok()?, path.clone()))
+            })
+            .collect();
+        found.sort();
+
+        if let Some((last, _)) = found.last() {
+            missing.extend(
+                (first..*last)
+                    .filter(|n| !found.iter().any(|(number, _)| number == n))
+                    .map(|n| expected(format!("{}{}", prefix, n)))
+            );
+        }
+        found.into_iter().map(|(_, path)| path).collect()
+    }
 }
 
 /// Moteur de stockage principal gérant l'accès aux données HFSQL
 pub struct StorageEngine {
     /// Chemin du dossier contenant les fichiers .fic/.mmo/.ndx
     data_dir: PathBuf,
-    /// Cache des tables détectées (thread-safe)
+    /// Cache des tables détectées, par nom qualifié "base.TABLE" (thread-safe)
     tables: Arc<RwLock<HashMap<String, TableFiles>>>,
+    /// Bases de données enregistrées (nom -> dossier), "" pour la base racine
+    databases: Arc<RwLock<BTreeMap<String, DatabaseEntry>>>,
+    /// Base utilisée pour résoudre les noms de tables non qualifiés
+    current_database: Arc<RwLock<Option<String>>>,
+    /// Schémas et fichiers ouverts par table, invalidés si un fichier change
+    schema_cache: Arc<TableCache>,
+    /// Dossiers scannés, par préfixe de base ("" = dossier de données)
+    sources: Arc<RwLock<BTreeMap<String, PathBuf>>>,
+    /// Archives des dossiers scannés, conservées ouvertes (index et extractions)
+    archives: Arc<Mutex<HashMap<PathBuf, Arc<Archive>>>>,
+    /// État des fichiers des tables au dernier scan, pour détecter les changements
+    table_states: Arc<Mutex<HashMap<String, TableState>>>,
+    /// Canal de publication des changements de tables
+    changes: broadcast::Sender<TableChange>,
     /// Mode lecture seule (désactive les modifications)
     read_only: bool,
     /// Active le multi-threading pour la lecture parallèle
     parallel: bool,
+    /// Encodages des textes (global, par base, table et rubrique)
+    encodings: EncodingConfig,
 }
 
 impl StorageEngine {
@@ -130,7 +612,8 @@ impl StorageEngine {
      * Crée un nouveau moteur de stockage.
      * 
      * Initialise le moteur avec le dossier de données spécifié.
-     * Crée le dossier s'il n'existe pas.
+     * Crée le dossier s'il n'existe pas. Le dossier peut aussi être une
+     * archive (.zip, .tar, .tar.gz, .tgz), lue sans extraction.
      * 
      * @param data_dir - Chemin vers le dossier contenant les fichiers HFSQL
      * @param read_only - Active le mode lecture seule
@@ -154,7 +637,7 @@ impl StorageEngine {
     pub fn new_with_parallel(data_dir: impl AsRef<Path>, read_only: bool, parallel: bool) -> Result<Self> {
         let data_dir = data_dir.as_ref().to_path_buf();
         
-        if !data_dir.exists() {
+        if !source::exists(&data_dir) {
             std::fs::create_dir_all(&data_dir)
                 .with_context(|| format!("Impossible de créer le dossier: {:?}", data_dir))?;
         }
@@ -162,11 +645,33 @@ impl StorageEngine {
         Ok(Self {
             data_dir,
             tables: Arc::new(RwLock::new(HashMap::new())),
+            databases: Arc::new(RwLock::new(BTreeMap::new())),
+            current_database: Arc::new(RwLock::new(None)),
+            schema_cache: Arc::new(TableCache::default()),
+            sources: Arc::new(RwLock::new(BTreeMap::new())),
+            archives: Arc::new(Mutex::new(HashMap::new())),
+            table_states: Arc::new(Mutex::new(HashMap::new())),
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
             read_only,
             parallel,
+            encodings: EncodingConfig::default(),
         })
     }
 
+    /**
+     * Définit les encodages des textes des tables.
+     *
+     * À appeler avant le premier accès aux tables : l'encodage de chaque
+     * rubrique est résolu au chargement du schéma.
+     *
+     * @param encodings - Encodages (défaut, par base, par table, par rubrique)
+     * @returns StorageEngine - Moteur configuré
+     */
+    pub fn with_encodings(mut self, encodings: EncodingConfig) -> Self {
+        self.encodings = encodings;
+        self
+    }
+
     /**
      * Retourne le chemin du dossier de données.
      * 
@@ -179,218 +684,1759 @@ impl StorageEngine {
     /**
      * Scanne le dossier de données et détecte toutes les tables HFSQL.
      * 
-     * Parcourt le dossier, détecte les fichiers .fic, les associe avec
-     * leurs fichiers .mmo et .ndx correspondants, puis met à jour le cache
-     * des tables.
+     * Parcourt le dossier et ses sous-dossiers, détecte les fichiers .fic, les
+     * associe avec leurs fichiers .mmo, .ndx, .ftx et .jnl correspondants, puis
+     * met à jour le cache des tables. Chaque dossier contenant des fichiers .fic
+     * forme une base : les tables du dossier racine gardent leur nom, celles de
+     * `compta/` sont nommées `compta.CLIENT`, celles de `compta/archives/`
+     * `compta.archives.CLIENT`.
      * 
-     * @returns Result<Vec<String>> - Liste des noms de tables détectées
+     * @returns Result<Vec<String>> - Liste des noms qualifiés des tables détectées
      * 
      * Effets de bord :
-     * - Lit le contenu du dossier de données
-     * - Met à jour le cache interne des tables
+     * - Lit le contenu du dossier de données et de ses sous-dossiers
+     * - Met à jour le cache interne des tables et des bases
+     * - Publie les tables ajoutées, supprimées ou modifiées depuis le scan précédent
      */
     pub fn scan_tables(&self) -> Result<Vec<String>> {
+        let data_dir = self.data_dir.clone();
+        let tables = self.scan_databases(&data_dir, "")?;
+        self.sources.write().unwrap().insert(String::new(), data_dir.clone());
+        self.publish_changes();
+        self.log_scan(&data_dir, &tables);
+        Ok(tables)
+    }
+
+    /**
+     * Ajoute une base de données située dans un autre dossier.
+     *
+     * Le dossier est scanné récursivement comme le dossier de données ; ses
+     * tables sont préfixées par `name` (et ses sous-dossiers par `name.sous_dossier`).
+     * Un sous-dossier du dossier de données (ou d'une autre base ajoutée) passe
+     * sous ce nom : il n'est plus enregistré par le scan du dossier parent.
+     *
+     * @param name - Nom de la base (lettres, chiffres, '_' ; '.' pour une sous-base)
+     * @param path - Dossier contenant les fichiers .fic
+     * @returns Result<Vec<String>> - Noms qualifiés des tables ajoutées
+     *
+     * Effets de bord :
+     * - Lit le contenu du dossier et de ses sous-dossiers
+     * - Remplace les tables déjà enregistrées sous ce nom de base ou détectées
+     *   dans ce dossier par le scan d'un dossier parent
+     * - Publie les tables ajoutées, supprimées ou modifiées depuis le scan précédent
+     */
+    pub fn add_database(&self, name: &str, path: impl AsRef<Path>) -> Result<Vec<String>> {
+        let path = path.as_ref();
+        if name.is_empty() || !name.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')) {
+            anyhow::bail!("Nom de base invalide: '{}' (lettres, chiffres et '_' attendus)", name);
+        }
+        if !source::is_dir(path) {
+            anyhow::bail!("Le chemin n'est pas un dossier ni une archive: {:?}", path);
+        }
+        let canonical = canonical_dir(path);
+        if let Some((other, _)) = self.sources.read().unwrap().iter()
+            .find(|(other, root)| other.as_str() != name && canonical_dir(root) == canonical)
+        {
+            let other = if other.is_empty() { "(racine)" } else { other.as_str() };
+            anyhow::bail!("Le dossier {:?} est déjà enregistré comme base '{}'", path, other);
+        }
+        let tables = self.scan_databases(path, name)?;
+        self.publish_changes();
+        self.log_scan(path, &tables);
+        if tables.is_empty() {
+            anyhow::bail!("Aucun fichier .fic trouvé dans {:?}", path);
+        }
+        self.sources.write().unwrap().insert(name.to_string(), path.to_path_buf());
+        Ok(tables)
+    }
+
+    /**
+     * Rescanne tous les dossiers enregistrés et retourne les changements.
+     *
+     * Le dossier de données et les bases ajoutées par add_database sont
+     * rescannés ; les tables ajoutées, supprimées ou dont un fichier a changé
+     * (taille ou date de modification) depuis le scan précédent sont publiées
+     * aux abonnés et leur schéma en cache est invalidé.
+     *
+     * @returns Result<Vec<TableChange>> - Changements détectés, triés par table
+     *
+     * Effets de bord :
+     * - Lit le contenu des dossiers enregistrés et l'état des fichiers des tables
+     * - Met à jour le cache interne des tables et des bases
+     * - Log un avertissement pour chaque dossier illisible (les autres sont rescannés)
+     */
+    pub fn refresh_tables(&self) -> Result<Vec<TableChange>> {
+        let sources: Vec<(String, PathBuf)> = self.sources.read().unwrap()
+            .iter()
+            .map(|(prefix, root)| (prefix.clone(), root.clone()))
+            .collect();
+        for (prefix, root) in sources {
+            if let Err(e) = self.scan_databases(&root, &prefix) {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    format!("Rescan de {:?} impossible: {:#}", root, e),
+                    Some("Storage".to_string()),
+                );
+            }
+        }
+        Ok(self.publish_changes())
+    }
+
+    /**
+     * S'abonne aux changements de tables détectés lors des scans.
+     *
+     * Un abonné qui ne lit pas assez vite perd les événements les plus
+     * anciens (RecvError::Lagged) au-delà de CHANGE_CHANNEL_CAPACITY.
+     *
+     * @returns broadcast::Receiver<TableChange> - Changements publiés après l'abonnement
+     */
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<TableChange> {
+        self.changes.subscribe()
+    }
+
+    /**
+     * Compare l'état des fichiers des tables avec le scan précédent et publie
+     * les différences.
+     *
+     * @returns Vec<TableChange> - Changements détectés, triés par table
+     *
+     * Effets de bord :
+     * - Invalide le schéma en cache des tables modifiées ou supprimées
+     * - Envoie chaque changement aux abonnés
+     */
+    fn publish_changes(&self) -> Vec<TableChange> {
+        let tables = self.tables.read().unwrap();
+        let states: HashMap<String, TableState> = tables.iter()
+            .map(|(name, files)| (name.clone(), TableState::read(files)))
+            .collect();
+        let mut previous = self.table_states.lock().unwrap();
+        let changes = watcher::diff_states(&previous, &states);
+        *previous = states;
+
+        for change in &changes {
+            if let Some(files) = tables.get(&change.table) {
+                self.schema_cache.invalidate(&files.fic_path);
+            }
+            // Aucun abonné : l'événement est simplement ignoré
+            let _ = self.changes.send(change.clone());
+        }
+        changes
+    }
+
+    /// Log le résultat du scan d'un dossier
+    fn log_scan(&self, root: &Path, tables: &[String]) {
+        get_logger().log_with_source(
+            LogLevel::Info,
+            format!("Scan de {:?}: {} table(s), {} base(s) enregistrée(s)", root, tables.len(), self.databases.read().unwrap().len()),
+            Some("Storage".to_string()),
+        );
+    }
+
+    /**
+     * Choisit la base utilisée pour résoudre les noms de tables non qualifiés.
+     *
+     * @param name - Nom de la base, ou None pour revenir à la base racine
+     * @returns Result<()> - Erreur si la base n'est pas enregistrée
+     *
+     * Effets de bord :
+     * - Modifie la résolution des noms de tables de toutes les requêtes suivantes
+     */
+    pub fn use_database(&self, name: Option<&str>) -> Result<()> {
+        if let Some(name) = name {
+            if !self.databases.read().unwrap().contains_key(name) {
+                anyhow::bail!("Base '{}' non trouvée", name);
+            }
+        }
+        *self.current_database.write().unwrap() = name.map(str::to_string);
+        get_logger().log_with_source(
+            LogLevel::Info,
+            format!("Base courante: {}", name.unwrap_or("(racine)")),
+            Some("Storage".to_string()),
+        );
+        Ok(())
+    }
+
+    /**
+     * Retourne la base utilisée pour les noms de tables non qualifiés.
+     *
+     * @returns Option<String> - Nom de la base, None pour la base racine
+     */
+    pub fn current_database(&self) -> Option<String> {
+        self.current_database.read().unwrap().clone()
+    }
+
+    /**
+     * Liste les bases de données enregistrées et leurs tables.
+     *
+     * @returns Vec<DatabaseInfo> - Bases triées par nom
+     */
+    pub fn list_databases(&self) -> Vec<DatabaseInfo> {
+        let current = self.current_database().unwrap_or_default();
+        let tables = self.tables.read().unwrap();
+        self.databases.read().unwrap()
+            .iter()
+            .map(|(name, entry)| {
+                let mut names: Vec<String> = tables.iter()
+                    .filter(|(_, files)| &files.database == name)
+                    .map(|(qualified, _)| qualified.clone())
+                    .collect();
+                names.sort();
+                DatabaseInfo {
+                    name: name.clone(),
+                    path: entry.path.clone(),
+                    tables: names,
+                    current: name == &current,
+                    missing_tables: entry.missing_tables.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /**
+     * Scanne récursivement un dossier et enregistre chaque sous-dossier
+     * contenant des fichiers .fic comme une base.
+     *
+     * Les tables et bases déjà détectées par un scan de `prefix` sont remplacées,
+     * ainsi que celles d'un dossier parent enregistré situées sous `root`. Les
+     * dossiers enregistrés sous `root` par une autre base ne sont pas explorés :
+     * un dossier n'est jamais enregistré deux fois (chemins canoniques comparés).
+     *
+     * @param root - Dossier à scanner
+     * @param prefix - Nom de la base du dossier racine ("" = tables non préfixées)
+     * @returns Result<Vec<String>> - Noms qualifiés des tables détectées
+     */
+    fn scan_databases(&self, root: &Path, prefix: &str) -> Result<Vec<String>> {
+        // Archive conservée ouverte : les lectures suivantes réutilisent son index
+        let mut archives = self.archives.lock().unwrap();
+        match source::archive_containing(root).with_context(|| format!("Impossible de lire l'archive: {:?}", root))? {
+            Some(archive) => archives.insert(root.to_path_buf(), archive),
+            None => archives.remove(root),
+        };
+        drop(archives);
+
+        // Un dossier appartient au dossier enregistré le plus profond qui le contient :
+        // les autres dossiers enregistrés sous la racine ne sont pas explorés, et
+        // les bases d'un dossier parent situées sous la racine sont reprises
+        let canonical_root = canonical_dir(root);
+        let (nested, parents): (Vec<_>, Vec<_>) = self.sources.read().unwrap()
+            .iter()
+            .filter(|(other, _)| other.as_str() != prefix)
+            .map(|(other, path)| (other.clone(), canonical_dir(path)))
+            .filter(|(_, path)| path.starts_with(&canonical_root) || canonical_root.starts_with(path))
+            .partition(|(_, path)| path != &canonical_root && path.starts_with(&canonical_root));
+        let nested: Vec<PathBuf> = nested.into_iter().map(|(_, path)| path).collect();
+        let parents: Vec<String> = parents.into_iter().map(|(other, _)| other).collect();
+
+        let mut folders = Vec::new();
+        Self::collect_folders(root, Vec::new(), &nested, &mut folders)
+            .with_context(|| format!("Impossible de lire le dossier: {:?}", root))?;
+
+        let mut found: Vec<ScannedDatabase> = Vec::new();
+        for (components, dir) in folders {
+            let database = std::iter::once(prefix)
+                .filter(|p| !p.is_empty())
+                .chain(components.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(".");
+            let directory = DirectoryFiles::read(&dir)?;
+
+            // Détection des fichiers .fic (case-insensitive)
+            let fic_files: HashMap<String, PathBuf> = directory.files
+                .values()
+                .filter(|path| source::is_file(path) && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("fic")))
+                .filter_map(|path| Some((path.file_stem()?.to_string_lossy().to_string(), path.clone())))
+                .collect();
+            let repositories: Vec<&PathBuf> = directory.files
+                .values()
+                .filter(|path| source::is_file(path) && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rep")))
+                .collect();
+            if fic_files.is_empty() && repositories.is_empty() {
+                continue;
+            }
+
+            // Association avec les fichiers compagnons (en parallèle si activé)
+            let mut table_files_vec: Vec<(String, TableFiles)> = if self.parallel && fic_files.len() > 5 {
+                // Version parallèle pour plusieurs fichiers
+                fic_files
+                    .into_par_iter()
+                    .map(|(name, fic_path)| {
+                        self.process_table_files(&database, name, fic_path, &directory)
+                    })
+                    .collect()
+            } else {
+                // Version séquentielle pour peu de fichiers
+                fic_files
+                    .into_iter()
+                    .map(|(name, fic_path)| {
+                        self.process_table_files(&database, name, fic_path, &directory)
+                    })
+                    .collect()
+            };
+
+            // Tables déclarées dans les fichiers .rep (prioritaires sur les fichiers du dossier)
+            let mut missing_tables = Vec::new();
+            for repository in repositories {
+                let declared = self.repository_tables(&database, repository, &mut missing_tables);
+                table_files_vec.retain(|(name, _)| !declared.iter().any(|(declared, _)| declared.eq_ignore_ascii_case(name)));
+                table_files_vec.extend(declared);
+            }
+
+            found.push(ScannedDatabase {
+                name: database,
+                entry: DatabaseEntry { path: dir, source: prefix.to_string(), missing_tables },
+                tables: table_files_vec,
+            });
+        }
+
+        // Remplacer les bases de ce préfixe (et celles reprises aux dossiers parents)
         let mut tables = Vec::new();
-        let entries = std::fs::read_dir(&self.data_dir)
-            .with_context(|| format!("Impossible de lire le dossier: {:?}", self.data_dir))?;
-
-        let mut fic_files: HashMap<String, PathBuf> = HashMap::new();
-
-        // Détection des fichiers .fic (case-insensitive)
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            if let Some(ext) = path.extension() {
-                let ext_lower = ext.to_string_lossy().to_lowercase();
-                if ext_lower == "fic" {
-                    if let Some(name) = path.file_stem() {
-                        let name = name.to_string_lossy().to_string();
-                        fic_files.insert(name.clone(), path.clone());
-                    }
+        let mut tables_cache = self.tables.write().unwrap();
+        let mut databases = self.databases.write().unwrap();
+        let replaced: Vec<String> = databases.iter()
+            .filter(|(_, entry)| entry.source == prefix
+                || (parents.contains(&entry.source) && canonical_dir(&entry.path).starts_with(&canonical_root)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        tables_cache.retain(|_, files| !replaced.contains(&files.database));
+        databases.retain(|name, _| !replaced.contains(name));
+        for scanned in found {
+            for (name, table_files) in scanned.tables {
+                let qualified = if scanned.name.is_empty() { name } else { format!("{}.{}", scanned.name, name) };
+                tables_cache.insert(qualified.clone(), table_files);
+                tables.push(qualified);
+            }
+            databases.insert(scanned.name, scanned.entry);
+        }
+        self.schema_cache.retain(|fic_path| tables_cache.values().any(|files| files.fic_path == fic_path));
+
+        Ok(tables)
+    }
+
+    /**
+     * Lit un fichier .rep et prépare les tables qu'il déclare.
+     *
+     * Chaque entrée active est résolue (chemin relatif au .rep, casse ignorée) ;
+     * le chemin désigne le fichier .fic ou le dossier qui contient
+     * `{nom logique}.fic`. Les fichiers compagnons sont cherchés à côté du .fic.
+     *
+     * @param database - Nom de la base du dossier contenant le .rep
+     * @param repository - Chemin du fichier .rep
+     * @param missing_tables - Entrées sans fichier .fic, à compléter
+     * @returns Vec<(String, TableFiles)> - Tables trouvées, par nom logique
+     *
+     * Effets de bord :
+     * - Lit le fichier .rep et les dossiers qu'il référence
+     * - Log un avertissement par entrée introuvable
+     */
+    fn repository_tables(&self, database: &str, repository: &Path, missing_tables: &mut Vec<MissingTable>) -> Vec<(String, TableFiles)> {
+        let rep = match RepFile::open(repository) {
+            Ok(rep) => rep,
+            Err(e) => {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    format!("Fichier .rep ignoré {:?}: {:#}", repository, e),
+                    Some("Storage".to_string()),
+                );
+                return Vec::new();
+            }
+        };
+
+        let mut tables = Vec::new();
+        for entry in rep.entries().iter().filter(|entry| !entry.disabled && !entry.name.is_empty()) {
+            let declared = entry.resolve(rep.base_dir());
+            let candidate = if declared.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("fic")) {
+                declared.clone()
+            } else {
+                declared.join(format!("{}.fic", entry.name))
+            };
+
+            let found = find_case_insensitive(&candidate)
+                .filter(|path| source::is_file(path))
+                .and_then(|fic_path| {
+                    let directory = DirectoryFiles::read(fic_path.parent()?).ok()?;
+                    Some(self.process_table_files(database, entry.name.clone(), fic_path, &directory))
+                });
+            match found {
+                Some(table) => tables.push(table),
+                None => {
+                    get_logger().log_with_source(
+                        LogLevel::Warn,
+                        format!("Table {} déclarée dans {:?} introuvable: {:?}", entry.name, repository, candidate),
+                        Some("Storage".to_string()),
+                    );
+                    missing_tables.push(MissingTable {
+                        name: entry.name.clone(),
+                        repository: repository.to_path_buf(),
+                        declared_path: entry.path.clone(),
+                        resolved_path: candidate,
+                    });
                 }
             }
         }
+        tables
+    }
 
-        // Association avec .mmo et .ndx (en parallèle si activé)
-        let data_dir = self.data_dir.clone();
-        let table_files_vec: Vec<(String, TableFiles)> = if self.parallel && fic_files.len() > 5 {
-            // Version parallèle pour plusieurs fichiers
-            fic_files
-                .into_par_iter()
-                .map(|(name, fic_path)| {
-                    self.process_table_files(name, fic_path, &data_dir)
-                })
-                .collect()
+    /**
+     * Liste récursivement un dossier et ses sous-dossiers.
+     *
+     * Les liens symboliques, les dossiers cachés, les dossiers de journaux
+     * (JNL) et les archives d'un dossier ne sont pas explorés.
+     *
+     * @param dir - Dossier à explorer
+     * @param components - Chemin relatif du dossier depuis la racine
+     * @param skipped - Chemins canoniques des dossiers à ne pas explorer
+     * @param folders - Dossiers trouvés (chemin relatif, chemin complet)
+     */
+    fn collect_folders(dir: &Path, components: Vec<String>, skipped: &[PathBuf], folders: &mut Vec<(Vec<String>, PathBuf)>) -> std::io::Result<()> {
+        let mut children = Vec::new();
+        if components.len() < MAX_SCAN_DEPTH {
+            let in_archive = source::archive_containing(dir)?.is_some();
+            for path in source::read_dir(dir)? {
+                let Some(name) = path.file_name().map(|name| name.to_string_lossy().to_string()) else { continue };
+                let is_dir = if in_archive { source::is_dir(&path) } else { std::fs::symlink_metadata(&path)?.is_dir() };
+                if is_dir && !name.starts_with('.') && !name.eq_ignore_ascii_case("jnl") && !skipped.contains(&canonical_dir(&path)) {
+                    children.push((name, path));
+                }
+            }
+        }
+        children.sort();
+        folders.push((components.clone(), dir.to_path_buf()));
+        for (name, path) in children {
+            let mut child = components.clone();
+            child.push(name);
+            if let Err(e) = Self::collect_folders(&path, child, skipped, folders) {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    format!("Dossier ignoré {:?}: {}", path, e),
+                    Some("Storage".to_string()),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Traite les fichiers associés à une table (.mmo, .ndx, .ftx, .jnl).
+     * 
+     * Fonction helper pour éviter la duplication de code entre
+     * les versions parallèle et séquentielle de scan_tables.
+     * Les compagnons portent le nom du fichier .fic (qui peut différer du nom
+     * logique déclaré dans un .rep). Les noms sont comparés sans tenir compte
+     * de la casse ; les extensions attendues mais absentes sont écrites dans
+     * la casse de l'extension du fichier .fic.
+     *
+     * Une table n'a pas forcément de mémo ni d'index : le .mmo et le .ndx ne
+     * sont signalés manquants que si leurs segments (.mm1, .nd1, ...) sont
+     * présents. Un numéro manquant dans une série (.ndx0, .ndx2) est signalé.
+     */
+    fn process_table_files(&self, database: &str, name: String, fic_path: PathBuf, directory: &DirectoryFiles) -> (String, TableFiles) {
+        let file_stem = fic_path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| name.clone());
+        let stem = file_stem.to_lowercase();
+        let upper = fic_path.extension().is_some_and(|ext| !ext.to_string_lossy().chars().any(|c| c.is_ascii_lowercase()));
+        let expected = |ext: String| format!("{}.{}", file_stem, if upper { ext.to_uppercase() } else { ext });
+        let mut missing = Vec::new();
+
+        // Segments des fichiers de plus de 2 Go : .fi1, .mm1, .nd1, ...
+        let [fic_segments, mmo_segments, ndx_segments] = ["fi", "mm", "nd"]
+            .map(|prefix| directory.numbered(&stem, prefix, 1, &mut missing, &expected));
+
+        let mmo_path = directory.find(&format!("{}.mmo", stem));
+        if mmo_path.is_none() && !mmo_segments.is_empty() {
+            missing.push(expected("mmo".to_string()));
+        }
+
+        // Index : {name}.ndx (HFSQL) puis {name}.ndx0, {name}.ndx1, ...
+        let mut ndx_paths: Vec<PathBuf> = directory.find(&format!("{}.ndx", stem)).into_iter().collect();
+        ndx_paths.extend(directory.numbered(&stem, "ndx", 0, &mut missing, &expected));
+        if ndx_paths.is_empty() && !ndx_segments.is_empty() {
+            missing.push(expected("ndx".to_string()));
+        }
+
+        // Index full-text : {name}.ftx puis {name}.ftx0, {name}.ftx1, ...
+        let mut ftx_paths: Vec<PathBuf> = directory.find(&format!("{}.ftx", stem)).into_iter().collect();
+        ftx_paths.extend(directory.numbered(&stem, "ftx", 0, &mut missing, &expected));
+
+        // Journaux : {name}.jnl dans le dossier de données ou dans le sous-dossier JNL
+        let journal_paths = directory.find(&format!("{}.jnl", stem))
+            .into_iter()
+            .chain(directory.find_journal(&format!("{}.jnl", stem)))
+            .collect();
+
+        let segment_paths: Vec<PathBuf> = fic_segments.into_iter().chain(mmo_segments).chain(ndx_segments).collect();
+        if !segment_paths.is_empty() {
+            get_logger().log_with_source(
+                LogLevel::Warn,
+                format!("Table {}: segments {:?} ignorés, seul le premier segment de chaque fichier est lu", name, segment_paths),
+                Some("Storage".to_string()),
+            );
+        }
+
+        let table_files = TableFiles {
+            name: name.clone(),
+            database: database.to_string(),
+            fic_path,
+            mmo_path,
+            ndx_paths,
+            ftx_paths,
+            journal_paths,
+            segment_paths,
+            missing,
+        };
+
+        (name, table_files)
+    }
+
+    /**
+     * Retourne les fichiers détectés pour une table.
+     *
+     * Un nom non qualifié est d'abord cherché tel quel, puis dans la base
+     * courante (voir use_database).
+     *
+     * @param table - Nom de la table
+     * @returns Result<TableFiles> - Fichiers trouvés et manquants, ou erreur si la table est inconnue
+     *
+     * Effets de bord : Aucun
+     */
+    pub fn table_files(&self, table: &str) -> Result<TableFiles> {
+        let tables = self.tables.read().unwrap();
+        tables.get(table)
+            .or_else(|| {
+                let database = self.current_database.read().unwrap();
+                tables.get(&format!("{}.{}", database.as_deref()?, table))
+            })
+            .cloned()
+            .with_context(|| format!("Table '{}' non trouvée", table))
+    }
+
+    /**
+     * Indique si une table est connue (nom qualifié ou relatif à la base courante).
+     *
+     * @param table - Nom de la table
+     * @returns bool - true si la table a été détectée
+     */
+    pub fn has_table(&self, table: &str) -> bool {
+        self.table_files(table).is_ok()
+    }
+
+    /**
+     * Retourne la liste des noms de toutes les tables détectées.
+     * 
+     * @returns Vec<String> - Liste des noms de tables
+     * 
+     * Effets de bord : Aucun
+     */
+    pub fn list_tables(&self) -> Vec<String> {
+        self.tables.read().unwrap().keys().cloned().collect()
+    }
+
+    /**
+     * Obtient le schéma complet d'une table.
+     * 
+     * Ouvre le fichier .fic, analyse sa structure et retourne le schéma
+     * avec la liste des champs, leurs types, offsets et longueurs.
+     * 
+     * @param table - Nom de la table
+     * @returns Result<TableSchema> - Schéma de la table ou erreur
+     * 
+     * Effets de bord :
+     * - Lit le fichier .fic pour analyser le schéma
+     */
+    pub fn get_schema(&self, table: &str) -> Result<TableSchema> {
+        let cached = self.cached_table(table)?;
+        Ok(Self::table_schema(&cached, table))
+    }
+
+    /**
+     * Retourne l'entrée du cache d'une table, (re)chargée si l'un de ses
+     * fichiers (.fic, .mmo, .ndx, .ftx) a changé de taille ou de date.
+     *
+     * @param table - Nom de la table
+     * @returns Result<Arc<CachedTable>> - Schéma, header et fichiers ouverts de la table
+     *
+     * Effets de bord :
+     * - Lit l'état des fichiers de la table ; en cas de rechargement, ouvre
+     *   le .fic et lit les headers des index
+     */
+    fn cached_table(&self, table: &str) -> Result<Arc<CachedTable>> {
+        let table_files = self.table_files(table)?;
+        self.schema_cache.get_or_load(&table_files, |fic| {
+            let mut fields = fic.analyze_schema();
+            let bare_name = table_files.name
+                .strip_prefix(&format!("{}.", table_files.database))
+                .unwrap_or(&table_files.name);
+            for field in &mut fields {
+                field.encoding = self.encodings.resolve(&table_files.database, bare_name, &field.name);
+            }
+            let indexes = Self::read_index_definitions(&table_files, &fields);
+            self.save_key_definitions(&table_files, &indexes);
+            let full_text = Self::read_full_text_definitions(&table_files, &fields);
+
+            Ok(TableSchema {
+                name: table.to_string(),
+                record_length: fic.header().record_length,
+                field_count: fields.len() as u32,
+                fields,
+                indexes,
+                full_text,
+            })
+        })
+    }
+
+    /// Schéma en cache, nommé comme demandé par l'appelant (nom qualifié ou non)
+    fn table_schema(cached: &CachedTable, table: &str) -> TableSchema {
+        let mut schema = cached.schema.clone();
+        schema.name = table.to_string();
+        schema
+    }
+
+    /**
+     * Retourne les statistiques du cache des schémas et fichiers ouverts.
+     *
+     * @returns CacheStats - Accès servis par le cache, rechargements, fichiers réutilisés
+     */
+    pub fn cache_stats(&self) -> CacheStats {
+        self.schema_cache.stats()
+    }
+
+    /**
+     * Lit les rubriques couvertes par les fichiers .ftx d'une table.
+     *
+     * @param table_files - Fichiers de la table
+     * @param fields - Rubriques du schéma
+     * @returns Vec<FullTextDefinition> - Définitions des index full-text lisibles
+     *
+     * Effets de bord :
+     * - Lit le header et le dictionnaire de chaque fichier .ftx
+     */
+    fn read_full_text_definitions(table_files: &TableFiles, fields: &[crate::core::FieldInfo]) -> Vec<FullTextDefinition> {
+        let mut definitions = Vec::new();
+        for ftx_path in &table_files.ftx_paths {
+            let ftx = match FtxFile::open(ftx_path) {
+                Ok(ftx) => ftx,
+                Err(e) => {
+                    get_logger().log_with_source(LogLevel::Warn, format!("Index full-text ignoré {:?}: {}", ftx_path, e), Some("Storage".to_string()));
+                    continue;
+                }
+            };
+            let ftx_fields: Vec<Option<&crate::core::FieldInfo>> = ftx.header().fields.iter()
+                .map(|ftx_field| fields.iter().find(|f| f.offset == ftx_field.offset && f.length == ftx_field.length))
+                .collect();
+            definitions.push(FullTextDefinition {
+                name: ftx_path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: ftx_path.clone(),
+                fields: ftx_fields.iter().map(|f| f.map(|f| f.name.clone())).collect(),
+                encoding: ftx_fields.iter().flatten().map(|f| f.encoding).next().unwrap_or_default(),
+            });
+        }
+        definitions
+    }
+
+    /**
+     * Recherche full-text dans une table.
+     *
+     * Interroge les index .ftx de la table (ou seulement ceux couvrant la
+     * rubrique demandée). Un enregistrement trouvé par plusieurs index garde
+     * son meilleur score.
+     *
+     * @param table - Nom de la table
+     * @param query - Mots recherchés ("client*" = préfixe)
+     * @param field - Rubrique à interroger (None = tous les index full-text)
+     * @returns Result<Vec<FullTextHit>> - Enregistrements classés par pertinence
+     *
+     * Effets de bord :
+     * - Lit les fichiers .ftx de la table
+     */
+    pub fn search(&self, table: &str, query: &str, field: Option<&str>) -> Result<Vec<FullTextHit>> {
+        let schema = self.get_schema(table)?;
+        Self::search_schema(&schema, query, field)
+    }
+
+    /**
+     * Recherche full-text à partir d'un schéma déjà chargé (voir search).
+     */
+    fn search_schema(schema: &TableSchema, query: &str, field: Option<&str>) -> Result<Vec<FullTextHit>> {
+        let table = &schema.name;
+        let definitions: Vec<&FullTextDefinition> = schema.full_text.iter()
+            .filter(|d| field.map(|f| d.fields.iter().any(|name| name.as_deref() == Some(f))).unwrap_or(true))
+            .collect();
+        if definitions.is_empty() {
+            match field {
+                Some(field) => anyhow::bail!("Aucun index full-text sur la rubrique {} de la table {}", field, table),
+                None => anyhow::bail!("Aucun index full-text pour la table {}", table),
+            }
+        }
+
+        let mut best: HashMap<u32, f64> = HashMap::new();
+        for definition in definitions {
+            let mut ftx = FtxFile::open_as(&definition.path, definition.encoding)?;
+            for hit in ftx.search(query)
+                .with_context(|| format!("Erreur de recherche dans l'index {}", definition.name))?
+            {
+                let score = best.entry(hit.record_id).or_insert(hit.score);
+                *score = score.max(hit.score);
+            }
+        }
+
+        let mut hits: Vec<FullTextHit> = best.into_iter()
+            .map(|(record_id, score)| FullTextHit { record_id, score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.record_id.cmp(&b.record_id)));
+        Ok(hits)
+    }
+
+    /**
+     * Lit les définitions de clés depuis les headers des fichiers .ndx d'une table.
+     *
+     * Chaque segment de clé est associé à la rubrique du schéma située au même
+     * offset. Les index illisibles sont ignorés (avec un avertissement).
+     *
+     * @param table_files - Fichiers de la table
+     * @param fields - Rubriques du schéma
+     * @returns Vec<IndexDefinition> - Définitions des index lisibles
+     *
+     * Effets de bord :
+     * - Lit le header de chaque fichier .ndx
+     */
+    fn read_index_definitions(table_files: &TableFiles, fields: &[crate::core::FieldInfo]) -> Vec<IndexDefinition> {
+        let mut indexes = Vec::new();
+        for ndx_path in &table_files.ndx_paths {
+            let ndx = match NdxFile::open(ndx_path) {
+                Ok(ndx) => ndx,
+                Err(e) => {
+                    get_logger().log_with_source(LogLevel::Warn, format!("Index ignoré {:?}: {}", ndx_path, e), Some("Storage".to_string()));
+                    continue;
+                }
+            };
+            let segments = ndx.segments().to_vec();
+            let (index_fields, encodings) = Self::segment_fields(&segments, fields);
+            let name = ndx_path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            indexes.push(IndexDefinition {
+                name,
+                path: ndx_path.clone(),
+                fields: index_fields,
+                segments,
+                encodings,
+                unique: ndx.header().unique,
+            });
+        }
+        indexes
+    }
+
+    /**
+     * Associe chaque segment de clé à la rubrique du schéma située au même
+     * offset et de même longueur.
+     *
+     * @param segments - Segments de la clé
+     * @param fields - Rubriques du schéma
+     * @returns (Vec<Option<String>>, Vec<TextEncoding>) - Rubrique et encodage
+     *          de chaque segment (auto si aucune rubrique ne correspond)
+     */
+    fn segment_fields(segments: &[KeySegment], fields: &[crate::core::FieldInfo]) -> (Vec<Option<String>>, Vec<TextEncoding>) {
+        segments.iter()
+            .map(|segment| {
+                let field = fields.iter()
+                    .find(|f| f.name != "id" && f.offset == segment.offset && f.length == segment.length);
+                (field.map(|f| f.name.clone()), field.map(|f| f.encoding).unwrap_or_default())
+            })
+            .unzip()
+    }
+
+    /**
+     * Sauvegarde les définitions de clés lues dans les headers des index
+     * (voir keydefs.rs), pour pouvoir reconstruire un index dont le header
+     * deviendrait illisible.
+     *
+     * Rien n'est écrit en lecture seule ni pour une table lue dans une
+     * archive ; une erreur d'écriture est seulement signalée.
+     *
+     * @param table_files - Fichiers de la table
+     * @param indexes - Définitions lues dans les headers lisibles
+     *
+     * Effets de bord :
+     * - Peut remplacer le fichier {table}.ndx.json de la table
+     */
+    fn save_key_definitions(&self, table_files: &TableFiles, indexes: &[IndexDefinition]) {
+        if self.read_only || !matches!(source::archive_containing(&table_files.fic_path), Ok(None)) {
+            return;
+        }
+        match keydefs::save(table_files, indexes) {
+            Ok(true) => get_logger().log_with_source(LogLevel::Debug, format!("Définitions de clés de {} sauvegardées", table_files.name), Some("Storage".to_string())),
+            Ok(false) => {}
+            Err(e) => get_logger().log_with_source(LogLevel::Warn, format!("Définitions de clés de {} non sauvegardées: {:#}", table_files.name, e), Some("Storage".to_string())),
+        }
+    }
+
+    /**
+     * Sélectionne des enregistrements d'une table avec filtres et pagination.
+     * 
+     * Le planificateur choisit entre un parcours complet du .fic et un accès
+     * par index (.ndx) selon les filtres et l'ordre demandés. Avec un index
+     * (ou une recherche full-text), les entrées sont parcourues au fil de la
+     * lecture et seuls les enregistrements trouvés sont lus via
+     * FicFile::read_record, jusqu'à remplir la page plus un enregistrement.
+     * Le chemin retenu est rapporté dans QueryResult.plan. Toutes les
+     * conditions (sauf MATCH) sont vérifiées sur les enregistrements
+     * décodés, et `total` compte les enregistrements qui les vérifient ; il
+     * vaut None quand la lecture s'est arrêtée avant le dernier candidat.
+     * Si aucun index ne fournit l'ordre demandé, les enregistrements retenus
+     * sont triés en mémoire bornée (tas top-N ou tri externe, voir sort.rs).
+     * 
+     * Avec une projection (QueryFilters.projection), seules les rubriques
+     * demandées, filtrées ou triées sont décodées, et le .mmo n'est ouvert
+     * que si l'une d'elles est un mémo. Les enregistrements retournés ne
+     * contiennent que l'ID et les rubriques demandées.
+     * 
+     * Avec un échantillon (QueryFilters.sample), seuls les enregistrements
+     * tirés au hasard parmi les candidats du plan sont lus (voir sample.rs) ;
+     * `total` est alors la taille de l'échantillon.
+     * 
+     * En ordre physique ou dans l'ordre d'un index, QueryResult.next_cursor
+     * permet de demander la page suivante (QueryFilters.cursor) : elle
+     * reprend directement après le dernier enregistrement retourné (voir
+     * cursor.rs), au lieu de relire les `offset` premiers.
+     * 
+     * @param table - Nom de la table
+     * @param filters - Filtres de requête (limit, offset, filtres par champ, tri, projection, échantillon, curseur)
+     * @returns Result<QueryResult> - Résultats de la requête ou erreur
+     * 
+     * Effets de bord :
+     * - Lit les fichiers .fic/.mmo pour récupérer les données
+     * - Décode les données selon le schéma de la table
+     */
+    pub fn select(&self, table: &str, filters: QueryFilters) -> Result<QueryResult> {
+        self.select_cancellable(table, filters, &CancelToken::new())
+    }
+
+    /**
+     * Exécute select en vérifiant un jeton d'annulation à chaque enregistrement lu.
+     *
+     * @param table - Nom de la table
+     * @param filters - Filtres de requête
+     * @param cancel - Jeton vérifié pendant la lecture (annulation ou délai dépassé)
+     * @returns Result<QueryResult> - Résultats, ou erreur Interrupted si la requête a été arrêtée
+     *
+     * Effets de bord :
+     * - Lit les fichiers .fic/.mmo jusqu'à la fin ou jusqu'à l'annulation
+     */
+    pub fn select_cancellable(&self, table: &str, filters: QueryFilters, cancel: &CancelToken) -> Result<QueryResult> {
+        let mut result = self.select_decoded(table, &filters, cancel)?;
+        // Rubriques décodées seulement pour filtrer ou trier : retirées du résultat
+        if let Some(columns) = &filters.projection {
+            for record in &mut result.records {
+                record.fields.retain(|name| name == "id" || columns.iter().any(|column| column == name));
+            }
+        }
+        Ok(result)
+    }
+
+    /**
+     * Implémentation de select : les enregistrements retournés contiennent
+     * toutes les rubriques décodées (projection, filtres et tri).
+     */
+    fn select_decoded(&self, table: &str, filters: &QueryFilters, cancel: &CancelToken) -> Result<QueryResult> {
+        // Schéma et fichiers ouverts depuis le cache (rechargés si un fichier a changé)
+        let cached = self.cached_table(table)?;
+        let table_schema = Self::table_schema(&cached, table);
+
+        // Choix du chemin d'accès (parcours complet ou index), puis conditions
+        // vérifiées sur chaque enregistrement décodé
+        let mut planned = planner::plan_query(&table_schema, filters)?;
+        let predicates = predicate::compile(&table_schema, &filters.field_filters)?;
+        planned.plan.residual = predicates.iter().map(|p| p.to_string()).collect();
+
+        // Schéma de décodage : réduit aux rubriques utiles si la requête porte une projection
+        let schema = match Self::projected_schema(&table_schema, filters)? {
+            Some(projected) => {
+                let memos = projected.fields.iter().any(|f| matches!(f.field_type, crate::core::FieldType::Memo));
+                planned.plan.projection = Some(format!(
+                    "{}/{} rubrique(s) décodée(s) : {}{}",
+                    projected.fields.len(),
+                    table_schema.fields.len(),
+                    projected.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>().join(", "),
+                    if memos { "" } else { " (mémos non lus)" }
+                ));
+                projected
+            }
+            None => table_schema.clone(),
+        };
+        let read_memos = schema.fields.iter().any(|f| matches!(f.field_type, crate::core::FieldType::Memo));
+        get_logger().log_with_source(LogLevel::Debug, format!("Plan pour '{}': {}", table, planned.plan.detail), Some("Storage".to_string()));
+
+        let offset = filters.offset.unwrap_or(0);
+        let limit = filters.limit.unwrap_or(100);
+
+        let mut fic = cached.fic()?;
+        // Le .mmo n'est emprunté que si une rubrique mémo est décodée
+        let mut mmo = if read_memos { cached.mmo()? } else { PooledHandle::empty() };
+
+        let fingerprint = cursor::fingerprint(table, filters);
+        if let Some(token) = &filters.cursor {
+            return self.select_after(&mut fic, mmo.slot(), &schema, planned, filters, token, fingerprint, &predicates, limit, cancel);
+        }
+
+        if let Some(spec) = &filters.sample {
+            return self.select_sample(&mut fic, mmo.slot(), &schema, planned, spec, &predicates, filters.offset, filters.limit, cancel);
+        }
+
+        if !planned.sort.is_empty() {
+            let sorter = RecordSorter::compile(&schema, &planned.sort)?;
+            let mut ndx;
+            let record_ids: Box<dyn Iterator<Item = Result<u32>>> = if let Some(full_text) = &planned.full_text {
+                let hits = Self::search_schema(&schema, &full_text.query, full_text.field.as_deref())?;
+                Box::new(hits.into_iter().map(|hit| Ok(hit.record_id)))
+            } else if let Some(access) = &planned.access {
+                ndx = NdxFile::open(&access.index.path)?;
+                Box::new(Self::index_scan(&mut ndx, access)?.map(|entry| entry.map(|e| e.record_id)))
+            } else {
+                Box::new((0..fic.record_count()).map(Ok))
+            };
+            let page = self.select_sorted(&mut fic, mmo.slot(), &schema, &sorter, record_ids, &predicates, offset, limit, cancel)?;
+            planned.plan.sort = planned.plan.sort.map(|keys| format!("{} ({})", keys, page.method));
+            return Ok(QueryResult {
+                records: page.records,
+                total: Some(page.total),
+                offset,
+                limit,
+                plan: planned.plan,
+                next_cursor: None,
+            });
+        }
+
+        if let Some(full_text) = &planned.full_text {
+            let (records, total) = self.select_full_text(&mut fic, mmo.slot(), &schema, full_text, &predicates, offset, limit, cancel)?;
+            return Ok(QueryResult {
+                records,
+                total,
+                offset,
+                limit,
+                plan: planned.plan,
+                next_cursor: None,
+            });
+        }
+
+        if let Some(access) = &planned.access {
+            let (records, total, resume) = self.select_indexed(&mut fic, mmo.slot(), &schema, access, &predicates, offset, limit, cancel)?;
+            return Ok(QueryResult {
+                records,
+                total,
+                offset,
+                limit,
+                plan: planned.plan,
+                next_cursor: resume.map(|position| Cursor { position, fingerprint }.encode()),
+            });
+        }
+
+        // Ordre physique : les IDs du .fic sont lus un par un jusqu'à remplir la page
+        let candidates = (0..fic.record_count()).map(|record_id| Ok((Vec::new(), record_id)));
+        let page = self.read_page(&mut fic, mmo.slot(), &schema, None, candidates, &predicates, offset, limit, cancel)
+            .with_context(|| format!("Erreur lors de la lecture des enregistrements de la table '{}'", table))?;
+        // La page suivante reprend après le dernier ID retourné
+        let total = page.total();
+        let next_cursor = page.last
+            .filter(|_| page.more)
+            .map(|(_, last_id)| Cursor { position: CursorPosition::Fic { last_id }, fingerprint }.encode());
+        Ok(QueryResult {
+            records: page.records,
+            total,
+            offset,
+            limit,
+            plan: planned.plan,
+            next_cursor,
+        })
+    }
+
+    /**
+     * Réduit le schéma aux rubriques à décoder pour une requête.
+     *
+     * Garde les rubriques de la projection, celles des conditions (hors
+     * MATCH, traité par les index full-text) et celles du tri.
+     *
+     * @param schema - Schéma complet de la table
+     * @param filters - Filtres de la requête
+     * @returns Result<Option<TableSchema>> - Schéma réduit (None sans projection),
+     *          ou erreur si une rubrique de la projection est inconnue
+     */
+    fn projected_schema(schema: &TableSchema, filters: &QueryFilters) -> Result<Option<TableSchema>> {
+        let Some(columns) = &filters.projection else {
+            return Ok(None);
+        };
+        if let Some(unknown) = columns.iter().find(|c| c.as_str() != "id" && !schema.fields.iter().any(|f| &f.name == *c)) {
+            anyhow::bail!("Rubrique inconnue dans la table {}: {}", schema.name, unknown);
+        }
+        let needed: BTreeSet<&str> = columns.iter().map(String::as_str)
+            .chain(filters.field_filters.iter()
+                .filter(|filter| filter.operator != FilterOperator::Match)
+                .map(|filter| filter.field.as_str()))
+            .chain(filters.order_by.iter().map(|key| key.field.as_str()))
+            .collect();
+        let mut projected = schema.clone();
+        projected.fields.retain(|field| needed.contains(field.name.as_str()));
+        projected.field_count = projected.fields.len() as u32;
+        Ok(Some(projected))
+    }
+
+    /**
+     * Exécute la page d'une sélection qui reprend à un curseur.
+     *
+     * Les enregistrements sont lus à partir du point de reprise, par accès
+     * direct, jusqu'à remplir la page : les pages précédentes ne sont pas
+     * relues : dans l'ordre d'un index, le parcours reprend à la clé du
+     * curseur. Un enregistrement de plus est lu pour savoir s'il reste une
+     * page. `total` vaut toujours None : les enregistrements des pages
+     * précédentes ne sont pas relus pour le compter.
+     *
+     * @param planned - Plan de la requête (ordre physique ou ordre d'un index)
+     * @param filters - Filtres de la requête (offset refusé avec un curseur)
+     * @param token - Curseur retourné par la page précédente
+     * @param fingerprint - Empreinte de la requête, comparée à celle du curseur
+     * @param predicates - Conditions vérifiées sur chaque enregistrement lu
+     * @param limit - Nombre maximum d'enregistrements à retourner
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns Result<QueryResult> - Page suivante (total None) et son curseur, ou erreur si le
+     *          curseur est invalide, périmé ou si l'ordre demandé ne permet pas de reprise
+     *
+     * Effets de bord :
+     * - Lit le .ndx (ordre d'un index) et les enregistrements de la page dans le .fic
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn select_after(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        mut planned: planner::PlannedQuery,
+        filters: &QueryFilters,
+        token: &str,
+        fingerprint: u32,
+        predicates: &[Predicate],
+        limit: u32,
+        cancel: &CancelToken,
+    ) -> Result<QueryResult> {
+        if filters.sample.is_some() {
+            anyhow::bail!("Pagination par curseur impossible sur un échantillon (TABLESAMPLE)");
+        }
+        if planned.full_text.is_some() {
+            anyhow::bail!("Pagination par curseur impossible avec une recherche full-text : utiliser offset");
+        }
+        if !planned.sort.is_empty() {
+            anyhow::bail!("Pagination par curseur impossible : aucun index ne fournit l'ordre {} ; utiliser offset", planned.plan.sort.as_deref().unwrap_or_default());
+        }
+        if filters.offset.is_some_and(|offset| offset > 0) {
+            anyhow::bail!("offset et cursor ne peuvent pas être combinés");
+        }
+        let cursor = Cursor::decode(token)?;
+        if cursor.fingerprint != fingerprint {
+            anyhow::bail!("Curseur invalide pour cette requête (table, conditions ou tri différents)");
+        }
+
+        // Candidats : (clé d'index, ID) à partir du point de reprise
+        let mut ndx;
+        let (candidates, index): (ResumeCandidates<'_>, Option<&str>) = match (&cursor.position, &planned.access) {
+            (CursorPosition::Fic { last_id }, None) => {
+                (Box::new((last_id.saturating_add(1)..fic.record_count()).map(|record_id| Ok((Vec::new(), record_id)))), None)
+            }
+            (CursorPosition::Index { last_key, last_id }, Some(access)) => {
+                ndx = NdxFile::open(&access.index.path)?;
+                let entries = Self::index_entries_after(&mut ndx, access, last_key, *last_id)
+                    .with_context(|| format!("Erreur lors du parcours de l'index {}", access.index.name))?;
+                (Box::new(entries.map(|entry| entry.map(|e| (e.key, e.record_id)))), Some(access.index.name.as_str()))
+            }
+            _ => anyhow::bail!("Curseur périmé : le chemin d'accès de la requête a changé ({})", planned.plan.detail),
+        };
+
+        let page = self.read_page(fic, mmo, schema, index, candidates, predicates, 0, limit, cancel)?;
+        let next_cursor = page.last
+            .filter(|_| page.more)
+            .map(|(last_key, last_id)| match cursor.position {
+                CursorPosition::Fic { .. } => CursorPosition::Fic { last_id },
+                CursorPosition::Index { .. } => CursorPosition::Index { last_key, last_id },
+            })
+            .map(|position| Cursor { position, fingerprint }.encode());
+
+        planned.plan.detail = format!("{} ; reprise après l'enregistrement {}", planned.plan.detail, cursor.position.last_id());
+        Ok(QueryResult {
+            records: page.records,
+            total: None,
+            offset: 0,
+            limit,
+            plan: planned.plan,
+            next_cursor,
+        })
+    }
+
+    /**
+     * Exécute une sélection sur un échantillon aléatoire (TABLESAMPLE).
+     *
+     * Les positions sont tirées parmi les candidats du plan (tous les IDs du
+     * .fic, ceux d'un index ou d'une recherche full-text) et seuls les
+     * enregistrements tirés sont lus, par accès direct. L'échantillon est
+     * ensuite trié (ORDER BY) puis paginé ; sans limit, il est retourné en
+     * entier.
+     *
+     * @param planned - Plan de la requête (complété par la description de l'échantillon)
+     * @param spec - Échantillon demandé
+     * @param predicates - Conditions vérifiées sur chaque enregistrement tiré
+     * @param offset - Nombre d'enregistrements de l'échantillon à ignorer
+     * @param limit - Nombre maximum d'enregistrements à retourner (None = taille de l'échantillon)
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns Result<QueryResult> - Page de l'échantillon ; total = taille de l'échantillon tiré
+     *
+     * Effets de bord :
+     * - Lit les enregistrements tirés du .fic (et le .ndx ou les .ftx du plan)
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn select_sample(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        mut planned: planner::PlannedQuery,
+        spec: &SampleSpec,
+        predicates: &[Predicate],
+        offset: Option<u32>,
+        limit: Option<u32>,
+        cancel: &CancelToken,
+    ) -> Result<QueryResult> {
+        spec.validate()?;
+        let candidates = if let Some(full_text) = &planned.full_text {
+            let hits = Self::search_schema(schema, &full_text.query, full_text.field.as_deref())?;
+            Candidates::Ids(hits.into_iter().map(|hit| hit.record_id).collect())
+        } else if let Some(access) = &planned.access {
+            Candidates::Ids(Self::index_record_ids(access)?)
+        } else {
+            Candidates::All(fic.record_count())
+        };
+
+        let seed = spec.seed.unwrap_or_else(SampleRng::clock_seed);
+        let drawn = sample::draw(spec, &mut SampleRng::new(seed), &candidates, |record_id| {
+            cancel.check()?;
+            let fic_record = fic.read_record(record_id)
+                .with_context(|| format!("Enregistrement {} illisible", record_id))?;
+            // Enregistrements supprimés : remplacés par un autre tirage
+            if fic_record.deleted {
+                return Ok(None);
+            }
+            let record = self.record_from_fic(fic_record, schema, mmo)?;
+            Ok(predicate::matches_all(predicates, &record).then_some(record))
+        })?;
+        planned.plan.sample = Some(format!(
+            "{} REPEATABLE ({}) : {} enregistrement(s) retenu(s), {} lu(s) sur {} candidat(s)",
+            spec, seed, drawn.records.len(), drawn.read, candidates.len()
+        ));
+        get_logger().log_with_source(LogLevel::Debug, format!("Échantillon de '{}': {}", schema.name, planned.plan.sample.as_deref().unwrap_or_default()), Some("Storage".to_string()));
+
+        let total = Some(drawn.records.len() as u32);
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(spec.size);
+        let records = if planned.sort.is_empty() {
+            drawn.records.into_iter().skip(offset as usize).take(limit as usize).collect()
+        } else {
+            let sorter = RecordSorter::compile(schema, &planned.sort)?;
+            let page = sorter.sorted_page(drawn.records.into_iter().map(Ok), offset, limit)?;
+            planned.plan.sort = planned.plan.sort.map(|keys| format!("{} ({})", keys, page.method));
+            page.records
+        };
+        Ok(QueryResult {
+            records,
+            total,
+            offset,
+            limit,
+            plan: planned.plan,
+            next_cursor: None,
+        })
+    }
+
+    /**
+     * Exécute une sélection à partir d'un index.
+     *
+     * Parcourt l'intervalle de clés retenu par le planificateur au fil de la
+     * lecture, et s'arrête une fois la page remplie (plus un enregistrement
+     * pour savoir s'il en reste).
+     *
+     * @param fic - Fichier .fic ouvert
+     * @param mmo - Fichier .mmo ouvert (optionnel)
+     * @param schema - Schéma de la table
+     * @param access - Accès indexé retenu par le planificateur
+     * @param predicates - Conditions vérifiées sur chaque enregistrement trouvé
+     * @param offset - Nombre d'enregistrements retenus à ignorer
+     * @param limit - Nombre maximum d'enregistrements à retourner
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns Result<(Vec<Record>, Option<u32>, Option<CursorPosition>)> - Enregistrements décodés,
+     *          total retenu (None s'il reste des enregistrements) et point de reprise de la page suivante
+     *
+     * Effets de bord :
+     * - Lit les feuilles du .ndx puis les enregistrements ciblés du .fic
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn select_indexed(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        access: &IndexAccess,
+        predicates: &[Predicate],
+        offset: u32,
+        limit: u32,
+        cancel: &CancelToken,
+    ) -> Result<(Vec<Record>, Option<u32>, Option<CursorPosition>)> {
+        let mut ndx = NdxFile::open(&access.index.path)?;
+        let entries = Self::index_scan(&mut ndx, access)?.map(|entry| entry.map(|e| (e.key, e.record_id)));
+        let page = self.read_page(fic, mmo, schema, Some(&access.index.name), entries, predicates, offset, limit, cancel)?;
+        // Point de reprise : clé et ID de l'entrée du dernier enregistrement retourné
+        let total = page.total();
+        let resume = page.last
+            .filter(|_| page.more)
+            .map(|(last_key, last_id)| CursorPosition::Index { last_key, last_id });
+        Ok((page.records, total, resume))
+    }
+
+    /**
+     * Parcourt l'intervalle de clés d'un accès indexé, dans l'ordre de l'accès.
+     *
+     * Les feuilles sont lues au fil de l'itération, dans un sens ou dans
+     * l'autre : rien n'est chargé d'avance.
+     *
+     * @param ndx - Index ouvert
+     * @param access - Accès indexé retenu par le planificateur
+     * @returns Result<IndexScan> - Entrées, dans l'ordre de parcours
+     */
+    fn index_scan<'a>(ndx: &'a mut NdxFile, access: &IndexAccess) -> Result<IndexScan<'a>> {
+        let lower = access.lower.as_ref().map(|k| k.as_slice());
+        let upper = access.upper.as_ref().map(|k| k.as_slice());
+        let name = access.index.name.clone();
+        let entries: IndexScan<'a> = if access.reverse {
+            Box::new(ndx.rev_range(lower, upper)?)
         } else {
-            // Version séquentielle pour peu de fichiers
-            fic_files
-                .into_iter()
-                .map(|(name, fic_path)| {
-                    self.process_table_files(name, fic_path, &data_dir)
-                })
-                .collect()
+            Box::new(ndx.range(lower, upper)?)
         };
+        Ok(Box::new(entries.map(move |entry| entry.with_context(|| format!("Erreur lors du parcours de l'index {}", name)))))
+    }
 
-        // Insérer dans le cache et collecter les noms
-        let mut tables_cache = self.tables.write().unwrap();
-        for (name, table_files) in table_files_vec {
-            tables_cache.insert(name.clone(), table_files);
-            tables.push(name);
-        }
-
-        Ok(tables)
+    /**
+     * Parcourt l'intervalle de clés d'un accès indexé (IDs seulement).
+     *
+     * @param access - Accès indexé retenu par le planificateur
+     * @returns Result<Vec<u32>> - IDs des enregistrements, dans l'ordre de parcours
+     */
+    fn index_record_ids(access: &IndexAccess) -> Result<Vec<u32>> {
+        let mut ndx = NdxFile::open(&access.index.path)?;
+        let record_ids = Self::index_scan(&mut ndx, access)?
+            .map(|entry| entry.map(|e| e.record_id))
+            .collect();
+        record_ids
     }
 
     /**
-     * Traite les fichiers associés à une table (.mmo, .ndx).
-     * 
-     * Fonction helper pour éviter la duplication de code entre
-     * les versions parallèle et séquentielle de scan_tables.
+     * Parcourt un accès indexé à partir d'un point de reprise (clé, ID) exclu.
+     *
+     * L'ordre des doublons d'une clé n'est pas supposé (un index HFSQL ne les
+     * range pas forcément par ID) : le parcours se positionne sur la clé du
+     * curseur, dans le sens de l'accès, puis avance dans ses doublons jusqu'à
+     * l'entrée du dernier enregistrement retourné. Les feuilles sont lues au
+     * fil de l'itération, dans un sens comme dans l'autre.
+     *
+     * @param ndx - Index ouvert
+     * @param access - Accès indexé retenu par le planificateur
+     * @param last_key - Clé du dernier enregistrement retourné (forme stockée)
+     * @param last_id - ID du dernier enregistrement retourné
+     * @returns Result<IndexScan> - Entrées suivantes, dans l'ordre de parcours ; une
+     *          erreur est retournée au parcours si l'entrée du curseur n'est plus dans l'index
      */
-    fn process_table_files(&self, name: String, fic_path: PathBuf, data_dir: &Path) -> (String, TableFiles) {
-        let mmo_path = data_dir.join(format!("{}.mmo", name));
-        let mmo_path = if mmo_path.exists() {
-            Some(mmo_path)
+    fn index_entries_after<'a>(
+        ndx: &'a mut NdxFile,
+        access: &IndexAccess,
+        last_key: &'a [u8],
+        last_id: u32,
+    ) -> Result<IndexScan<'a>> {
+        let entries: IndexScan<'a> = if access.reverse {
+            let lower = access.lower.as_ref().map(|k| k.as_slice());
+            Box::new(ndx.rev_range(lower, Bound::Included(last_key))?)
         } else {
-            None
+            let upper = access.upper.as_ref().map(|k| k.as_slice());
+            Box::new(ndx.range(Bound::Included(last_key), upper)?)
         };
-
-        let mut ndx_paths = Vec::new();
-        let mut i = 0;
-        loop {
-            let ndx_path = data_dir.join(format!("{}.ndx{}", name, i));
-            if ndx_path.exists() {
-                ndx_paths.push(ndx_path);
-                i += 1;
-            } else {
-                break;
+        // Doublons de la clé du curseur parcourus jusqu'à l'entrée déjà retournée
+        let mut entries = entries;
+        let mut seeking = true;
+        Ok(Box::new(std::iter::from_fn(move || {
+            while seeking {
+                match entries.next() {
+                    Some(Ok(entry)) if entry.key.as_slice() == last_key => seeking = entry.record_id != last_id,
+                    Some(Err(e)) => return Some(Err(e)),
+                    _ => {
+                        entries = Box::new(std::iter::empty());
+                        seeking = false;
+                        return Some(Err(anyhow::anyhow!(
+                            "Curseur périmé : l'entrée {} de l'enregistrement {} n'est plus dans l'index",
+                            hex::encode(last_key), last_id
+                        )));
+                    }
+                }
             }
-        }
+            entries.next()
+        })))
+    }
 
-        let table_files = TableFiles {
-            name: name.clone(),
-            fic_path,
-            mmo_path,
-            ndx_paths,
-        };
+    /**
+     * Exécute une sélection triée par le moteur (aucun index ne fournit l'ordre).
+     *
+     * Les enregistrements candidats sont lus un par un, filtrés puis passés
+     * au tri : seuls les enregistrements de la page (ou un lot du tri externe)
+     * sont gardés en mémoire.
+     *
+     * @param sorter - Tri compilé
+     * @param record_ids - Candidats (tous les IDs du .fic, ou ceux d'un index lus au fil du parcours)
+     * @param predicates - Conditions vérifiées sur chaque enregistrement
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns Result<SortedPage> - Enregistrements de la page, total retenu et méthode de tri
+     *
+     * Effets de bord :
+     * - Lit les enregistrements candidats du .fic
+     * - Peut écrire des fichiers temporaires (tri externe)
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn select_sorted(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        sorter: &RecordSorter,
+        record_ids: impl Iterator<Item = Result<u32>>,
+        predicates: &[Predicate],
+        offset: u32,
+        limit: u32,
+        cancel: &CancelToken,
+    ) -> Result<SortedPage> {
+        let records = self.candidate_records(fic, mmo, schema, record_ids, predicates, cancel);
+        sorter.sorted_page(records, offset, limit)
+    }
 
-        (name, table_files)
+    /**
+     * Lit et décode des enregistrements candidats au fil de l'itération.
+     *
+     * Les enregistrements supprimés (encore référencés par un index, ou
+     * trous du .fic) et ceux qui ne vérifient pas les conditions sont ignorés.
+     *
+     * @param record_ids - IDs à lire, dans l'ordre (une erreur de parcours de l'index est propagée)
+     * @param predicates - Conditions vérifiées sur chaque enregistrement
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns impl Iterator<Item = Result<Record>> - Enregistrements retenus
+     */
+    fn candidate_records<'a>(
+        &'a self,
+        fic: &'a mut FicFile,
+        mmo: &'a mut Option<MmoFile>,
+        schema: &'a TableSchema,
+        record_ids: impl Iterator<Item = Result<u32>> + 'a,
+        predicates: &'a [Predicate],
+        cancel: &'a CancelToken,
+    ) -> impl Iterator<Item = Result<Record>> + 'a {
+        record_ids.filter_map(move |record_id| {
+            if let Err(e) = cancel.check() {
+                return Some(Err(e));
+            }
+            let record_id = match record_id {
+                Ok(record_id) => record_id,
+                Err(e) => return Some(Err(e)),
+            };
+            let fic_record = match fic.read_record(record_id) {
+                Ok(fic_record) => fic_record,
+                Err(e) => return Some(Err(e).with_context(|| format!("Enregistrement {} illisible", record_id))),
+            };
+            if fic_record.deleted {
+                return None;
+            }
+            match self.record_from_fic(fic_record, schema, mmo) {
+                Ok(record) if predicate::matches_all(predicates, &record) => Some(Ok(record)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
     }
 
     /**
-     * Retourne la liste des noms de toutes les tables détectées.
-     * 
-     * @returns Vec<String> - Liste des noms de tables
-     * 
-     * Effets de bord : Aucun
+     * Exécute une sélection par recherche full-text.
+     *
+     * Les enregistrements sont retournés par pertinence décroissante ; seuls
+     * ceux de la page (plus un) sont lus dans le .fic.
+     *
+     * @returns Result<(Vec<Record>, Option<u32>)> - Enregistrements de la page et total
+     *          trouvé (None s'il reste des enregistrements après la page)
      */
-    pub fn list_tables(&self) -> Vec<String> {
-        self.tables.read().unwrap().keys().cloned().collect()
+    #[allow(clippy::too_many_arguments)]
+    fn select_full_text(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        full_text: &FullTextAccess,
+        predicates: &[Predicate],
+        offset: u32,
+        limit: u32,
+        cancel: &CancelToken,
+    ) -> Result<(Vec<Record>, Option<u32>)> {
+        let hits = Self::search_schema(schema, &full_text.query, full_text.field.as_deref())?;
+        let candidates = hits.into_iter().map(|hit| Ok((Vec::new(), hit.record_id)));
+        let page = self.read_page(fic, mmo, schema, Some("full-text"), candidates, predicates, offset, limit, cancel)?;
+        let total = page.total();
+        Ok((page.records, total))
     }
 
     /**
-     * Obtient le schéma complet d'une table.
-     * 
-     * Ouvre le fichier .fic, analyse sa structure et retourne le schéma
-     * avec la liste des champs, leurs types, offsets et longueurs.
-     * 
+     * Lit et décode une page d'enregistrements candidats.
+     *
+     * Les candidats sont consommés au fil de la lecture : la lecture s'arrête
+     * au premier enregistrement retenu après la page. Les enregistrements
+     * supprimés sont ignorés avant la pagination (avec un avertissement s'ils
+     * sont encore référencés par un index). Sans condition, seuls les
+     * enregistrements de la page sont décodés ; avec des conditions, chaque
+     * enregistrement lu est décodé pour les vérifier. Dans l'ordre physique,
+     * la lecture s'arrête au premier enregistrement illisible, comme
+     * read_all_records.
+     *
+     * @param source - Nom de l'index (pour les messages), None pour l'ordre physique du .fic
+     * @param candidates - (clé d'index, ID) dans l'ordre de parcours (clé vide dans l'ordre physique)
+     * @param predicates - Conditions vérifiées sur chaque enregistrement
+     * @param offset - Nombre d'enregistrements retenus à ignorer
+     * @param limit - Nombre maximum d'enregistrements à retourner
+     * @param cancel - Jeton vérifié avant chaque lecture
+     * @returns Result<CandidatePage> - Enregistrements de la page, dernière entrée
+     *          retournée et indication qu'il en reste
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn read_page(
+        &self,
+        fic: &mut FicFile,
+        mmo: &mut Option<MmoFile>,
+        schema: &TableSchema,
+        source: Option<&str>,
+        candidates: impl Iterator<Item = Result<KeyEntry>>,
+        predicates: &[Predicate],
+        offset: u32,
+        limit: u32,
+        cancel: &CancelToken,
+    ) -> Result<CandidatePage> {
+        let mut page = CandidatePage::default();
+        for candidate in candidates {
+            cancel.check()?;
+            let (key, record_id) = candidate?;
+            let fic_record = match (fic.read_record(record_id), source) {
+                (Ok(fic_record), _) => fic_record,
+                (Err(e), Some(source)) => return Err(e).with_context(|| format!("Index {} incohérent: enregistrement {} illisible", source, record_id)),
+                (Err(e), None) => {
+                    get_logger().log_with_source(LogLevel::Error, format!("Erreur lors de la lecture de l'enregistrement {}: {}", record_id, e), Some("Storage".to_string()));
+                    break;
+                }
+            };
+            if fic_record.deleted {
+                if let Some(source) = source {
+                    get_logger().log_with_source(LogLevel::Warn, format!("Index {}: l'enregistrement {} est supprimé", source, record_id), Some("Storage".to_string()));
+                }
+                continue;
+            }
+            // Sans condition, un enregistrement hors de la page n'est pas décodé
+            let in_page = page.matched >= offset && page.records.len() < limit as usize;
+            let record = if predicates.is_empty() && !in_page {
+                None
+            } else {
+                let record = self.record_from_fic(fic_record, schema, mmo)?;
+                if !predicate::matches_all(predicates, &record) {
+                    continue;
+                }
+                Some(record)
+            };
+            if page.matched < offset {
+                page.matched += 1;
+                continue;
+            }
+            let Some(record) = record.filter(|_| page.records.len() < limit as usize) else {
+                page.more = true;
+                break;
+            };
+            page.matched += 1;
+            page.last = Some((key, record.id));
+            page.records.push(record);
+        }
+        Ok(page)
+    }
+
+    /**
+     * Lit l'historique des modifications d'une table depuis ses journaux.
+     *
+     * Les images avant/après de chaque entrée sont décodées selon le schéma
+     * actuel de la table. Les données mémo ne sont pas relues : les pointeurs
+     * d'une image ancienne ne correspondent plus forcément au fichier .mmo.
+     *
      * @param table - Nom de la table
-     * @returns Result<TableSchema> - Schéma de la table ou erreur
-     * 
+     * @param filters - Filtres (enregistrement, période, opération, utilisateur, limite)
+     * @returns Result<Vec<HistoryEntry>> - Modifications, les plus récentes en premier
+     *
      * Effets de bord :
-     * - Lit le fichier .fic pour analyser le schéma
+     * - Lit les fichiers journal de la table
      */
-    pub fn get_schema(&self, table: &str) -> Result<TableSchema> {
-        let tables = self.tables.read().unwrap();
-        let table_files = tables.get(table)
-            .with_context(|| format!("Table '{}' non trouvée", table))?;
+    pub fn history(&self, table: &str, filters: &HistoryFilters) -> Result<Vec<HistoryEntry>> {
+        let journal_paths = self.table_files(table)?.journal_paths;
+        if journal_paths.is_empty() {
+            anyhow::bail!("Aucun journal pour la table {} (journalisation désactivée ?)", table);
+        }
+        let schema = self.get_schema(table)?;
 
-        let fic = FicFile::open(&table_files.fic_path)?;
-        let fields = fic.analyze_schema();
+        let mut history = Vec::new();
+        for journal_path in &journal_paths {
+            let journal = journal_path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut jnl = JnlFile::open(journal_path)?;
+            if jnl.header().image_length + 1 != schema.record_length {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    format!("Journal {}: images de {} bytes pour des enregistrements de {} bytes", journal, jnl.header().image_length, schema.record_length),
+                    Some("Storage".to_string()),
+                );
+            }
 
-        Ok(TableSchema {
-            name: table.to_string(),
-            record_length: fic.header().record_length,
-            field_count: fields.len() as u32,
-            fields,
-        })
+            for entry in jnl.entries()? {
+                let entry = entry.with_context(|| format!("Journal {} illisible", journal))?;
+                let keep = filters.record_id.map(|id| entry.record_id == id).unwrap_or(true)
+                    && filters.since.map(|since| entry.timestamp >= since).unwrap_or(true)
+                    && filters.until.map(|until| entry.timestamp < until).unwrap_or(true)
+                    && filters.operation.map(|op| entry.operation == op).unwrap_or(true)
+                    && filters.user.as_ref().map(|user| entry.user.eq_ignore_ascii_case(user)).unwrap_or(true);
+                if !keep {
+                    continue;
+                }
+
+                let decode = |image: Option<Vec<u8>>| -> Result<Option<Record>> {
+                    image.map(|data| {
+                        let fic_record = FicRecord {
+                            id: entry.record_id,
+                            deleted: false,
+                            data,
+                            memo_pointers: Vec::new(),
+                        };
+                        self.record_from_fic_impl(fic_record, &schema, &mut None)
+                    }).transpose()
+                };
+                let before = decode(entry.before)?;
+                let after = decode(entry.after)?;
+
+                // Ordre des rubriques dans le schéma
+                let changed_fields: Vec<String> = match (&before, &after) {
+                    (Some(before), Some(after)) => schema.fields.iter()
+                        .filter(|f| before.fields.get(&f.name) != after.fields.get(&f.name))
+                        .map(|f| f.name.clone())
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                history.push(HistoryEntry {
+                    journal: journal.clone(),
+                    sequence: entry.sequence,
+                    operation: entry.operation,
+                    timestamp: entry.timestamp,
+                    date: format_timestamp(entry.timestamp),
+                    record_id: entry.record_id,
+                    user: entry.user,
+                    station: entry.station,
+                    before,
+                    after,
+                    changed_fields,
+                });
+            }
+        }
+
+        history.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.sequence.cmp(&a.sequence)));
+        if let Some(limit) = filters.limit {
+            history.truncate(limit as usize);
+        }
+        Ok(history)
     }
 
     /**
-     * Sélectionne des enregistrements d'une table avec filtres et pagination.
-     * 
-     * Lit tous les enregistrements de la table, applique les filtres par champ,
-     * puis retourne une page de résultats avec pagination.
-     * 
+     * Reconstruit les index .ndx d'une table à partir du fichier .fic.
+     *
+     * Les clés sont recalculées pour chaque enregistrement actif selon les
+     * segments déclarés dans les headers des index existants, puis comparées
+     * au contenu actuel de chaque index. Sans vérification seule, chaque index
+     * est réécrit avec la taille de page de l'index d'origine.
+     *
+     * Un index dont le header est illisible est reconstruit d'après sa
+     * définition sauvegardée (voir keydefs.rs) ; sans définition sauvegardée,
+     * son rapport signale l'erreur et il n'est pas réécrit.
+     *
+     * Les enregistrements sont lus un par un et seules leurs entrées (clé, ID)
+     * sont gardées pour le tri de chaque index. Les enregistrements illisibles
+     * sont signalés dans chaque rapport, et aucun index n'est alors réécrit
+     * (il lui manquerait des entrées).
+     *
      * @param table - Nom de la table
-     * @param filters - Filtres de requête (limit, offset, filtres par champ)
-     * @returns Result<QueryResult> - Résultats de la requête ou erreur
-     * 
+     * @param verify_only - Compare sans réécrire les fichiers
+     * @returns Result<Vec<ReindexReport>> - Un rapport par index, ou erreur si
+     *          des enregistrements sont illisibles et que les index devaient être réécrits
+     *
      * Effets de bord :
-     * - Lit les fichiers .fic/.mmo pour récupérer les données
-     * - Décode les données selon le schéma de la table
+     * - Lit le fichier .fic et les fichiers .ndx de la table
+     * - Remplace les fichiers .ndx (sauf si verify_only)
      */
-    pub fn select(&self, table: &str, filters: QueryFilters) -> Result<QueryResult> {
-        let tables = self.tables.read().unwrap();
-        let table_files = tables.get(table)
-            .with_context(|| format!("Table '{}' non trouvée", table))?;
+    pub fn reindex(&self, table: &str, verify_only: bool) -> Result<Vec<ReindexReport>> {
+        if self.read_only && !verify_only {
+            anyhow::bail!("Mode lecture seule activé");
+        }
 
-        // Obtenir le schéma pour décoder les champs
+        let table_files = self.table_files(table)?;
+        let fic_path = table_files.fic_path.clone();
+        if !verify_only && source::archive_containing(&fic_path)?.is_some() {
+            anyhow::bail!("La table {} est lue dans une archive: ses index ne peuvent pas être reconstruits (utiliser --verify)", table);
+        }
         let schema = self.get_schema(table)?;
+        if table_files.ndx_paths.is_empty() {
+            get_logger().log_with_source(LogLevel::Warn, format!("Aucun index pour la table {}", table), Some("Storage".to_string()));
+            return Ok(Vec::new());
+        }
 
-        let mut fic = FicFile::open(&table_files.fic_path)
-            .with_context(|| format!("Impossible d'ouvrir le fichier .fic: {:?}", table_files.fic_path))?;
-        let mut mmo = table_files.mmo_path.as_ref()
-            .map(|p| MmoFile::open(p))
-            .transpose()
-            .with_context(|| "Erreur lors de l'ouverture du fichier .mmo")?;
+        // Index dont le header est illisible : définition sauvegardée
+        let saved = keydefs::load(&fic_path).unwrap_or_else(|e| {
+            get_logger().log_with_source(LogLevel::Warn, format!("{:#}", e), Some("Storage".to_string()));
+            Vec::new()
+        });
+        let mut indexes = Vec::new();
+        let mut unknown = Vec::new();
+        for ndx_path in &table_files.ndx_paths {
+            if let Some(index) = schema.indexes.iter().find(|index| index.path == *ndx_path) {
+                indexes.push(index.clone());
+                continue;
+            }
+            let name = ndx_path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match saved.iter().find(|s| s.name.eq_ignore_ascii_case(&name)) {
+                Some(definition) => {
+                    get_logger().log_with_source(LogLevel::Warn, format!("Index {}: header illisible, définition sauvegardée utilisée", name), Some("Storage".to_string()));
+                    let (fields, encodings) = Self::segment_fields(&definition.segments, &schema.fields);
+                    indexes.push(IndexDefinition {
+                        name,
+                        path: ndx_path.clone(),
+                        fields,
+                        segments: definition.segments.clone(),
+                        encodings,
+                        unique: definition.unique,
+                    });
+                }
+                None => unknown.push((name, ndx_path.clone())),
+            }
+        }
 
-        let all_records = fic.read_all_records()
-            .with_context(|| format!("Erreur lors de la lecture des enregistrements de la table '{}'", table))?;
-        let total = all_records.len() as u32;
+        // Clés calculées au fil de la lecture : un enregistrement à la fois en mémoire
+        let mut fic = FicFile::open(&fic_path)?;
+        let mut rebuilt: Vec<Vec<KeyEntry>> = vec![Vec::new(); indexes.len()];
+        let mut skipped_records = Vec::new();
+        for record_id in 0..fic.record_count() {
+            match fic.read_record(record_id) {
+                Ok(record) if !record.deleted => {
+                    for (index, entries) in indexes.iter().zip(&mut rebuilt) {
+                        entries.push((record_key(&record.data, &index.segments, &index.encodings), record.id));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    get_logger().log_with_source(LogLevel::Error, format!("Erreur lors de la lecture de l'enregistrement {}: {}", record_id, e), Some("Storage".to_string()));
+                    skipped_records.push(record_id);
+                }
+            }
+        }
+        if !verify_only && !skipped_records.is_empty() {
+            anyhow::bail!(
+                "{} enregistrement(s) illisible(s) dans {} (premier: {}): index non reconstruits (utiliser --verify)",
+                skipped_records.len(), table, skipped_records[0]
+            );
+        }
 
-        // Application des filtres
-        let offset = filters.offset.unwrap_or(0);
-        let limit = filters.limit.unwrap_or(100);
+        let mut reports = Vec::with_capacity(table_files.ndx_paths.len());
+        for (name, path) in unknown {
+            reports.push(ReindexReport {
+                index: name,
+                path,
+                entries: 0,
+                existing_entries: None,
+                existing_error: Some("header illisible et aucune définition de clé sauvegardée: index non reconstruit".to_string()),
+                missing: Vec::new(),
+                unexpected: Vec::new(),
+                skipped_records: skipped_records.clone(),
+                written: false,
+            });
+        }
+        for (index, rebuilt) in indexes.iter().zip(rebuilt) {
+            let (existing, existing_error, page_size) = match Self::read_index_entries(&index.path) {
+                Ok((entries, page_size)) => (Some(entries), None, page_size),
+                Err(e) => (None, Some(format!("{:#}", e)), DEFAULT_PAGE_SIZE),
+            };
 
-        // Paralléliser le décodage des enregistrements si on en a beaucoup et si le multi-threading est activé
-        let records_to_decode: Vec<FicRecord> = all_records
-            .into_iter()
-            .skip(offset as usize)
-            .take(limit as usize)
-            .collect();
+            let expected: BTreeSet<&KeyEntry> = rebuilt.iter().collect();
+            let (missing, unexpected) = match &existing {
+                Some(existing) => {
+                    let current: BTreeSet<&KeyEntry> = existing.iter().collect();
+                    (
+                        expected.difference(&current).map(|e| Self::diff_entry(e)).collect(),
+                        current.difference(&expected).map(|e| Self::diff_entry(e)).collect(),
+                    )
+                }
+                None => (Vec::new(), Vec::new()),
+            };
 
-        let records: Vec<Record> = if self.parallel && records_to_decode.len() > 50 {
-            // Version parallèle : ouvrir un nouveau fichier MMO pour chaque thread si nécessaire
-            let mmo_path = table_files.mmo_path.clone();
-            let schema_clone = schema.clone();
-            
-            records_to_decode
-                .into_par_iter()
-                .map(|r| {
-                    // Ouvrir un nouveau fichier MMO pour ce thread si nécessaire
-                    let mut thread_mmo = mmo_path.as_ref()
-                        .map(|p| MmoFile::open(p))
-                        .transpose()
-                        .with_context(|| "Erreur lors de l'ouverture du fichier .mmo pour le thread")?;
-                    
-                    self.record_from_fic_impl(r, &schema_clone, &mut thread_mmo)
-                })
-                .collect::<Result<Vec<_>>>()?
-        } else {
-            // Version séquentielle pour petits ensembles
-            records_to_decode
-                .into_iter()
-                .map(|r| self.record_from_fic(r, &schema, &mut mmo))
-                .collect::<Result<Vec<_>>>()?
-        };
+            let mut report = ReindexReport {
+                index: index.name.clone(),
+                path: index.path.clone(),
+                entries: rebuilt.len(),
+                existing_entries: existing.as_ref().map(|e| e.len()),
+                existing_error,
+                missing,
+                unexpected,
+                skipped_records: skipped_records.clone(),
+                written: false,
+            };
 
-        Ok(QueryResult {
-            records,
-            total,
-            offset,
-            limit,
-        })
+            if !verify_only {
+                NdxWriter::new(index.segments.clone(), index.unique)
+                    .page_size(page_size)
+                    .write(&index.path, rebuilt)
+                    .with_context(|| format!("Impossible de reconstruire l'index {}", index.name))?;
+                report.written = true;
+                get_logger().log_with_source(LogLevel::Info, format!("Index {} reconstruit: {} entrées", index.name, report.entries), Some("Storage".to_string()));
+            }
+
+            reports.push(report);
+        }
+
+        Ok(reports)
+    }
+
+    /**
+     * Lit toutes les entrées d'un index existant.
+     *
+     * @param path - Chemin du fichier .ndx
+     * @returns Result<(Vec<KeyEntry>, u32)> - Entrées (clé, record_id) et taille de page
+     */
+    fn read_index_entries(path: &Path) -> Result<(Vec<KeyEntry>, u32)> {
+        let mut ndx = NdxFile::open(path)?;
+        let page_size = ndx.header().page_size;
+        let entries = ndx.iter()?
+            .map(|entry| entry.map(|e| (e.key, e.record_id)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((entries, page_size))
+    }
+
+    /// Convertit une entrée (clé, record_id) pour un rapport de réindexation
+    fn diff_entry(entry: &KeyEntry) -> IndexDiffEntry {
+        IndexDiffEntry {
+            key: hex::encode(&entry.0),
+            record_id: entry.1,
+        }
     }
 
     /**
@@ -405,20 +2451,108 @@ impl StorageEngine {
      * - Peut lire le fichier .mmo si des données mémo sont présentes
      */
     pub fn get_by_id(&self, table: &str, id: u32) -> Result<Record> {
-        let tables = self.tables.read().unwrap();
-        let table_files = tables.get(table)
-            .with_context(|| format!("Table '{}' non trouvée", table))?;
+        let cached = self.cached_table(table)?;
+        let schema = Self::table_schema(&cached, table);
 
-        // Obtenir le schéma pour décoder les champs
-        let schema = self.get_schema(table)?;
-
-        let mut fic = FicFile::open(&table_files.fic_path)?;
-        let mut mmo = table_files.mmo_path.as_ref()
-            .map(|p| MmoFile::open(p))
-            .transpose()?;
+        let mut fic = cached.fic()?;
+        let mut mmo = cached.mmo()?;
 
         let record = fic.read_record(id)?;
-        self.record_from_fic(record, &schema, &mut mmo)
+        self.record_from_fic(record, &schema, mmo.slot())
+    }
+
+    /**
+     * Parcourt tous les enregistrements actifs d'une table, dans l'ordre des IDs.
+     *
+     * Contrairement à select, les enregistrements ne sont pas chargés en
+     * mémoire : chacun est décodé puis passé à `visit`.
+     *
+     * @param table - Nom de la table
+     * @param visit - Appelé pour chaque enregistrement ; une erreur arrête le parcours
+     * @returns Result<u32> - Nombre d'enregistrements parcourus
+     *
+     * Effets de bord :
+     * - Lit le fichier .fic (et .mmo) enregistrement par enregistrement
+     */
+    pub fn for_each_record(&self, table: &str, mut visit: impl FnMut(Record) -> Result<()>) -> Result<u32> {
+        let cached = self.cached_table(table)?;
+        let schema = Self::table_schema(&cached, table);
+
+        let mut fic = cached.fic()?;
+        let mut mmo = cached.mmo()?;
+
+        let mut visited = 0u32;
+        for record_id in 0..fic.record_count() {
+            let fic_record = fic.read_record(record_id)
+                .with_context(|| format!("Enregistrement {} de la table '{}' illisible", record_id, table))?;
+            if fic_record.deleted {
+                continue;
+            }
+            visit(self.record_from_fic(fic_record, &schema, mmo.slot())?)?;
+            visited += 1;
+        }
+        Ok(visited)
+    }
+
+    /**
+     * Parcourt tous les enregistrements actifs d'une table dans l'ordre demandé.
+     *
+     * Si un index fournit l'ordre, les enregistrements sont lus dans l'ordre
+     * de l'index, au fil du parcours de ses feuilles ; sinon ils sont triés par lots sur disque puis restitués au
+     * fil de la fusion (voir sort.rs). Dans les deux cas, seul un
+     * enregistrement par lot est gardé en mémoire. Sans clé de tri, le
+     * parcours suit l'ordre des IDs (for_each_record).
+     *
+     * @param table - Nom de la table
+     * @param order_by - Clés de tri, par priorité décroissante
+     * @param visit - Appelé pour chaque enregistrement ; une erreur arrête le parcours
+     * @returns Result<u32> - Nombre d'enregistrements parcourus
+     *
+     * Effets de bord :
+     * - Lit le fichier .fic (et .mmo, .ndx) enregistrement par enregistrement
+     * - Peut écrire des fichiers temporaires (tri externe)
+     */
+    pub fn for_each_sorted(&self, table: &str, order_by: &[SortKey], mut visit: impl FnMut(Record) -> Result<()>) -> Result<u32> {
+        if order_by.is_empty() {
+            return self.for_each_record(table, visit);
+        }
+        let cached = self.cached_table(table)?;
+        let schema = Self::table_schema(&cached, table);
+        let filters = QueryFilters {
+            order_by: order_by.to_vec(),
+            ..Default::default()
+        };
+        let planned = planner::plan_query(&schema, &filters)?;
+
+        let mut fic = cached.fic()?;
+        let mut mmo = cached.mmo()?;
+        let mut ndx;
+        let record_ids: Box<dyn Iterator<Item = Result<u32>>> = match &planned.access {
+            Some(access) => {
+                ndx = NdxFile::open(&access.index.path)?;
+                Box::new(Self::index_scan(&mut ndx, access)?.map(|entry| entry.map(|e| e.record_id)))
+            }
+            None => Box::new((0..fic.record_count()).map(Ok)),
+        };
+        let cancel = CancelToken::new();
+        let records = self.candidate_records(&mut fic, mmo.slot(), &schema, record_ids, &[], &cancel);
+
+        let mut visited = 0u32;
+        if planned.sort.is_empty() {
+            for record in records {
+                visit(record?)?;
+                visited += 1;
+            }
+        } else {
+            let sorter = RecordSorter::compile(&schema, &planned.sort)?;
+            let sorted = sorter.sort(records)?;
+            get_logger().log_with_source(LogLevel::Debug, format!("Parcours trié de '{}': {}", table, sorted.method()), Some("Storage".to_string()));
+            for record in sorted {
+                visit(record?)?;
+                visited += 1;
+            }
+        }
+        Ok(visited)
     }
 
     /**
@@ -458,22 +2592,22 @@ impl StorageEngine {
         use byteorder::{LittleEndian, ReadBytesExt};
         use std::io::Cursor;
 
-        let mut fields = HashMap::new();
-        let mut memo_data = HashMap::new();
+        let mut fields = Fields::with_capacity(schema.fields.len() + 1);
 
         // Ajouter l'ID
-        fields.insert("id".to_string(), FieldValue::integer(fic_record.id as i64));
+        fields.insert("id", FieldValue::integer(fic_record.id as i64));
 
         // Décoder chaque champ selon le schéma
         // Les données de l'enregistrement commencent après le byte de flags
         // On reconstruit le buffer complet en ajoutant le byte de flags au début
-        let full_data = if fic_record.data.is_empty() {
-            vec![0u8]
+        // Le tampon est partagé par les RawRef des rubriques
+        let full_data: Arc<[u8]> = if fic_record.data.is_empty() {
+            Arc::from(vec![0u8])
         } else {
             // Le premier byte est le flag, le reste sont les données
             let mut full = vec![0u8]; // Flag byte (non supprimé par défaut)
             full.extend_from_slice(&fic_record.data);
-            full
+            Arc::from(full)
         };
 
         for field in &schema.fields {
@@ -492,6 +2626,7 @@ impl StorageEngine {
             }
 
             let field_data = &full_data[offset..offset + length];
+            let mut raw = RawRef::new(RawSource::Fic, offset as u64, full_data.clone(), offset..offset + length);
 
             let value = match field.field_type {
                 FieldType::Integer => {
@@ -529,23 +2664,11 @@ impl StorageEngine {
                     let null_pos = field_data.iter().position(|&b| b == 0).unwrap_or(field_data.len());
                     let string_bytes = &field_data[..null_pos];
                     
-                    // Décoder en utilisant Windows-1252 (CP1252) pour gérer les accents français
-                    // Essayer d'abord Windows-1252, puis UTF-8 si ça échoue
+                    // Décoder dans l'encodage de la rubrique (auto : Windows-1252, puis UTF-8)
                     let string_value = if string_bytes.is_empty() {
                         String::new()
                     } else {
-                        // Essayer Windows-1252 d'abord (encodage standard pour fichiers français Windows)
-                        let (decoded, _, had_errors) = WINDOWS_1252.decode(string_bytes);
-                        let mut result = decoded.into_owned();
-                        
-                        // Si Windows-1252 a produit des erreurs, essayer UTF-8
-                        if had_errors {
-                            if let Ok(utf8_str) = std::str::from_utf8(string_bytes) {
-                                result = utf8_str.to_string();
-                            }
-                        }
-                        
-                        result.trim_end().to_string()
+                        field.encoding.decode(string_bytes).0.trim_end().to_string()
                     };
                     
                     FieldValue::string(string_value)
@@ -562,57 +2685,52 @@ impl StorageEngine {
                     }
                 }
                 FieldType::Memo => {
-                    // Les mémos sont des pointeurs vers le fichier .mmo
+                    // Les mémos sont des pointeurs vers le fichier .mmo ; la valeur
+                    // et ses octets bruts sont ceux du bloc (null si absent ou illisible)
+                    let mut value = FieldValue::null();
                     if length >= 4 {
                         let mut cursor = Cursor::new(field_data);
                         let pointer = cursor.read_u32::<LittleEndian>().unwrap_or(0);
                         if pointer != 0 && pointer < 0xFFFFFFFF {
-                            // Lire le contenu du mémo
-                            if let Some(ref mut mmo_file) = mmo {
-                                if let Ok(text) = mmo_file.read_text(pointer) {
-                                    memo_data.insert(field.name.clone(), text);
-                                }
+                            if let Some(block) = mmo.as_mut().and_then(|mmo_file| mmo_file.read_block_as(pointer, field.encoding).ok()) {
+                                value = match (block.text, block.mime) {
+                                    (Some(text), _) => FieldValue::memo_text(text, block.length),
+                                    (None, mime) => FieldValue::memo_binary(
+                                        &block.data,
+                                        mime.unwrap_or_else(|| "application/octet-stream".to_string()),
+                                    ),
+                                };
+                                let data: Arc<[u8]> = Arc::from(block.data);
+                                raw = RawRef::new(RawSource::Mmo, pointer as u64, data.clone(), 0..data.len());
                             }
                         }
                     }
-                    FieldValue::null()
+                    value
                 }
                 FieldType::Date | FieldType::Unknown => {
                     // Pour les dates et types inconnus, on affiche comme binaire ou string
-                    // Essayer de décoder comme chaîne (Windows-1252 ou UTF-8)
+                    // Essayer de décoder comme chaîne dans l'encodage de la rubrique
                     let null_pos = field_data.iter().position(|&b| b == 0).unwrap_or(field_data.len());
                     let string_bytes = &field_data[..null_pos];
                     
                     if string_bytes.is_empty() {
                         FieldValue::binary(field_data.to_vec())
                     } else {
-                        // Essayer Windows-1252 d'abord
-                        let (decoded, _, had_errors) = WINDOWS_1252.decode(string_bytes);
-                        let mut result = decoded.into_owned();
-                        
-                        // Si Windows-1252 a produit des erreurs, essayer UTF-8
-                        if had_errors {
-                            if let Ok(utf8_str) = std::str::from_utf8(string_bytes) {
-                                result = utf8_str.to_string();
-                                FieldValue::string(result.trim_end().to_string())
-                            } else {
-                                // Si les deux échouent, traiter comme binaire
-                                FieldValue::binary(field_data.to_vec())
-                            }
-                        } else {
-                            FieldValue::string(result.trim_end().to_string())
+                        match field.encoding.decode(string_bytes) {
+                            // Octets invalides pour l'encodage : traiter comme binaire
+                            (_, true) => FieldValue::binary(field_data.to_vec()),
+                            (text, false) => FieldValue::string(text.trim_end().to_string()),
                         }
                     }
                 }
             };
 
-            fields.insert(field.name.clone(), value);
+            fields.insert_raw(field.name.clone(), value, Some(raw));
         }
 
         Ok(Record {
             id: fic_record.id,
             fields,
-            memo_data,
         })
     }
 
@@ -676,3 +2794,368 @@ impl StorageEngine {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use crate::core::KeyKind;
+    use crate::storage::planner::AccessStrategy;
+    use crate::storage::sample::SampleMethod;
+    use tempfile::TempDir;
+
+    /// Écrit CLIENT.NDX sur la rubrique data (3 derniers octets des enregistrements de 8 octets de write_fic)
+    fn write_data_index(dir: &Path, entries: &[(u8, u32)]) -> Result<()> {
+        let segments = vec![KeySegment { offset: 5, length: 3, kind: KeyKind::Binary, flags: 0 }];
+        let entries = entries.iter().map(|&(value, record_id)| (vec![0, 0, value], record_id)).collect();
+        NdxWriter::new(segments, false).write(dir.join("CLIENT.NDX"), entries)?;
+        Ok(())
+    }
+
+    fn open_engine(dir: &Path) -> Result<StorageEngine> {
+        let engine = StorageEngine::new_with_parallel(dir, true, false)?;
+        engine.scan_tables()?;
+        Ok(engine)
+    }
+
+    fn ids(result: &QueryResult) -> Vec<u32> {
+        result.records.iter().map(|record| record.id).collect()
+    }
+
+    /// Parcourt toutes les pages d'une requête en suivant next_cursor
+    fn follow_cursor(engine: &StorageEngine, filters: QueryFilters) -> Result<Vec<u32>> {
+        let mut all = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = engine.select("CLIENT", QueryFilters { cursor: cursor.take(), ..filters.clone() })?;
+            all.extend(ids(&page));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(all),
+            }
+        }
+    }
+
+    /// Crée des fichiers vides (fichiers compagnons dont seul le nom compte)
+    fn touch(dir: &Path, names: &[&str]) -> Result<()> {
+        for name in names {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap_or(dir))?;
+            std::fs::write(path, b"")?;
+        }
+        Ok(())
+    }
+
+    fn file_names(paths: &[PathBuf]) -> Vec<String> {
+        paths.iter().filter_map(|p| Some(p.file_name()?.to_string_lossy().to_string())).collect()
+    }
+
+    #[test]
+    fn test_indexed_reads_stop_after_the_page() -> Result<()> {
+        // L'index référence 1000 enregistrements mais le .fic n'en contient que 4 :
+        // toute lecture au-delà de la page (plus un enregistrement) échouerait
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 7), (false, 7), (false, 9), (false, 9)])?;
+        let mut entries: Vec<(u8, u32)> = (4..1000).map(|record_id| (7, record_id)).collect();
+        entries.extend([(7, 0), (7, 1), (9, 2), (9, 3)]);
+        write_data_index(dir.path(), &entries)?;
+        let engine = open_engine(dir.path())?;
+
+        let lookup = QueryFilters {
+            limit: Some(1),
+            field_filters: vec![FieldFilter { field: "data".to_string(), operator: FilterOperator::Equal, value: "000007".to_string() }],
+            ..Default::default()
+        };
+        let result = engine.select("CLIENT", lookup)?;
+        assert_eq!(result.plan.strategy, AccessStrategy::IndexLookup);
+        assert_eq!(ids(&result), vec![0]);
+        assert_eq!(result.total, None);
+        assert!(result.next_cursor.is_some());
+
+        // ORDER BY data DESC LIMIT 1 : l'index est parcouru depuis la fin
+        let ordered = QueryFilters { limit: Some(1), order_by: vec![SortKey::new("data", true)], ..Default::default() };
+        let result = engine.select("CLIENT", ordered)?;
+        assert_eq!(result.plan.strategy, AccessStrategy::IndexOrder);
+        assert_eq!(ids(&result), vec![3]);
+        assert_eq!(result.total, None);
+
+        let mut visited = Vec::new();
+        let stopped = engine.for_each_sorted("CLIENT", &[SortKey::new("data", true)], |record| {
+            visited.push(record.id);
+            anyhow::ensure!(visited.len() < 2, "arrêt du parcours");
+            Ok(())
+        });
+        assert_eq!(stopped.unwrap_err().to_string(), "arrêt du parcours");
+        assert_eq!(visited, vec![3, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_companion_files_ignore_case() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("client.fic"), 8, &[(false, 1)])?;
+        touch(dir.path(), &["CLIENT.MMO", "Client.Ndx", "CLIENT.ftx", "Jnl/CLIENT.JNL"])?;
+        let engine = open_engine(dir.path())?;
+
+        assert_eq!(engine.list_tables(), vec!["client".to_string()]);
+        let files = engine.table_files("client")?;
+        assert_eq!(file_names(&files.mmo_path.into_iter().collect::<Vec<_>>()), vec!["CLIENT.MMO"]);
+        assert_eq!(file_names(&files.ndx_paths), vec!["Client.Ndx"]);
+        assert_eq!(file_names(&files.ftx_paths), vec!["CLIENT.ftx"]);
+        assert_eq!(file_names(&files.journal_paths), vec!["CLIENT.JNL"]);
+        assert!(files.missing.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_numbered_index_extensions() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1)])?;
+        touch(dir.path(), &["CLIENT.NDX0", "CLIENT.NDX2", "CLIENT.NDXA", "CLIENT.NDX1B", "CLIENT.FTX1"])?;
+        let engine = open_engine(dir.path())?;
+
+        let files = engine.table_files("CLIENT")?;
+        assert_eq!(file_names(&files.ndx_paths), vec!["CLIENT.NDX0", "CLIENT.NDX2"]);
+        assert_eq!(file_names(&files.ftx_paths), vec!["CLIENT.FTX1"]);
+        assert_eq!(files.missing, vec!["CLIENT.NDX1", "CLIENT.FTX0"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_companions_follow_fic_case() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("orders.fic"), 8, &[(false, 1)])?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1)])?;
+        touch(dir.path(), &["orders.fi2", "orders.mm1", "orders.nd1", "CLIENT.MM1", "CLIENT.MMO.BAK"])?;
+        let engine = open_engine(dir.path())?;
+
+        // Segments sans le fichier principal : .mmo et .ndx signalés, ainsi que le segment .fi1
+        let orders = engine.table_files("orders")?;
+        assert_eq!(orders.missing, vec!["orders.fi1", "orders.mmo", "orders.ndx"]);
+        assert_eq!(file_names(&orders.segment_paths), vec!["orders.fi2", "orders.mm1", "orders.nd1"]);
+        assert!(orders.mmo_path.is_none() && orders.ndx_paths.is_empty());
+
+        let client = engine.table_files("CLIENT")?;
+        assert_eq!(client.missing, vec!["CLIENT.MMO"]);
+        Ok(())
+    }
+
+    fn sorted_tables(engine: &StorageEngine) -> Vec<String> {
+        let mut tables = engine.list_tables();
+        tables.sort();
+        tables
+    }
+
+    fn database_names(engine: &StorageEngine) -> Vec<String> {
+        engine.list_databases().into_iter().map(|database| database.name).collect()
+    }
+
+    #[test]
+    fn test_subfolders_become_namespaced_databases() -> Result<()> {
+        let dir = TempDir::new()?;
+        for folder in ["compta/archives", ".cache", "vide"] {
+            std::fs::create_dir_all(dir.path().join(folder))?;
+        }
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1)])?;
+        write_fic(&dir.path().join("compta/CLIENT.FIC"), 8, &[(false, 2)])?;
+        write_fic(&dir.path().join("compta/archives/FACTURE.FIC"), 8, &[(false, 3)])?;
+        write_fic(&dir.path().join(".cache/CACHE.FIC"), 8, &[(false, 4)])?;
+        let engine = open_engine(dir.path())?;
+
+        assert_eq!(sorted_tables(&engine), vec!["CLIENT", "compta.CLIENT", "compta.archives.FACTURE"]);
+        assert_eq!(database_names(&engine), vec!["", "compta", "compta.archives"]);
+
+        // Les noms non qualifiés sont cherchés à la racine, puis dans la base courante
+        assert!(!engine.has_table("FACTURE"));
+        engine.use_database(Some("compta.archives"))?;
+        assert!(engine.table_files("FACTURE")?.fic_path.starts_with(dir.path().join("compta/archives")));
+        assert_eq!(engine.table_files("CLIENT")?.fic_path, dir.path().join("CLIENT.FIC"));
+        assert!(engine.use_database(Some("inconnue")).is_err());
+        assert_eq!(engine.current_database().as_deref(), Some("compta.archives"));
+        engine.use_database(None)?;
+        assert!(!engine.has_table("FACTURE"));
+
+        assert!(engine.add_database("", dir.path().join("compta")).is_err());
+        assert!(engine.add_database("compta..bis", dir.path().join("compta")).is_err());
+        assert!(engine.add_database("compta-bis", dir.path().join("compta")).is_err());
+        assert!(engine.add_database("absente", dir.path().join("absente")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_added_databases_survive_rescans_without_duplicates() -> Result<()> {
+        let dir = TempDir::new()?;
+        let other = TempDir::new()?;
+        std::fs::create_dir(dir.path().join("sub"))?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1)])?;
+        write_fic(&dir.path().join("sub/FACTURE.FIC"), 8, &[(false, 2)])?;
+        write_fic(&other.path().join("ARCHIVE.FIC"), 8, &[(false, 3)])?;
+        let engine = open_engine(dir.path())?;
+        assert_eq!(sorted_tables(&engine), vec!["CLIENT", "sub.FACTURE"]);
+
+        assert_eq!(engine.add_database("archive", other.path())?, vec!["archive.ARCHIVE"]);
+        // Sous-dossier du dossier de données : enregistré sous son nom seulement
+        assert_eq!(engine.add_database("ventes", dir.path().join("sub"))?, vec!["ventes.FACTURE"]);
+        let expected = vec!["CLIENT", "archive.ARCHIVE", "ventes.FACTURE"];
+        assert_eq!(sorted_tables(&engine), expected);
+
+        engine.scan_tables()?;
+        assert_eq!(sorted_tables(&engine), expected);
+        engine.refresh_tables()?;
+        assert_eq!(sorted_tables(&engine), expected);
+        assert_eq!(database_names(&engine), vec!["", "archive", "ventes"]);
+
+        // Le même dossier ne peut pas être enregistré sous deux noms
+        assert!(engine.add_database("doublon", dir.path().join("sub/../sub")).is_err());
+        assert!(engine.add_database("racine", dir.path()).is_err());
+        assert_eq!(engine.add_database("ventes", dir.path().join("sub"))?, vec!["ventes.FACTURE"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reindex_rebuilds_from_records() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 3), (true, 1), (false, 2)])?;
+        // Index périmé : l'enregistrement 1 est supprimé et la clé de 2 a changé
+        write_data_index(dir.path(), &[(3, 0), (1, 1), (5, 2)])?;
+        let engine = StorageEngine::new_with_parallel(dir.path(), false, false)?;
+        engine.scan_tables()?;
+
+        let reports = engine.reindex("CLIENT", true)?;
+        let report = &reports[0];
+        assert_eq!((report.entries, report.existing_entries), (2, Some(3)));
+        let record_ids = |entries: &[IndexDiffEntry]| entries.iter().map(|e| e.record_id).collect::<Vec<_>>();
+        assert_eq!(record_ids(&report.missing), vec![2]);
+        assert_eq!(record_ids(&report.unexpected), vec![1, 2]);
+        assert!(!report.written);
+
+        assert!(engine.reindex("CLIENT", false)?[0].written);
+        assert!(engine.reindex("CLIENT", true)?[0].is_consistent());
+        Ok(())
+    }
+
+    #[test]
+    fn test_projection_decodes_only_needed_fields() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1), (false, 2)])?;
+        touch(dir.path(), &["CLIENT.MMO"])?;
+        let engine = open_engine(dir.path())?;
+        let files = engine.table_files("CLIENT")?;
+        assert!(files.mmo_path.is_some());
+
+        // Schéma avec une rubrique mémo ; le .mmo est supprimé après le scan :
+        // toute ouverture du .mmo fait échouer la requête
+        std::fs::remove_file(dir.path().join("CLIENT.MMO"))?;
+        engine.schema_cache.get_or_load(&files, |fic| {
+            let mut fields = fic.analyze_schema();
+            fields.push(crate::core::FieldInfo {
+                name: "notes".to_string(),
+                offset: 1,
+                length: 4,
+                field_type: crate::core::FieldType::Memo,
+                encoding: Default::default(),
+            });
+            Ok(TableSchema {
+                name: "CLIENT".to_string(),
+                record_length: fic.header().record_length,
+                field_count: fields.len() as u32,
+                fields,
+                indexes: Vec::new(),
+                full_text: Vec::new(),
+            })
+        })?;
+
+        let filters = QueryFilters {
+            projection: Some(vec!["data".to_string()]),
+            field_filters: vec![FieldFilter { field: "flags".to_string(), operator: FilterOperator::Equal, value: "0".to_string() }],
+            order_by: vec![SortKey::new("id", false)],
+            ..Default::default()
+        };
+        let decoded = engine.select_decoded("CLIENT", &filters, &CancelToken::new())?;
+        assert_eq!(decoded.plan.projection.as_deref(), Some("3/4 rubrique(s) décodée(s) : id, flags, data (mémos non lus)"));
+        for record in &decoded.records {
+            let mut names: Vec<&str> = record.fields.iter().map(|(name, _)| name.as_str()).collect();
+            names.sort();
+            assert_eq!(names, vec!["data", "flags", "id"]);
+        }
+        let result = engine.select("CLIENT", filters)?;
+        assert_eq!(ids(&result), vec![0, 1]);
+
+        let memo = QueryFilters { projection: Some(vec!["notes".to_string()]), ..Default::default() };
+        let error = engine.select("CLIENT", memo).unwrap_err();
+        assert!(format!("{:#}", error).contains("Impossible d'ouvrir le fichier .mmo"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_cursor_resumes_inside_duplicate_keys() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 7), (false, 7), (false, 7), (false, 7), (false, 9), (false, 9)])?;
+        write_data_index(dir.path(), &[(7, 0), (7, 1), (7, 2), (7, 3), (9, 4), (9, 5)])?;
+        // Doublons dans un ordre quelconque, comme dans un index HFSQL : les IDs
+        // de l'unique feuille (page 1, après un header de nœud de 8 octets) sont réécrits
+        let path = dir.path().join("CLIENT.NDX");
+        let mut bytes = std::fs::read(&path)?;
+        for (position, record_id) in [3u32, 0, 2, 1, 5, 4].into_iter().enumerate() {
+            let at = DEFAULT_PAGE_SIZE as usize + 8 + position * 7 + 3;
+            bytes[at..at + 4].copy_from_slice(&record_id.to_le_bytes());
+        }
+        std::fs::write(&path, bytes)?;
+        let engine = open_engine(dir.path())?;
+
+        let lookup = QueryFilters {
+            limit: Some(1),
+            field_filters: vec![FieldFilter { field: "data".to_string(), operator: FilterOperator::Equal, value: "000007".to_string() }],
+            ..Default::default()
+        };
+        assert_eq!(follow_cursor(&engine, lookup.clone())?, vec![3, 0, 2, 1]);
+        let ascending = QueryFilters { limit: Some(2), order_by: vec![SortKey::new("data", false)], ..Default::default() };
+        assert_eq!(follow_cursor(&engine, ascending)?, vec![3, 0, 2, 1, 5, 4]);
+        let descending = QueryFilters { limit: Some(2), order_by: vec![SortKey::new("data", true)], ..Default::default() };
+        assert_eq!(follow_cursor(&engine, descending)?, vec![4, 5, 1, 2, 0, 3]);
+
+        // L'entrée du curseur a disparu de l'index : reprise refusée
+        let first = engine.select("CLIENT", lookup.clone())?;
+        assert_eq!(ids(&first), vec![3]);
+        write_data_index(dir.path(), &[(7, 0), (7, 1), (7, 2), (9, 4), (9, 5)])?;
+        let error = engine.select("CLIENT", QueryFilters { cursor: first.next_cursor, ..lookup }).unwrap_err();
+        assert!(format!("{:#}", error).contains("Curseur périmé"), "{:#}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_physical_cursor_skips_deleted_records() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 8, &[(false, 1), (true, 2), (false, 3), (true, 4), (true, 5), (false, 6), (false, 7)])?;
+        let engine = open_engine(dir.path())?;
+
+        let page = QueryFilters { limit: Some(2), ..Default::default() };
+        let first = engine.select("CLIENT", page.clone())?;
+        assert_eq!(first.plan.strategy, AccessStrategy::FullScan);
+        assert_eq!(ids(&first), vec![0, 2]);
+        assert_eq!(first.total, None);
+        let second = engine.select("CLIENT", QueryFilters { cursor: first.next_cursor.clone(), ..page.clone() })?;
+        assert_eq!(ids(&second), vec![5, 6]);
+        assert_eq!((second.total, second.next_cursor), (None, None));
+        assert_eq!(follow_cursor(&engine, QueryFilters { limit: Some(1), ..Default::default() })?, vec![0, 2, 5, 6]);
+
+        // Toute la table lue sur la première page : total connu, pas de curseur
+        let last = engine.select("CLIENT", QueryFilters { limit: Some(4), ..Default::default() })?;
+        assert_eq!((last.total, last.next_cursor), (Some(4), None));
+
+        // Curseur d'une autre requête, ou combiné avec offset ou un échantillon
+        let filtered = QueryFilters {
+            field_filters: vec![FieldFilter { field: "data".to_string(), operator: FilterOperator::Equal, value: "000007".to_string() }],
+            cursor: first.next_cursor.clone(),
+            ..page.clone()
+        };
+        let error = engine.select("CLIENT", filtered).unwrap_err();
+        assert!(error.to_string().contains("Curseur invalide pour cette requête"), "{:#}", error);
+        let error = engine.select("CLIENT", QueryFilters { offset: Some(1), cursor: first.next_cursor.clone(), ..page.clone() }).unwrap_err();
+        assert!(error.to_string().contains("offset et cursor"), "{:#}", error);
+        let sampled = QueryFilters { sample: Some(SampleSpec::new(SampleMethod::Reservoir, 2)), cursor: first.next_cursor, ..page };
+        let error = engine.select("CLIENT", sampled).unwrap_err();
+        assert!(error.to_string().contains("échantillon"), "{:#}", error);
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+/**
+ * Définitions de clés sauvegardées hors des fichiers .ndx.
+ *
+ * Les segments d'un index ne sont décrits que dans le header de son .ndx :
+ * si ce header devient illisible, l'index ne peut plus être reconstruit.
+ * Les définitions lues dans les headers sont donc recopiées dans un
+ * fichier annexe à côté du .fic ({table}.ndx.json), relu par reindex pour
+ * les index dont le header est illisible.
+ *
+ * Le fichier annexe n'est écrit que par un moteur en écriture, hors
+ * archive, et seulement si les définitions ont changé. Une définition dont
+ * le header est illisible est conservée tant que le fichier .ndx existe.
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/source.rs (lecture dans un dossier ou une archive)
+ * - Utilisé par src/storage/engine.rs (chargement des schémas, reindex)
+ */
+
+use crate::core::{source, IndexDefinition, KeySegment, TableFiles};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Définition d'un index sauvegardée dans le fichier annexe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedIndex {
+    /// Nom du fichier .ndx
+    pub name: String,
+    /// Segments de la clé
+    pub segments: Vec<KeySegment>,
+    /// Index à clé unique
+    pub unique: bool,
+}
+
+/**
+ * Chemin du fichier annexe d'une table.
+ *
+ * @param fic_path - Chemin du fichier .fic
+ * @returns PathBuf - {table}.ndx.json (ou .NDX.JSON si le .fic est en majuscules)
+ */
+pub fn sidecar_path(fic_path: &Path) -> PathBuf {
+    let stem = fic_path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let upper = fic_path.extension().is_some_and(|ext| !ext.to_string_lossy().chars().any(|c| c.is_ascii_lowercase()));
+    fic_path.with_file_name(format!("{}.{}", stem, if upper { "NDX.JSON" } else { "ndx.json" }))
+}
+
+/**
+ * Lit les définitions sauvegardées d'une table.
+ *
+ * @param fic_path - Chemin du fichier .fic
+ * @returns Result<Vec<SavedIndex>> - Définitions (vide si le fichier annexe
+ *          n'existe pas), ou erreur s'il est illisible
+ */
+pub fn load(fic_path: &Path) -> Result<Vec<SavedIndex>> {
+    let path = sidecar_path(fic_path);
+    if !source::exists(&path) {
+        return Ok(Vec::new());
+    }
+    let file = source::open(&path).with_context(|| format!("Impossible d'ouvrir {:?}", path))?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .with_context(|| format!("Définitions de clés invalides dans {:?}", path))
+}
+
+/**
+ * Met à jour le fichier annexe avec les définitions lues dans les headers.
+ *
+ * Les définitions sauvegardées d'index présents mais absents de `indexes`
+ * (header illisible) sont conservées ; celles des fichiers .ndx disparus
+ * sont retirées.
+ *
+ * @param table_files - Fichiers de la table
+ * @param indexes - Définitions lues dans les headers lisibles
+ * @returns Result<bool> - true si le fichier annexe a été (ré)écrit
+ *
+ * Effets de bord :
+ * - Lit et peut remplacer {table}.ndx.json
+ */
+pub fn save(table_files: &TableFiles, indexes: &[IndexDefinition]) -> Result<bool> {
+    let previous = load(&table_files.fic_path).unwrap_or_default();
+    let saved: Vec<SavedIndex> = table_files.ndx_paths.iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            match indexes.iter().find(|index| index.path == *path) {
+                Some(index) => Some(SavedIndex { name, segments: index.segments.clone(), unique: index.unique }),
+                None => previous.iter().find(|s| s.name.eq_ignore_ascii_case(&name)).cloned(),
+            }
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&saved)?;
+    let unchanged = serde_json::to_string_pretty(&previous).is_ok_and(|previous| previous == json);
+    if unchanged {
+        return Ok(false);
+    }
+    let path = sidecar_path(&table_files.fic_path);
+    std::fs::write(&path, json).with_context(|| format!("Impossible d'écrire {:?}", path))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::KeyKind;
+
+    fn definition(path: &Path, offset: u32) -> IndexDefinition {
+        IndexDefinition {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path: path.to_path_buf(),
+            fields: vec![None],
+            segments: vec![KeySegment { offset, length: 4, kind: KeyKind::Integer, flags: 0 }],
+            encodings: Vec::new(),
+            unique: true,
+        }
+    }
+
+    #[test]
+    fn test_saved_definitions_outlive_headers() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let fic_path = dir.path().join("CLIENT.FIC");
+        let ndx_paths = vec![dir.path().join("CLIENT.NDX"), dir.path().join("CLIENT.NDX0")];
+        let table_files = TableFiles {
+            name: "CLIENT".to_string(),
+            database: String::new(),
+            fic_path: fic_path.clone(),
+            mmo_path: None,
+            ndx_paths: ndx_paths.clone(),
+            ftx_paths: Vec::new(),
+            journal_paths: Vec::new(),
+            segment_paths: Vec::new(),
+            missing: Vec::new(),
+        };
+        assert_eq!(sidecar_path(&fic_path), dir.path().join("CLIENT.NDX.JSON"));
+
+        let indexes = vec![definition(&ndx_paths[0], 1), definition(&ndx_paths[1], 5)];
+        assert!(save(&table_files, &indexes)?);
+        assert!(!save(&table_files, &indexes)?);
+
+        // Header de CLIENT.NDX0 illisible : sa définition reste sauvegardée
+        assert!(!save(&table_files, &indexes[..1])?);
+        let saved = load(&fic_path)?;
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[1].name, "CLIENT.NDX0");
+        assert_eq!(saved[1].segments[0].offset, 5);
+        Ok(())
+    }
+}
@@ -4,22 +4,64 @@
  * Ce module fournit une couche d'abstraction haut niveau pour accéder
  * aux données HFSQL. Il gère :
  * 
- * - La détection et le scan des tables dans un dossier
+ * - La détection et le scan des tables dans un dossier et ses sous-dossiers
+ * - Plusieurs bases de données (une par dossier) avec noms qualifiés
  * - La lecture et l'écriture d'enregistrements
  * - La conversion des données brutes en structures typées
  * - Les requêtes avec filtres et pagination
  * - La gestion des schémas de tables
+ * - La reconstruction des index .ndx
+ * - L'historique des modifications (journaux .jnl)
  * 
  * Structure :
  * - engine.rs : Moteur de stockage principal (StorageEngine)
+ * - async_engine.rs : Façade asynchrone pour le serveur (pool dédié, annulation, délai)
+ * - cache.rs : Cache des schémas et fichiers ouverts (invalidé si un fichier change)
+ * - cancel.rs : Jeton d'annulation et délai d'exécution des requêtes
+ * - cursor.rs : Curseurs de pagination (reprise après le dernier enregistrement d'une page)
+ * - keydefs.rs : Définitions de clés des index sauvegardées hors des .ndx (reindex)
+ * - planner.rs : Choix du chemin d'accès (parcours complet ou index)
+ * - predicate.rs : Évaluation typée des conditions WHERE
+ * - record.rs : Enregistrements décodés (rubriques ordonnées, mémos, octets bruts)
+ * - sample.rs : Échantillonnage aléatoire des enregistrements (TABLESAMPLE)
+ * - sort.rs : Tri des enregistrements (ORDER BY) en mémoire bornée
+ * - watcher.rs : Surveillance des dossiers et événements de modification des tables
  * 
  * Exports :
  * - StorageEngine : Moteur principal de stockage
+ * - AsyncEngine : Lectures du moteur exécutées hors du runtime tokio
+ * - CancelToken, Interrupted : Annulation et délai des requêtes
+ * - DatabaseInfo, MissingTable : Base de données enregistrée (dossier, tables, entrées .rep introuvables)
  * - QueryFilters, QueryResult : Structures pour les requêtes
- * - Record, FieldValue : Structures pour les données
+ * - SortKey, NullsOrder, Collation : Clés de tri (ORDER BY)
+ * - SampleSpec, SampleMethod : Échantillon aléatoire (TABLESAMPLE)
+ * - QueryPlan : Chemin d'accès retenu pour une requête
+ * - CacheStats : Statistiques du cache des schémas
+ * - TableWatcher, TableChange, TableChangeKind : Surveillance des fichiers des tables
+ * - ReindexReport : Résultat de la reconstruction d'un index
+ * - HistoryFilters, HistoryEntry : Historique des modifications
+ * - Record, Fields, FieldValue, RawRef : Structures pour les données
  */
 
+pub mod async_engine;
+pub mod cache;
+pub mod cancel;
+pub mod cursor;
 pub mod engine;
+pub mod keydefs;
+pub mod planner;
+pub mod predicate;
+pub mod record;
+pub mod sample;
+pub mod sort;
+pub mod watcher;
 
-pub use engine::{QueryFilters, QueryResult, Record, StorageEngine};
+pub use engine::{Collation, DatabaseInfo, FieldFilter, FilterOperator, HistoryEntry, HistoryFilters, MissingTable, NullsOrder, QueryFilters, QueryResult, ReindexReport, SortKey, StorageEngine};
+pub use async_engine::AsyncEngine;
+pub use cache::CacheStats;
+pub use cancel::{CancelToken, Interrupted};
+pub use planner::QueryPlan;
+pub use record::{FieldValue, Fields, RawRef, RawSource, Record, RecordField};
+pub use sample::{SampleMethod, SampleSpec};
+pub use watcher::{TableChange, TableChangeKind, TableWatcher};
 
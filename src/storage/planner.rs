@@ -0,0 +1,586 @@
+/**
+ * Planificateur de requêtes pour le moteur de stockage.
+ *
+ * Ce fichier décide comment StorageEngine::select accède aux données :
+ * parcours complet du fichier .fic, ou accès par un index .ndx dont le
+ * premier segment porte sur une rubrique filtrée ou triée.
+ *
+ * Règles de choix :
+ * - ORDER BY sur une seule rubrique indexée, avec une collation compatible
+ *   avec l'index et la position par défaut des NULL : l'ordre est fourni
+ *   par l'index
+ * - Autre ORDER BY : les enregistrements retenus par le chemin d'accès
+ *   ci-dessous sont triés par src/storage/sort.rs
+ * - Égalité ou intervalle borné des deux côtés sur une rubrique indexée : index
+ * - Intervalle ouvert (ex: age > 30) : parcours séquentiel, généralement plus
+ *   rapide que des lectures aléatoires sur une grande partie du fichier
+ * - Sinon : parcours complet
+ * - MATCH/CONTAINS : recherche dans les index full-text (.ftx), résultats
+ *   classés par pertinence (ou triés si la requête a un ORDER BY)
+ *
+ * Quel que soit le chemin retenu, toutes les conditions sont ensuite vérifiées
+ * sur les enregistrements décodés (src/storage/predicate.rs).
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/key.rs pour encoder les valeurs en clés d'index
+ * - Utilisé par src/storage/engine.rs (select)
+ */
+
+use crate::core::key::encode_value_as;
+use crate::core::{FieldType, IndexDefinition, KeyKind, TableSchema};
+use crate::storage::engine::{FieldFilter, FilterOperator, QueryFilters, SortKey};
+use crate::storage::predicate::normalize_date;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+
+/// Stratégie d'accès aux données
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessStrategy {
+    /// Lecture séquentielle de tout le fichier .fic
+    FullScan,
+    /// Recherche d'une valeur exacte dans un index
+    IndexLookup,
+    /// Parcours d'un intervalle de clés d'un index
+    IndexRange,
+    /// Parcours complet d'un index pour obtenir l'ordre de tri
+    IndexOrder,
+    /// Recherche dans les index full-text, par pertinence
+    FullText,
+}
+
+/// Plan d'exécution retenu pour une requête
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlan {
+    /// Stratégie d'accès utilisée
+    pub strategy: AccessStrategy,
+    /// Nom de l'index utilisé (le cas échéant)
+    pub index: Option<String>,
+    /// Rubrique sur laquelle porte l'index (le cas échéant)
+    pub field: Option<String>,
+    /// Explication lisible du choix
+    pub detail: String,
+    /// Conditions évaluées sur les enregistrements décodés (filtre résiduel)
+    #[serde(default)]
+    pub residual: Vec<String>,
+    /// Tri effectué sur les enregistrements lus et méthode utilisée
+    /// (None si la requête n'a pas d'ORDER BY ou si l'index fournit l'ordre)
+    #[serde(default)]
+    pub sort: Option<String>,
+    /// Rubriques décodées quand la requête porte une projection
+    /// (None si toutes les rubriques sont décodées)
+    #[serde(default)]
+    pub projection: Option<String>,
+    /// Échantillon tiré (méthode, graine pour le rejouer, enregistrements lus)
+    #[serde(default)]
+    pub sample: Option<String>,
+}
+
+impl QueryPlan {
+    fn full_scan(detail: String) -> Self {
+        Self {
+            strategy: AccessStrategy::FullScan,
+            index: None,
+            field: None,
+            detail,
+            residual: Vec::new(),
+            sort: None,
+            projection: None,
+            sample: None,
+        }
+    }
+}
+
+/// Accès indexé à exécuter
+#[derive(Debug, Clone)]
+pub struct IndexAccess {
+    /// Index à parcourir
+    pub index: IndexDefinition,
+    /// Borne inférieure (forme stockée de la clé)
+    pub lower: Bound<Vec<u8>>,
+    /// Borne supérieure (forme stockée de la clé)
+    pub upper: Bound<Vec<u8>>,
+    /// Parcourir l'intervalle en sens inverse
+    pub reverse: bool,
+}
+
+/// Recherche full-text à exécuter
+#[derive(Debug, Clone)]
+pub struct FullTextAccess {
+    /// Mots recherchés
+    pub query: String,
+    /// Rubrique interrogée (None = tous les index full-text)
+    pub field: Option<String>,
+}
+
+/// Résultat de la planification
+#[derive(Debug, Clone)]
+pub struct PlannedQuery {
+    /// Plan rapporté à l'appelant
+    pub plan: QueryPlan,
+    /// Accès indexé (None = parcours complet)
+    pub access: Option<IndexAccess>,
+    /// Recherche full-text (prioritaire sur l'accès indexé)
+    pub full_text: Option<FullTextAccess>,
+    /// Tri à effectuer sur les enregistrements lus (vide si l'ordre est fourni par l'accès)
+    pub sort: Vec<SortKey>,
+}
+
+/// Bornes calculées pour un index à partir des filtres
+struct IndexBounds {
+    lower: Bound<Vec<u8>>,
+    upper: Bound<Vec<u8>>,
+    equality: bool,
+}
+
+impl IndexBounds {
+    fn is_bounded(&self) -> bool {
+        !matches!(self.lower, Bound::Unbounded) || !matches!(self.upper, Bound::Unbounded)
+    }
+
+    fn is_closed(&self) -> bool {
+        !matches!(self.lower, Bound::Unbounded) && !matches!(self.upper, Bound::Unbounded)
+    }
+}
+
+/**
+ * Choisit le chemin d'accès d'une requête de sélection.
+ *
+ * @param schema - Schéma de la table (avec ses index)
+ * @param filters - Filtres et tri de la requête
+ * @returns Result<PlannedQuery> - Plan retenu, ou erreur si aucun index
+ *          full-text ne couvre une condition MATCH
+ *
+ * Effets de bord : Aucun
+ */
+pub fn plan_query(schema: &TableSchema, filters: &QueryFilters) -> Result<PlannedQuery> {
+    let mut planned = match plan_full_text(schema, filters)? {
+        Some(planned) => planned,
+        None => {
+            if let Some(planned) = plan_index_order(schema, filters) {
+                return Ok(planned);
+            }
+            plan_filters(schema, filters)
+        }
+    };
+
+    if !filters.order_by.is_empty() {
+        let keys: Vec<String> = filters.order_by.iter().map(|key| key.to_string()).collect();
+        planned.plan.sort = Some(keys.join(", "));
+        planned.sort = filters.order_by.clone();
+    }
+    Ok(planned)
+}
+
+/**
+ * Utilise un index pour fournir l'ordre d'un ORDER BY sur une seule rubrique.
+ *
+ * L'index convient si son premier segment porte sur la rubrique triée, si
+ * ses options de texte correspondent à la collation demandée, et si la
+ * position des NULL est celle par défaut.
+ */
+fn plan_index_order(schema: &TableSchema, filters: &QueryFilters) -> Option<PlannedQuery> {
+    let [sort] = filters.order_by.as_slice() else {
+        return None;
+    };
+    if sort.nulls_first() != sort.descending {
+        return None;
+    }
+
+    let index = schema.indexes.iter()
+        .filter(|index| first_field(index) == Some(sort.field.as_str()) && provides_collation(index, sort))
+        .max_by_key(|index| index.unique)?;
+
+    let bounds = index_bounds(schema, index, &filters.field_filters);
+    let reverse = sort.descending != index.segments[0].is_descending();
+    let strategy = if bounds.equality {
+        AccessStrategy::IndexLookup
+    } else if bounds.is_bounded() {
+        AccessStrategy::IndexRange
+    } else {
+        AccessStrategy::IndexOrder
+    };
+    Some(index_plan(index, bounds, reverse, strategy, format!("ordre de {} fourni par l'index {}", sort, index.name)))
+}
+
+/// Indique si les clés de l'index sont comparées selon la collation de la clé de tri
+fn provides_collation(index: &IndexDefinition, sort: &SortKey) -> bool {
+    let Some(segment) = index.segments.first() else {
+        return false;
+    };
+    segment.kind != KeyKind::String
+        || (segment.is_case_insensitive(), segment.is_accent_insensitive()) == sort.collation.folding()
+}
+
+/// Choisit l'accès (index ou parcours complet) d'après les filtres seuls
+fn plan_filters(schema: &TableSchema, filters: &QueryFilters) -> PlannedQuery {
+    let mut best: Option<(&IndexDefinition, IndexBounds)> = None;
+    for index in &schema.indexes {
+        if first_field(index).is_none() {
+            continue;
+        }
+        let bounds = index_bounds(schema, index, &filters.field_filters);
+        if !bounds.is_bounded() {
+            continue;
+        }
+        let better = match &best {
+            None => true,
+            Some((current, current_bounds)) => {
+                (bounds.equality, bounds.is_closed(), index.unique)
+                    > (current_bounds.equality, current_bounds.is_closed(), current.unique)
+            }
+        };
+        if better {
+            best = Some((index, bounds));
+        }
+    }
+
+    match best {
+        Some((index, bounds)) if bounds.equality => {
+            let detail = format!("égalité sur {} via l'index {}", first_field(index).unwrap_or_default(), index.name);
+            index_plan(index, bounds, false, AccessStrategy::IndexLookup, detail)
+        }
+        Some((index, bounds)) if bounds.is_closed() => {
+            let detail = format!("intervalle sur {} via l'index {}", first_field(index).unwrap_or_default(), index.name);
+            index_plan(index, bounds, false, AccessStrategy::IndexRange, detail)
+        }
+        Some((index, _)) => PlannedQuery {
+            plan: QueryPlan::full_scan(format!(
+                "intervalle ouvert sur {}: parcours séquentiel préféré à l'index {}",
+                first_field(index).unwrap_or_default(),
+                index.name
+            )),
+            access: None,
+            full_text: None,
+            sort: Vec::new(),
+        },
+        None => PlannedQuery {
+            plan: QueryPlan::full_scan("aucun filtre ne porte sur une rubrique indexée".to_string()),
+            access: None,
+            full_text: None,
+            sort: Vec::new(),
+        },
+    }
+}
+
+/**
+ * Planifie une condition MATCH/CONTAINS, si la requête en contient une.
+ *
+ * La rubrique "*" interroge tous les index full-text de la table.
+ * Les résultats sont classés par pertinence, sauf si la requête a un ORDER BY.
+ */
+fn plan_full_text(schema: &TableSchema, filters: &QueryFilters) -> Result<Option<PlannedQuery>> {
+    let mut matches = filters.field_filters.iter().filter(|f| f.operator == FilterOperator::Match);
+    let Some(filter) = matches.next() else {
+        return Ok(None);
+    };
+    if matches.next().is_some() {
+        anyhow::bail!("Une seule condition MATCH/CONTAINS est supportée par requête");
+    }
+    let field = (filter.field != "*").then(|| filter.field.clone());
+    let names: Vec<&str> = schema.full_text.iter()
+        .filter(|d| field.as_ref().map(|f| d.fields.iter().any(|name| name.as_ref() == Some(f))).unwrap_or(true))
+        .map(|d| d.name.as_str())
+        .collect();
+    if names.is_empty() {
+        anyhow::bail!("MATCH sur {} nécessite un index full-text (.ftx)", filter.field);
+    }
+
+    Ok(Some(PlannedQuery {
+        plan: QueryPlan {
+            strategy: AccessStrategy::FullText,
+            index: Some(names.join(", ")),
+            field: field.clone(),
+            detail: format!("recherche full-text '{}' via {}", filter.value, names.join(", ")),
+            residual: Vec::new(),
+            sort: None,
+            projection: None,
+            sample: None,
+        },
+        access: None,
+        full_text: Some(FullTextAccess {
+            query: filter.value.clone(),
+            field,
+        }),
+        sort: Vec::new(),
+    }))
+}
+
+/// Construit un plan d'accès indexé
+fn index_plan(index: &IndexDefinition, bounds: IndexBounds, reverse: bool, strategy: AccessStrategy, detail: String) -> PlannedQuery {
+    PlannedQuery {
+        plan: QueryPlan {
+            strategy,
+            index: Some(index.name.clone()),
+            field: first_field(index).map(|f| f.to_string()),
+            detail,
+            residual: Vec::new(),
+            sort: None,
+            projection: None,
+            sample: None,
+        },
+        access: Some(IndexAccess {
+            index: index.clone(),
+            lower: bounds.lower,
+            upper: bounds.upper,
+            reverse,
+        }),
+        full_text: None,
+        sort: Vec::new(),
+    }
+}
+
+/// Rubrique couverte par le premier segment de l'index
+fn first_field(index: &IndexDefinition) -> Option<&str> {
+    index.fields.first().and_then(|f| f.as_deref())
+}
+
+/**
+ * Calcule l'intervalle de clés couvert par les filtres portant sur la
+ * première rubrique de l'index.
+ *
+ * Pour un index composé, les segments suivants sont complétés par 0x00
+ * (borne basse) ou 0xFF (borne haute). Pour un segment descendant, les
+ * comparaisons sont inversées puisque les octets de la clé le sont.
+ * Les filtres dont la valeur n'est pas convertible sont ignorés. Les
+ * chaînes sont encodées dans l'encodage de la rubrique, les dates sous
+ * leur forme stockée (AAAAMMJJ, voir predicate::normalize_date).
+ *
+ * Un segment insensible à la casse ou aux accents ne borne que les
+ * égalités : ses clés repliées ne suivent pas l'ordre des comparaisons
+ * (sensibles à la casse) des prédicats, un intervalle écarterait des
+ * enregistrements qui les vérifient ("Zed" < 'a' mais "ZED" > "A").
+ */
+fn index_bounds(schema: &TableSchema, index: &IndexDefinition, filters: &[FieldFilter]) -> IndexBounds {
+    let mut bounds = IndexBounds {
+        lower: Bound::Unbounded,
+        upper: Bound::Unbounded,
+        equality: false,
+    };
+    let (Some(field), Some(segment)) = (first_field(index), index.segments.first()) else {
+        return bounds;
+    };
+    let rest: usize = index.segments[1..].iter().map(|s| s.length as usize).sum();
+    let info = schema.fields.iter().find(|f| f.name == field);
+    let encoding = info.map(|f| f.encoding).unwrap_or_default();
+    let is_date = segment.kind == KeyKind::Date || info.map(|f| matches!(f.field_type, FieldType::Date)).unwrap_or(false);
+    let folded = segment.kind == KeyKind::String && (segment.is_case_insensitive() || segment.is_accent_insensitive());
+
+    for filter in filters.iter().filter(|f| f.field == field) {
+        if folded && filter.operator != FilterOperator::Equal {
+            continue;
+        }
+        let value = if is_date { normalize_date(&filter.value) } else { filter.value.clone() };
+        let Ok(prefix) = encode_value_as(&value, segment, encoding) else {
+            continue;
+        };
+        let low_key = padded(&prefix, rest, 0x00);
+        let high_key = padded(&prefix, rest, 0xFF);
+
+        let operator = if segment.is_descending() {
+            match filter.operator {
+                FilterOperator::GreaterThan => FilterOperator::LessThan,
+                FilterOperator::GreaterThanOrEqual => FilterOperator::LessThanOrEqual,
+                FilterOperator::LessThan => FilterOperator::GreaterThan,
+                FilterOperator::LessThanOrEqual => FilterOperator::GreaterThanOrEqual,
+                other => other,
+            }
+        } else {
+            filter.operator
+        };
+
+        match operator {
+            FilterOperator::Equal => {
+                bounds.lower = tighten(bounds.lower, Bound::Included(low_key), true);
+                bounds.upper = tighten(bounds.upper, Bound::Included(high_key), false);
+                bounds.equality = true;
+            }
+            FilterOperator::GreaterThan => bounds.lower = tighten(bounds.lower, Bound::Excluded(high_key), true),
+            FilterOperator::GreaterThanOrEqual => bounds.lower = tighten(bounds.lower, Bound::Included(low_key), true),
+            FilterOperator::LessThan => bounds.upper = tighten(bounds.upper, Bound::Excluded(low_key), false),
+            FilterOperator::LessThanOrEqual => bounds.upper = tighten(bounds.upper, Bound::Included(high_key), false),
+            FilterOperator::NotEqual | FilterOperator::Like | FilterOperator::NotLike | FilterOperator::Match => {}
+        }
+    }
+
+    bounds
+}
+
+/// Complète un préfixe de clé avec `rest` octets de remplissage
+fn padded(prefix: &[u8], rest: usize, fill: u8) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.resize(prefix.len() + rest, fill);
+    key
+}
+
+/**
+ * Retourne la plus restrictive de deux bornes.
+ *
+ * @param lower - true pour une borne inférieure, false pour une borne supérieure
+ */
+fn tighten(current: Bound<Vec<u8>>, candidate: Bound<Vec<u8>>, lower: bool) -> Bound<Vec<u8>> {
+    let (current_key, candidate_key) = match (&current, &candidate) {
+        (Bound::Unbounded, _) => return candidate,
+        (_, Bound::Unbounded) => return current,
+        (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b) | Bound::Excluded(b)) => (a, b),
+    };
+    if candidate_key == current_key {
+        return if matches!(candidate, Bound::Excluded(_)) { candidate } else { current };
+    }
+    let candidate_is_tighter = if lower {
+        candidate_key > current_key
+    } else {
+        candidate_key < current_key
+    };
+    if candidate_is_tighter { candidate } else { current }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FieldInfo, FieldType, KeyKind, KeySegment};
+    use crate::storage::engine::SortKey;
+
+    fn schema_with_index() -> TableSchema {
+        TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: 16,
+            field_count: 2,
+            fields: vec![
+                FieldInfo { name: "code".to_string(), offset: 1, length: 4, field_type: FieldType::Integer, encoding: Default::default() },
+                FieldInfo { name: "nom".to_string(), offset: 5, length: 11, field_type: FieldType::String, encoding: Default::default() },
+            ],
+            indexes: vec![IndexDefinition {
+                name: "CLIENT.NDX".to_string(),
+                path: "CLIENT.NDX".into(),
+                fields: vec![Some("code".to_string())],
+                segments: vec![KeySegment { offset: 1, length: 4, kind: KeyKind::Integer, flags: 0 }],
+                encodings: Vec::new(),
+                unique: true,
+            }],
+            full_text: Vec::new(),
+        }
+    }
+
+    fn filter(field: &str, operator: FilterOperator, value: &str) -> FieldFilter {
+        FieldFilter { field: field.to_string(), operator, value: value.to_string() }
+    }
+
+    #[test]
+    fn test_equality_uses_index() -> Result<()> {
+        let filters = QueryFilters {
+            field_filters: vec![filter("code", FilterOperator::Equal, "42")],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::IndexLookup);
+        let access = planned.access.expect("accès indexé attendu");
+        assert_eq!(access.lower, Bound::Included(vec![0x80, 0, 0, 42]));
+        assert_eq!(access.upper, Bound::Included(vec![0x80, 0, 0, 42]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_range_prefers_scan() -> Result<()> {
+        let filters = QueryFilters {
+            field_filters: vec![filter("code", FilterOperator::GreaterThan, "10")],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::FullScan);
+
+        let filters = QueryFilters {
+            field_filters: vec![
+                filter("code", FilterOperator::GreaterThan, "-5"),
+                filter("code", FilterOperator::LessThanOrEqual, "10"),
+            ],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::IndexRange);
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by_index_or_sort() -> Result<()> {
+        let filters = QueryFilters {
+            order_by: vec![SortKey::new("code", true)],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::IndexOrder);
+        assert!(planned.sort.is_empty());
+        assert!(planned.access.map(|a| a.reverse).unwrap_or(false));
+
+        // Sans index sur la rubrique, ou avec plusieurs clés : tri des enregistrements lus
+        let filters = QueryFilters {
+            field_filters: vec![filter("code", FilterOperator::Equal, "42")],
+            order_by: vec![SortKey::new("nom", false)],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::IndexLookup);
+        assert_eq!(planned.sort, filters.order_by);
+        assert_eq!(planned.plan.sort.as_deref(), Some("nom ASC"));
+
+        let mut code = SortKey::new("code", false);
+        code.nulls = Some(crate::storage::engine::NullsOrder::First);
+        let filters = QueryFilters {
+            order_by: vec![code, SortKey::new("nom", true)],
+            ..Default::default()
+        };
+        let planned = plan_query(&schema_with_index(), &filters)?;
+        assert_eq!(planned.plan.strategy, AccessStrategy::FullScan);
+        assert_eq!(planned.sort.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounds_follow_stored_keys() -> Result<()> {
+        let mut schema = schema_with_index();
+        schema.fields.push(FieldInfo { name: "naissance".to_string(), offset: 16, length: 8, field_type: FieldType::Date, encoding: Default::default() });
+        schema.indexes = vec![
+            IndexDefinition {
+                name: "NOM.NDX".to_string(),
+                path: "NOM.NDX".into(),
+                fields: vec![Some("nom".to_string())],
+                segments: vec![KeySegment { offset: 5, length: 11, kind: KeyKind::String, flags: crate::core::ndx::SEGMENT_CASE_INSENSITIVE }],
+                encodings: Vec::new(),
+                unique: false,
+            },
+            IndexDefinition {
+                name: "NAISSANCE.NDX".to_string(),
+                path: "NAISSANCE.NDX".into(),
+                fields: vec![Some("naissance".to_string())],
+                segments: vec![KeySegment { offset: 16, length: 8, kind: KeyKind::Date, flags: 0 }],
+                encodings: Vec::new(),
+                unique: false,
+            },
+        ];
+
+        // Date saisie avec séparateurs : clé AAAAMMJJ
+        let filters = QueryFilters {
+            field_filters: vec![filter("naissance", FilterOperator::Equal, "1982-03-15")],
+            ..Default::default()
+        };
+        let access = plan_query(&schema, &filters)?.access.expect("accès indexé attendu");
+        assert_eq!(access.lower, Bound::Included(b"19820315".to_vec()));
+
+        // Index sans casse : égalité seulement, pas d'intervalle
+        let filters = QueryFilters {
+            field_filters: vec![
+                filter("nom", FilterOperator::GreaterThanOrEqual, "A"),
+                filter("nom", FilterOperator::LessThan, "a"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(plan_query(&schema, &filters)?.plan.strategy, AccessStrategy::FullScan);
+        let filters = QueryFilters {
+            field_filters: vec![filter("nom", FilterOperator::Equal, "Zed")],
+            ..Default::default()
+        };
+        assert_eq!(plan_query(&schema, &filters)?.plan.strategy, AccessStrategy::IndexLookup);
+        Ok(())
+    }
+}
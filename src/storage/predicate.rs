@@ -0,0 +1,315 @@
+/**
+ * Évaluation des conditions WHERE sur les enregistrements décodés.
+ *
+ * Ce fichier compile les FieldFilter d'une requête en prédicats typés selon
+ * le schéma de la table, puis les évalue sur chaque Record. Il sert de filtre
+ * résiduel quel que soit le chemin d'accès retenu par le planificateur : un
+ * index ou une recherche full-text réduit les candidats, les prédicats
+ * décident des enregistrements retournés.
+ *
+ * Sémantique des comparaisons :
+ * - Rubriques numériques : comparaison numérique (entier, ou réel si la
+ *   valeur contient une décimale) ; une valeur non numérique est une erreur
+ * - Chaînes : comparaison exacte, espaces de fin ignorés
+ * - Dates : "2024-01-15" et "20240115" sont équivalentes
+ * - Binaires : comparaison hexadécimale, préfixe "0x" accepté
+ * - Mémos : comparaison sur le texte lu dans le fichier .mmo
+ * - LIKE : jokers % et _, insensible à la casse, sur la forme texte de la valeur
+ * - Valeur NULL : aucune condition n'est vérifiée (comme en SQL)
+ *
+ * Les conditions MATCH sont traitées par les index full-text et ignorées ici.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (select)
+ */
+
+use crate::core::{FieldType, TableSchema};
+use crate::storage::engine::{FieldFilter, FieldValue, FilterOperator, Record};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Valeur de comparaison convertie selon le type de la rubrique
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Date(String),
+    Binary(String),
+}
+
+/// Condition compilée, prête à être évaluée sur un enregistrement
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    /// Rubrique testée
+    field: String,
+    /// Opérateur de comparaison
+    operator: FilterOperator,
+    /// Valeur convertie
+    operand: Operand,
+    /// Valeur telle que saisie (pour LIKE et l'affichage)
+    raw: String,
+}
+
+/**
+ * Compile les conditions d'une requête selon le schéma de la table.
+ *
+ * @param schema - Schéma de la table
+ * @param filters - Conditions de la requête (combinées par AND)
+ * @returns Result<Vec<Predicate>> - Prédicats, ou erreur si une rubrique est
+ *          inconnue ou si une valeur n'est pas convertible dans son type
+ *
+ * Effets de bord : Aucun
+ */
+pub fn compile(schema: &TableSchema, filters: &[FieldFilter]) -> Result<Vec<Predicate>> {
+    filters.iter()
+        .filter(|filter| filter.operator != FilterOperator::Match)
+        .map(|filter| {
+            let field_type = if filter.field == "id" {
+                FieldType::Integer
+            } else {
+                schema.fields.iter()
+                    .find(|f| f.name == filter.field)
+                    .map(|f| f.field_type.clone())
+                    .ok_or_else(|| anyhow::anyhow!("Rubrique inconnue dans la table {}: {}", schema.name, filter.field))?
+            };
+            let operand = if matches!(filter.operator, FilterOperator::Like | FilterOperator::NotLike) {
+                Operand::Text(filter.value.clone())
+            } else {
+                convert(&filter.value, &field_type)
+                    .ok_or_else(|| anyhow::anyhow!("Valeur '{}' invalide pour la rubrique {} ({:?})", filter.value, filter.field, field_type))?
+            };
+            Ok(Predicate {
+                field: filter.field.clone(),
+                operator: filter.operator,
+                operand,
+                raw: filter.value.clone(),
+            })
+        })
+        .collect()
+}
+
+/**
+ * Indique si un enregistrement vérifie toutes les conditions.
+ *
+ * @param predicates - Prédicats compilés
+ * @param record - Enregistrement décodé
+ * @returns bool - true si toutes les conditions sont vérifiées
+ */
+pub fn matches_all(predicates: &[Predicate], record: &Record) -> bool {
+    predicates.iter().all(|predicate| predicate.matches(record))
+}
+
+/// Convertit la valeur saisie selon le type de la rubrique
+fn convert(value: &str, field_type: &FieldType) -> Option<Operand> {
+    let value = value.trim();
+    match field_type {
+        FieldType::Integer | FieldType::Float => value.parse::<i64>()
+            .map(Operand::Integer)
+            .or_else(|_| value.replace(',', ".").parse::<f64>().map(Operand::Float))
+            .ok(),
+        FieldType::Date => Some(Operand::Date(normalize_date(value))),
+        FieldType::Binary => Some(Operand::Binary(normalize_hex(value))),
+        FieldType::String | FieldType::Memo | FieldType::Unknown => Some(Operand::Text(value.to_string())),
+    }
+}
+
+/// Forme comparable d'une date : chiffres uniquement (AAAAMMJJ[HHMMSS])
+pub(crate) fn normalize_date(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Forme comparable d'une valeur hexadécimale
+fn normalize_hex(value: &str) -> String {
+    let value = value.trim();
+    value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value).to_lowercase()
+}
+
+impl Predicate {
+    /**
+     * Évalue la condition sur un enregistrement.
+     *
+     * @param record - Enregistrement décodé
+     * @returns bool - true si la condition est vérifiée
+     */
+    pub fn matches(&self, record: &Record) -> bool {
+        let Some(value) = record.fields.get(&self.field) else {
+            return false;
+        };
+
+        if matches!(self.operator, FilterOperator::Like | FilterOperator::NotLike) {
+            return match text_of(value) {
+                Some(text) => like(&text, &self.raw) == (self.operator == FilterOperator::Like),
+                None => false,
+            };
+        }
+
+        let Some(ordering) = self.compare(value) else {
+            return false;
+        };
+        match self.operator {
+            FilterOperator::Equal => ordering == Ordering::Equal,
+            FilterOperator::NotEqual => ordering != Ordering::Equal,
+            FilterOperator::GreaterThan => ordering == Ordering::Greater,
+            FilterOperator::GreaterThanOrEqual => ordering != Ordering::Less,
+            FilterOperator::LessThan => ordering == Ordering::Less,
+            FilterOperator::LessThanOrEqual => ordering != Ordering::Greater,
+            FilterOperator::Like | FilterOperator::NotLike | FilterOperator::Match => false,
+        }
+    }
+
+    /// Compare la valeur de l'enregistrement à l'opérande (None si incomparable)
+    fn compare(&self, value: &FieldValue) -> Option<Ordering> {
+        match (value, &self.operand) {
+            (FieldValue::Null { .. }, _) => None,
+            (FieldValue::Integer { value }, Operand::Integer(operand)) => Some(value.cmp(operand)),
+            (FieldValue::Integer { value }, Operand::Float(operand)) => (*value as f64).partial_cmp(operand),
+            (FieldValue::Float { value }, Operand::Integer(operand)) => value.partial_cmp(&(*operand as f64)),
+            (FieldValue::Float { value }, Operand::Float(operand)) => value.partial_cmp(operand),
+            (FieldValue::String { value }, Operand::Date(operand)) => Some(normalize_date(value).cmp(operand)),
+            (FieldValue::String { value } | FieldValue::MemoText { value, .. }, Operand::Text(operand)) => Some(value.trim_end().cmp(operand.trim_end())),
+            (FieldValue::Binary { value }, Operand::Binary(operand)) => Some(value.to_lowercase().cmp(operand)),
+            (value, operand) => {
+                // Type décodé différent du type déclaré : comparaison sur la forme texte
+                let text = text_of(value)?;
+                let operand = match operand {
+                    Operand::Integer(i) => i.to_string(),
+                    Operand::Float(f) => f.to_string(),
+                    Operand::Text(s) | Operand::Date(s) | Operand::Binary(s) => s.clone(),
+                };
+                Some(text.trim_end().cmp(operand.as_str()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self.operator {
+            FilterOperator::Equal => "=",
+            FilterOperator::NotEqual => "!=",
+            FilterOperator::GreaterThan => ">",
+            FilterOperator::GreaterThanOrEqual => ">=",
+            FilterOperator::LessThan => "<",
+            FilterOperator::LessThanOrEqual => "<=",
+            FilterOperator::Like => "LIKE",
+            FilterOperator::NotLike => "NOT LIKE",
+            FilterOperator::Match => "MATCH",
+        };
+        write!(f, "{} {} '{}'", self.field, operator, self.raw)
+    }
+}
+
+/// Forme texte d'une valeur (None pour NULL)
+fn text_of(value: &FieldValue) -> Option<String> {
+    match value {
+        FieldValue::String { value } | FieldValue::MemoText { value, .. } => Some(value.clone()),
+        FieldValue::Integer { value } => Some(value.to_string()),
+        FieldValue::Float { value } => Some(value.to_string()),
+        FieldValue::Binary { value } | FieldValue::MemoBinary { value, .. } => Some(value.clone()),
+        FieldValue::Null { .. } => None,
+    }
+}
+
+/**
+ * Teste un motif LIKE (% = suite quelconque, _ = un caractère), sans tenir
+ * compte de la casse.
+ *
+ * @param text - Texte testé
+ * @param pattern - Motif LIKE
+ * @returns bool - true si le texte correspond au motif
+ */
+fn like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.trim_end().to_lowercase().chars().collect();
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+
+    // Parcours glouton avec retour au dernier '%'
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '_' || pattern[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '%' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FieldInfo;
+    use crate::storage::Fields;
+
+    fn schema() -> TableSchema {
+        let field = |name: &str, field_type: FieldType| FieldInfo {
+            name: name.to_string(),
+            field_type,
+            offset: 0,
+            length: 4,
+            encoding: Default::default(),
+        };
+        TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: 32,
+            field_count: 4,
+            fields: vec![
+                field("nom", FieldType::String),
+                field("age", FieldType::Integer),
+                field("solde", FieldType::Float),
+                field("naissance", FieldType::Date),
+            ],
+            indexes: Vec::new(),
+            full_text: Vec::new(),
+        }
+    }
+
+    fn record() -> Record {
+        let mut fields = Fields::new();
+        fields.insert("id".to_string(), FieldValue::integer(1));
+        fields.insert("nom".to_string(), FieldValue::string("Dupont".to_string()));
+        fields.insert("age".to_string(), FieldValue::integer(42));
+        fields.insert("solde".to_string(), FieldValue::float(12.5));
+        fields.insert("naissance".to_string(), FieldValue::string("19820315".to_string()));
+        Record { id: 1, fields }
+    }
+
+    fn check(field: &str, operator: FilterOperator, value: &str) -> Result<bool> {
+        let filter = FieldFilter { field: field.to_string(), operator, value: value.to_string() };
+        Ok(matches_all(&compile(&schema(), &[filter])?, &record()))
+    }
+
+    #[test]
+    fn test_typed_comparisons() -> Result<()> {
+        assert!(check("nom", FilterOperator::Equal, "Dupont")?);
+        assert!(!check("nom", FilterOperator::Equal, "dupont")?);
+        assert!(check("age", FilterOperator::GreaterThan, "9")?); // numérique, pas lexicographique
+        assert!(check("age", FilterOperator::LessThanOrEqual, "42")?);
+        assert!(check("solde", FilterOperator::GreaterThanOrEqual, "12")?);
+        assert!(check("naissance", FilterOperator::LessThan, "1990-01-01")?);
+        assert!(check("id", FilterOperator::NotEqual, "2")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_like_and_errors() -> Result<()> {
+        assert!(check("nom", FilterOperator::Like, "du%")?);
+        assert!(check("nom", FilterOperator::Like, "%p_nt")?);
+        assert!(!check("nom", FilterOperator::Like, "%x%")?);
+        assert!(check("nom", FilterOperator::NotLike, "%x%")?);
+        assert!(!check("nom", FilterOperator::NotLike, "du%")?);
+        assert!(check("age", FilterOperator::Equal, "abc").is_err());
+        assert!(check("inconnu", FilterOperator::Equal, "1").is_err());
+        Ok(())
+    }
+}
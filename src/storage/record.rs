@@ -0,0 +1,394 @@
+/**
+ * Modèle des enregistrements décodés.
+ *
+ * Un Record porte ses rubriques dans l'ordre du schéma (Fields), ce qui
+ * fixe l'ordre des colonnes en JSON, en CSV et dans les résultats SQL. Les
+ * mémos sont des valeurs comme les autres (FieldValue::MemoText ou
+ * FieldValue::MemoBinary) et chaque rubrique décodée garde une référence
+ * vers ses octets bruts (RawRef) pour l'inspection.
+ *
+ * Sérialisation :
+ * - `fields` est un objet JSON dont les clés suivent l'ordre du schéma
+ * - Les octets bruts ne sont pas sérialisés : un Record relu depuis du JSON
+ *   (tri externe, point de reprise) n'a plus de RawRef
+ *
+ * Liens avec d'autres modules :
+ * - Construit par src/storage/engine.rs (décodage des .fic/.mmo)
+ * - Lu par src/sql/, src/cli/, src/api/, src/cdc/, src/diff/, src/profile/
+ */
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Représente un enregistrement avec ses données décodées et typées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// Identifiant de l'enregistrement
+    pub id: u32,
+    /// Rubriques de l'enregistrement, dans l'ordre du schéma (mémos compris)
+    pub fields: Fields,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FieldValue {
+    #[serde(rename = "string")]
+    String { value: String },
+    #[serde(rename = "integer")]
+    Integer { value: i64 },
+    #[serde(rename = "float")]
+    Float { value: f64 },
+    #[serde(rename = "binary")]
+    Binary { value: String }, // hex string
+    /// Mémo texte lu dans le .mmo (size : taille du bloc en octets)
+    #[serde(rename = "memo_text")]
+    MemoText { value: String, size: u32 },
+    /// Mémo binaire lu dans le .mmo (image, PDF…), en hexadécimal
+    #[serde(rename = "memo_binary")]
+    MemoBinary { value: String, size: u32, mime: String },
+    #[serde(rename = "null")]
+    Null { #[serde(serialize_with = "serialize_null")] value: () },
+}
+
+// Helpers pour créer les valeurs plus facilement
+impl FieldValue {
+    pub fn string(s: String) -> Self {
+        FieldValue::String { value: s }
+    }
+
+    pub fn integer(i: i64) -> Self {
+        FieldValue::Integer { value: i }
+    }
+
+    pub fn float(f: f64) -> Self {
+        FieldValue::Float { value: f }
+    }
+
+    pub fn binary(bytes: Vec<u8>) -> Self {
+        FieldValue::Binary { value: hex(&bytes) }
+    }
+
+    pub fn memo_text(text: String, size: u32) -> Self {
+        FieldValue::MemoText { value: text, size }
+    }
+
+    pub fn memo_binary(bytes: &[u8], mime: String) -> Self {
+        FieldValue::MemoBinary { value: hex(bytes), size: bytes.len() as u32, mime }
+    }
+
+    pub fn null() -> Self {
+        FieldValue::Null { value: () }
+    }
+
+    /// Texte de la valeur (chaîne ou mémo texte)
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            FieldValue::String { value } | FieldValue::MemoText { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Vrai pour un mémo (texte ou binaire)
+    pub fn is_memo(&self) -> bool {
+        matches!(self, FieldValue::MemoText { .. } | FieldValue::MemoBinary { .. })
+    }
+}
+
+/// Représentation lisible : chaîne brute, binaire en 0x…, NULL
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::String { value } | FieldValue::MemoText { value, .. } => write!(f, "{}", value),
+            FieldValue::Integer { value } => write!(f, "{}", value),
+            FieldValue::Float { value } => write!(f, "{}", value),
+            FieldValue::Binary { value } | FieldValue::MemoBinary { value, .. } => write!(f, "0x{}", value),
+            FieldValue::Null { .. } => write!(f, "NULL"),
+        }
+    }
+}
+
+fn serialize_null<S>(_value: &(), serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    // Sérialiser explicitement comme null pour correspondre au format frontend
+    serializer.serialize_some(&serde_json::Value::Null)
+}
+
+/// Octets en hexadécimal minuscule
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fichier d'où proviennent les octets bruts d'une valeur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RawSource {
+    /// Enregistrement du .fic (offset relatif au début de l'enregistrement, flag compris)
+    Fic,
+    /// Bloc du .mmo (offset du bloc dans le fichier)
+    Mmo,
+}
+
+/// Référence vers les octets bruts d'une valeur décodée
+///
+/// Les rubriques d'un même enregistrement partagent le tampon de
+/// l'enregistrement : cloner une RawRef ne copie pas les octets.
+#[derive(Clone)]
+pub struct RawRef {
+    /// Fichier d'origine
+    pub source: RawSource,
+    /// Position des octets (voir RawSource)
+    pub offset: u64,
+    buffer: Arc<[u8]>,
+    range: Range<usize>,
+}
+
+impl RawRef {
+    /**
+     * Crée une référence vers une partie d'un tampon.
+     *
+     * @param source - Fichier d'origine
+     * @param offset - Position des octets dans leur source
+     * @param buffer - Tampon partagé (enregistrement ou bloc mémo)
+     * @param range - Plage des octets de la valeur dans le tampon (bornée à sa taille)
+     * @returns RawRef - Référence sans copie
+     */
+    pub fn new(source: RawSource, offset: u64, buffer: Arc<[u8]>, range: Range<usize>) -> Self {
+        let end = range.end.min(buffer.len());
+        let start = range.start.min(end);
+        Self { source, offset, buffer, range: start..end }
+    }
+
+    /// Octets bruts de la valeur
+    pub fn bytes(&self) -> &[u8] {
+        &self.buffer[self.range.clone()]
+    }
+
+    /// Nombre d'octets
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Vrai si la valeur n'occupe aucun octet
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+}
+
+impl fmt::Debug for RawRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawRef")
+            .field("source", &self.source)
+            .field("offset", &self.offset)
+            .field("length", &self.len())
+            .finish()
+    }
+}
+
+/// Rubrique d'un enregistrement
+#[derive(Debug, Clone)]
+pub struct RecordField {
+    /// Nom de la rubrique
+    pub name: String,
+    /// Valeur décodée
+    pub value: FieldValue,
+    /// Octets bruts (None pour l'ID, une valeur modifiée ou relue depuis du JSON)
+    pub raw: Option<RawRef>,
+}
+
+/// Rubriques d'un enregistrement, dans l'ordre d'insertion (ordre du schéma)
+///
+/// La recherche par nom est linéaire : un enregistrement HFSQL compte au plus
+/// quelques centaines de rubriques.
+#[derive(Debug, Clone, Default)]
+pub struct Fields {
+    entries: Vec<RecordField>,
+}
+
+impl Fields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: Vec::with_capacity(capacity) }
+    }
+
+    /// Valeur d'une rubrique
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.entry(name).map(|field| &field.value)
+    }
+
+    /// Octets bruts d'une rubrique
+    pub fn raw(&self, name: &str) -> Option<&RawRef> {
+        self.entry(name).and_then(|field| field.raw.as_ref())
+    }
+
+    /// Rubrique complète (valeur et octets bruts)
+    pub fn entry(&self, name: &str) -> Option<&RecordField> {
+        self.entries.iter().find(|field| field.name == name)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    /**
+     * Ajoute ou remplace une rubrique.
+     *
+     * Une rubrique existante garde sa position ; ses octets bruts sont
+     * oubliés car ils ne correspondent plus à la valeur.
+     *
+     * @param name - Nom de la rubrique
+     * @param value - Nouvelle valeur
+     * @returns Option<FieldValue> - Ancienne valeur
+     */
+    pub fn insert(&mut self, name: impl Into<String>, value: FieldValue) -> Option<FieldValue> {
+        self.insert_raw(name, value, None)
+    }
+
+    /**
+     * Ajoute ou remplace une rubrique avec ses octets bruts.
+     *
+     * @param name - Nom de la rubrique
+     * @param value - Valeur décodée
+     * @param raw - Octets bruts de la valeur
+     * @returns Option<FieldValue> - Ancienne valeur
+     */
+    pub fn insert_raw(&mut self, name: impl Into<String>, value: FieldValue, raw: Option<RawRef>) -> Option<FieldValue> {
+        let name = name.into();
+        match self.entries.iter_mut().find(|field| field.name == name) {
+            Some(field) => {
+                field.raw = raw;
+                Some(std::mem::replace(&mut field.value, value))
+            }
+            None => {
+                self.entries.push(RecordField { name, value, raw });
+                None
+            }
+        }
+    }
+
+    /// Retire une rubrique (les suivantes gardent leur ordre)
+    pub fn remove(&mut self, name: &str) -> Option<FieldValue> {
+        let position = self.entries.iter().position(|field| field.name == name)?;
+        Some(self.entries.remove(position).value)
+    }
+
+    /// Garde les rubriques pour lesquelles `keep` retourne true (ordre conservé)
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.entries.retain(|field| keep(&field.name));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Paires (nom, valeur) dans l'ordre
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldValue)> {
+        self.entries.iter().map(|field| (&field.name, &field.value))
+    }
+
+    /// Noms des rubriques dans l'ordre
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|field| &field.name)
+    }
+
+    /// Rubriques complètes dans l'ordre
+    pub fn entries(&self) -> &[RecordField] {
+        &self.entries
+    }
+}
+
+impl IntoIterator for Fields {
+    type Item = (String, FieldValue);
+    type IntoIter = std::iter::Map<std::vec::IntoIter<RecordField>, fn(RecordField) -> (String, FieldValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter().map(|field| (field.name, field.value))
+    }
+}
+
+impl FromIterator<(String, FieldValue)> for Fields {
+    fn from_iter<I: IntoIterator<Item = (String, FieldValue)>>(iter: I) -> Self {
+        let mut fields = Fields::new();
+        for (name, value) in iter {
+            fields.insert(name, value);
+        }
+        fields
+    }
+}
+
+impl Serialize for Fields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for field in &self.entries {
+            map.serialize_entry(&field.name, &field.value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = Fields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("un objet nom de rubrique -> valeur")
+            }
+
+            // Lecture clé par clé pour conserver l'ordre du document
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Fields, A::Error> {
+                let mut fields = Fields::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((name, value)) = access.next_entry::<String, FieldValue>()? {
+                    fields.insert(name, value);
+                }
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_keep_order() -> anyhow::Result<()> {
+        let buffer: Arc<[u8]> = Arc::from(vec![0u8, 7, 0, 0, 0, b'A']);
+        let mut fields = Fields::new();
+        fields.insert("id", FieldValue::integer(3));
+        fields.insert_raw("ville", FieldValue::string("A".to_string()), Some(RawRef::new(RawSource::Fic, 5, buffer.clone(), 5..6)));
+        fields.insert_raw("code", FieldValue::integer(7), Some(RawRef::new(RawSource::Fic, 1, buffer, 1..5)));
+        fields.insert("notes", FieldValue::memo_text("à rappeler".to_string(), 11));
+        assert_eq!(fields.raw("code").map(|raw| raw.bytes()), Some(&[7u8, 0, 0, 0][..]));
+
+        let record = Record { id: 3, fields };
+        let json = serde_json::to_string(&record)?;
+        assert!(json.find("\"ville\"") < json.find("\"code\""));
+        assert!(json.contains("{\"type\":\"memo_text\",\"value\":\"à rappeler\",\"size\":11}"));
+
+        let mut decoded: Record = serde_json::from_str(&json)?;
+        assert_eq!(decoded.fields.keys().collect::<Vec<_>>(), vec!["id", "ville", "code", "notes"]);
+        assert!(decoded.fields.raw("code").is_none());
+
+        // Le remplacement garde la position
+        decoded.fields.insert("ville", FieldValue::null());
+        assert_eq!(decoded.fields.keys().nth(1).map(String::as_str), Some("ville"));
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+/**
+ * Échantillonnage aléatoire des enregistrements (TABLESAMPLE).
+ *
+ * Les premières pages d'une table ne représentent que ses enregistrements
+ * les plus anciens. Un échantillon tire des positions au hasard parmi les
+ * candidats (tous les IDs du .fic, ou ceux retenus par un index) et ne lit
+ * que les enregistrements tirés, par accès direct (FicFile::read_record) :
+ *
+ * - reservoir : tirage simple sans remise, chaque enregistrement retenu a
+ *   la même probabilité d'être choisi (même loi qu'un échantillon par
+ *   réservoir, mais les positions sont tirées directement par un mélange
+ *   de Fisher-Yates partiel au lieu de parcourir tout le fichier)
+ * - stratified : les candidats sont découpés en strates contiguës de même
+ *   taille (périodes successives pour un fichier alimenté par ajouts) et
+ *   chaque strate fournit sa part de l'échantillon
+ *
+ * Un enregistrement supprimé ou qui ne vérifie pas les conditions est
+ * remplacé par un autre tirage (dans la même strate pour stratified).
+ * Une strate qui n'a pas assez d'enregistrements retenus fournit ce
+ * qu'elle a : l'échantillon peut alors être plus petit que demandé.
+ *
+ * La graine est tirée de l'horloge si la requête n'en fournit pas ; elle
+ * est indiquée dans le plan pour rejouer le même échantillon.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (select, reprise au curseur)
+ * - Utilisé par src/sql/executor.rs (TABLESAMPLE) et src/api/handlers.rs (paramètre sample)
+ */
+
+use crate::storage::engine::Record;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Méthode d'échantillonnage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleMethod {
+    /// Tirage simple sans remise sur l'ensemble des candidats
+    Reservoir,
+    /// Tirage réparti sur des strates contiguës de candidats
+    Stratified,
+}
+
+impl std::str::FromStr for SampleMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "reservoir" => Ok(SampleMethod::Reservoir),
+            "stratified" => Ok(SampleMethod::Stratified),
+            other => anyhow::bail!("Méthode d'échantillonnage inconnue: {} (reservoir ou stratified)", other),
+        }
+    }
+}
+
+impl std::fmt::Display for SampleMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleMethod::Reservoir => write!(f, "reservoir"),
+            SampleMethod::Stratified => write!(f, "stratified"),
+        }
+    }
+}
+
+/// Échantillon demandé (TABLESAMPLE)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleSpec {
+    /// Méthode de tirage
+    pub method: SampleMethod,
+    /// Nombre d'enregistrements à tirer
+    pub size: u32,
+    /// Nombre de strates (stratified uniquement, None = une strate par enregistrement tiré)
+    #[serde(default)]
+    pub strata: Option<u32>,
+    /// Graine du tirage (None = tirée de l'horloge)
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl SampleSpec {
+    /**
+     * Crée un échantillon sans strate ni graine.
+     *
+     * @param method - Méthode de tirage
+     * @param size - Nombre d'enregistrements à tirer
+     * @returns SampleSpec - Échantillon demandé
+     */
+    pub fn new(method: SampleMethod, size: u32) -> Self {
+        Self { method, size, strata: None, seed: None }
+    }
+
+    /**
+     * Vérifie la cohérence de l'échantillon demandé.
+     *
+     * @returns Result<()> - Erreur si la taille est nulle ou si les strates sont invalides
+     */
+    pub fn validate(&self) -> Result<()> {
+        if self.size == 0 {
+            anyhow::bail!("Taille d'échantillon nulle");
+        }
+        match (self.method, self.strata) {
+            (SampleMethod::Reservoir, Some(_)) => anyhow::bail!("Le nombre de strates ne s'applique qu'à la méthode stratified"),
+            (SampleMethod::Stratified, Some(0)) => anyhow::bail!("Nombre de strates nul"),
+            (SampleMethod::Stratified, Some(strata)) if strata > self.size => {
+                anyhow::bail!("Le nombre de strates ({}) dépasse la taille de l'échantillon ({})", strata, self.size)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Lit un échantillon au format `méthode:taille[:strates]`
+ * (paramètre `sample` de l'API REST, ex: `stratified:500:20`).
+ */
+impl std::str::FromStr for SampleSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().split(':');
+        let method: SampleMethod = parts.next().unwrap_or_default().parse()?;
+        let size = parts.next()
+            .ok_or_else(|| anyhow::anyhow!("Taille d'échantillon attendue: {}", s))?;
+        let size = size.trim().parse::<u32>()
+            .with_context(|| format!("Taille d'échantillon invalide: {}", size))?;
+        let strata = parts.next()
+            .map(|strata| strata.trim().parse::<u32>().with_context(|| format!("Nombre de strates invalide: {}", strata)))
+            .transpose()?;
+        if parts.next().is_some() {
+            anyhow::bail!("Échantillon invalide (méthode:taille[:strates] attendu): {}", s);
+        }
+        let spec = SampleSpec { method, size, strata, seed: None };
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+impl std::fmt::Display for SampleSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}", self.method.to_string().to_uppercase(), self.size)?;
+        if let Some(strata) = self.strata {
+            write!(f, ", {}", strata)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Générateur pseudo-aléatoire (SplitMix64) : rapide, reproductible à partir de sa graine
+#[derive(Debug, Clone)]
+pub struct SampleRng(u64);
+
+impl SampleRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Graine tirée de l'horloge
+    pub fn clock_seed() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Entier uniforme dans [0, bound) (bound > 0)
+    pub fn below(&mut self, bound: u32) -> u32 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u32
+    }
+}
+
+/// Candidats au tirage, dans leur ordre de parcours
+#[derive(Debug, Clone)]
+pub enum Candidates {
+    /// Tous les IDs du .fic (0..record_count)
+    All(u32),
+    /// IDs retenus par un index ou une recherche full-text
+    Ids(Vec<u32>),
+}
+
+impl Candidates {
+    pub fn len(&self) -> u32 {
+        match self {
+            Candidates::All(count) => *count,
+            Candidates::Ids(ids) => ids.len() as u32,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// ID du candidat à une position
+    pub fn get(&self, position: u32) -> u32 {
+        match self {
+            Candidates::All(_) => position,
+            Candidates::Ids(ids) => ids[position as usize],
+        }
+    }
+}
+
+/// Mélange de Fisher-Yates partiel d'un intervalle de positions :
+/// seules les positions déplacées sont mémorisées
+struct Shuffle {
+    next: u32,
+    end: u32,
+    moved: HashMap<u32, u32>,
+}
+
+impl Shuffle {
+    fn new(start: u32, end: u32) -> Self {
+        Self { next: start, end, moved: HashMap::new() }
+    }
+
+    /// Position suivante, jamais tirée auparavant (None quand l'intervalle est épuisé)
+    fn draw(&mut self, rng: &mut SampleRng) -> Option<u32> {
+        if self.next >= self.end {
+            return None;
+        }
+        let current = self.next;
+        let target = current + rng.below(self.end - current);
+        let picked = self.moved.get(&target).copied().unwrap_or(target);
+        let displaced = self.moved.remove(&current).unwrap_or(current);
+        if target != current {
+            self.moved.insert(target, displaced);
+        }
+        self.next += 1;
+        Some(picked)
+    }
+}
+
+/// Échantillon tiré
+#[derive(Debug, Default)]
+pub struct Sample {
+    /// Enregistrements retenus, dans l'ordre des candidats
+    pub records: Vec<Record>,
+    /// Nombre d'enregistrements lus (retenus, supprimés ou écartés par les conditions)
+    pub read: u32,
+}
+
+/**
+ * Tire un échantillon parmi les candidats.
+ *
+ * @param spec - Échantillon demandé (validé)
+ * @param rng - Générateur initialisé avec la graine du tirage
+ * @param candidates - IDs candidats
+ * @param read - Lit un enregistrement par son ID : None s'il est supprimé ou
+ *        ne vérifie pas les conditions
+ * @returns Result<Sample> - Enregistrements retenus et nombre de lectures,
+ *          ou la première erreur de lecture
+ */
+pub fn draw(
+    spec: &SampleSpec,
+    rng: &mut SampleRng,
+    candidates: &Candidates,
+    mut read: impl FnMut(u32) -> Result<Option<Record>>,
+) -> Result<Sample> {
+    let count = candidates.len();
+    let strata = match spec.method {
+        SampleMethod::Reservoir => 1,
+        SampleMethod::Stratified => spec.strata.unwrap_or(spec.size).min(count.max(1)),
+    };
+
+    let mut picked: Vec<(u32, Record)> = Vec::new();
+    let mut reads = 0u32;
+    for stratum in 0..strata {
+        // Strate [start, end) et sa part de l'échantillon (le reste sur les premières strates)
+        let start = (stratum as u64 * count as u64 / strata as u64) as u32;
+        let end = ((stratum as u64 + 1) * count as u64 / strata as u64) as u32;
+        let quota = spec.size / strata + u32::from(stratum < spec.size % strata);
+
+        let mut shuffle = Shuffle::new(start, end);
+        let mut kept = 0;
+        while kept < quota {
+            let Some(position) = shuffle.draw(rng) else {
+                break;
+            };
+            reads += 1;
+            if let Some(record) = read(candidates.get(position))? {
+                picked.push((position, record));
+                kept += 1;
+            }
+        }
+    }
+
+    picked.sort_by_key(|(position, _)| *position);
+    Ok(Sample { records: picked.into_iter().map(|(_, record)| record).collect(), read: reads })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::record::Fields;
+
+    fn record(id: u32) -> Record {
+        Record { id, fields: Fields::new() }
+    }
+
+    #[test]
+    fn test_reservoir_is_reproducible_and_distinct() -> Result<()> {
+        let spec = SampleSpec::new(SampleMethod::Reservoir, 50);
+        let sample = |seed| draw(&spec, &mut SampleRng::new(seed), &Candidates::All(1000), |id| Ok(Some(record(id))));
+        let ids: Vec<u32> = sample(7)?.records.iter().map(|r| r.id).collect();
+        assert_eq!(ids.len(), 50);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ids, sample(7)?.records.iter().map(|r| r.id).collect::<Vec<_>>());
+        assert_ne!(ids, sample(8)?.records.iter().map(|r| r.id).collect::<Vec<_>>());
+
+        // Plus de place que de candidats : tous sont retenus, chacun une seule fois
+        let all = draw(&spec, &mut SampleRng::new(1), &Candidates::Ids(vec![9, 3, 5]), |id| Ok(Some(record(id))))?;
+        assert_eq!(all.records.iter().map(|r| r.id).collect::<Vec<_>>(), vec![9, 3, 5]);
+        assert_eq!(all.read, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejected_records_are_replaced() -> Result<()> {
+        // Seuls les IDs pairs sont retenus (les autres : supprimés ou hors conditions)
+        let spec = SampleSpec::new(SampleMethod::Reservoir, 20);
+        let sample = draw(&spec, &mut SampleRng::new(3), &Candidates::All(100), |id| Ok((id % 2 == 0).then(|| record(id))))?;
+        assert_eq!(sample.records.len(), 20);
+        assert!(sample.records.iter().all(|r| r.id % 2 == 0));
+        assert!(sample.read >= 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stratified_covers_every_stratum() -> Result<()> {
+        let spec = SampleSpec { method: SampleMethod::Stratified, size: 10, strata: Some(5), seed: None };
+        let sample = draw(&spec, &mut SampleRng::new(11), &Candidates::All(1000), |id| Ok(Some(record(id))))?;
+        assert_eq!(sample.read, 10);
+        for stratum in 0..5 {
+            let in_stratum = sample.records.iter().filter(|r| r.id / 200 == stratum).count();
+            assert_eq!(in_stratum, 2, "strate {}", stratum);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sample_spec() {
+        let spec: SampleSpec = "stratified:500:20".parse().unwrap();
+        assert_eq!(spec, SampleSpec { method: SampleMethod::Stratified, size: 500, strata: Some(20), seed: None });
+        assert_eq!(spec.to_string(), "STRATIFIED (500, 20)");
+        assert_eq!("Reservoir:10".parse::<SampleSpec>().unwrap(), SampleSpec::new(SampleMethod::Reservoir, 10));
+        assert!("reservoir".parse::<SampleSpec>().is_err());
+        assert!("reservoir:0".parse::<SampleSpec>().is_err());
+        assert!("reservoir:10:2".parse::<SampleSpec>().is_err());
+        assert!("stratified:10:20".parse::<SampleSpec>().is_err());
+        assert!("bernoulli:10".parse::<SampleSpec>().is_err());
+    }
+}
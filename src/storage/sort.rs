@@ -0,0 +1,562 @@
+/**
+ * Tri des enregistrements décodés (ORDER BY sans index adapté).
+ *
+ * Ce fichier compile les clés de tri d'une requête selon le schéma de la
+ * table, puis trie les enregistrements retenus avec une mémoire bornée :
+ *
+ * - Page courte (offset + limit ≤ TOP_N_MAX) : tas des N meilleurs
+ *   enregistrements, les autres sont écartés au fil de la lecture
+ * - Page longue (export complet) : tri externe, les enregistrements sont
+ *   triés par lots de RUN_SIZE, écrits dans des fichiers temporaires
+ *   (JSON, une ligne par enregistrement) puis fusionnés au fil de la
+ *   lecture (SortedRecords) ; les enregistrements relus n'ont plus leurs
+ *   octets bruts (RawRef)
+ *
+ * Sémantique des comparaisons :
+ * - Rubriques numériques : comparaison numérique
+ * - Chaînes : encodées comme la rubrique, espaces de fin ignorés, transformées selon
+ *   la collation (binary, nocase, noaccent) comme une clé d'index HFSQL
+ * - Dates : chiffres uniquement (AAAAMMJJ[HHMMSS]), une date vide est NULL
+ * - Mémos : texte lu dans le fichier .mmo, NULL s'il est absent
+ * - NULL : en dernier en ASC, en premier en DESC, sauf NULLS FIRST/LAST
+ * - Égalité sur toutes les clés : ordre des IDs (tri stable et déterministe)
+ *
+ * Liens avec d'autres modules :
+ * - Utilise src/core/key.rs pour les transformations de texte (fold_text)
+ * - Utilisé par src/storage/engine.rs (select, export trié)
+ */
+
+use crate::core::key::fold_text;
+use crate::core::{FieldType, TableSchema, TextEncoding};
+use crate::storage::engine::{Collation, FieldValue, Record, SortKey};
+use crate::storage::predicate::normalize_date;
+use anyhow::{Context, Result};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+/// Taille de page maximale triée par tas (au-delà : tri externe)
+pub const TOP_N_MAX: usize = 10_000;
+
+/// Nombre d'enregistrements triés en mémoire par fichier temporaire
+pub const RUN_SIZE: usize = 20_000;
+
+/// Compteur des dossiers temporaires créés par le processus
+static SPILL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Clé de tri compilée selon le schéma
+#[derive(Debug, Clone)]
+struct CompiledKey {
+    field: String,
+    descending: bool,
+    nulls_first: bool,
+    collation: Collation,
+    field_type: FieldType,
+    encoding: TextEncoding,
+}
+
+/// Tri compilé, prêt à classer des enregistrements
+#[derive(Debug, Clone)]
+pub struct RecordSorter {
+    keys: Vec<CompiledKey>,
+}
+
+/// Valeur comparable extraite d'un enregistrement
+#[derive(Debug, Clone)]
+enum SortValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Text(Vec<u8>),
+}
+
+/// Valeur d'une clé avec son sens de tri
+#[derive(Debug, Clone)]
+struct KeyPart {
+    value: SortValue,
+    descending: bool,
+    nulls_first: bool,
+}
+
+/// Enregistrement accompagné de ses clés de tri
+#[derive(Debug, Clone)]
+struct SortRow {
+    parts: Vec<KeyPart>,
+    record: Record,
+}
+
+/// Page triée
+#[derive(Debug, Clone)]
+pub struct SortedPage {
+    /// Enregistrements de la page, dans l'ordre demandé
+    pub records: Vec<Record>,
+    /// Nombre d'enregistrements triés (avant pagination)
+    pub total: u32,
+    /// Méthode utilisée (pour le plan d'exécution)
+    pub method: String,
+}
+
+/// Enregistrements triés, restitués au fil de la fusion des lots
+pub struct SortedRecords {
+    sorter: RecordSorter,
+    source: SortedSource,
+    total: u32,
+    method: String,
+}
+
+/// Origine des enregistrements triés
+enum SortedSource {
+    /// Tous les enregistrements tenaient dans un lot
+    Memory(std::vec::IntoIter<SortRow>),
+    /// Lots écrits sur disque, fusionnés à la lecture
+    Merge {
+        readers: Vec<Lines<BufReader<File>>>,
+        heap: BinaryHeap<Reverse<(SortRow, usize)>>,
+        _spill: SpillDir,
+    },
+}
+
+impl RecordSorter {
+    /**
+     * Compile les clés de tri d'une requête selon le schéma de la table.
+     *
+     * @param schema - Schéma de la table
+     * @param order_by - Clés de tri, par priorité décroissante
+     * @returns Result<RecordSorter> - Tri compilé, ou erreur si une rubrique est inconnue
+     *
+     * Effets de bord : Aucun
+     */
+    pub fn compile(schema: &TableSchema, order_by: &[SortKey]) -> Result<Self> {
+        let keys = order_by.iter()
+            .map(|key| {
+                let (field_type, encoding) = if key.field == "id" {
+                    (FieldType::Integer, TextEncoding::Auto)
+                } else {
+                    schema.fields.iter()
+                        .find(|f| f.name == key.field)
+                        .map(|f| (f.field_type.clone(), f.encoding))
+                        .ok_or_else(|| anyhow::anyhow!("Rubrique de tri inconnue dans la table {}: {}", schema.name, key.field))?
+                };
+                Ok(CompiledKey {
+                    field: key.field.clone(),
+                    descending: key.descending,
+                    nulls_first: key.nulls_first(),
+                    collation: key.collation,
+                    field_type,
+                    encoding,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { keys })
+    }
+
+    /**
+     * Trie des enregistrements et retourne la page demandée.
+     *
+     * @param records - Enregistrements à trier (déjà filtrés), lus au fil de l'eau
+     * @param offset - Nombre d'enregistrements à ignorer
+     * @param limit - Nombre maximum d'enregistrements à retourner
+     * @returns Result<SortedPage> - Page triée et nombre total d'enregistrements
+     *
+     * Effets de bord :
+     * - Pour une page longue, écrit puis supprime des fichiers temporaires
+     */
+    pub fn sorted_page(&self, records: impl Iterator<Item = Result<Record>>, offset: u32, limit: u32) -> Result<SortedPage> {
+        let wanted = offset as usize + limit as usize;
+        if wanted <= TOP_N_MAX {
+            self.top_n(records, offset as usize, wanted)
+        } else {
+            let sorted = self.sort(records)?;
+            let (total, method) = (sorted.total, sorted.method.clone());
+            Ok(SortedPage {
+                records: sorted.skip(offset as usize).take(limit as usize).collect::<Result<Vec<_>>>()?,
+                total,
+                method,
+            })
+        }
+    }
+
+    /// Garde les `wanted` premiers enregistrements dans un tas borné
+    fn top_n(&self, records: impl Iterator<Item = Result<Record>>, offset: usize, wanted: usize) -> Result<SortedPage> {
+        let mut heap: BinaryHeap<SortRow> = BinaryHeap::with_capacity(wanted + 1);
+        let mut total = 0u32;
+        for record in records {
+            total += 1;
+            if wanted == 0 {
+                record?;
+                continue;
+            }
+            heap.push(self.row(record?));
+            if heap.len() > wanted {
+                heap.pop();
+            }
+        }
+        Ok(SortedPage {
+            records: heap.into_sorted_vec().into_iter().skip(offset).map(|row| row.record).collect(),
+            total,
+            method: format!("tas top-N de {}", wanted),
+        })
+    }
+
+    /**
+     * Trie tous les enregistrements et les restitue dans l'ordre demandé.
+     *
+     * Les enregistrements sont triés par lots de RUN_SIZE écrits sur disque ;
+     * l'itérateur retourné fusionne les lots au fil de la lecture, sans
+     * garder plus d'un enregistrement par lot en mémoire. Si tout tient dans
+     * un lot, le tri est fait en mémoire.
+     *
+     * @param records - Enregistrements à trier (déjà filtrés), lus au fil de l'eau
+     * @returns Result<SortedRecords> - Enregistrements triés, nombre total et méthode
+     *
+     * Effets de bord :
+     * - Écrit des fichiers temporaires, supprimés quand l'itérateur est libéré
+     */
+    pub fn sort(&self, records: impl Iterator<Item = Result<Record>>) -> Result<SortedRecords> {
+        let mut spill: Option<SpillDir> = None;
+        let mut runs: Vec<PathBuf> = Vec::new();
+        let mut buffer: Vec<SortRow> = Vec::new();
+        let mut total = 0u32;
+
+        for record in records {
+            buffer.push(self.row(record?));
+            total += 1;
+            if buffer.len() >= RUN_SIZE {
+                if spill.is_none() {
+                    spill = Some(SpillDir::create()?);
+                }
+                if let Some(dir) = &spill {
+                    runs.push(dir.write_run(runs.len(), &mut buffer)?);
+                }
+            }
+        }
+
+        let Some(dir) = spill else {
+            buffer.sort();
+            return Ok(SortedRecords {
+                sorter: self.clone(),
+                source: SortedSource::Memory(buffer.into_iter()),
+                total,
+                method: "tri en mémoire".to_string(),
+            });
+        };
+        if !buffer.is_empty() {
+            runs.push(dir.write_run(runs.len(), &mut buffer)?);
+        }
+
+        // Fusion : le tas contient le plus petit enregistrement non lu de chaque lot
+        let mut readers = runs.iter()
+            .map(|path| File::open(path).map(|f| BufReader::new(f).lines()))
+            .collect::<std::io::Result<Vec<_>>>()
+            .context("Impossible de relire un fichier de tri temporaire")?;
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(row) = self.next_row(reader)? {
+                heap.push(Reverse((row, run)));
+            }
+        }
+
+        Ok(SortedRecords {
+            sorter: self.clone(),
+            source: SortedSource::Merge { readers, heap, _spill: dir },
+            total,
+            method: format!("tri externe, {} lots de {}", runs.len(), RUN_SIZE),
+        })
+    }
+
+    /// Lit l'enregistrement suivant d'un lot trié
+    fn next_row(&self, reader: &mut Lines<BufReader<File>>) -> Result<Option<SortRow>> {
+        let Some(line) = reader.next() else {
+            return Ok(None);
+        };
+        let record: Record = serde_json::from_str(&line?)
+            .context("Fichier de tri temporaire corrompu")?;
+        Ok(Some(self.row(record)))
+    }
+
+    /// Calcule les clés de tri d'un enregistrement
+    fn row(&self, record: Record) -> SortRow {
+        let parts = self.keys.iter()
+            .map(|key| KeyPart {
+                value: key.value(&record),
+                descending: key.descending,
+                nulls_first: key.nulls_first,
+            })
+            .collect();
+        SortRow { parts, record }
+    }
+}
+
+impl SortedRecords {
+    /// Nombre d'enregistrements triés
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Méthode utilisée (pour le plan d'exécution)
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+}
+
+impl Iterator for SortedRecords {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            SortedSource::Memory(rows) => rows.next().map(|row| Ok(row.record)),
+            SortedSource::Merge { readers, heap, .. } => {
+                let Reverse((row, run)) = heap.pop()?;
+                match self.sorter.next_row(&mut readers[run]) {
+                    Ok(Some(next)) => heap.push(Reverse((next, run))),
+                    Ok(None) => {}
+                    Err(e) => {
+                        heap.clear();
+                        return Some(Err(e));
+                    }
+                }
+                Some(Ok(row.record))
+            }
+        }
+    }
+}
+
+impl CompiledKey {
+    /// Extrait la valeur comparable de la rubrique
+    fn value(&self, record: &Record) -> SortValue {
+        match record.fields.get(&self.field) {
+            None | Some(FieldValue::Null { .. }) => SortValue::Null,
+            Some(FieldValue::Integer { value }) => SortValue::Integer(*value),
+            Some(FieldValue::Float { value }) => SortValue::Float(*value),
+            Some(FieldValue::String { value }) if matches!(self.field_type, FieldType::Date) => {
+                let digits = normalize_date(value);
+                if digits.is_empty() { SortValue::Null } else { SortValue::Text(digits.into_bytes()) }
+            }
+            Some(FieldValue::String { value } | FieldValue::MemoText { value, .. }) => SortValue::Text(self.text(value)),
+            Some(FieldValue::Binary { value }) if matches!(self.field_type, FieldType::Date) => {
+                // Date vide (octets nuls)
+                if value.chars().all(|c| c == '0') { SortValue::Null } else { SortValue::Text(value.to_lowercase().into_bytes()) }
+            }
+            Some(FieldValue::Binary { value } | FieldValue::MemoBinary { value, .. }) => SortValue::Text(value.to_lowercase().into_bytes()),
+        }
+    }
+
+    /// Forme comparable d'un texte selon la collation (dans l'encodage de la rubrique, comme les clés d'index)
+    fn text(&self, value: &str) -> Vec<u8> {
+        let encoded = self.encoding.encode(value.trim_end());
+        let (case_insensitive, accent_insensitive) = self.collation.folding();
+        fold_text(&encoded, case_insensitive, accent_insensitive, self.encoding)
+    }
+}
+
+impl SortValue {
+    fn cmp_value(&self, other: &SortValue) -> Ordering {
+        match (self, other) {
+            (SortValue::Integer(a), SortValue::Integer(b)) => a.cmp(b),
+            (SortValue::Integer(a), SortValue::Float(b)) => (*a as f64).total_cmp(b),
+            (SortValue::Float(a), SortValue::Integer(b)) => a.total_cmp(&(*b as f64)),
+            (SortValue::Float(a), SortValue::Float(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            // Types mélangés (décodage inattendu) : nombres avant textes
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            SortValue::Null => 0,
+            SortValue::Integer(_) | SortValue::Float(_) => 1,
+            SortValue::Text(_) => 2,
+        }
+    }
+}
+
+impl KeyPart {
+    fn cmp_part(&self, other: &KeyPart) -> Ordering {
+        let nulls = if self.nulls_first { Ordering::Less } else { Ordering::Greater };
+        match (&self.value, &other.value) {
+            (SortValue::Null, SortValue::Null) => Ordering::Equal,
+            (SortValue::Null, _) => nulls,
+            (_, SortValue::Null) => nulls.reverse(),
+            (a, b) if self.descending => b.cmp_value(a),
+            (a, b) => a.cmp_value(b),
+        }
+    }
+}
+
+impl Ord for SortRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts.iter()
+            .zip(&other.parts)
+            .map(|(a, b)| a.cmp_part(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then(self.record.id.cmp(&other.record.id))
+    }
+}
+
+impl PartialOrd for SortRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortRow {}
+
+/// Dossier temporaire des lots d'un tri externe, supprimé à la fin du tri
+struct SpillDir {
+    path: PathBuf,
+}
+
+impl SpillDir {
+    fn create() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "fic-sort-{}-{}",
+            std::process::id(),
+            SPILL_COUNTER.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path)
+            .with_context(|| format!("Impossible de créer le dossier de tri temporaire {:?}", path))?;
+        Ok(Self { path })
+    }
+
+    /// Trie le lot en mémoire et l'écrit dans un fichier (le lot est vidé)
+    fn write_run(&self, number: usize, rows: &mut Vec<SortRow>) -> Result<PathBuf> {
+        rows.sort();
+        let path = self.path.join(format!("run-{:04}.jsonl", number));
+        let file = File::create(&path)
+            .with_context(|| format!("Impossible de créer le fichier de tri {:?}", path))?;
+        let mut writer = BufWriter::new(file);
+        for row in rows.drain(..) {
+            serde_json::to_writer(&mut writer, &row.record)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(path)
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FieldInfo;
+    use crate::storage::engine::NullsOrder;
+    use crate::storage::Fields;
+
+    fn schema() -> TableSchema {
+        let field = |name: &str, field_type: FieldType| FieldInfo {
+            name: name.to_string(),
+            field_type,
+            offset: 0,
+            length: 4,
+            encoding: Default::default(),
+        };
+        TableSchema {
+            name: "CLIENT".to_string(),
+            record_length: 32,
+            field_count: 2,
+            fields: vec![field("nom", FieldType::String), field("age", FieldType::Integer)],
+            indexes: Vec::new(),
+            full_text: Vec::new(),
+        }
+    }
+
+    fn records() -> Vec<Record> {
+        let rows = [("dupont", Some(42)), ("Émile", None), ("Durand", Some(9)), ("alain", Some(42)), ("Eric", Some(30))];
+        rows.iter()
+            .enumerate()
+            .map(|(id, (nom, age))| {
+                let mut fields = Fields::new();
+                fields.insert("nom".to_string(), FieldValue::string(nom.to_string()));
+                fields.insert("age".to_string(), age.map(FieldValue::integer).unwrap_or_else(FieldValue::null));
+                Record { id: id as u32, fields }
+            })
+            .collect()
+    }
+
+    fn sorted_ids(order_by: &[SortKey], offset: u32, limit: u32) -> Result<Vec<u32>> {
+        let sorter = RecordSorter::compile(&schema(), order_by)?;
+        let page = sorter.sorted_page(records().into_iter().map(Ok), offset, limit)?;
+        assert_eq!(page.total, 5);
+        Ok(page.records.iter().map(|r| r.id).collect())
+    }
+
+    #[test]
+    fn test_multi_key_and_nulls() -> Result<()> {
+        // age DESC (NULL en premier par défaut), puis nom ASC
+        let order = [SortKey::new("age", true), SortKey::new("nom", false)];
+        assert_eq!(sorted_ids(&order, 0, 10)?, vec![1, 3, 0, 4, 2]);
+        assert_eq!(sorted_ids(&order, 1, 2)?, vec![3, 0]);
+
+        let mut age = SortKey::new("age", false);
+        age.nulls = Some(NullsOrder::First);
+        assert_eq!(sorted_ids(&[age], 0, 10)?, vec![1, 2, 4, 0, 3]);
+        assert!(sorted_ids(&[SortKey::new("inconnu", false)], 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_collations() -> Result<()> {
+        assert_eq!(sorted_ids(&[SortKey::new("nom", false)], 0, 10)?, vec![2, 4, 3, 0, 1]);
+        assert_eq!(sorted_ids(&["nom:nocase".parse()?], 0, 10)?, vec![3, 0, 2, 4, 1]);
+        assert_eq!(sorted_ids(&["nom:asc:noaccent".parse()?], 0, 10)?, vec![3, 0, 2, 1, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_sort() -> Result<()> {
+        let count = RUN_SIZE * 2 + 500;
+        let records = (0..count as u32).map(|id| {
+            let mut fields = Fields::new();
+            fields.insert("age".to_string(), FieldValue::integer((id % 1000) as i64));
+            Ok(Record { id, fields })
+        });
+        let sorter = RecordSorter::compile(&schema(), &[SortKey::new("age", true)])?;
+        let page = sorter.sorted_page(records, 10, u32::MAX)?;
+        assert!(page.method.starts_with("tri externe, 3 lots"));
+        assert_eq!(page.total as usize, count);
+        assert_eq!(page.records.len(), count - 10);
+        assert!(page.records.windows(2).all(|w| {
+            let age = |r: &Record| match r.fields.get("age") {
+                Some(FieldValue::Integer { value }) => *value,
+                _ => -1,
+            };
+            age(&w[0]) > age(&w[1]) || (age(&w[0]) == age(&w[1]) && w[0].id < w[1].id)
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorted_records_stream() -> Result<()> {
+        let count = RUN_SIZE + 10;
+        let records = (0..count as u32).rev().map(|id| {
+            let mut fields = Fields::new();
+            fields.insert("age".to_string(), FieldValue::integer(id as i64));
+            Ok(Record { id, fields })
+        });
+        let sorter = RecordSorter::compile(&schema(), &[SortKey::new("age", false)])?;
+        let mut sorted = sorter.sort(records)?;
+        assert_eq!(sorted.total() as usize, count);
+        assert!(sorted.method().starts_with("tri externe, 2 lots"));
+        assert_eq!(sorted.next().transpose()?.map(|r| r.id), Some(0));
+        let ids = sorted.map(|record| record.map(|r| r.id)).collect::<Result<Vec<_>>>()?;
+        assert_eq!(ids.len(), count - 1);
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+/**
+ * Surveillance du dossier de données et événements de modification des tables.
+ *
+ * La liste des tables n'est mise à jour que par un scan : sans surveillance,
+ * un fichier .fic ajouté, supprimé ou remplacé reste invisible jusqu'au
+ * redémarrage. Le TableWatcher rescanne périodiquement les dossiers
+ * enregistrés (StorageEngine::refresh_tables) et compare la taille et la date
+ * de modification des fichiers de chaque table avec le scan précédent.
+ *
+ * La surveillance se fait par scrutation plutôt que par notifications du
+ * système : les dossiers HFSQL sont souvent sur des partages réseau où les
+ * notifications ne sont pas fiables.
+ *
+ * Chaque différence produit un TableChange :
+ * - Added : nouvelle table (fichier .fic apparu ou entrée .rep résolue)
+ * - Removed : table disparue
+ * - Modified : un fichier de la table (.fic, .mmo, .ndx, .ftx, .jnl…) a changé
+ *
+ * Les changements sont publiés sur un canal broadcast auquel l'API s'abonne
+ * (GET /events/tables) ; le schéma en cache des tables modifiées ou
+ * supprimées est invalidé.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (refresh_tables, subscribe_changes)
+ * - Démarré par la commande serve (src/cli/mod.rs)
+ */
+
+use crate::core::TableFiles;
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::cache::FileStamp;
+use crate::storage::StorageEngine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Intervalle de scrutation par défaut
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Nombre d'événements conservés pour un abonné lent avant d'en perdre
+pub const CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// Nature d'un changement de table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableChangeKind {
+    /// Nouvelle table
+    Added,
+    /// Table disparue
+    Removed,
+    /// Fichier de la table modifié, ajouté ou supprimé
+    Modified,
+}
+
+/// Changement détecté sur une table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableChange {
+    /// Nom qualifié de la table ("base.TABLE")
+    pub table: String,
+    /// Base contenant la table, vide pour la base racine
+    pub database: String,
+    /// Nature du changement
+    pub kind: TableChangeKind,
+    /// Fichiers concernés (noms de fichiers, vides pour un ajout ou une suppression)
+    pub files: Vec<String>,
+    /// Date de détection (secondes depuis l'époque Unix)
+    pub timestamp: u64,
+}
+
+/// État des fichiers d'une table lors d'un scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TableState {
+    database: String,
+    files: Vec<(PathBuf, Option<FileStamp>)>,
+}
+
+impl TableState {
+    /**
+     * Lit la taille et la date de modification des fichiers d'une table.
+     *
+     * @param files - Fichiers de la table
+     * @returns TableState - État courant
+     */
+    pub(crate) fn read(files: &TableFiles) -> Self {
+        Self {
+            database: files.database.clone(),
+            files: files.found_paths()
+                .into_iter()
+                .map(|path| (path.clone(), FileStamp::read(path)))
+                .collect(),
+        }
+    }
+
+    /// Fichiers qui diffèrent entre deux états (ajoutés, supprimés ou modifiés)
+    fn changed_files(&self, other: &TableState) -> Vec<String> {
+        let mut changed: Vec<String> = self.files.iter()
+            .filter(|entry| !other.files.contains(entry))
+            .chain(other.files.iter().filter(|entry| !self.files.contains(entry)))
+            .filter_map(|(path, _)| Some(path.file_name()?.to_string_lossy().to_string()))
+            .collect();
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+/**
+ * Compare deux états des tables et liste les changements.
+ *
+ * @param before - État au scan précédent, par nom qualifié
+ * @param after - État au scan courant, par nom qualifié
+ * @returns Vec<TableChange> - Changements triés par table
+ */
+pub(crate) fn diff_states(before: &HashMap<String, TableState>, after: &HashMap<String, TableState>) -> Vec<TableChange> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let change = |table: &str, state: &TableState, kind, files| TableChange {
+        table: table.to_string(),
+        database: state.database.clone(),
+        kind,
+        files,
+        timestamp,
+    };
+
+    let mut changes: Vec<TableChange> = after.iter()
+        .filter_map(|(table, state)| match before.get(table) {
+            None => Some(change(table, state, TableChangeKind::Added, Vec::new())),
+            Some(previous) if previous != state => {
+                Some(change(table, state, TableChangeKind::Modified, previous.changed_files(state)))
+            }
+            Some(_) => None,
+        })
+        .chain(before.iter()
+            .filter(|(table, _)| !after.contains_key(*table))
+            .map(|(table, state)| change(table, state, TableChangeKind::Removed, Vec::new())))
+        .collect();
+    changes.sort_by(|a, b| a.table.cmp(&b.table));
+    changes
+}
+
+/**
+ * Surveille les dossiers enregistrés dans un thread dédié.
+ *
+ * Le thread s'arrête quand le TableWatcher est supprimé (ou via stop).
+ */
+pub struct TableWatcher {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TableWatcher {
+    /**
+     * Démarre la surveillance.
+     *
+     * @param engine - Moteur dont les dossiers sont surveillés
+     * @param interval - Délai entre deux scans
+     * @returns TableWatcher - Surveillance active jusqu'à sa suppression
+     *
+     * Effets de bord :
+     * - Lance un thread qui rescanne les dossiers à chaque intervalle
+     * - Log chaque changement détecté et les erreurs de scan
+     */
+    pub fn start(engine: Arc<StorageEngine>, interval: Duration) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = std::thread::spawn(move || {
+            get_logger().log_with_source(
+                LogLevel::Info,
+                format!("Surveillance de {:?} (toutes les {} ms)", engine.data_dir(), interval.as_millis()),
+                Some("Watcher".to_string()),
+            );
+            // Un message ou la fermeture du canal arrête la surveillance
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                match engine.refresh_tables() {
+                    Ok(changes) => {
+                        for change in changes {
+                            get_logger().log_with_source(
+                                LogLevel::Info,
+                                format!("Table {} : {:?} {}", change.table, change.kind, change.files.join(", ")),
+                                Some("Watcher".to_string()),
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        get_logger().log_with_source(
+                            LogLevel::Warn,
+                            format!("Échec du rescan des tables: {:#}", e),
+                            Some("Watcher".to_string()),
+                        );
+                    }
+                }
+            }
+        });
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    /**
+     * Arrête la surveillance et attend la fin du scan en cours.
+     */
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for TableWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fic::tests::write_fic;
+    use tempfile::TempDir;
+
+    fn kinds(changes: &[TableChange]) -> Vec<(&str, TableChangeKind)> {
+        changes.iter().map(|c| (c.table.as_str(), c.kind)).collect()
+    }
+
+    #[test]
+    fn test_refresh_reports_changes() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 16, &[(false, 0); 2])?;
+        let engine = StorageEngine::new_with_parallel(dir.path(), true, false)?;
+        engine.scan_tables()?;
+        let mut events = engine.subscribe_changes();
+        assert!(engine.refresh_tables()?.is_empty());
+
+        write_fic(&dir.path().join("COMMANDE.FIC"), 16, &[(false, 0); 1])?;
+        write_fic(&dir.path().join("CLIENT.FIC"), 16, &[(false, 0); 3])?;
+        let changes = engine.refresh_tables()?;
+        assert_eq!(kinds(&changes), vec![("CLIENT", TableChangeKind::Modified), ("COMMANDE", TableChangeKind::Added)]);
+        assert_eq!(changes[0].files, vec!["CLIENT.FIC".to_string()]);
+        assert!(engine.has_table("COMMANDE"));
+        assert_eq!(events.try_recv()?.table, "CLIENT");
+
+        std::fs::remove_file(dir.path().join("COMMANDE.FIC"))?;
+        let changes = engine.refresh_tables()?;
+        assert_eq!(kinds(&changes), vec![("COMMANDE", TableChangeKind::Removed)]);
+        assert!(!engine.has_table("COMMANDE"));
+        Ok(())
+    }
+}